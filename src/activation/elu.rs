@@ -0,0 +1,25 @@
+use super::ActivationLayer;
+use crate::linalg::{Matrix, MatrixTrait, Scalar};
+
+// https://arxiv.org/pdf/1511.07289.pdf
+
+pub fn elu(m: &Matrix, alpha: Scalar) -> Matrix {
+    let zeros = Matrix::constant(m.dim().0, m.dim().1, 0.);
+    let negative = m.minof(&zeros).exp().scalar_sub(1.).scalar_mul(alpha);
+    m.maxof(&zeros).component_add(&negative)
+}
+
+pub fn elu_prime(m: &Matrix, alpha: Scalar) -> Matrix {
+    let zeros = Matrix::constant(m.dim().0, m.dim().1, 0.);
+    let step = m.sign().maxof(&zeros);
+    let negative = m
+        .minof(&zeros)
+        .exp()
+        .scalar_mul(alpha)
+        .component_mul(&step.scalar_mul(-1.).scalar_add(1.));
+    step.component_add(&negative)
+}
+
+pub fn new(alpha: Scalar) -> ActivationLayer {
+    ActivationLayer::new(move |m| elu(m, alpha), move |m| elu_prime(m, alpha))
+}
@@ -0,0 +1,35 @@
+use super::{sigmoid::sigmoid, ActivationLayer};
+use crate::linalg::{Matrix, MatrixTrait, Scalar};
+
+// tanh approximation from https://arxiv.org/pdf/1606.08415.pdf
+
+const SQRT_2_OVER_PI: Scalar = 0.797_884_6;
+const CUBIC_COEF: Scalar = 0.044_715;
+
+// tanh(√(2/π) (x + 0.044715 x³)), computed as 2σ(2z) - 1 so that it doesn't overflow
+fn inner_tanh(m: &Matrix) -> Matrix {
+    let cube = m.square().component_mul(m);
+    let z = m.component_add(&cube.scalar_mul(CUBIC_COEF)).scalar_mul(SQRT_2_OVER_PI);
+    sigmoid(&z.scalar_mul(2.)).scalar_mul(2.).scalar_sub(1.)
+}
+
+pub fn gelu(m: &Matrix) -> Matrix {
+    m.component_mul(&inner_tanh(m).scalar_add(1.)).scalar_mul(0.5)
+}
+
+pub fn gelu_prime(m: &Matrix) -> Matrix {
+    let t = inner_tanh(m);
+    let dz = m
+        .square()
+        .scalar_mul(3. * CUBIC_COEF)
+        .scalar_add(1.)
+        .scalar_mul(SQRT_2_OVER_PI);
+    let sech2 = t.square().scalar_mul(-1.).scalar_add(1.);
+    t.scalar_add(1.)
+        .component_add(&m.component_mul(&sech2).component_mul(&dz))
+        .scalar_mul(0.5)
+}
+
+pub fn new() -> ActivationLayer {
+    ActivationLayer::new(gelu, gelu_prime)
+}
@@ -0,0 +1,21 @@
+use super::ActivationLayer;
+use crate::linalg::{Matrix, MatrixTrait};
+
+pub fn hard_sigmoid(m: &Matrix) -> Matrix {
+    // max(0, min(1, x / 6 + 1 / 2))
+    let zeros = Matrix::constant(m.dim().0, m.dim().1, 0.);
+    let ones = Matrix::constant(m.dim().0, m.dim().1, 1.);
+    m.scalar_div(6.).scalar_add(0.5).minof(&ones).maxof(&zeros)
+}
+
+pub fn hard_sigmoid_prime(m: &Matrix) -> Matrix {
+    // 1 / 6 for -3 < x < 3, 0 elsewhere
+    let zeros = Matrix::constant(m.dim().0, m.dim().1, 0.);
+    let above = m.scalar_add(3.).sign().maxof(&zeros);
+    let below = m.scalar_sub(3.).scalar_mul(-1.).sign().maxof(&zeros);
+    above.component_mul(&below).scalar_div(6.)
+}
+
+pub fn new() -> ActivationLayer {
+    ActivationLayer::new(hard_sigmoid, hard_sigmoid_prime)
+}
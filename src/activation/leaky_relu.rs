@@ -0,0 +1,20 @@
+use super::ActivationLayer;
+use crate::linalg::{Matrix, MatrixTrait, Scalar};
+
+pub fn leaky_relu(m: &Matrix, alpha: Scalar) -> Matrix {
+    let zeros = Matrix::constant(m.dim().0, m.dim().1, 0.);
+    m.maxof(&zeros).component_add(&m.minof(&zeros).scalar_mul(alpha))
+}
+
+pub fn leaky_relu_prime(m: &Matrix, alpha: Scalar) -> Matrix {
+    let zeros = Matrix::constant(m.dim().0, m.dim().1, 0.);
+    let step = m.sign().maxof(&zeros);
+    step.component_add(&step.scalar_mul(-1.).scalar_add(1.).scalar_mul(alpha))
+}
+
+pub fn new(alpha: Scalar) -> ActivationLayer {
+    ActivationLayer::new(
+        move |m| leaky_relu(m, alpha),
+        move |m| leaky_relu_prime(m, alpha),
+    )
+}
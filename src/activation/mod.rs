@@ -1,72 +1,323 @@
-use std::fmt;
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+    sync::{Arc, Mutex, OnceLock, RwLock},
+};
 
-use crate::linalg::MatrixTrait;
 use serde::{Deserialize, Serialize};
 
-use crate::{layer::Layer, linalg::Matrix};
+use crate::{
+    autodiff::{Tape, Var},
+    gradient_clipping::GradientMut,
+    layer::{
+        accumulate_gradient, defaults::default_biases_optimizer, GradientLayer, Layer,
+        LearnableLayer,
+    },
+    linalg::{Matrix, MatrixTrait, Scalar},
+    optimizer::Optimizers,
+};
 
+pub mod elu;
+pub mod gelu;
+pub mod hard_sigmoid;
+pub mod leaky_relu;
 pub mod linear;
+pub mod mish;
+pub mod prelu;
 pub mod relu;
+pub mod selu;
 pub mod sigmoid;
 pub mod softmax;
+pub mod softplus;
+pub mod swish;
 pub mod tanh;
 
-pub type ActivationFn = fn(&Matrix) -> Matrix;
-pub type GradDepActivationFn = fn(&Matrix, &Matrix) -> Matrix;
+pub type ActivationFn = Arc<dyn Fn(&Matrix) -> Matrix + Send + Sync>;
+pub type GradDepActivationFn = Arc<dyn Fn(&Matrix, &Matrix) -> Matrix + Send + Sync>;
+/// Activation (or derivative) depending on a slope, e.g. `x -> max(x, 0) + slope * min(x, 0)`
+pub type SlopeActivationFn = fn(&Matrix, Scalar) -> Matrix;
+
+/// User-defined component-wise activation, usable in models as `Activation::Custom(name)`
+/// once registered with `register_activation`.
+pub trait ActivationFunction: Send + Sync {
+    fn activate(&self, m: &Matrix) -> Matrix;
+
+    /// Returns `f'(X)`, the gradient being computed as `∂E/∂X = ∂E/∂Y ⊙ f'(X)`.
+    fn derivative(&self, m: &Matrix) -> Matrix;
+}
+
+type ActivationRegistry = RwLock<HashMap<String, Arc<dyn ActivationFunction>>>;
+
+fn activation_registry() -> &'static ActivationRegistry {
+    static REGISTRY: OnceLock<ActivationRegistry> = OnceLock::new();
+    REGISTRY.get_or_init(|| RwLock::new(HashMap::new()))
+}
+
+/// Makes `activation` available as `Activation::Custom(name)`, replacing any activation previously
+/// registered under `name`.
+pub fn register_activation(name: &str, activation: impl ActivationFunction + 'static) {
+    activation_registry()
+        .write()
+        .unwrap()
+        .insert(name.to_string(), Arc::new(activation));
+}
+
+pub fn get_registered_activation(name: &str) -> Option<Arc<dyn ActivationFunction>> {
+    activation_registry().read().unwrap().get(name).cloned()
+}
+
+/// Name of an activation registered with `register_activation`, interned so that `Activation` stays `Copy`.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ActivationName(&'static str);
+
+impl ActivationName {
+    pub fn new(name: &str) -> Self {
+        static NAMES: OnceLock<Mutex<HashSet<&'static str>>> = OnceLock::new();
+        let mut names = NAMES.get_or_init(|| Mutex::new(HashSet::new())).lock().unwrap();
+        match names.get(name) {
+            Some(interned) => Self(interned),
+            None => {
+                let interned: &'static str = Box::leak(name.to_string().into_boxed_str());
+                names.insert(interned);
+                Self(interned)
+            }
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        self.0
+    }
+}
+
+impl From<&str> for ActivationName {
+    fn from(name: &str) -> Self {
+        Self::new(name)
+    }
+}
+
+impl fmt::Debug for ActivationName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl Serialize for ActivationName {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.0)
+    }
+}
+
+impl<'de> Deserialize<'de> for ActivationName {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(Self::new(&String::deserialize(deserializer)?))
+    }
+}
 
 pub enum ActivationFnPrime {
     ActivationFn(ActivationFn),
     GradDepActivationFn(GradDepActivationFn),
 }
 
+/// Slope of an activation learned alongside the layer's other parameters (e.g. PReLU).
+pub struct LearnableSlope {
+    pub slope: Scalar,
+    optimizer: Optimizers,
+    activation: SlopeActivationFn,
+    derivative: SlopeActivationFn,
+    // ∂Y/∂slope
+    slope_derivative: fn(&Matrix) -> Matrix,
+    // accumulated by backward, applied by step
+    gradient: Option<Matrix>,
+}
+
 pub struct ActivationLayer {
     // i inputs = i outputs (it's just a map)
-    input: Option<Matrix>,
-    output: Option<Matrix>,
+    tape: Tape<Matrix>,
+    input: Option<Var>,
+    output: Option<Var>,
     activation: ActivationFn,
     derivative: ActivationFnPrime,
+    learnable_slope: Option<LearnableSlope>,
+    // softmax's gradient may be fused with the loss' one
+    pub(crate) is_softmax: bool,
 }
 
 impl ActivationLayer {
-    pub fn new(activation: ActivationFn, derivative: ActivationFn) -> Self {
+    pub fn new(
+        activation: impl Fn(&Matrix) -> Matrix + Send + Sync + 'static,
+        derivative: impl Fn(&Matrix) -> Matrix + Send + Sync + 'static,
+    ) -> Self {
         Self {
+            tape: Tape::new(),
             input: None,
             output: None,
-            activation,
-            derivative: ActivationFnPrime::ActivationFn(derivative),
+            activation: Arc::new(activation),
+            derivative: ActivationFnPrime::ActivationFn(Arc::new(derivative)),
+            learnable_slope: None,
+            is_softmax: false,
         }
     }
 
-    pub fn new_grad_dep(activation: ActivationFn, derivative: GradDepActivationFn) -> Self {
+    pub fn new_grad_dep(
+        activation: impl Fn(&Matrix) -> Matrix + Send + Sync + 'static,
+        derivative: impl Fn(&Matrix, &Matrix) -> Matrix + Send + Sync + 'static,
+    ) -> Self {
         Self {
+            tape: Tape::new(),
             input: None,
             output: None,
+            activation: Arc::new(activation),
+            derivative: ActivationFnPrime::GradDepActivationFn(Arc::new(derivative)),
+            learnable_slope: None,
+            is_softmax: false,
+        }
+    }
+
+    pub fn from_activation_function(activation: Arc<dyn ActivationFunction>) -> Self {
+        let derivative = activation.clone();
+        Self::new(
+            move |m| activation.activate(m),
+            move |m| derivative.derivative(m),
+        )
+    }
+
+    /// Activation whose `slope` is trained by `optimizer` along with the layer's other parameters.
+    pub fn new_learnable_slope(
+        activation: SlopeActivationFn,
+        derivative: SlopeActivationFn,
+        slope_derivative: fn(&Matrix) -> Matrix,
+        slope: Scalar,
+        optimizer: Optimizers,
+    ) -> Self {
+        let mut layer = Self::new(|m| m.clone(), |m| m.clone());
+        layer.learnable_slope = Some(LearnableSlope {
+            slope,
+            optimizer,
             activation,
-            derivative: ActivationFnPrime::GradDepActivationFn(derivative),
+            derivative,
+            slope_derivative,
+            gradient: None,
+        });
+        layer.set_slope(slope);
+        layer
+    }
+
+    pub fn get_slope(&self) -> Option<Scalar> {
+        self.learnable_slope.as_ref().map(|s| s.slope)
+    }
+
+    /// Does nothing if the activation has no learnable slope.
+    pub fn set_slope(&mut self, slope: Scalar) {
+        if let Some(learnable_slope) = &mut self.learnable_slope {
+            let (activation, derivative) = (learnable_slope.activation, learnable_slope.derivative);
+            learnable_slope.slope = slope;
+            self.activation = Arc::new(move |m| activation(m, slope));
+            self.derivative = ActivationFnPrime::ActivationFn(Arc::new(move |m| derivative(m, slope)));
         }
     }
 }
 
 impl Layer for ActivationLayer {
     fn forward(&mut self, input: Matrix) -> Matrix {
-        self.input = Some(input.clone());
-        let output = (self.activation)(&input);
-        self.output = Some(output.clone());
-        output
+        self.tape.clear();
+        let input = self.tape.var(input);
+        let output = match &self.derivative {
+            // ∂E/∂X = ∂E/∂Y ⊙ f'(X)
+            ActivationFnPrime::ActivationFn(f) => {
+                let f = f.clone();
+                self.tape.map(input, &*self.activation, move |m| f(m))
+            }
+            ActivationFnPrime::GradDepActivationFn(f) => {
+                let f = f.clone();
+                self.tape
+                    .map_grad_dep(input, &*self.activation, move |m, g| f(m, g))
+            }
+        };
+        self.input = Some(input);
+        self.output = Some(output);
+        self.tape.value(output).clone()
     }
 
     fn backward(&mut self, _epoch: usize, output_gradient: Matrix) -> Matrix {
-        match self.derivative {
-            ActivationFnPrime::ActivationFn(f) => {
-                // ∂E/∂X = ∂E/∂Y ⊙ f'(X)
-                let input = self.input.clone().unwrap();
-                let fprime_x = (f)(&input);
-                output_gradient.component_mul(&fprime_x)
-            },
-            ActivationFnPrime::GradDepActivationFn(f) => {
-                let output = self.output.clone().unwrap();
-                (f)(&output, &output_gradient)
-            },
+        if let Some(learnable_slope) = &mut self.learnable_slope {
+            let x = self.tape.value(self.input.unwrap());
+            // ∂E/∂slope = Σ ∂E/∂Y ⊙ ∂Y/∂slope
+            let slope_gradient = output_gradient
+                .component_mul(&(learnable_slope.slope_derivative)(x))
+                .sum();
+            accumulate_gradient(
+                &mut learnable_slope.gradient,
+                Matrix::constant(1, 1, slope_gradient),
+            );
+        }
+
+        let mut grads = self
+            .tape
+            .backward(self.output.unwrap(), output_gradient);
+
+        grads.take(self.input.unwrap()).unwrap()
+    }
+
+    fn has_softmax_output(&self) -> bool {
+        self.is_softmax
+    }
+
+    fn backward_skipping_softmax(&mut self, epoch: usize, logits_gradient: Matrix) -> Matrix {
+        if self.is_softmax {
+            logits_gradient
+        } else {
+            self.backward(epoch, logits_gradient)
+        }
+    }
+}
+
+impl GradientLayer for ActivationLayer {
+    fn gradients_mut(&mut self) -> Vec<GradientMut<'_>> {
+        self.learnable_slope
+            .iter_mut()
+            .filter_map(|s| s.gradient.as_mut())
+            .map(GradientMut::Matrix)
+            .collect()
+    }
+
+    fn zero_grad(&mut self) {
+        if let Some(learnable_slope) = &mut self.learnable_slope {
+            learnable_slope.gradient = None;
+        }
+    }
+
+    fn step(&mut self, epoch: usize) {
+        let slope = self.learnable_slope.as_mut().and_then(|learnable_slope| {
+            let gradient = learnable_slope.gradient.take()?;
+            Some(
+                learnable_slope
+                    .optimizer
+                    .update_parameters(
+                        epoch,
+                        &Matrix::constant(1, 1, learnable_slope.slope),
+                        &gradient,
+                    )
+                    .index(0, 0),
+            )
+        });
+        if let Some(slope) = slope {
+            self.set_slope(slope);
+        }
+    }
+}
+
+impl LearnableLayer for ActivationLayer {
+    // returns a single column holding the slope, or nothing if the activation has no learnable slope
+    fn get_learnable_parameters(&self) -> Vec<Vec<Scalar>> {
+        match self.get_slope() {
+            Some(slope) => vec![vec![slope]],
+            None => vec![],
+        }
+    }
+
+    fn set_learnable_parameters(&mut self, params_matrix: &Vec<Vec<Scalar>>) {
+        if let Some(slope) = params_matrix.first() {
+            self.set_slope(slope[0]);
         }
     }
 }
@@ -77,17 +328,56 @@ pub enum Activation {
     Sigmoid,
     ReLU,
     Linear,
-    Softmax
+    Softmax,
+    /// Slope of the negative part
+    LeakyReLU(Scalar),
+    /// Saturation value of the negative part
+    ELU(Scalar),
+    SELU,
+    GELU,
+    #[serde(alias = "SiLU")]
+    Swish,
+    Softplus,
+    HardSigmoid,
+    Mish,
+    /// Initial slope of the negative part, which is then learned
+    PReLU(Scalar),
+    /// Activation registered with `register_activation` under this name
+    Custom(ActivationName),
 }
 
 impl Activation {
+    /// Activation registered with `register_activation` under `name`
+    pub fn custom(name: &str) -> Self {
+        Self::Custom(ActivationName::new(name))
+    }
+
     pub fn to_layer(&self) -> ActivationLayer {
+        self.to_layer_with_optimizer(default_biases_optimizer())
+    }
+
+    /// `optimizer` trains the activation's learnable parameters, if any (PReLU's slope).
+    pub fn to_layer_with_optimizer(&self, optimizer: Optimizers) -> ActivationLayer {
         match self {
             Self::Linear => linear::new(),
             Self::Tanh => tanh::new(),
             Self::Sigmoid => sigmoid::new(),
             Self::ReLU => relu::new(),
             Self::Softmax => softmax::new(),
+            Self::LeakyReLU(alpha) => leaky_relu::new(*alpha),
+            Self::ELU(alpha) => elu::new(*alpha),
+            Self::SELU => selu::new(),
+            Self::GELU => gelu::new(),
+            Self::Swish => swish::new(),
+            Self::Softplus => softplus::new(),
+            Self::HardSigmoid => hard_sigmoid::new(),
+            Self::Mish => mish::new(),
+            Self::PReLU(slope) => prelu::new(*slope, optimizer),
+            Self::Custom(name) => {
+                let activation = get_registered_activation(name.as_str())
+                    .unwrap_or_else(|| panic!("Activation {} is not registered", name.as_str()));
+                ActivationLayer::from_activation_function(activation)
+            }
         }
     }
 }
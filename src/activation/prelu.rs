@@ -0,0 +1,26 @@
+use super::{
+    leaky_relu::{leaky_relu, leaky_relu_prime},
+    ActivationLayer,
+};
+use crate::{
+    linalg::{Matrix, MatrixTrait, Scalar},
+    optimizer::Optimizers,
+};
+
+// https://arxiv.org/pdf/1502.01852.pdf
+
+/// ∂Y/∂slope
+pub fn prelu_slope_prime(m: &Matrix) -> Matrix {
+    m.minof(&Matrix::constant(m.dim().0, m.dim().1, 0.))
+}
+
+/// Leaky ReLU whose slope, shared by all the inputs, is trained by `optimizer`.
+pub fn new(slope: Scalar, optimizer: Optimizers) -> ActivationLayer {
+    ActivationLayer::new_learnable_slope(
+        leaky_relu,
+        leaky_relu_prime,
+        prelu_slope_prime,
+        slope,
+        optimizer,
+    )
+}
@@ -0,0 +1,22 @@
+use super::{
+    elu::{elu, elu_prime},
+    ActivationLayer,
+};
+use crate::linalg::{Matrix, MatrixTrait, Scalar};
+
+// https://arxiv.org/pdf/1706.02515.pdf
+
+pub const SELU_ALPHA: Scalar = 1.673_263_2;
+pub const SELU_SCALE: Scalar = 1.050_701;
+
+pub fn selu(m: &Matrix) -> Matrix {
+    elu(m, SELU_ALPHA).scalar_mul(SELU_SCALE)
+}
+
+pub fn selu_prime(m: &Matrix) -> Matrix {
+    elu_prime(m, SELU_ALPHA).scalar_mul(SELU_SCALE)
+}
+
+pub fn new() -> ActivationLayer {
+    ActivationLayer::new(selu, selu_prime)
+}
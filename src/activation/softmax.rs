@@ -13,7 +13,7 @@ fn stablesoftmax_col(col: &Matrix) -> Matrix {
     exps.scalar_div(sum)
 }
 
-fn stablesoftmax(m: &Matrix) -> Matrix {
+pub fn stablesoftmax(m: &Matrix) -> Matrix {
     let ncol = m.dim().1;
     let mut columns: Vec<Matrix> = Vec::with_capacity(ncol);
 
@@ -62,7 +62,7 @@ fn softmax_prime_col(col: &Matrix, output_gradient: &Matrix) -> Matrix {
     result
 }
 
-fn softmax_prime(m: &Matrix, output_gradient: &Matrix) -> Matrix {
+pub fn softmax_prime(m: &Matrix, output_gradient: &Matrix) -> Matrix {
     let ncol = m.dim().1;
     let mut columns: Vec<Matrix> = Vec::with_capacity(ncol);
 
@@ -103,5 +103,7 @@ fn softmax_prime(m: &Matrix, output_gradient: &Matrix) -> Matrix {
 }
 
 pub fn new() -> ActivationLayer {
-    ActivationLayer::new_grad_dep(stablesoftmax, softmax_prime)
+    let mut layer = ActivationLayer::new_grad_dep(stablesoftmax, softmax_prime);
+    layer.is_softmax = true;
+    layer
 }
@@ -0,0 +1,18 @@
+use super::{sigmoid::sigmoid, ActivationLayer};
+use crate::linalg::{Matrix, MatrixTrait};
+
+pub fn softplus(m: &Matrix) -> Matrix {
+    // max(x, 0) + ln(1 + e^-|x|) doesn't overflow for large inputs
+    let zeros = Matrix::constant(m.dim().0, m.dim().1, 0.);
+    let abs = m.component_mul(&m.sign());
+    m.maxof(&zeros)
+        .component_add(&abs.scalar_mul(-1.).exp().scalar_add(1.).log())
+}
+
+pub fn softplus_prime(m: &Matrix) -> Matrix {
+    sigmoid(m)
+}
+
+pub fn new() -> ActivationLayer {
+    ActivationLayer::new(softplus, softplus_prime)
+}
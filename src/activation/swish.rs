@@ -0,0 +1,18 @@
+use super::{sigmoid::sigmoid, ActivationLayer};
+use crate::linalg::{Matrix, MatrixTrait};
+
+// also known as SiLU: https://arxiv.org/pdf/1710.05941.pdf
+
+pub fn swish(m: &Matrix) -> Matrix {
+    m.component_mul(&sigmoid(m))
+}
+
+pub fn swish_prime(m: &Matrix) -> Matrix {
+    let sig = sigmoid(m);
+    let sig_prime = sig.component_mul(&sig.scalar_mul(-1.).scalar_add(1.));
+    sig.component_add(&m.component_mul(&sig_prime))
+}
+
+pub fn new() -> ActivationLayer {
+    ActivationLayer::new(swish, swish_prime)
+}
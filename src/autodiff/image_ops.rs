@@ -0,0 +1,269 @@
+use crate::{
+    linalg::Scalar,
+    vision::image::{Image, ImageTrait},
+};
+
+use super::{Differentiable, Tape, Var};
+
+pub type ImageFn = fn(&Image) -> Image;
+
+impl Differentiable for Image {
+    fn accumulate(&self, other: &Self) -> Self {
+        self.component_add(other)
+    }
+}
+
+/// Component-wise image operations broadcast `other`'s first sample when it has less samples,
+/// so its gradient is the sum of the gradients of all samples.
+fn unbroadcast(grad: Image, like_samples: usize) -> Image {
+    if like_samples < grad.samples() {
+        grad.sum_samples()
+    } else {
+        grad
+    }
+}
+
+impl Tape<Image> {
+    pub fn add(&mut self, a: Var, b: Var) -> Var {
+        let value = self.value(a).component_add(self.value(b));
+        let b_samples = self.value(b).samples();
+        let req = self.requires_grads(&[a, b]);
+        self.record(
+            value,
+            &[a, b],
+            Box::new(move |g| {
+                vec![
+                    req[0].then(|| g.clone()),
+                    req[1].then(|| unbroadcast(g.clone(), b_samples)),
+                ]
+            }),
+        )
+    }
+
+    pub fn sub(&mut self, a: Var, b: Var) -> Var {
+        let value = self.value(a).component_sub(self.value(b));
+        let b_samples = self.value(b).samples();
+        let req = self.requires_grads(&[a, b]);
+        self.record(
+            value,
+            &[a, b],
+            Box::new(move |g| {
+                vec![
+                    req[0].then(|| g.clone()),
+                    req[1].then(|| unbroadcast(g.scalar_mul(-1.), b_samples)),
+                ]
+            }),
+        )
+    }
+
+    /// Component-wise multiplication.
+    pub fn mul(&mut self, a: Var, b: Var) -> Var {
+        let (va, vb) = (self.value(a).clone(), self.value(b).clone());
+        let value = va.component_mul(&vb);
+        let req = self.requires_grads(&[a, b]);
+        self.record(
+            value,
+            &[a, b],
+            Box::new(move |g| {
+                vec![
+                    req[0].then(|| g.component_mul(&vb)),
+                    req[1].then(|| unbroadcast(g.component_mul(&va), vb.samples())),
+                ]
+            }),
+        )
+    }
+
+    /// Component-wise division.
+    pub fn div(&mut self, a: Var, b: Var) -> Var {
+        let (va, vb) = (self.value(a).clone(), self.value(b).clone());
+        let value = va.component_div(&vb);
+        let req = self.requires_grads(&[a, b]);
+        self.record(
+            value,
+            &[a, b],
+            Box::new(move |g| {
+                vec![
+                    req[0].then(|| g.component_div(&vb)),
+                    req[1].then(|| {
+                        unbroadcast(
+                            g.component_mul(&va)
+                                .component_div(&vb.square())
+                                .scalar_mul(-1.),
+                            vb.samples(),
+                        )
+                    }),
+                ]
+            }),
+        )
+    }
+
+    pub fn scalar_add(&mut self, a: Var, scalar: Scalar) -> Var {
+        let value = self.value(a).scalar_add(scalar);
+        self.record(value, &[a], Box::new(|g| vec![Some(g.clone())]))
+    }
+
+    pub fn scalar_sub(&mut self, a: Var, scalar: Scalar) -> Var {
+        let value = self.value(a).scalar_sub(scalar);
+        self.record(value, &[a], Box::new(|g| vec![Some(g.clone())]))
+    }
+
+    pub fn scalar_mul(&mut self, a: Var, scalar: Scalar) -> Var {
+        let value = self.value(a).scalar_mul(scalar);
+        self.record(
+            value,
+            &[a],
+            Box::new(move |g| vec![Some(g.scalar_mul(scalar))]),
+        )
+    }
+
+    pub fn scalar_div(&mut self, a: Var, scalar: Scalar) -> Var {
+        let value = self.value(a).scalar_div(scalar);
+        self.record(
+            value,
+            &[a],
+            Box::new(move |g| vec![Some(g.scalar_div(scalar))]),
+        )
+    }
+
+    pub fn exp(&mut self, a: Var) -> Var {
+        let value = self.value(a).exp();
+        let y = value.clone();
+        self.record(value, &[a], Box::new(move |g| vec![Some(g.component_mul(&y))]))
+    }
+
+    pub fn sqrt(&mut self, a: Var) -> Var {
+        let value = self.value(a).sqrt();
+        let y = value.clone();
+        self.record(
+            value,
+            &[a],
+            Box::new(move |g| vec![Some(g.component_div(&y.scalar_mul(2.)))]),
+        )
+    }
+
+    pub fn square(&mut self, a: Var) -> Var {
+        let x = self.value(a).clone();
+        let value = x.square();
+        self.record(
+            value,
+            &[a],
+            Box::new(move |g| vec![Some(g.component_mul(&x.scalar_mul(2.)))]),
+        )
+    }
+
+    pub fn sum_samples(&mut self, a: Var) -> Var {
+        let samples = self.value(a).samples();
+        let value = self.value(a).sum_samples();
+        self.record(
+            value,
+            &[a],
+            Box::new(move |g| vec![Some(g.tile(1, 1, 1, samples))]),
+        )
+    }
+
+    pub fn get_channel_across_samples(&mut self, a: Var, channel: usize) -> Var {
+        let x = self.value(a);
+        let (nrow, ncol, nchan) = x.image_dims();
+        let samples = x.samples();
+        let value = x.get_channel_across_samples(channel);
+        self.record(
+            value,
+            &[a],
+            Box::new(move |g| {
+                let channels = (0..nchan)
+                    .map(|c| {
+                        if c == channel {
+                            g.clone()
+                        } else {
+                            Image::zeros(nrow, ncol, 1, samples)
+                        }
+                    })
+                    .collect();
+                vec![Some(Image::join_channels(channels))]
+            }),
+        )
+    }
+
+    pub fn join_channels(&mut self, channels: &[Var]) -> Var {
+        let values = channels.iter().map(|c| self.value(*c).clone()).collect();
+        let value = Image::join_channels(values);
+        let n = channels.len();
+        self.record(
+            value,
+            channels,
+            Box::new(move |g| {
+                (0..n)
+                    .map(|c| Some(g.get_channel_across_samples(c)))
+                    .collect()
+            }),
+        )
+    }
+
+    /// Cross-correlates the `(r, c, C, N)` input with `(kr, kc, C, K)` kernels,
+    /// giving a `(r - kr + 1, c - kc + 1, K, N)` output.
+    pub fn cross_correlate(&mut self, input: Var, kernels: Var) -> Var {
+        let (x, k) = (self.value(input).clone(), self.value(kernels).clone());
+        let value = x.cross_correlate(&k);
+        let req = self.requires_grads(&[input, kernels]);
+        self.record(
+            value,
+            &[input, kernels],
+            Box::new(move |g| {
+                let input_grad = req[0].then(|| {
+                    let mut channels = vec![];
+                    for i in 0..x.channels() {
+                        let (nrow, ncol, _) = x.image_dims();
+                        let mut sum = Image::zeros(nrow, ncol, 1, x.samples());
+                        for kern in 0..k.samples() {
+                            let kernel = k.get_sample(kern).get_channel(i);
+                            let grad_k = g.get_channel_across_samples(kern);
+                            sum = sum.component_add(&grad_k.convolve_full(&kernel));
+                        }
+                        channels.push(sum);
+                    }
+                    Image::join_channels(channels)
+                });
+
+                let kernels_grad = req[1].then(|| {
+                    let mut kernels = vec![];
+                    for kern in 0..k.samples() {
+                        let mut channels = vec![];
+                        for i in 0..k.channels() {
+                            let mut sum: Option<Image> = None;
+                            for s in 0..x.samples() {
+                                let input_i = x.get_sample(s).get_channel(i);
+                                let grad_k = g.get_sample(s).get_channel(kern);
+                                let correlated = input_i.cross_correlate(&grad_k);
+                                sum = Some(match sum {
+                                    Some(sum) => sum.component_add(&correlated),
+                                    None => correlated,
+                                });
+                            }
+                            channels.push(sum.unwrap());
+                        }
+                        kernels.push(Image::join_channels(channels));
+                    }
+                    Image::join_samples(kernels)
+                });
+
+                vec![input_grad, kernels_grad]
+            }),
+        )
+    }
+
+    /// Applies `f` component-wise, `derivative` being its derivative (`∂E/∂X = ∂E/∂Y ⊙ f'(X)`).
+    pub fn map(
+        &mut self,
+        a: Var,
+        f: impl Fn(&Image) -> Image,
+        derivative: impl Fn(&Image) -> Image + Send + 'static,
+    ) -> Var {
+        let x = self.value(a).clone();
+        let value = f(&x);
+        self.record(
+            value,
+            &[a],
+            Box::new(move |g| vec![Some(g.component_mul(&derivative(&x)))]),
+        )
+    }
+}
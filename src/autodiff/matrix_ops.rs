@@ -0,0 +1,328 @@
+use crate::linalg::{Matrix, MatrixTrait, Scalar};
+
+use super::{Differentiable, Tape, Var};
+
+pub type MatrixFn = fn(&Matrix) -> Matrix;
+pub type GradDepMatrixFn = fn(&Matrix, &Matrix) -> Matrix;
+
+impl Differentiable for Matrix {
+    fn accumulate(&self, other: &Self) -> Self {
+        self.component_add(other)
+    }
+}
+
+impl Tape<Matrix> {
+    pub fn add(&mut self, a: Var, b: Var) -> Var {
+        let value = self.value(a).component_add(self.value(b));
+        let req = self.requires_grads(&[a, b]);
+        self.record(
+            value,
+            &[a, b],
+            Box::new(move |g| {
+                vec![
+                    req[0].then(|| g.clone()),
+                    req[1].then(|| g.clone()),
+                ]
+            }),
+        )
+    }
+
+    pub fn sub(&mut self, a: Var, b: Var) -> Var {
+        let value = self.value(a).component_sub(self.value(b));
+        let req = self.requires_grads(&[a, b]);
+        self.record(
+            value,
+            &[a, b],
+            Box::new(move |g| {
+                vec![
+                    req[0].then(|| g.clone()),
+                    req[1].then(|| g.scalar_mul(-1.)),
+                ]
+            }),
+        )
+    }
+
+    /// Component-wise multiplication.
+    pub fn mul(&mut self, a: Var, b: Var) -> Var {
+        let (va, vb) = (self.value(a).clone(), self.value(b).clone());
+        let value = va.component_mul(&vb);
+        let req = self.requires_grads(&[a, b]);
+        self.record(
+            value,
+            &[a, b],
+            Box::new(move |g| {
+                vec![
+                    req[0].then(|| g.component_mul(&vb)),
+                    req[1].then(|| g.component_mul(&va)),
+                ]
+            }),
+        )
+    }
+
+    /// Component-wise division.
+    pub fn div(&mut self, a: Var, b: Var) -> Var {
+        let (va, vb) = (self.value(a).clone(), self.value(b).clone());
+        let value = va.component_div(&vb);
+        let req = self.requires_grads(&[a, b]);
+        self.record(
+            value,
+            &[a, b],
+            Box::new(move |g| {
+                vec![
+                    req[0].then(|| g.component_div(&vb)),
+                    req[1].then(|| {
+                        g.component_mul(&va)
+                            .component_div(&vb.square())
+                            .scalar_mul(-1.)
+                    }),
+                ]
+            }),
+        )
+    }
+
+    pub fn scalar_add(&mut self, a: Var, scalar: Scalar) -> Var {
+        let value = self.value(a).scalar_add(scalar);
+        self.record(value, &[a], Box::new(|g| vec![Some(g.clone())]))
+    }
+
+    pub fn scalar_sub(&mut self, a: Var, scalar: Scalar) -> Var {
+        let value = self.value(a).scalar_sub(scalar);
+        self.record(value, &[a], Box::new(|g| vec![Some(g.clone())]))
+    }
+
+    pub fn scalar_mul(&mut self, a: Var, scalar: Scalar) -> Var {
+        let value = self.value(a).scalar_mul(scalar);
+        self.record(
+            value,
+            &[a],
+            Box::new(move |g| vec![Some(g.scalar_mul(scalar))]),
+        )
+    }
+
+    pub fn scalar_div(&mut self, a: Var, scalar: Scalar) -> Var {
+        let value = self.value(a).scalar_div(scalar);
+        self.record(
+            value,
+            &[a],
+            Box::new(move |g| vec![Some(g.scalar_div(scalar))]),
+        )
+    }
+
+    pub fn exp(&mut self, a: Var) -> Var {
+        let value = self.value(a).exp();
+        let y = value.clone();
+        self.record(value, &[a], Box::new(move |g| vec![Some(g.component_mul(&y))]))
+    }
+
+    pub fn log(&mut self, a: Var) -> Var {
+        let x = self.value(a).clone();
+        let value = x.log();
+        self.record(value, &[a], Box::new(move |g| vec![Some(g.component_div(&x))]))
+    }
+
+    pub fn sqrt(&mut self, a: Var) -> Var {
+        let value = self.value(a).sqrt();
+        let y = value.clone();
+        self.record(
+            value,
+            &[a],
+            Box::new(move |g| vec![Some(g.component_div(&y.scalar_mul(2.)))]),
+        )
+    }
+
+    pub fn square(&mut self, a: Var) -> Var {
+        let x = self.value(a).clone();
+        let value = x.square();
+        self.record(
+            value,
+            &[a],
+            Box::new(move |g| vec![Some(g.component_mul(&x.scalar_mul(2.)))]),
+        )
+    }
+
+    /// Matrix product `a . b`.
+    pub fn dot(&mut self, a: Var, b: Var) -> Var {
+        let (va, vb) = (self.value(a).clone(), self.value(b).clone());
+        let value = va.dot(&vb);
+        let req = self.requires_grads(&[a, b]);
+        self.record(
+            value,
+            &[a, b],
+            Box::new(move |g| {
+                vec![
+                    req[0].then(|| g.dot(&vb.transpose())),
+                    req[1].then(|| va.transpose().dot(g)),
+                ]
+            }),
+        )
+    }
+
+    pub fn transpose(&mut self, a: Var) -> Var {
+        let value = self.value(a).transpose();
+        self.record(value, &[a], Box::new(|g| vec![Some(g.transpose())]))
+    }
+
+    /// Rows `start..start + count` of `a`.
+    pub fn slice_rows(&mut self, a: Var, start: usize, count: usize) -> Var {
+        let (nrow, ncol) = self.value(a).dim();
+        let rows = self.value(a).get_data_row_leading();
+        let value = Matrix::from_row_leading_vector2(&rows[start..start + count].to_vec());
+        self.record(
+            value,
+            &[a],
+            Box::new(move |g| {
+                let mut rows = vec![vec![0.; ncol]; nrow];
+                for (i, row) in g.get_data_row_leading().into_iter().enumerate() {
+                    rows[start + i] = row;
+                }
+                vec![Some(Matrix::from_row_leading_vector2(&rows))]
+            }),
+        )
+    }
+
+    /// Columns `start..start + count` of `a`.
+    pub fn slice_columns(&mut self, a: Var, start: usize, count: usize) -> Var {
+        let (nrow, ncol) = self.value(a).dim();
+        let columns = self.value(a).get_data_col_leading();
+        let value = Matrix::from_column_leading_vector2(&columns[start..start + count].to_vec());
+        self.record(
+            value,
+            &[a],
+            Box::new(move |g| {
+                let mut columns = vec![vec![0.; nrow]; ncol];
+                for (j, column) in g.get_data_col_leading().into_iter().enumerate() {
+                    columns[start + j] = column;
+                }
+                vec![Some(Matrix::from_column_leading_vector2(&columns))]
+            }),
+        )
+    }
+
+    /// Stacks the rows of all the `parts` on top of each other.
+    pub fn concat_rows(&mut self, parts: &[Var]) -> Var {
+        let mut rows = vec![];
+        let mut sizes = vec![];
+        for part in parts.iter() {
+            let mut part_rows = self.value(*part).get_data_row_leading();
+            sizes.push(part_rows.len());
+            rows.append(&mut part_rows);
+        }
+        let value = Matrix::from_row_leading_vector2(&rows);
+        let req = self.requires_grads(parts);
+        self.record(
+            value,
+            parts,
+            Box::new(move |g| {
+                let rows = g.get_data_row_leading();
+                let mut start = 0;
+                sizes
+                    .iter()
+                    .zip(req.iter())
+                    .map(|(size, req)| {
+                        let part = rows[start..start + size].to_vec();
+                        start += size;
+                        req.then(|| Matrix::from_row_leading_vector2(&part))
+                    })
+                    .collect()
+            }),
+        )
+    }
+
+    /// Puts the columns of all the `parts` next to each other.
+    pub fn concat_columns(&mut self, parts: &[Var]) -> Var {
+        let mut columns = vec![];
+        let mut sizes = vec![];
+        for part in parts.iter() {
+            let mut part_columns = self.value(*part).get_data_col_leading();
+            sizes.push(part_columns.len());
+            columns.append(&mut part_columns);
+        }
+        let value = Matrix::from_column_leading_vector2(&columns);
+        let req = self.requires_grads(parts);
+        self.record(
+            value,
+            parts,
+            Box::new(move |g| {
+                let columns = g.get_data_col_leading();
+                let mut start = 0;
+                sizes
+                    .iter()
+                    .zip(req.iter())
+                    .map(|(size, req)| {
+                        let part = columns[start..start + size].to_vec();
+                        start += size;
+                        req.then(|| Matrix::from_column_leading_vector2(&part))
+                    })
+                    .collect()
+            }),
+        )
+    }
+
+    /// Sums the columns of `a` into a single column.
+    pub fn columns_sum(&mut self, a: Var) -> Var {
+        let ncol = self.value(a).dim().1;
+        let value = self.value(a).columns_sum();
+        self.record(
+            value,
+            &[a],
+            Box::new(move |g| vec![Some(g.dot(&Matrix::constant(1, ncol, 1.0)))]),
+        )
+    }
+
+    /// Sums all the components of `a` into a `1x1` matrix.
+    pub fn sum(&mut self, a: Var) -> Var {
+        let (nrow, ncol) = self.value(a).dim();
+        let value = Matrix::constant(1, 1, self.value(a).sum());
+        self.record(
+            value,
+            &[a],
+            Box::new(move |g| vec![Some(Matrix::constant(nrow, ncol, g.sum()))]),
+        )
+    }
+
+    /// Averages all the components of `a` into a `1x1` matrix.
+    pub fn mean(&mut self, a: Var) -> Var {
+        let (nrow, ncol) = self.value(a).dim();
+        let value = Matrix::constant(1, 1, self.value(a).mean());
+        self.record(
+            value,
+            &[a],
+            Box::new(move |g| {
+                vec![Some(Matrix::constant(
+                    nrow,
+                    ncol,
+                    g.sum() / (nrow * ncol) as Scalar,
+                ))]
+            }),
+        )
+    }
+
+    /// Applies `f` component-wise, `derivative` being its derivative (`∂E/∂X = ∂E/∂Y ⊙ f'(X)`).
+    pub fn map(
+        &mut self,
+        a: Var,
+        f: impl Fn(&Matrix) -> Matrix,
+        derivative: impl Fn(&Matrix) -> Matrix + Send + 'static,
+    ) -> Var {
+        let x = self.value(a).clone();
+        let value = f(&x);
+        self.record(
+            value,
+            &[a],
+            Box::new(move |g| vec![Some(g.component_mul(&derivative(&x)))]),
+        )
+    }
+
+    /// Applies `f` to `a`, `derivative` computing `∂E/∂X` from `f`'s output and `∂E/∂Y`
+    /// (useful when the jacobian is not diagonal, as with softmax).
+    pub fn map_grad_dep(
+        &mut self,
+        a: Var,
+        f: impl Fn(&Matrix) -> Matrix,
+        derivative: impl Fn(&Matrix, &Matrix) -> Matrix + Send + 'static,
+    ) -> Var {
+        let value = f(self.value(a));
+        let y = value.clone();
+        self.record(value, &[a], Box::new(move |g| vec![Some(derivative(&y, g))]))
+    }
+}
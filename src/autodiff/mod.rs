@@ -0,0 +1,188 @@
+use std::fmt;
+
+pub mod image_ops;
+pub mod matrix_ops;
+
+/// A value that can flow through a `Tape` (a `Matrix` or an `Image`).
+pub trait Differentiable: Clone + Send + 'static {
+    /// Sums two gradients flowing into the same variable.
+    fn accumulate(&self, other: &Self) -> Self;
+}
+
+/// Given the gradient of an operation's output, returns the gradient of each of its parents.
+///
+/// Parents that don't require a gradient may be given `None`.
+pub type BackwardFn<T> = Box<dyn Fn(&T) -> Vec<Option<T>> + Send>;
+
+/// Handle to a value recorded on a `Tape`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Var(usize);
+
+struct Node<T> {
+    value: T,
+    parents: Vec<Var>,
+    requires_grad: bool,
+    backward: Option<BackwardFn<T>>,
+}
+
+/// Records the operations of a forward pass so that their gradients can be computed
+/// automatically in reverse order (reverse-mode automatic differentiation).
+///
+/// ```rust
+/// # use jiro_nn::{autodiff::Tape, linalg::{Matrix, MatrixTrait}};
+/// # let (input, weights) = (Matrix::constant(3, 4, 1.0), Matrix::constant(2, 3, 0.5));
+/// # let output_gradient = Matrix::constant(2, 4, 1.0);
+/// let mut tape = Tape::new();
+/// let x = tape.var(input);
+/// let w = tape.var(weights);
+/// let y = tape.dot(w, x);
+///
+/// let mut grads = tape.backward(y, output_gradient);
+/// let weights_gradient = grads.take(w).unwrap();
+/// let input_gradient = grads.take(x).unwrap();
+/// # assert_eq!(weights_gradient.dim(), (2, 3));
+/// # assert_eq!(input_gradient.dim(), (3, 4));
+/// ```
+pub struct Tape<T: Differentiable> {
+    nodes: Vec<Node<T>>,
+}
+
+impl<T: Differentiable> Tape<T> {
+    pub fn new() -> Self {
+        Self { nodes: Vec::new() }
+    }
+
+    /// Forgets all the recorded operations. Previously returned `Var`s become invalid.
+    pub fn clear(&mut self) {
+        self.nodes.clear();
+    }
+
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// Records a leaf value whose gradient will be computed.
+    pub fn var(&mut self, value: T) -> Var {
+        self.push(Node {
+            value,
+            parents: vec![],
+            requires_grad: true,
+            backward: None,
+        })
+    }
+
+    /// Records a leaf value whose gradient is not needed.
+    pub fn constant(&mut self, value: T) -> Var {
+        self.push(Node {
+            value,
+            parents: vec![],
+            requires_grad: false,
+            backward: None,
+        })
+    }
+
+    pub fn value(&self, var: Var) -> &T {
+        &self.nodes[var.0].value
+    }
+
+    pub fn requires_grad(&self, var: Var) -> bool {
+        self.nodes[var.0].requires_grad
+    }
+
+    /// Records the result of a custom operation.
+    ///
+    /// `backward` must return one gradient per parent, in the same order as `parents`.
+    pub fn record(&mut self, value: T, parents: &[Var], backward: BackwardFn<T>) -> Var {
+        let requires_grad = parents.iter().any(|p| self.requires_grad(*p));
+        self.push(Node {
+            value,
+            parents: parents.to_vec(),
+            requires_grad,
+            backward: if requires_grad { Some(backward) } else { None },
+        })
+    }
+
+    /// Propagates `output_gradient` from `output` back to every leaf it depends on.
+    ///
+    /// Returns the gradients of the leaves created with `var`.
+    pub fn backward(&self, output: Var, output_gradient: T) -> Gradients<T> {
+        self.backward_many(vec![(output, output_gradient)])
+    }
+
+    /// Propagates the gradients of several outputs at once (e.g. every time step of a sequence).
+    ///
+    /// Returns the gradients of the leaves created with `var`.
+    pub fn backward_many(&self, outputs: Vec<(Var, T)>) -> Gradients<T> {
+        let mut grads: Vec<Option<T>> = (0..self.nodes.len()).map(|_| None).collect();
+        let mut last = 0;
+        for (output, output_gradient) in outputs {
+            last = last.max(output.0);
+            grads[output.0] = match grads[output.0].take() {
+                Some(acc) => Some(acc.accumulate(&output_gradient)),
+                None => Some(output_gradient),
+            };
+        }
+
+        for i in (0..=last).rev() {
+            let node = &self.nodes[i];
+            let backward = match &node.backward {
+                Some(backward) => backward,
+                None => continue,
+            };
+            // intermediate gradients are not kept once propagated
+            let grad = match grads[i].take() {
+                Some(grad) => grad,
+                None => continue,
+            };
+
+            for (parent, parent_grad) in node.parents.iter().zip(backward(&grad)) {
+                if let Some(parent_grad) = parent_grad {
+                    grads[parent.0] = match grads[parent.0].take() {
+                        Some(acc) => Some(acc.accumulate(&parent_grad)),
+                        None => Some(parent_grad),
+                    };
+                }
+            }
+        }
+
+        Gradients(grads)
+    }
+
+    pub(crate) fn requires_grads(&self, vars: &[Var]) -> Vec<bool> {
+        vars.iter().map(|v| self.requires_grad(*v)).collect()
+    }
+
+    fn push(&mut self, node: Node<T>) -> Var {
+        self.nodes.push(node);
+        Var(self.nodes.len() - 1)
+    }
+}
+
+impl<T: Differentiable> Default for Tape<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Differentiable> fmt::Debug for Tape<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Tape ({} nodes)", self.nodes.len())
+    }
+}
+
+/// Gradients computed by `Tape::backward`.
+pub struct Gradients<T>(Vec<Option<T>>);
+
+impl<T> Gradients<T> {
+    pub fn get(&self, var: Var) -> Option<&T> {
+        self.0[var.0].as_ref()
+    }
+
+    pub fn take(&mut self, var: Var) -> Option<T> {
+        self.0[var.0].take()
+    }
+}
@@ -106,7 +106,11 @@ impl Dataset {
     pub fn in_features_names(&self) -> Vec<&str> {
         let mut names = Vec::new();
         for feature in &self.features {
-            if !feature.predicted && !feature.is_id && !feature.date_format.is_some() {
+            if !feature.predicted
+                && !feature.is_id
+                && !feature.sample_weight
+                && !feature.date_format.is_some()
+            {
                 names.push(feature.name.as_str());
             }
         }
@@ -204,6 +208,25 @@ impl Dataset {
         dataset
     }
 
+    /// Returns the `(input index, categories count, embedding dimension)` of the embedded input features.
+    pub fn embedded_inputs(&self) -> Vec<(usize, usize, usize)> {
+        let mut embedded_inputs = Vec::new();
+        let in_features_names = self.in_features_names();
+        for (i, name) in in_features_names.iter().enumerate() {
+            let feature = self.features.iter().find(|f| f.name == *name).unwrap();
+            if let Some(dim) = feature.embedded {
+                let categories_count = feature.categories_count.unwrap_or_else(|| {
+                    panic!(
+                        "Feature {} is embedded but its categories count is unknown, run it through a Pipeline with CategoryIds first",
+                        feature.name
+                    )
+                });
+                embedded_inputs.push((i, categories_count, dim));
+            }
+        }
+        embedded_inputs
+    }
+
     pub fn get_id_column(&self) -> Option<&str> {
         for feature in &self.features {
             if feature.is_id {
@@ -212,6 +235,16 @@ impl Dataset {
         }
         None
     }
+
+    /// Returns the name of the feature holding the weight of each sample in the loss, if any.
+    pub fn get_sample_weight_column(&self) -> Option<&str> {
+        for feature in &self.features {
+            if feature.sample_weight {
+                return Some(feature.name.as_str());
+            }
+        }
+        None
+    }
 }
 
 /// A structure that holds metadata of a _feature_ (aka. a "column") of a data table.
@@ -246,6 +279,15 @@ pub struct Feature {
     pub one_hot_encoded: bool,
     #[serde(default)]
     pub is_id: bool,
+    /// Whether the feature holds the weight of each sample in the loss instead of being an input
+    #[serde(default)]
+    pub sample_weight: bool,
+    /// Dimension of the vectors the feature's categories are embedded into
+    #[serde(default)]
+    pub embedded: Option<usize>,
+    /// Number of categories of an embedded feature, set by the pipeline
+    #[serde(default)]
+    pub categories_count: Option<usize>,
 }
 
 impl Feature {
@@ -291,6 +333,7 @@ impl Feature {
 /// - `UsedInModel`: Disables the pruning of the feature at the end of the pipeline. Features are all not pruned by default.
 /// - `Predicted`: Sets the feature as a _predicted feature_. Features are all not _predicted features_ by default.
 /// - `IsId`: Identifies the feature as an id. Features are all not ids by default.
+/// - `SampleWeight`: Uses the feature as the weight of each sample in the training and validation losses instead of as an input.
 /// - `DateFormat`: The date format to use for date/time features.
 ///
 /// **Feature replacement/mapping tags**:
@@ -298,6 +341,7 @@ impl Feature {
 /// - `ToTimestamp`: Enables conversion of the date/time feature to a Unix timestamp. Requires the feature to have a `DateFormat` configurationified.
 /// - `ExtractMonth`: Enables conversion of the date/time to its month. Requires the feature to have a `DateFormat` configurationified.
 /// - `OneHotEncode`: Enables one-hot encoding of the feature.
+/// - `Embedded`: Replaces the feature's categories with integer ids, embedded by the network into vectors of the given dimension.
 /// - `Log10`: Enables applying base-10 logarithm to the feature.
 /// - `Normalized`: Enables normalizing the feature.
 /// - `FilterOutliers`: Enables filtering outliers from the feature.
@@ -338,6 +382,8 @@ pub enum FeatureTags<'a> {
     DateFormat(&'a str),
     /// The `OneHotEncod` tag enables conversion to one-hot encoding of the feature.
     OneHotEncode,
+    /// The `Embedded` tag replaces the categories of the feature with integer ids, which the network embeds into learned vectors of the given dimension.
+    Embedded(usize),
     /// The `ToTimestamp` tag enables conversion of the date/time feature to a Unix timestamp.
     ToTimestamp,
     /// The `ExtractMonth` tag enables conversion of the date/time to its month.
@@ -354,6 +400,8 @@ pub enum FeatureTags<'a> {
     UsedInModel,
     /// The `IsId` tag identifies the feature as an id.
     IsId,
+    /// The `SampleWeight` tag uses the feature as the weight of each sample in the loss.
+    SampleWeight,
     /// The `AddExtractedMonth` tag enables the extracted month feature extraction from that feature.
     AddExtractedMonth,
     /// The `AddExtractedTimestamp` tag enables the extracted Unix timestamp feature extraction from that feature.
@@ -421,8 +469,10 @@ impl<'a> FeatureTags<'a> {
             FeatureTags::FilterOutliers => feature.filter_outliers = value,
             FeatureTags::Squared => feature.squared = value,
             FeatureTags::OneHotEncode => feature.one_hot_encoded = value,
+            FeatureTags::Embedded(dim) => feature.embedded = if value { Some(*dim) } else { None },
             FeatureTags::UsedInModel => feature.used_in_model = value,
             FeatureTags::IsId => feature.is_id = value,
+            FeatureTags::SampleWeight => feature.sample_weight = value,
             FeatureTags::AddFeatureExtractedMonth(with_extracted_month) => {
                 feature.with_extracted_month =
                     Some(Box::new(Feature::from_tags(with_extracted_month)))
@@ -0,0 +1,135 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    linalg::{Matrix, MatrixTrait, Scalar},
+    vision::image::{Image, ImageTrait},
+};
+
+/// Gradient of one of a layer's parameters, computed by `backward` and not applied yet.
+pub enum GradientMut<'a> {
+    Matrix(&'a mut Matrix),
+    Image(&'a mut Image),
+}
+
+impl GradientMut<'_> {
+    pub fn squared_norm(&self) -> Scalar {
+        match self {
+            GradientMut::Matrix(m) => m.square().sum(),
+            GradientMut::Image(i) => i.square().sum(),
+        }
+    }
+
+    pub fn scale(&mut self, factor: Scalar) {
+        match self {
+            GradientMut::Matrix(m) => **m = m.scalar_mul(factor),
+            GradientMut::Image(i) => **i = i.scalar_mul(factor),
+        }
+    }
+
+    /// Clamps each component to `[-max, max]`.
+    pub fn clamp(&mut self, max: Scalar) {
+        match self {
+            GradientMut::Matrix(m) => {
+                let (nrow, ncol) = m.dim();
+                **m = m
+                    .maxof(&Matrix::constant(nrow, ncol, -max))
+                    .minof(&Matrix::constant(nrow, ncol, max));
+            }
+            GradientMut::Image(i) => {
+                let (nrow, ncol, nchan) = i.image_dims();
+                let samples = i.samples();
+                **i = i
+                    .maxof(&Image::constant(nrow, ncol, nchan, samples, -max))
+                    .minof(&Image::constant(nrow, ncol, nchan, samples, max));
+            }
+        }
+    }
+}
+
+/// Euclidean norm of all the gradients, as if they were a single vector.
+pub fn gradients_norm(gradients: &[GradientMut]) -> Scalar {
+    gradients.iter().map(|g| g.squared_norm()).sum::<Scalar>().sqrt()
+}
+
+/// Clipping applied to a network's gradients before the optimizers update its parameters.
+///
+/// The thresholds must be positive, which is checked when deserializing.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(try_from = "UncheckedGradientClipping")]
+pub enum GradientClipping {
+    /// Clamps each component of the gradients to `[-max, max]`.
+    Value(Scalar),
+    /// Rescales the gradients of each of the network's layers whose norm exceeds the given one.
+    Norm(Scalar),
+    /// Rescales all the network's gradients when their global norm exceeds the given one,
+    /// which keeps their direction.
+    GlobalNorm(Scalar),
+}
+
+impl GradientClipping {
+    pub fn threshold(&self) -> Scalar {
+        match self {
+            GradientClipping::Value(max)
+            | GradientClipping::Norm(max)
+            | GradientClipping::GlobalNorm(max) => *max,
+        }
+    }
+
+    /// Returns an error if the threshold isn't positive (or is NaN).
+    pub fn validate(self) -> Result<Self, String> {
+        let threshold = self.threshold();
+        if threshold > 0. {
+            Ok(self)
+        } else {
+            Err(format!(
+                "Gradient clipping threshold must be positive, got {}",
+                threshold
+            ))
+        }
+    }
+
+    /// `layers_gradients` holds the gradients of each of the network's layers.
+    pub fn clip(&self, layers_gradients: Vec<Vec<GradientMut>>) {
+        match self {
+            GradientClipping::Value(max) => layers_gradients
+                .into_iter()
+                .flatten()
+                .for_each(|mut g| g.clamp(*max)),
+            GradientClipping::Norm(max_norm) => layers_gradients
+                .into_iter()
+                .for_each(|mut gradients| rescale(&mut gradients, *max_norm)),
+            GradientClipping::GlobalNorm(max_norm) => {
+                let mut gradients: Vec<_> = layers_gradients.into_iter().flatten().collect();
+                rescale(&mut gradients, *max_norm);
+            }
+        }
+    }
+}
+
+#[derive(Deserialize)]
+enum UncheckedGradientClipping {
+    Value(Scalar),
+    Norm(Scalar),
+    GlobalNorm(Scalar),
+}
+
+impl TryFrom<UncheckedGradientClipping> for GradientClipping {
+    type Error = String;
+
+    fn try_from(unchecked: UncheckedGradientClipping) -> Result<Self, Self::Error> {
+        match unchecked {
+            UncheckedGradientClipping::Value(max) => GradientClipping::Value(max),
+            UncheckedGradientClipping::Norm(max) => GradientClipping::Norm(max),
+            UncheckedGradientClipping::GlobalNorm(max) => GradientClipping::GlobalNorm(max),
+        }
+        .validate()
+    }
+}
+
+fn rescale(gradients: &mut [GradientMut], max_norm: Scalar) {
+    let norm = gradients_norm(gradients);
+    if norm > max_norm {
+        let factor = max_norm / norm;
+        gradients.iter_mut().for_each(|g| g.scale(factor));
+    }
+}
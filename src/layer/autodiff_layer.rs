@@ -0,0 +1,178 @@
+use std::fmt;
+
+use crate::{
+    autodiff::{Tape, Var},
+    gradient_clipping::GradientMut,
+    linalg::{Matrix, MatrixTrait, Scalar},
+    network::NetworkLayer,
+    optimizer::Optimizers,
+};
+
+use super::{
+    accumulate_gradient, DropoutLayer, GradientLayer, Layer, LearnableLayer, ParameterableLayer,
+};
+
+/// Records the forward pass of an `AutodiffLayer` on the tape.
+///
+/// Takes the input variable and the parameters variables (in the order they were given to the layer),
+/// returns the output variable.
+pub type AutodiffForwardFn = Box<dyn Fn(&mut Tape<Matrix>, Var, &[Var]) -> Var + Send>;
+
+/// A layer defined only by its forward pass, its gradients being derived automatically.
+///
+/// ```rust
+/// # use jiro_nn::{
+/// #     activation::tanh::{tanh, tanh_prime}, initializers::Initializers,
+/// #     layer::autodiff_layer::AutodiffLayer, optimizer::adam,
+/// # };
+/// # let (i, j) = (3, 2);
+/// // y = tanh(W . x)
+/// let layer = AutodiffLayer::new(
+///     Box::new(|tape, input, params| {
+///         let weighted = tape.dot(params[0], input);
+///         tape.map(weighted, tanh, tanh_prime)
+///     }),
+///     vec![Initializers::GlorotUniform.gen_matrix(j, i)],
+///     vec![adam()],
+/// );
+/// ```
+pub struct AutodiffLayer {
+    forward_fn: AutodiffForwardFn,
+    parameters: Vec<Matrix>,
+    optimizers: Vec<Optimizers>,
+    // one per parameter, accumulated by backward and applied by step
+    gradients: Vec<Option<Matrix>>,
+    tape: Tape<Matrix>,
+    input: Option<Var>,
+    parameters_vars: Vec<Var>,
+    output: Option<Var>,
+}
+
+impl AutodiffLayer {
+    /// `optimizers` must contain one optimizer per parameter.
+    pub fn new(
+        forward_fn: AutodiffForwardFn,
+        parameters: Vec<Matrix>,
+        optimizers: Vec<Optimizers>,
+    ) -> Self {
+        assert_eq!(parameters.len(), optimizers.len());
+        Self {
+            forward_fn,
+            gradients: vec![None; parameters.len()],
+            parameters,
+            optimizers,
+            tape: Tape::new(),
+            input: None,
+            parameters_vars: vec![],
+            output: None,
+        }
+    }
+
+    pub fn parameters(&self) -> &[Matrix] {
+        &self.parameters
+    }
+}
+
+impl Layer for AutodiffLayer {
+    fn forward(&mut self, input: Matrix) -> Matrix {
+        self.tape.clear();
+        let input = self.tape.var(input);
+        self.parameters_vars = self
+            .parameters
+            .iter()
+            .map(|p| self.tape.var(p.clone()))
+            .collect();
+
+        let output = (self.forward_fn)(&mut self.tape, input, &self.parameters_vars);
+
+        self.input = Some(input);
+        self.output = Some(output);
+        self.tape.value(output).clone()
+    }
+
+    fn backward(&mut self, _epoch: usize, output_gradient: Matrix) -> Matrix {
+        let mut grads = self.tape.backward(self.output.unwrap(), output_gradient);
+
+        // a parameter not used in the forward pass has no gradient
+        for (var, acc) in self.parameters_vars.iter().zip(self.gradients.iter_mut()) {
+            if let Some(gradient) = grads.take(*var) {
+                accumulate_gradient(acc, gradient);
+            }
+        }
+
+        let (nrow, ncol) = self.tape.value(self.input.unwrap()).dim();
+        grads
+            .take(self.input.unwrap())
+            .unwrap_or_else(|| Matrix::zeros(nrow, ncol))
+    }
+}
+
+impl GradientLayer for AutodiffLayer {
+    fn gradients_mut(&mut self) -> Vec<GradientMut<'_>> {
+        self.gradients
+            .iter_mut()
+            .flatten()
+            .map(GradientMut::Matrix)
+            .collect()
+    }
+
+    fn zero_grad(&mut self) {
+        self.gradients.iter_mut().for_each(|g| *g = None);
+    }
+
+    fn step(&mut self, epoch: usize) {
+        for ((param, gradient), optimizer) in self
+            .parameters
+            .iter_mut()
+            .zip(self.gradients.iter_mut())
+            .zip(self.optimizers.iter_mut())
+        {
+            if let Some(gradient) = gradient.take() {
+                *param = optimizer.update_parameters(epoch, param, &gradient);
+            }
+        }
+    }
+}
+
+impl LearnableLayer for AutodiffLayer {
+    // returns the columns of all the parameters one after the other
+    fn get_learnable_parameters(&self) -> Vec<Vec<Scalar>> {
+        let mut params = vec![];
+        for param in self.parameters.iter() {
+            params.append(&mut param.get_data_col_leading());
+        }
+        params
+    }
+
+    // takes the columns of all the parameters one after the other
+    fn set_learnable_parameters(&mut self, params_matrix: &Vec<Vec<Scalar>>) {
+        let mut columns = params_matrix.iter();
+        for param in self.parameters.iter_mut() {
+            let ncol = param.dim().1;
+            let param_columns: Vec<Vec<Scalar>> = columns.by_ref().take(ncol).cloned().collect();
+            *param = Matrix::from_column_leading_vector2(&param_columns);
+        }
+    }
+}
+
+impl ParameterableLayer for AutodiffLayer {
+    fn as_learnable_layer(&self) -> Option<&dyn LearnableLayer> {
+        Some(self)
+    }
+
+    fn as_learnable_layer_mut(&mut self) -> Option<&mut dyn LearnableLayer> {
+        Some(self)
+    }
+
+    fn as_dropout_layer(&mut self) -> Option<&mut dyn DropoutLayer> {
+        None
+    }
+}
+
+impl NetworkLayer for AutodiffLayer {}
+
+impl fmt::Debug for AutodiffLayer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Autodiff Layer")
+    }
+}
@@ -0,0 +1,226 @@
+use std::fmt;
+
+use crate::{
+    autodiff::{Tape, Var},
+    gradient_clipping::GradientMut,
+    linalg::{Matrix, MatrixTrait, Scalar},
+    network::NetworkLayer,
+    optimizer::Optimizers,
+};
+
+use super::{
+    accumulate_gradient, DropoutLayer, GradientLayer, Layer, LearnableLayer, ParameterableLayer,
+};
+
+struct BatchNormVars {
+    input: Var,
+    gamma: Var,
+    beta: Var,
+    output: Var,
+}
+
+// https://arxiv.org/pdf/1502.03167.pdf
+pub struct BatchNormLayer {
+    // i inputs = i outputs, each input row is normalized across the batch
+    tape: Tape<Matrix>,
+    vars: Option<BatchNormVars>,
+    // i scales (single column)
+    pub gamma: Matrix,
+    // i shifts (single column)
+    pub beta: Matrix,
+    // i means and variances (single columns) used outside of training
+    pub running_mean: Matrix,
+    pub running_var: Matrix,
+    momentum: Scalar,
+    epsilon: Scalar,
+    training: bool,
+    gamma_optimizer: Optimizers,
+    beta_optimizer: Optimizers,
+    // accumulated by backward, applied by step
+    gamma_gradient: Option<Matrix>,
+    beta_gradient: Option<Matrix>,
+}
+
+impl BatchNormLayer {
+    pub fn new(
+        i: usize,
+        momentum: Scalar,
+        epsilon: Scalar,
+        gamma_optimizer: Optimizers,
+        beta_optimizer: Optimizers,
+    ) -> Self {
+        Self {
+            tape: Tape::new(),
+            vars: None,
+            gamma: Matrix::constant(i, 1, 1.0),
+            beta: Matrix::zeros(i, 1),
+            running_mean: Matrix::zeros(i, 1),
+            running_var: Matrix::constant(i, 1, 1.0),
+            momentum,
+            epsilon,
+            training: false,
+            gamma_optimizer,
+            beta_optimizer,
+            gamma_gradient: None,
+            beta_gradient: None,
+        }
+    }
+}
+
+impl Layer for BatchNormLayer {
+    /// `input` has shape `(i, n)` where `i` is the number of inputs and `n` is the number of samples.
+    ///
+    /// Returns output which has shape `(i, n)`.
+    fn forward(&mut self, input: Matrix) -> Matrix {
+        let (i, n) = input.dim();
+        self.tape.clear();
+        let ones_col = self.tape.constant(Matrix::constant(n, 1, 1.0));
+        let ones_row = self.tape.constant(Matrix::constant(1, n, 1.0));
+        let input = self.tape.var(input);
+        let gamma = self.tape.var(self.gamma.clone());
+        let beta = self.tape.var(self.beta.clone());
+
+        let (centered, var) = if self.training {
+            // μ = X . (1...1)ᵀ / n
+            let sum = self.tape.dot(input, ones_col);
+            let mean = self.tape.scalar_div(sum, n as Scalar);
+            let mean_repeated = self.tape.dot(mean, ones_row);
+            // σ² = (X - μ)² . (1...1)ᵀ / n
+            let centered = self.tape.sub(input, mean_repeated);
+            let squared = self.tape.square(centered);
+            let squared_sum = self.tape.dot(squared, ones_col);
+            let var = self.tape.scalar_div(squared_sum, n as Scalar);
+
+            // the running variance tracks the unbiased estimate n / (n - 1) σ²
+            let unbiased = if n > 1 { n as Scalar / (n - 1) as Scalar } else { 1. };
+            self.running_mean = self
+                .running_mean
+                .scalar_mul(self.momentum)
+                .component_add(&self.tape.value(mean).scalar_mul(1. - self.momentum));
+            self.running_var = self
+                .running_var
+                .scalar_mul(self.momentum)
+                .component_add(&self.tape.value(var).scalar_mul(unbiased * (1. - self.momentum)));
+
+            (centered, var)
+        } else {
+            let mean = self.tape.constant(self.running_mean.clone());
+            let mean_repeated = self.tape.dot(mean, ones_row);
+            (
+                self.tape.sub(input, mean_repeated),
+                self.tape.constant(self.running_var.clone()),
+            )
+        };
+
+        // X̂ = (X - μ) / √(σ² + ε)
+        let var_eps = self.tape.scalar_add(var, self.epsilon);
+        let std = self.tape.sqrt(var_eps);
+        let std_repeated = self.tape.dot(std, ones_row);
+        let normalized = self.tape.div(centered, std_repeated);
+
+        // Y = γ ⊙ X̂ + β
+        let gamma_repeated = self.tape.dot(gamma, ones_row);
+        let beta_repeated = self.tape.dot(beta, ones_row);
+        let scaled = self.tape.mul(normalized, gamma_repeated);
+        let output = self.tape.add(scaled, beta_repeated);
+
+        debug_assert_eq!(self.tape.value(output).dim(), (i, n));
+        self.vars = Some(BatchNormVars {
+            input,
+            gamma,
+            beta,
+            output,
+        });
+        self.tape.value(output).clone()
+    }
+
+    fn backward(&mut self, _epoch: usize, output_gradient: Matrix) -> Matrix {
+        let vars = self.vars.as_ref().unwrap();
+        let mut grads = self.tape.backward(vars.output, output_gradient);
+
+        accumulate_gradient(&mut self.gamma_gradient, grads.take(vars.gamma).unwrap());
+        accumulate_gradient(&mut self.beta_gradient, grads.take(vars.beta).unwrap());
+        grads.take(vars.input).unwrap()
+    }
+}
+
+impl GradientLayer for BatchNormLayer {
+    fn gradients_mut(&mut self) -> Vec<GradientMut<'_>> {
+        self.gamma_gradient
+            .iter_mut()
+            .chain(self.beta_gradient.iter_mut())
+            .map(GradientMut::Matrix)
+            .collect()
+    }
+
+    fn zero_grad(&mut self) {
+        self.gamma_gradient = None;
+        self.beta_gradient = None;
+    }
+
+    fn step(&mut self, epoch: usize) {
+        if let Some(gamma_gradient) = self.gamma_gradient.take() {
+            self.gamma = self
+                .gamma_optimizer
+                .update_parameters(epoch, &self.gamma, &gamma_gradient);
+        }
+        if let Some(beta_gradient) = self.beta_gradient.take() {
+            self.beta = self
+                .beta_optimizer
+                .update_parameters(epoch, &self.beta, &beta_gradient);
+        }
+    }
+}
+
+impl LearnableLayer for BatchNormLayer {
+    // returns the (i) gamma, beta, running mean and running variance columns
+    fn get_learnable_parameters(&self) -> Vec<Vec<Scalar>> {
+        vec![
+            self.gamma.get_column(0),
+            self.beta.get_column(0),
+            self.running_mean.get_column(0),
+            self.running_var.get_column(0),
+        ]
+    }
+
+    // takes the (i) gamma, beta, running mean and running variance columns
+    fn set_learnable_parameters(&mut self, params_matrix: &Vec<Vec<Scalar>>) {
+        self.gamma = Matrix::from_column_vector(&params_matrix[0]);
+        self.beta = Matrix::from_column_vector(&params_matrix[1]);
+        self.running_mean = Matrix::from_column_vector(&params_matrix[2]);
+        self.running_var = Matrix::from_column_vector(&params_matrix[3]);
+    }
+}
+
+/// Batch statistics are used while dropout is enabled (training) and running statistics otherwise.
+impl DropoutLayer for BatchNormLayer {
+    fn enable_dropout(&mut self) {
+        self.training = true;
+    }
+
+    fn disable_dropout(&mut self) {
+        self.training = false;
+    }
+}
+
+impl ParameterableLayer for BatchNormLayer {
+    fn as_learnable_layer(&self) -> Option<&dyn LearnableLayer> {
+        Some(self)
+    }
+
+    fn as_learnable_layer_mut(&mut self) -> Option<&mut dyn LearnableLayer> {
+        Some(self)
+    }
+
+    fn as_dropout_layer(&mut self) -> Option<&mut dyn DropoutLayer> {
+        Some(self)
+    }
+}
+
+impl NetworkLayer for BatchNormLayer {}
+
+impl fmt::Debug for BatchNormLayer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Batch Normalization Layer")
+    }
+}
@@ -1,5 +1,6 @@
 use crate::{
     initializers::Initializers,
+    linalg::Scalar,
     optimizer::{sgd, Optimizers},
 };
 
@@ -18,3 +19,11 @@ pub fn default_biases_optimizer() -> Optimizers {
 pub fn default_weights_optimizer() -> Optimizers {
     sgd()
 }
+
+pub fn default_running_momentum() -> Scalar {
+    0.9
+}
+
+pub fn default_norm_epsilon() -> Scalar {
+    1e-5
+}
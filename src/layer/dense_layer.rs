@@ -1,24 +1,41 @@
 use std::fmt;
 
+use crate::autodiff::{Tape, Var};
 use crate::linalg::{MatrixTrait, Scalar};
 use crate::{
+    gradient_clipping::GradientMut,
     initializers::Initializers,
-    layer::Layer,
+    layer::{accumulate_gradient, GradientLayer, Layer},
     linalg::Matrix,
     optimizer::{Optimizers},
+    regularization::Regularization,
 };
 
-use super::LearnableLayer;
+use super::{DropoutLayer, LearnableLayer};
+
+struct DenseVars {
+    input: Var,
+    weights: Var,
+    biases: Var,
+    output: Var,
+}
 
 pub struct DenseLayer {
     // i inputs, j outputs, i x j connections
-    input: Option<Matrix>,
+    tape: Tape<Matrix>,
+    vars: Option<DenseVars>,
     // j x i connection weights
     pub weights: Matrix,
     // j output biases (single column)
     pub biases: Matrix,
     weights_optimizer: Optimizers,
     biases_optimizer: Optimizers,
+    regularization: Option<Regularization>,
+    // the tape is only recorded for backward while training
+    training: bool,
+    // accumulated by backward, applied by step
+    weights_gradient: Option<Matrix>,
+    biases_gradient: Option<Matrix>,
 }
 
 impl DenseLayer {
@@ -38,9 +55,21 @@ impl DenseLayer {
         Self {
             weights: weights,
             biases: biases,
-            input: None,
+            tape: Tape::new(),
+            vars: None,
             weights_optimizer,
             biases_optimizer,
+            regularization: None,
+            training: true,
+            weights_gradient: None,
+            biases_gradient: None,
+        }
+    }
+
+    pub fn with_regularization(self, regularization: Regularization) -> Self {
+        Self {
+            regularization: Some(regularization),
+            ..self
         }
     }
 }
@@ -52,39 +81,100 @@ impl Layer for DenseLayer {
     fn forward(&mut self, input: Matrix) -> Matrix {
         // Y = W . X + B * (1...1)
 
-        // println!("WEIGHTS");
-        // self
-        //     .weights.print();
-
-        let res = self
-            .weights
-            .dot(&input)
-            .component_add(&self.biases.dot(&Matrix::constant(1, input.dim().1, 1.0)));
+        self.tape.clear();
+        let ones = Matrix::constant(1, input.dim().1, 1.0);
+        if !self.training {
+            self.vars = None;
+            return self.weights.dot(&input).component_add(&self.biases.dot(&ones));
+        }
 
-        self.input = Some(input);
-        res
+        let input = self.tape.var(input);
+        let weights = self.tape.var(self.weights.clone());
+        let biases = self.tape.var(self.biases.clone());
+        let ones = self.tape.constant(ones);
+
+        let weighted = self.tape.dot(weights, input);
+        let biases_repeated = self.tape.dot(biases, ones);
+        let output = self.tape.add(weighted, biases_repeated);
+
+        self.vars = Some(DenseVars {
+            input,
+            weights,
+            biases,
+            output,
+        });
+        self.tape.value(output).clone()
     }
 
     /// `output_gradient` has shape `(j, n)` where `j` is the number of outputs and `n` is the number of samples.
     ///
     /// Returns `input_gradient` which has shape `(i, n)` where `i` is the number of inputs and `n` is the number of samples.
-    fn backward(&mut self, epoch: usize, output_gradient: Matrix) -> Matrix {
-        let input = self.input.as_ref().unwrap();
+    fn backward(&mut self, _epoch: usize, output_gradient: Matrix) -> Matrix {
+        let vars = self
+            .vars
+            .as_ref()
+            .expect("The dense layer's backward pass needs a forward pass in training mode");
+        let mut grads = self.tape.backward(vars.output, output_gradient);
+
+        let mut weights_gradient = grads.take(vars.weights).unwrap();
+
+        let mut biases_gradient = grads.take(vars.biases).unwrap();
+
+        let input_gradient = grads.take(vars.input).unwrap();
+
+        if let Some(regularization) = &self.regularization {
+            // the penalty is added once to the loss averaged over the samples, whose gradients are summed
+            let samples = input_gradient.dim().1 as Scalar;
+            weights_gradient = weights_gradient
+                .component_add(&regularization.gradient(&self.weights).scalar_mul(samples));
+            if regularization.biases {
+                biases_gradient = biases_gradient
+                    .component_add(&regularization.gradient(&self.biases).scalar_mul(samples));
+            }
+        }
 
-        let weights_gradient = &output_gradient.dot(&input.transpose());
+        accumulate_gradient(&mut self.weights_gradient, weights_gradient);
+        accumulate_gradient(&mut self.biases_gradient, biases_gradient);
 
-        let biases_gradient = output_gradient.columns_sum();
+        input_gradient
+    }
 
-        let input_gradient = self.weights.transpose().dot(&output_gradient);
+    fn regularization_loss(&self) -> Scalar {
+        match &self.regularization {
+            Some(regularization) if regularization.biases => {
+                regularization.penalty(&self.weights) + regularization.penalty(&self.biases)
+            }
+            Some(regularization) => regularization.penalty(&self.weights),
+            None => 0.0,
+        }
+    }
+}
 
-        self.weights =
-            self.weights_optimizer
-                .update_parameters(epoch, &self.weights, &weights_gradient);
-        self.biases =
-            self.biases_optimizer
-                .update_parameters(epoch, &self.biases, &biases_gradient);
+impl GradientLayer for DenseLayer {
+    fn gradients_mut(&mut self) -> Vec<GradientMut<'_>> {
+        self.weights_gradient
+            .iter_mut()
+            .chain(self.biases_gradient.iter_mut())
+            .map(GradientMut::Matrix)
+            .collect()
+    }
 
-        input_gradient
+    fn zero_grad(&mut self) {
+        self.weights_gradient = None;
+        self.biases_gradient = None;
+    }
+
+    fn step(&mut self, epoch: usize) {
+        if let Some(weights_gradient) = self.weights_gradient.take() {
+            self.weights =
+                self.weights_optimizer
+                    .update_parameters(epoch, &self.weights, &weights_gradient);
+        }
+        if let Some(biases_gradient) = self.biases_gradient.take() {
+            self.biases =
+                self.biases_optimizer
+                    .update_parameters(epoch, &self.biases, &biases_gradient);
+        }
     }
 }
 
@@ -105,6 +195,17 @@ impl LearnableLayer for DenseLayer {
     }
 }
 
+/// The forward pass is only recorded for the backward pass while dropout is enabled (training), which is the default.
+impl DropoutLayer for DenseLayer {
+    fn enable_dropout(&mut self) {
+        self.training = true;
+    }
+
+    fn disable_dropout(&mut self) {
+        self.training = false;
+    }
+}
+
 impl fmt::Debug for DenseLayer {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "Dense Layer")
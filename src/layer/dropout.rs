@@ -0,0 +1,310 @@
+use std::cmp::Ordering;
+
+use rand::Rng;
+use rand_distr::Distribution;
+
+use crate::linalg::{Matrix, MatrixTrait, Scalar};
+use crate::network::NetworkLayer;
+
+use super::{DropoutLayer, GradientLayer, Layer, LearnableLayer, ParameterableLayer};
+
+// dropout resources : https://jmlr.org/papers/volume15/srivastava14a/srivastava14a.pdf
+
+/// Returns a mask of 1s kept with probability `1 - rate` and 0s.
+pub(crate) fn bernoulli_mask(shape: (usize, usize), rate: Scalar) -> Matrix {
+    let mut rng = rand::thread_rng();
+    Matrix::from_fn(shape.0, shape.1, |_, _| {
+        if rng
+            .gen_range((0.0 as Scalar)..(1.0 as Scalar))
+            .total_cmp(&rate)
+            == Ordering::Greater
+        {
+            1.0
+        } else {
+            0.0
+        }
+    })
+}
+
+fn normal_matrix(shape: (usize, usize), mean: Scalar, std_dev: Scalar) -> Matrix {
+    let mut rng = rand::thread_rng();
+    let normal = rand_distr::Normal::new(mean, std_dev).unwrap();
+    Matrix::from_fn(shape.0, shape.1, |_, _| normal.sample(&mut rng))
+}
+
+/// Zeroes each input with probability `rate` during training, scaling the kept inputs by `1 / (1 - rate)`
+/// so that nothing has to be rescaled at inference (inverted dropout).
+#[derive(Debug)]
+pub struct Dropout {
+    pub rate: Scalar,
+    enabled: bool,
+    // scaled mask of the last training forward pass
+    mask: Option<Matrix>,
+}
+
+impl Dropout {
+    pub fn new(rate: Scalar) -> Self {
+        Self {
+            rate,
+            enabled: false,
+            mask: None,
+        }
+    }
+}
+
+impl Layer for Dropout {
+    fn forward(&mut self, input: Matrix) -> Matrix {
+        if self.enabled {
+            let mask = bernoulli_mask(input.dim(), self.rate).scalar_div(1.0 - self.rate);
+            let output = input.component_mul(&mask);
+            self.mask = Some(mask);
+            output
+        } else {
+            self.mask = None;
+            input
+        }
+    }
+
+    fn backward(&mut self, _epoch: usize, output_gradient: Matrix) -> Matrix {
+        match &self.mask {
+            Some(mask) => output_gradient.component_mul(mask),
+            None => output_gradient,
+        }
+    }
+}
+
+impl GradientLayer for Dropout {}
+
+/// Adds a centered gaussian noise of standard deviation `std_dev` to the inputs during training.
+#[derive(Debug)]
+pub struct GaussianNoise {
+    pub std_dev: Scalar,
+    enabled: bool,
+}
+
+impl GaussianNoise {
+    pub fn new(std_dev: Scalar) -> Self {
+        Self {
+            std_dev,
+            enabled: false,
+        }
+    }
+}
+
+impl Layer for GaussianNoise {
+    fn forward(&mut self, input: Matrix) -> Matrix {
+        if self.enabled {
+            input.component_add(&normal_matrix(input.dim(), 0.0, self.std_dev))
+        } else {
+            input
+        }
+    }
+
+    fn backward(&mut self, _epoch: usize, output_gradient: Matrix) -> Matrix {
+        output_gradient
+    }
+}
+
+impl GradientLayer for GaussianNoise {}
+
+/// Multiplies the inputs by a gaussian noise of mean 1 and variance `rate / (1 - rate)` during training.
+#[derive(Debug)]
+pub struct GaussianDropout {
+    pub rate: Scalar,
+    enabled: bool,
+    mask: Option<Matrix>,
+}
+
+impl GaussianDropout {
+    pub fn new(rate: Scalar) -> Self {
+        Self {
+            rate,
+            enabled: false,
+            mask: None,
+        }
+    }
+}
+
+impl Layer for GaussianDropout {
+    fn forward(&mut self, input: Matrix) -> Matrix {
+        if self.enabled {
+            let std_dev = (self.rate / (1.0 - self.rate)).sqrt();
+            let mask = normal_matrix(input.dim(), 1.0, std_dev);
+            let output = input.component_mul(&mask);
+            self.mask = Some(mask);
+            output
+        } else {
+            self.mask = None;
+            input
+        }
+    }
+
+    fn backward(&mut self, _epoch: usize, output_gradient: Matrix) -> Matrix {
+        match &self.mask {
+            Some(mask) => output_gradient.component_mul(mask),
+            None => output_gradient,
+        }
+    }
+}
+
+impl GradientLayer for GaussianDropout {}
+
+// https://arxiv.org/pdf/1706.02515.pdf
+/// Dropout keeping the mean and variance of SELU activated inputs, the dropped inputs being set to SELU's
+/// negative saturation value instead of 0 before an affine correction.
+#[derive(Debug)]
+pub struct AlphaDropout {
+    pub rate: Scalar,
+    enabled: bool,
+    // affine correction's scale times the mask of the last training forward pass
+    mask: Option<Matrix>,
+}
+
+impl AlphaDropout {
+    // -scale * alpha of SELU
+    const SATURATION: Scalar = -1.758_099_3;
+
+    pub fn new(rate: Scalar) -> Self {
+        Self {
+            rate,
+            enabled: false,
+            mask: None,
+        }
+    }
+}
+
+impl Layer for AlphaDropout {
+    fn forward(&mut self, input: Matrix) -> Matrix {
+        if self.enabled {
+            let (p, s) = (self.rate, Self::SATURATION);
+            let a = ((1.0 - p) * (1.0 + p * s * s)).powf(-0.5);
+            let b = -a * s * p;
+
+            let mask = bernoulli_mask(input.dim(), p);
+            let dropped = mask.scalar_mul(-1.0).scalar_add(1.0).scalar_mul(s);
+            let output = input
+                .component_mul(&mask)
+                .component_add(&dropped)
+                .scalar_mul(a)
+                .scalar_add(b);
+            self.mask = Some(mask.scalar_mul(a));
+            output
+        } else {
+            self.mask = None;
+            input
+        }
+    }
+
+    fn backward(&mut self, _epoch: usize, output_gradient: Matrix) -> Matrix {
+        match &self.mask {
+            Some(mask) => output_gradient.component_mul(mask),
+            None => output_gradient,
+        }
+    }
+}
+
+impl GradientLayer for AlphaDropout {}
+
+impl DropoutLayer for Dropout {
+    fn enable_dropout(&mut self) {
+        self.enabled = true;
+    }
+
+    fn disable_dropout(&mut self) {
+        self.enabled = false;
+    }
+}
+
+impl ParameterableLayer for Dropout {
+    fn as_learnable_layer(&self) -> Option<&dyn LearnableLayer> {
+        None
+    }
+
+    fn as_learnable_layer_mut(&mut self) -> Option<&mut dyn LearnableLayer> {
+        None
+    }
+
+    fn as_dropout_layer(&mut self) -> Option<&mut dyn DropoutLayer> {
+        Some(self)
+    }
+}
+
+impl NetworkLayer for Dropout {}
+
+impl DropoutLayer for GaussianNoise {
+    fn enable_dropout(&mut self) {
+        self.enabled = true;
+    }
+
+    fn disable_dropout(&mut self) {
+        self.enabled = false;
+    }
+}
+
+impl ParameterableLayer for GaussianNoise {
+    fn as_learnable_layer(&self) -> Option<&dyn LearnableLayer> {
+        None
+    }
+
+    fn as_learnable_layer_mut(&mut self) -> Option<&mut dyn LearnableLayer> {
+        None
+    }
+
+    fn as_dropout_layer(&mut self) -> Option<&mut dyn DropoutLayer> {
+        Some(self)
+    }
+}
+
+impl NetworkLayer for GaussianNoise {}
+
+impl DropoutLayer for GaussianDropout {
+    fn enable_dropout(&mut self) {
+        self.enabled = true;
+    }
+
+    fn disable_dropout(&mut self) {
+        self.enabled = false;
+    }
+}
+
+impl ParameterableLayer for GaussianDropout {
+    fn as_learnable_layer(&self) -> Option<&dyn LearnableLayer> {
+        None
+    }
+
+    fn as_learnable_layer_mut(&mut self) -> Option<&mut dyn LearnableLayer> {
+        None
+    }
+
+    fn as_dropout_layer(&mut self) -> Option<&mut dyn DropoutLayer> {
+        Some(self)
+    }
+}
+
+impl NetworkLayer for GaussianDropout {}
+
+impl DropoutLayer for AlphaDropout {
+    fn enable_dropout(&mut self) {
+        self.enabled = true;
+    }
+
+    fn disable_dropout(&mut self) {
+        self.enabled = false;
+    }
+}
+
+impl ParameterableLayer for AlphaDropout {
+    fn as_learnable_layer(&self) -> Option<&dyn LearnableLayer> {
+        None
+    }
+
+    fn as_learnable_layer_mut(&mut self) -> Option<&mut dyn LearnableLayer> {
+        None
+    }
+
+    fn as_dropout_layer(&mut self) -> Option<&mut dyn DropoutLayer> {
+        Some(self)
+    }
+}
+
+impl NetworkLayer for AlphaDropout {}
@@ -0,0 +1,176 @@
+use std::{collections::BTreeMap, fmt};
+
+use crate::{
+    gradient_clipping::GradientMut,
+    initializers::Initializers,
+    linalg::{Matrix, MatrixTrait, Scalar},
+    network::NetworkLayer,
+    optimizer::Optimizers,
+};
+
+use super::{DropoutLayer, GradientLayer, Layer, LearnableLayer, ParameterableLayer};
+
+/// Replaces the input row holding integer category ids with a learned `dim` values vector per category,
+/// the other rows being passed through.
+///
+/// Each category has its own optimizer, so that only the embeddings of the categories seen
+/// in a batch are updated (sparse updates).
+pub struct EmbeddingLayer {
+    // row of the input holding the category ids
+    input_index: usize,
+    // one vector of `dim` values per category
+    pub embeddings: Vec<Vec<Scalar>>,
+    optimizers: Vec<Optimizers>,
+    ids: Option<Vec<usize>>,
+    // gradients of the categories seen since the last step, applied by step
+    gradients: BTreeMap<usize, Matrix>,
+}
+
+impl EmbeddingLayer {
+    pub fn new(
+        input_index: usize,
+        categories_count: usize,
+        dim: usize,
+        initializer: Initializers,
+        optimizer: Optimizers,
+    ) -> Self {
+        Self {
+            input_index,
+            embeddings: initializer
+                .gen_matrix(dim, categories_count)
+                .get_data_col_leading(),
+            optimizers: vec![optimizer; categories_count],
+            ids: None,
+            gradients: BTreeMap::new(),
+        }
+    }
+
+    fn category_id(&self, value: Scalar) -> usize {
+        let id = value.round();
+        if id < 0.0 || id as usize >= self.embeddings.len() {
+            panic!(
+                "Category id {} is out of the embedding's {} categories",
+                value,
+                self.embeddings.len()
+            );
+        }
+        id as usize
+    }
+}
+
+impl Layer for EmbeddingLayer {
+    /// `input` has shape `(i, n)` where `i` is the number of inputs and `n` is the number of samples.
+    ///
+    /// Returns output which has shape `(i - 1 + dim, n)` where `n` is the number of samples.
+    fn forward(&mut self, input: Matrix) -> Matrix {
+        let samples = input.get_data_col_leading();
+        let mut ids = Vec::with_capacity(samples.len());
+
+        let embedded: Vec<Vec<Scalar>> = samples
+            .into_iter()
+            .map(|sample| {
+                let id = self.category_id(sample[self.input_index]);
+                ids.push(id);
+                let mut out = sample[..self.input_index].to_vec();
+                out.extend(self.embeddings[id].iter());
+                out.extend(sample[self.input_index + 1..].iter());
+                out
+            })
+            .collect();
+
+        self.ids = Some(ids);
+        Matrix::from_column_leading_vector2(&embedded)
+    }
+
+    /// `output_gradient` has shape `(i - 1 + dim, n)` where `n` is the number of samples.
+    ///
+    /// Returns `input_gradient` which has shape `(i, n)`, the ids' gradient being zero.
+    fn backward(&mut self, _epoch: usize, output_gradient: Matrix) -> Matrix {
+        let ids = self.ids.as_ref().expect("Embedding backward called before forward");
+        let dim = self.embeddings[0].len();
+        let (start, end) = (self.input_index, self.input_index + dim);
+
+        // gradients summed per category seen in the batch
+        let mut embeddings_gradients: BTreeMap<usize, Vec<Scalar>> = BTreeMap::new();
+        let input_gradient: Vec<Vec<Scalar>> = output_gradient
+            .get_data_col_leading()
+            .into_iter()
+            .zip(ids.iter())
+            .map(|(sample, id)| {
+                let gradient = embeddings_gradients.entry(*id).or_insert(vec![0.0; dim]);
+                for (g, s) in gradient.iter_mut().zip(sample[start..end].iter()) {
+                    *g += s;
+                }
+                let mut input_grad = sample[..start].to_vec();
+                input_grad.push(0.0);
+                input_grad.extend(sample[end..].iter());
+                input_grad
+            })
+            .collect();
+
+        for (id, gradient) in embeddings_gradients {
+            let gradient = Matrix::from_column_vector(&gradient);
+            match self.gradients.remove(&id) {
+                Some(acc) => self.gradients.insert(id, acc.component_add(&gradient)),
+                None => self.gradients.insert(id, gradient),
+            };
+        }
+
+        Matrix::from_column_leading_vector2(&input_gradient)
+    }
+}
+
+impl GradientLayer for EmbeddingLayer {
+    fn gradients_mut(&mut self) -> Vec<GradientMut<'_>> {
+        self.gradients.values_mut().map(GradientMut::Matrix).collect()
+    }
+
+    fn zero_grad(&mut self) {
+        self.gradients.clear();
+    }
+
+    fn step(&mut self, epoch: usize) {
+        for (id, gradient) in std::mem::take(&mut self.gradients) {
+            self.embeddings[id] = self.optimizers[id]
+                .update_parameters(
+                    epoch,
+                    &Matrix::from_column_vector(&self.embeddings[id]),
+                    &gradient,
+                )
+                .get_column(0);
+        }
+    }
+}
+
+impl LearnableLayer for EmbeddingLayer {
+    // returns one column per category
+    fn get_learnable_parameters(&self) -> Vec<Vec<Scalar>> {
+        self.embeddings.clone()
+    }
+
+    fn set_learnable_parameters(&mut self, params_matrix: &Vec<Vec<Scalar>>) {
+        self.embeddings = params_matrix.clone();
+    }
+}
+
+impl ParameterableLayer for EmbeddingLayer {
+    fn as_learnable_layer(&self) -> Option<&dyn LearnableLayer> {
+        Some(self)
+    }
+
+    fn as_learnable_layer_mut(&mut self) -> Option<&mut dyn LearnableLayer> {
+        Some(self)
+    }
+
+    fn as_dropout_layer(&mut self) -> Option<&mut dyn DropoutLayer> {
+        None
+    }
+}
+
+impl NetworkLayer for EmbeddingLayer {}
+
+impl fmt::Debug for EmbeddingLayer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Embedding Layer")
+    }
+}
@@ -2,11 +2,12 @@ use std::cmp::Ordering;
 
 use rand::Rng;
 
+use crate::gradient_clipping::GradientMut;
 use crate::linalg::{Matrix, MatrixTrait, Scalar};
 use crate::network::NetworkLayer;
 use crate::{activation::ActivationLayer, layer::dense_layer::DenseLayer, layer::Layer};
 
-use super::{DropoutLayer, LearnableLayer, ParameterableLayer};
+use super::{DropoutLayer, GradientLayer, LearnableLayer, ParameterableLayer};
 
 #[derive(Debug)]
 pub struct FullLayer {
@@ -50,37 +51,70 @@ impl FullLayer {
     }
 }
 
+impl FullLayer {
+    fn dense_backward(&mut self, epoch: usize, activation_input_gradient: Matrix) -> Matrix {
+        let input_gradient = self.dense.backward(epoch, activation_input_gradient);
+
+        if let Some(mask) = &self.mask {
+            input_gradient.component_mul(&mask)
+        } else {
+            input_gradient
+        }
+    }
+}
+
 impl Layer for FullLayer {
     fn forward(&mut self, mut input: Matrix) -> Matrix {
-        let output = if self.dropout_enabled {
-            if let Some((mask, _)) = self.generate_dropout_mask(input.dim()) {
+        // inverted dropout: kept inputs are scaled up while training so that inference uses the weights as is
+        self.mask = None;
+        if self.dropout_enabled {
+            if let Some((mask, dropout_rate)) = self.generate_dropout_mask(input.dim()) {
+                let mask = mask.scalar_div(1.0 - dropout_rate);
                 input = input.component_mul(&mask);
                 self.mask = Some(mask);
             };
-            self.dense.forward(input)
-        } else {
-            if let Some(dropout_rate) = self.dropout_rate {
-                self.dense.weights = self.dense.weights.scalar_mul(1.0 - dropout_rate);
-                let output = self.dense.forward(input);
-                self.dense.weights = self.dense.weights.scalar_div(1.0 - dropout_rate);
-                output
-            } else {
-                self.dense.forward(input)
-            }
-        };
+        }
 
+        let output = self.dense.forward(input);
         self.activation.forward(output)
     }
 
     fn backward(&mut self, epoch: usize, output_gradient: Matrix) -> Matrix {
         let activation_input_gradient = self.activation.backward(epoch, output_gradient);
-        let input_gradient = self.dense.backward(epoch, activation_input_gradient);
+        self.dense_backward(epoch, activation_input_gradient)
+    }
 
-        if let Some(mask) = &self.mask {
-            input_gradient.component_mul(&mask)
-        } else {
-            input_gradient
-        }
+    fn has_softmax_output(&self) -> bool {
+        self.activation.has_softmax_output()
+    }
+
+    fn backward_skipping_softmax(&mut self, epoch: usize, logits_gradient: Matrix) -> Matrix {
+        let activation_input_gradient = self
+            .activation
+            .backward_skipping_softmax(epoch, logits_gradient);
+        self.dense_backward(epoch, activation_input_gradient)
+    }
+
+    fn regularization_loss(&self) -> Scalar {
+        self.dense.regularization_loss()
+    }
+}
+
+impl GradientLayer for FullLayer {
+    fn gradients_mut(&mut self) -> Vec<GradientMut<'_>> {
+        let mut gradients = self.dense.gradients_mut();
+        gradients.append(&mut self.activation.gradients_mut());
+        gradients
+    }
+
+    fn zero_grad(&mut self) {
+        self.dense.zero_grad();
+        self.activation.zero_grad();
+    }
+
+    fn step(&mut self, epoch: usize) {
+        self.dense.step(epoch);
+        self.activation.step(epoch);
     }
 }
 
@@ -101,23 +135,35 @@ impl ParameterableLayer for FullLayer {
 }
 
 impl LearnableLayer for FullLayer {
-    // returns a matrix of the (jxi) weights and the final column being the (j) biases
+    // returns a matrix of the (jxi) weights and the (j) biases column,
+    // followed by the activation's slope column if it is learnable (PReLU)
     fn get_learnable_parameters(&self) -> Vec<Vec<Scalar>> {
-        self.dense.get_learnable_parameters()
+        let mut params = self.dense.get_learnable_parameters();
+        params.append(&mut self.activation.get_learnable_parameters());
+        params
     }
 
-    // takes a matrix of the (jxi) weights and the final column being the (j) biases
+    // takes a matrix of the (jxi) weights and the (j) biases column,
+    // followed by the activation's slope column if it is learnable (PReLU)
     fn set_learnable_parameters(&mut self, params_matrix: &Vec<Vec<Scalar>>) {
-        self.dense.set_learnable_parameters(params_matrix)
+        match (self.activation.get_slope(), params_matrix.split_last()) {
+            (Some(_), Some((slope, dense_params))) => {
+                self.dense.set_learnable_parameters(&dense_params.to_vec());
+                self.activation.set_learnable_parameters(&vec![slope.clone()]);
+            }
+            _ => self.dense.set_learnable_parameters(params_matrix),
+        }
     }
 }
 
 impl DropoutLayer for FullLayer {
     fn enable_dropout(&mut self) {
         self.dropout_enabled = true;
+        self.dense.enable_dropout();
     }
 
     fn disable_dropout(&mut self) {
         self.dropout_enabled = false;
+        self.dense.disable_dropout();
     }
 }
@@ -0,0 +1,176 @@
+use std::fmt;
+
+use crate::{
+    autodiff::{Tape, Var},
+    gradient_clipping::GradientMut,
+    linalg::{Matrix, MatrixTrait, Scalar},
+    network::NetworkLayer,
+    optimizer::Optimizers,
+};
+
+use super::{
+    accumulate_gradient, DropoutLayer, GradientLayer, Layer, LearnableLayer, ParameterableLayer,
+};
+
+struct LayerNormVars {
+    input: Var,
+    gamma: Var,
+    beta: Var,
+    output: Var,
+}
+
+// https://arxiv.org/pdf/1607.06450.pdf
+pub struct LayerNormLayer {
+    // i inputs = i outputs, each sample (column) is normalized across its i inputs
+    tape: Tape<Matrix>,
+    vars: Option<LayerNormVars>,
+    // i scales (single column)
+    pub gamma: Matrix,
+    // i shifts (single column)
+    pub beta: Matrix,
+    epsilon: Scalar,
+    gamma_optimizer: Optimizers,
+    beta_optimizer: Optimizers,
+    // accumulated by backward, applied by step
+    gamma_gradient: Option<Matrix>,
+    beta_gradient: Option<Matrix>,
+}
+
+impl LayerNormLayer {
+    pub fn new(
+        i: usize,
+        epsilon: Scalar,
+        gamma_optimizer: Optimizers,
+        beta_optimizer: Optimizers,
+    ) -> Self {
+        Self {
+            tape: Tape::new(),
+            vars: None,
+            gamma: Matrix::constant(i, 1, 1.0),
+            beta: Matrix::zeros(i, 1),
+            epsilon,
+            gamma_optimizer,
+            beta_optimizer,
+            gamma_gradient: None,
+            beta_gradient: None,
+        }
+    }
+}
+
+impl Layer for LayerNormLayer {
+    /// `input` has shape `(i, n)` where `i` is the number of inputs and `n` is the number of samples.
+    ///
+    /// Returns output which has shape `(i, n)`.
+    fn forward(&mut self, input: Matrix) -> Matrix {
+        let (i, n) = input.dim();
+        self.tape.clear();
+        let ones_col = self.tape.constant(Matrix::constant(i, 1, 1.0));
+        let ones_row = self.tape.constant(Matrix::constant(1, i, 1.0));
+        let ones_samples = self.tape.constant(Matrix::constant(1, n, 1.0));
+        let input = self.tape.var(input);
+        let gamma = self.tape.var(self.gamma.clone());
+        let beta = self.tape.var(self.beta.clone());
+
+        // μ = (1...1) . X / i
+        let sum = self.tape.dot(ones_row, input);
+        let mean = self.tape.scalar_div(sum, i as Scalar);
+        let mean_repeated = self.tape.dot(ones_col, mean);
+        // σ² = (1...1) . (X - μ)² / i
+        let centered = self.tape.sub(input, mean_repeated);
+        let squared = self.tape.square(centered);
+        let squared_sum = self.tape.dot(ones_row, squared);
+        let var = self.tape.scalar_div(squared_sum, i as Scalar);
+
+        // X̂ = (X - μ) / √(σ² + ε)
+        let var_eps = self.tape.scalar_add(var, self.epsilon);
+        let std = self.tape.sqrt(var_eps);
+        let std_repeated = self.tape.dot(ones_col, std);
+        let normalized = self.tape.div(centered, std_repeated);
+
+        // Y = γ ⊙ X̂ + β
+        let gamma_repeated = self.tape.dot(gamma, ones_samples);
+        let beta_repeated = self.tape.dot(beta, ones_samples);
+        let scaled = self.tape.mul(normalized, gamma_repeated);
+        let output = self.tape.add(scaled, beta_repeated);
+
+        self.vars = Some(LayerNormVars {
+            input,
+            gamma,
+            beta,
+            output,
+        });
+        self.tape.value(output).clone()
+    }
+
+    fn backward(&mut self, _epoch: usize, output_gradient: Matrix) -> Matrix {
+        let vars = self.vars.as_ref().unwrap();
+        let mut grads = self.tape.backward(vars.output, output_gradient);
+
+        accumulate_gradient(&mut self.gamma_gradient, grads.take(vars.gamma).unwrap());
+        accumulate_gradient(&mut self.beta_gradient, grads.take(vars.beta).unwrap());
+        grads.take(vars.input).unwrap()
+    }
+}
+
+impl GradientLayer for LayerNormLayer {
+    fn gradients_mut(&mut self) -> Vec<GradientMut<'_>> {
+        self.gamma_gradient
+            .iter_mut()
+            .chain(self.beta_gradient.iter_mut())
+            .map(GradientMut::Matrix)
+            .collect()
+    }
+
+    fn zero_grad(&mut self) {
+        self.gamma_gradient = None;
+        self.beta_gradient = None;
+    }
+
+    fn step(&mut self, epoch: usize) {
+        if let Some(gamma_gradient) = self.gamma_gradient.take() {
+            self.gamma = self
+                .gamma_optimizer
+                .update_parameters(epoch, &self.gamma, &gamma_gradient);
+        }
+        if let Some(beta_gradient) = self.beta_gradient.take() {
+            self.beta = self
+                .beta_optimizer
+                .update_parameters(epoch, &self.beta, &beta_gradient);
+        }
+    }
+}
+
+impl LearnableLayer for LayerNormLayer {
+    // returns the (i) gamma and beta columns
+    fn get_learnable_parameters(&self) -> Vec<Vec<Scalar>> {
+        vec![self.gamma.get_column(0), self.beta.get_column(0)]
+    }
+
+    // takes the (i) gamma and beta columns
+    fn set_learnable_parameters(&mut self, params_matrix: &Vec<Vec<Scalar>>) {
+        self.gamma = Matrix::from_column_vector(&params_matrix[0]);
+        self.beta = Matrix::from_column_vector(&params_matrix[1]);
+    }
+}
+
+impl ParameterableLayer for LayerNormLayer {
+    fn as_learnable_layer(&self) -> Option<&dyn LearnableLayer> {
+        Some(self)
+    }
+
+    fn as_learnable_layer_mut(&mut self) -> Option<&mut dyn LearnableLayer> {
+        Some(self)
+    }
+
+    fn as_dropout_layer(&mut self) -> Option<&mut dyn DropoutLayer> {
+        None
+    }
+}
+
+impl NetworkLayer for LayerNormLayer {}
+
+impl fmt::Debug for LayerNormLayer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Layer Normalization Layer")
+    }
+}
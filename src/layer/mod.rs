@@ -1,18 +1,28 @@
 use crate::{
     activation::Activation,
-    linalg::{Matrix, Scalar},
+    gradient_clipping::GradientMut,
+    linalg::{Matrix, MatrixTrait, Scalar},
 };
 
+pub mod autodiff_layer;
+pub mod batch_norm_layer;
 pub mod defaults;
 pub mod dense_layer;
+pub mod dropout;
+pub mod embedding_layer;
 pub mod full_layer;
+pub mod layer_norm_layer;
+pub mod multi_head_attention_layer;
+pub mod recurrent_layer;
+pub mod residual_block;
+pub mod transformer_encoder_block;
 
 pub enum Layers {
     Dense,
     Activation(Activation),
 }
 
-pub trait Layer {
+pub trait Layer: GradientLayer {
     /// `input` has shape `(i, n)` where `i` is the number of inputs and `n` is the number of samples.
     ///
     /// Returns output which has shape `(j, n)` where `j` is the number of outputs and `n` is the number of samples.
@@ -22,6 +32,48 @@ pub trait Layer {
     ///
     /// Returns `input_gradient` which has shape `(i, n)` where `i` is the number of inputs and `n` is the number of samples.
     fn backward(&mut self, epoch: usize, output_gradient: Matrix) -> Matrix;
+
+    /// Whether the layer's output is a softmax, whose gradient can then be fused with the loss' one
+    /// (see `Loss::softmax_loss_prime`).
+    fn has_softmax_output(&self) -> bool {
+        false
+    }
+
+    /// Same as `backward`, `logits_gradient` being the gradient of the final softmax's input instead of its output.
+    ///
+    /// Only called when `has_softmax_output` is true.
+    fn backward_skipping_softmax(&mut self, epoch: usize, logits_gradient: Matrix) -> Matrix {
+        self.backward(epoch, logits_gradient)
+    }
+
+    /// Penalty added to the reported training loss by the layer's regularization, if any.
+    fn regularization_loss(&self) -> Scalar {
+        0.0
+    }
+}
+
+/// Gradients of a layer's parameters, accumulated by each `backward` and only applied by `step`.
+///
+/// Layers without learnable parameters keep the default implementations.
+pub trait GradientLayer {
+    /// Gradients of the layer's parameters accumulated since the last `zero_grad` or `step`.
+    fn gradients_mut(&mut self) -> Vec<GradientMut<'_>> {
+        vec![]
+    }
+
+    /// Discards the accumulated gradients without updating the parameters.
+    fn zero_grad(&mut self) {}
+
+    /// Updates the layer's parameters with the accumulated gradients, which are then discarded.
+    fn step(&mut self, _epoch: usize) {}
+}
+
+/// Adds `gradient` to the one accumulated in `acc`, if any.
+pub(crate) fn accumulate_gradient(acc: &mut Option<Matrix>, gradient: Matrix) {
+    *acc = Some(match acc.take() {
+        Some(acc) => acc.component_add(&gradient),
+        None => gradient,
+    });
 }
 
 pub trait ParameterableLayer {
@@ -39,3 +91,25 @@ pub trait LearnableLayer {
     fn get_learnable_parameters(&self) -> Vec<Vec<Scalar>>;
     fn set_learnable_parameters(&mut self, params_matrix: &Vec<Vec<Scalar>>);
 }
+
+/// Concatenates the params of several layers, each preceded by a column holding its amount of columns,
+/// so that composite layers can be nested and split back with `split_learnable_parameters`.
+pub(crate) fn join_learnable_parameters(layers_params: Vec<Vec<Vec<Scalar>>>) -> Vec<Vec<Scalar>> {
+    let mut params = Vec::new();
+    for mut layer_params in layers_params {
+        params.push(vec![layer_params.len() as Scalar]);
+        params.append(&mut layer_params);
+    }
+    params
+}
+
+/// Splits params joined by `join_learnable_parameters` back into each layer's params.
+pub(crate) fn split_learnable_parameters(params_matrix: &[Vec<Scalar>]) -> Vec<Vec<Vec<Scalar>>> {
+    let mut layers_params = Vec::new();
+    let mut params = params_matrix.iter();
+    while let Some(header) = params.next() {
+        let len = header[0] as usize;
+        layers_params.push(params.by_ref().take(len).cloned().collect());
+    }
+    layers_params
+}
@@ -0,0 +1,275 @@
+use std::fmt;
+
+use crate::{
+    activation::softmax::{softmax_prime, stablesoftmax},
+    autodiff::{Tape, Var},
+    gradient_clipping::GradientMut,
+    initializers::Initializers,
+    linalg::{Matrix, MatrixTrait, Scalar},
+    network::NetworkLayer,
+    optimizer::Optimizers,
+    regularization::Regularization,
+};
+
+use super::{
+    dense_layer::DenseLayer, DropoutLayer, GradientLayer, Layer, LearnableLayer,
+    ParameterableLayer,
+};
+
+/// `input` has shape `(t * d, n)`, a flattened sequence of `t` tokens of `d` features for each of the `n` samples.
+///
+/// Returns the `(d, t * n)` matrix of all the tokens, the tokens of each sample being contiguous columns.
+pub fn sequence_to_tokens(input: &Matrix, features: usize) -> Matrix {
+    let mut tokens = vec![];
+    for sample in input.get_data_col_leading() {
+        for token in sample.chunks(features) {
+            tokens.push(token.to_vec());
+        }
+    }
+    Matrix::from_column_leading_vector2(&tokens)
+}
+
+/// Inverse of `sequence_to_tokens`.
+pub fn tokens_to_sequence(tokens: &Matrix, time_steps: usize) -> Matrix {
+    let mut samples = vec![];
+    for sample_tokens in tokens.get_data_col_leading().chunks(time_steps) {
+        samples.push(sample_tokens.concat());
+    }
+    Matrix::from_column_leading_vector2(&samples)
+}
+
+// https://arxiv.org/pdf/1706.03762.pdf
+pub struct MultiHeadAttentionLayer {
+    // d features per token, split in h heads of d / h features
+    features: usize,
+    heads: usize,
+    queries: DenseLayer,
+    keys: DenseLayer,
+    values: DenseLayer,
+    outputs: DenseLayer,
+    tape: Tape<Matrix>,
+    vars: Option<(Var, Var, Var, Var)>,
+    time_steps: usize,
+}
+
+impl MultiHeadAttentionLayer {
+    pub fn new(
+        features: usize,
+        heads: usize,
+        weights_optimizer: Optimizers,
+        biases_optimizer: Optimizers,
+        weights_initializer: Initializers,
+        biases_initializer: Initializers,
+    ) -> Self {
+        assert!(
+            features / heads * heads == features,
+            "Tokens features ({}) must be divisible by the number of heads ({})",
+            features,
+            heads
+        );
+
+        let projection = || {
+            DenseLayer::new(
+                features,
+                features,
+                weights_optimizer.clone(),
+                biases_optimizer.clone(),
+                weights_initializer.clone(),
+                biases_initializer.clone(),
+            )
+        };
+
+        Self {
+            features,
+            heads,
+            queries: projection(),
+            keys: projection(),
+            values: projection(),
+            outputs: projection(),
+            tape: Tape::new(),
+            vars: None,
+            time_steps: 0,
+        }
+    }
+
+    /// Penalizes the weights of the queries, keys, values and outputs projections, see `Regularization`.
+    pub fn with_regularization(self, regularization: Regularization) -> Self {
+        Self {
+            queries: self.queries.with_regularization(regularization.clone()),
+            keys: self.keys.with_regularization(regularization.clone()),
+            values: self.values.with_regularization(regularization.clone()),
+            outputs: self.outputs.with_regularization(regularization),
+            ..self
+        }
+    }
+
+    /// `tokens` has shape `(d, t * n)`, see `sequence_to_tokens`.
+    ///
+    /// Returns the attended tokens, with shape `(d, t * n)`.
+    pub fn forward_tokens(&mut self, tokens: Matrix, time_steps: usize) -> Matrix {
+        let samples = tokens.dim().1 / time_steps;
+        let head_size = self.features / self.heads;
+        self.time_steps = time_steps;
+
+        let queries = self.queries.forward(tokens.clone());
+        let keys = self.keys.forward(tokens.clone());
+        let values = self.values.forward(tokens);
+
+        self.tape.clear();
+        let queries = self.tape.var(queries);
+        let keys = self.tape.var(keys);
+        let values = self.tape.var(values);
+
+        let mut attended_samples = vec![];
+        for sample in 0..samples {
+            let q = self.tape.slice_columns(queries, sample * time_steps, time_steps);
+            let k = self.tape.slice_columns(keys, sample * time_steps, time_steps);
+            let v = self.tape.slice_columns(values, sample * time_steps, time_steps);
+
+            let mut attended_heads = vec![];
+            for head in 0..self.heads {
+                let qh = self.tape.slice_rows(q, head * head_size, head_size);
+                let kh = self.tape.slice_rows(k, head * head_size, head_size);
+                let vh = self.tape.slice_rows(v, head * head_size, head_size);
+
+                // A = softmax(Kᵀ . Q / √dₕ), each column holding the weights of one query
+                let kt = self.tape.transpose(kh);
+                let scores = self.tape.dot(kt, qh);
+                let scores = self.tape.scalar_div(scores, (head_size as Scalar).sqrt());
+                let weights = self.tape.map_grad_dep(scores, stablesoftmax, softmax_prime);
+
+                attended_heads.push(self.tape.dot(vh, weights));
+            }
+            attended_samples.push(self.tape.concat_rows(&attended_heads));
+        }
+        let attended = self.tape.concat_columns(&attended_samples);
+
+        self.vars = Some((queries, keys, values, attended));
+        self.outputs.forward(self.tape.value(attended).clone())
+    }
+
+    /// `output_gradient` has shape `(d, t * n)`.
+    ///
+    /// Returns the `(d, t * n)` tokens gradient.
+    pub fn backward_tokens(&mut self, epoch: usize, output_gradient: Matrix) -> Matrix {
+        let (queries, keys, values, attended) = self.vars.unwrap();
+        let attended_gradient = self.outputs.backward(epoch, output_gradient);
+
+        let mut grads = self.tape.backward(attended, attended_gradient);
+        let queries_gradient = grads.take(queries).unwrap();
+        let keys_gradient = grads.take(keys).unwrap();
+        let values_gradient = grads.take(values).unwrap();
+
+        self.queries
+            .backward(epoch, queries_gradient)
+            .component_add(&self.keys.backward(epoch, keys_gradient))
+            .component_add(&self.values.backward(epoch, values_gradient))
+    }
+
+    pub fn features(&self) -> usize {
+        self.features
+    }
+
+    fn projections(&self) -> [&DenseLayer; 4] {
+        [&self.queries, &self.keys, &self.values, &self.outputs]
+    }
+
+    fn projections_mut(&mut self) -> [&mut DenseLayer; 4] {
+        [
+            &mut self.queries,
+            &mut self.keys,
+            &mut self.values,
+            &mut self.outputs,
+        ]
+    }
+}
+
+impl Layer for MultiHeadAttentionLayer {
+    /// `input` has shape `(t * d, n)`, see `sequence_to_tokens`.
+    ///
+    /// Returns output which has shape `(t * d, n)`.
+    fn forward(&mut self, input: Matrix) -> Matrix {
+        let time_steps = input.dim().0 / self.features;
+        let tokens = sequence_to_tokens(&input, self.features);
+        let output = self.forward_tokens(tokens, time_steps);
+        tokens_to_sequence(&output, time_steps)
+    }
+
+    fn backward(&mut self, epoch: usize, output_gradient: Matrix) -> Matrix {
+        let output_gradient = sequence_to_tokens(&output_gradient, self.features);
+        let input_gradient = self.backward_tokens(epoch, output_gradient);
+        tokens_to_sequence(&input_gradient, self.time_steps)
+    }
+
+    fn regularization_loss(&self) -> Scalar {
+        self.projections().iter().map(|p| p.regularization_loss()).sum()
+    }
+}
+
+impl GradientLayer for MultiHeadAttentionLayer {
+    fn gradients_mut(&mut self) -> Vec<GradientMut<'_>> {
+        self.projections_mut()
+            .into_iter()
+            .flat_map(|p| p.gradients_mut())
+            .collect()
+    }
+
+    fn zero_grad(&mut self) {
+        for projection in self.projections_mut() {
+            projection.zero_grad();
+        }
+    }
+
+    fn step(&mut self, epoch: usize) {
+        for projection in self.projections_mut() {
+            projection.step(epoch);
+        }
+    }
+}
+
+impl LearnableLayer for MultiHeadAttentionLayer {
+    // returns the parameters of the queries, keys, values and outputs projections one after the other
+    fn get_learnable_parameters(&self) -> Vec<Vec<Scalar>> {
+        let mut params = vec![];
+        for projection in self.projections() {
+            params.append(&mut projection.get_learnable_parameters());
+        }
+        params
+    }
+
+    // takes the parameters of the queries, keys, values and outputs projections one after the other
+    fn set_learnable_parameters(&mut self, params_matrix: &Vec<Vec<Scalar>>) {
+        // each projection has d weights columns and 1 biases column
+        let mut chunks = params_matrix.chunks(self.features + 1);
+        for projection in [
+            &mut self.queries,
+            &mut self.keys,
+            &mut self.values,
+            &mut self.outputs,
+        ] {
+            projection.set_learnable_parameters(&chunks.next().unwrap().to_vec());
+        }
+    }
+}
+
+impl ParameterableLayer for MultiHeadAttentionLayer {
+    fn as_learnable_layer(&self) -> Option<&dyn LearnableLayer> {
+        Some(self)
+    }
+
+    fn as_learnable_layer_mut(&mut self) -> Option<&mut dyn LearnableLayer> {
+        Some(self)
+    }
+
+    fn as_dropout_layer(&mut self) -> Option<&mut dyn DropoutLayer> {
+        None
+    }
+}
+
+impl NetworkLayer for MultiHeadAttentionLayer {}
+
+impl fmt::Debug for MultiHeadAttentionLayer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Multi-Head Attention Layer ({} heads)", self.heads)
+    }
+}
@@ -0,0 +1,346 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    activation::{
+        sigmoid::{sigmoid, sigmoid_prime},
+        tanh::{tanh, tanh_prime},
+    },
+    autodiff::{Tape, Var},
+    gradient_clipping::GradientMut,
+    initializers::Initializers,
+    linalg::{Matrix, MatrixTrait, Scalar},
+    network::NetworkLayer,
+    optimizer::Optimizers,
+};
+
+use super::{
+    accumulate_gradient, DropoutLayer, GradientLayer, Layer, LearnableLayer, ParameterableLayer,
+};
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub enum RecurrentCells {
+    /// Elman RNN: `h = tanh(W.x + U.h + b)`
+    SimpleRNN,
+    /// https://www.bioinf.jku.at/publications/older/2604.pdf
+    LSTM,
+    /// https://arxiv.org/pdf/1406.1078.pdf
+    GRU,
+}
+
+impl RecurrentCells {
+    /// Number of `(W, U, b)` parameter triplets of the cell.
+    pub fn gates(&self) -> usize {
+        match self {
+            RecurrentCells::SimpleRNN => 1,
+            // input, forget, output and candidate
+            RecurrentCells::LSTM => 4,
+            // update, reset and candidate
+            RecurrentCells::GRU => 3,
+        }
+    }
+}
+
+/// Recurrent layer trained with backpropagation through time.
+///
+/// As a `Layer`, its input is a flattened window of `t` time steps of `f` features each,
+/// with shape `(t * f, n)`, the features of step `s` being the rows `s * f..(s + 1) * f`.
+/// Its output is the last hidden state `(h, n)`, or all of them `(t * h, n)` when returning sequences.
+pub struct RecurrentLayer {
+    cell: RecurrentCells,
+    in_features: usize,
+    hidden_size: usize,
+    return_sequences: bool,
+    // the sequence is split into blocks of `truncation` steps and gradients don't cross block boundaries
+    truncation: Option<usize>,
+    // (W (h, f), U (h, h), b (h, 1)) for each gate, one after the other
+    parameters: Vec<Matrix>,
+    optimizers: Vec<Optimizers>,
+    // one per parameter, accumulated by backward and applied by step
+    gradients: Vec<Option<Matrix>>,
+    tape: Tape<Matrix>,
+    parameters_vars: Vec<Var>,
+    inputs_vars: Vec<Var>,
+    hidden_vars: Vec<Var>,
+}
+
+impl RecurrentLayer {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        cell: RecurrentCells,
+        in_features: usize,
+        hidden_size: usize,
+        return_sequences: bool,
+        truncation: Option<usize>,
+        weights_initializer: Initializers,
+        biases_initializer: Initializers,
+        weights_optimizer: Optimizers,
+        biases_optimizer: Optimizers,
+    ) -> Self {
+        let mut parameters = vec![];
+        let mut optimizers = vec![];
+        for _ in 0..cell.gates() {
+            parameters.push(weights_initializer.gen_matrix(hidden_size, in_features));
+            parameters.push(weights_initializer.gen_matrix(hidden_size, hidden_size));
+            parameters.push(biases_initializer.gen_vector(hidden_size));
+            optimizers.push(weights_optimizer.clone());
+            optimizers.push(weights_optimizer.clone());
+            optimizers.push(biases_optimizer.clone());
+        }
+
+        Self {
+            cell,
+            in_features,
+            hidden_size,
+            return_sequences,
+            truncation,
+            gradients: vec![None; parameters.len()],
+            parameters,
+            optimizers,
+            tape: Tape::new(),
+            parameters_vars: vec![],
+            inputs_vars: vec![],
+            hidden_vars: vec![],
+        }
+    }
+
+    /// `steps` has `t` matrices of shape `(f, n)`, one per time step.
+    ///
+    /// Returns the `t` hidden states, with shape `(h, n)`.
+    pub fn forward_sequence(&mut self, steps: Vec<Matrix>) -> Vec<Matrix> {
+        let n = steps[0].dim().1;
+        self.tape.clear();
+        self.parameters_vars = self
+            .parameters
+            .iter()
+            .map(|p| self.tape.var(p.clone()))
+            .collect();
+        self.inputs_vars = steps.into_iter().map(|s| self.tape.var(s)).collect();
+        self.hidden_vars = vec![];
+
+        let ones = self.tape.constant(Matrix::constant(1, n, 1.0));
+        let mut hidden = self.tape.constant(Matrix::zeros(self.hidden_size, n));
+        let mut cell_state = self.tape.constant(Matrix::zeros(self.hidden_size, n));
+
+        for step in 0..self.inputs_vars.len() {
+            if let Some(truncation) = self.truncation {
+                if step > 0 && step % truncation == 0 {
+                    hidden = self.tape.constant(self.tape.value(hidden).clone());
+                    cell_state = self.tape.constant(self.tape.value(cell_state).clone());
+                }
+            }
+
+            let input = self.inputs_vars[step];
+            (hidden, cell_state) = self.cell_step(input, hidden, cell_state, ones);
+            self.hidden_vars.push(hidden);
+        }
+
+        self.hidden_vars
+            .iter()
+            .map(|h| self.tape.value(*h).clone())
+            .collect()
+    }
+
+    /// `hidden_gradients` has `t` matrices of shape `(h, n)`, the gradients of each hidden state.
+    ///
+    /// Returns the `t` input gradients, with shape `(f, n)`.
+    pub fn backward_sequence(&mut self, _epoch: usize, hidden_gradients: Vec<Matrix>) -> Vec<Matrix> {
+        let seeds = self
+            .hidden_vars
+            .iter()
+            .cloned()
+            .zip(hidden_gradients)
+            .collect();
+        self.backpropagate(seeds)
+    }
+
+    fn backpropagate(&mut self, seeds: Vec<(Var, Matrix)>) -> Vec<Matrix> {
+        let mut grads = self.tape.backward_many(seeds);
+
+        for (var, acc) in self.parameters_vars.iter().zip(self.gradients.iter_mut()) {
+            if let Some(gradient) = grads.take(*var) {
+                accumulate_gradient(acc, gradient);
+            }
+        }
+
+        self.inputs_vars
+            .iter()
+            .map(|x| {
+                let (nrow, ncol) = self.tape.value(*x).dim();
+                grads.take(*x).unwrap_or_else(|| Matrix::zeros(nrow, ncol))
+            })
+            .collect()
+    }
+
+    /// `W_g . x + U_g . h + b_g`
+    fn gate(&mut self, gate: usize, input: Var, hidden: Var, ones: Var) -> Var {
+        let (w, u, b) = (
+            self.parameters_vars[3 * gate],
+            self.parameters_vars[3 * gate + 1],
+            self.parameters_vars[3 * gate + 2],
+        );
+        let weighted_input = self.tape.dot(w, input);
+        let weighted_hidden = self.tape.dot(u, hidden);
+        let biases = self.tape.dot(b, ones);
+        let sum = self.tape.add(weighted_input, weighted_hidden);
+        self.tape.add(sum, biases)
+    }
+
+    /// Returns the next hidden state and cell state (only used by LSTMs).
+    fn cell_step(&mut self, input: Var, hidden: Var, cell_state: Var, ones: Var) -> (Var, Var) {
+        match self.cell {
+            RecurrentCells::SimpleRNN => {
+                let z = self.gate(0, input, hidden, ones);
+                let hidden = self.tape.map(z, tanh, tanh_prime);
+                (hidden, cell_state)
+            }
+            RecurrentCells::LSTM => {
+                let i = self.gate(0, input, hidden, ones);
+                let i = self.tape.map(i, sigmoid, sigmoid_prime);
+                let f = self.gate(1, input, hidden, ones);
+                let f = self.tape.map(f, sigmoid, sigmoid_prime);
+                let o = self.gate(2, input, hidden, ones);
+                let o = self.tape.map(o, sigmoid, sigmoid_prime);
+                let g = self.gate(3, input, hidden, ones);
+                let g = self.tape.map(g, tanh, tanh_prime);
+
+                // c = f ⊙ c + i ⊙ g
+                let kept = self.tape.mul(f, cell_state);
+                let added = self.tape.mul(i, g);
+                let cell_state = self.tape.add(kept, added);
+                // h = o ⊙ tanh(c)
+                let activated = self.tape.map(cell_state, tanh, tanh_prime);
+                let hidden = self.tape.mul(o, activated);
+                (hidden, cell_state)
+            }
+            RecurrentCells::GRU => {
+                let z = self.gate(0, input, hidden, ones);
+                let z = self.tape.map(z, sigmoid, sigmoid_prime);
+                let r = self.gate(1, input, hidden, ones);
+                let r = self.tape.map(r, sigmoid, sigmoid_prime);
+
+                // ĥ = tanh(W . x + U . (r ⊙ h) + b)
+                let reset_hidden = self.tape.mul(r, hidden);
+                let candidate = self.gate(2, input, reset_hidden, ones);
+                let candidate = self.tape.map(candidate, tanh, tanh_prime);
+
+                // h = h + z ⊙ (ĥ - h)
+                let delta = self.tape.sub(candidate, hidden);
+                let update = self.tape.mul(z, delta);
+                let hidden = self.tape.add(hidden, update);
+                (hidden, cell_state)
+            }
+        }
+    }
+
+    fn split_steps(input: &Matrix, size: usize) -> Vec<Matrix> {
+        let rows = input.get_data_row_leading();
+        rows.chunks(size)
+            .map(|chunk| Matrix::from_row_leading_vector2(&chunk.to_vec()))
+            .collect()
+    }
+
+    fn join_steps(steps: &[Matrix]) -> Matrix {
+        let mut rows = vec![];
+        for step in steps.iter() {
+            rows.append(&mut step.get_data_row_leading());
+        }
+        Matrix::from_row_leading_vector2(&rows)
+    }
+}
+
+impl Layer for RecurrentLayer {
+    fn forward(&mut self, input: Matrix) -> Matrix {
+        let steps = Self::split_steps(&input, self.in_features);
+        let hidden_states = self.forward_sequence(steps);
+
+        if self.return_sequences {
+            Self::join_steps(&hidden_states)
+        } else {
+            hidden_states.last().unwrap().clone()
+        }
+    }
+
+    fn backward(&mut self, epoch: usize, output_gradient: Matrix) -> Matrix {
+        let inputs_gradients = if self.return_sequences {
+            let hidden_gradients = Self::split_steps(&output_gradient, self.hidden_size);
+            self.backward_sequence(epoch, hidden_gradients)
+        } else {
+            let last = *self.hidden_vars.last().unwrap();
+            self.backpropagate(vec![(last, output_gradient)])
+        };
+
+        Self::join_steps(&inputs_gradients)
+    }
+}
+
+impl GradientLayer for RecurrentLayer {
+    fn gradients_mut(&mut self) -> Vec<GradientMut<'_>> {
+        self.gradients
+            .iter_mut()
+            .flatten()
+            .map(GradientMut::Matrix)
+            .collect()
+    }
+
+    fn zero_grad(&mut self) {
+        self.gradients.iter_mut().for_each(|g| *g = None);
+    }
+
+    fn step(&mut self, epoch: usize) {
+        for ((param, gradient), optimizer) in self
+            .parameters
+            .iter_mut()
+            .zip(self.gradients.iter_mut())
+            .zip(self.optimizers.iter_mut())
+        {
+            if let Some(gradient) = gradient.take() {
+                *param = optimizer.update_parameters(epoch, param, &gradient);
+            }
+        }
+    }
+}
+
+impl LearnableLayer for RecurrentLayer {
+    // returns the columns of W, U and b of each gate one after the other
+    fn get_learnable_parameters(&self) -> Vec<Vec<Scalar>> {
+        let mut params = vec![];
+        for param in self.parameters.iter() {
+            params.append(&mut param.get_data_col_leading());
+        }
+        params
+    }
+
+    // takes the columns of W, U and b of each gate one after the other
+    fn set_learnable_parameters(&mut self, params_matrix: &Vec<Vec<Scalar>>) {
+        let mut columns = params_matrix.iter();
+        for param in self.parameters.iter_mut() {
+            let ncol = param.dim().1;
+            let param_columns: Vec<Vec<Scalar>> = columns.by_ref().take(ncol).cloned().collect();
+            *param = Matrix::from_column_leading_vector2(&param_columns);
+        }
+    }
+}
+
+impl ParameterableLayer for RecurrentLayer {
+    fn as_learnable_layer(&self) -> Option<&dyn LearnableLayer> {
+        Some(self)
+    }
+
+    fn as_learnable_layer_mut(&mut self) -> Option<&mut dyn LearnableLayer> {
+        Some(self)
+    }
+
+    fn as_dropout_layer(&mut self) -> Option<&mut dyn DropoutLayer> {
+        None
+    }
+}
+
+impl NetworkLayer for RecurrentLayer {}
+
+impl fmt::Debug for RecurrentLayer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Recurrent Layer ({:?})", self.cell)
+    }
+}
@@ -0,0 +1,230 @@
+use std::fmt;
+
+use crate::{
+    activation::{relu, ActivationLayer},
+    gradient_clipping::GradientMut,
+    initializers::Initializers,
+    linalg::{Matrix, MatrixTrait, Scalar},
+    network::NetworkLayer,
+    optimizer::Optimizers,
+};
+
+use super::{
+    dense_layer::DenseLayer,
+    layer_norm_layer::LayerNormLayer,
+    multi_head_attention_layer::{sequence_to_tokens, tokens_to_sequence, MultiHeadAttentionLayer},
+    join_learnable_parameters, split_learnable_parameters, DropoutLayer, GradientLayer, Layer,
+    LearnableLayer, ParameterableLayer,
+};
+
+/// Encoder block of the original transformer (post layer normalization):
+///
+/// `Z = LayerNorm(X + MultiHeadAttention(X))`
+///
+/// `Y = LayerNorm(Z + Dense(ReLU(Dense(Z))))`
+///
+/// Input and output are flattened sequences of `t` tokens of `d` features, with shape `(t * d, n)`.
+pub struct TransformerEncoderBlock {
+    attention: MultiHeadAttentionLayer,
+    attention_norm: LayerNormLayer,
+    feed_forward_in: DenseLayer,
+    feed_forward_activation: ActivationLayer,
+    feed_forward_out: DenseLayer,
+    feed_forward_norm: LayerNormLayer,
+    time_steps: usize,
+}
+
+impl TransformerEncoderBlock {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        features: usize,
+        heads: usize,
+        feed_forward_size: usize,
+        epsilon: Scalar,
+        weights_optimizer: Optimizers,
+        biases_optimizer: Optimizers,
+        weights_initializer: Initializers,
+        biases_initializer: Initializers,
+    ) -> Self {
+        let dense = |i, j| {
+            DenseLayer::new(
+                i,
+                j,
+                weights_optimizer.clone(),
+                biases_optimizer.clone(),
+                weights_initializer.clone(),
+                biases_initializer.clone(),
+            )
+        };
+        let layer_norm = || {
+            LayerNormLayer::new(
+                features,
+                epsilon,
+                weights_optimizer.clone(),
+                biases_optimizer.clone(),
+            )
+        };
+
+        Self {
+            attention: MultiHeadAttentionLayer::new(
+                features,
+                heads,
+                weights_optimizer.clone(),
+                biases_optimizer.clone(),
+                weights_initializer.clone(),
+                biases_initializer.clone(),
+            ),
+            attention_norm: layer_norm(),
+            feed_forward_in: dense(features, feed_forward_size),
+            feed_forward_activation: relu::new(),
+            feed_forward_out: dense(feed_forward_size, features),
+            feed_forward_norm: layer_norm(),
+            time_steps: 0,
+        }
+    }
+
+    fn learnable_layers(&self) -> [&dyn LearnableLayer; 5] {
+        [
+            &self.attention,
+            &self.attention_norm,
+            &self.feed_forward_in,
+            &self.feed_forward_out,
+            &self.feed_forward_norm,
+        ]
+    }
+
+    fn layers_mut(&mut self) -> [&mut dyn Layer; 6] {
+        [
+            &mut self.attention,
+            &mut self.attention_norm,
+            &mut self.feed_forward_in,
+            &mut self.feed_forward_activation,
+            &mut self.feed_forward_out,
+            &mut self.feed_forward_norm,
+        ]
+    }
+}
+
+impl Layer for TransformerEncoderBlock {
+    fn forward(&mut self, input: Matrix) -> Matrix {
+        let features = self.attention.features();
+        self.time_steps = input.dim().0 / features;
+        let tokens = sequence_to_tokens(&input, features);
+
+        let attended = self.attention.forward_tokens(tokens.clone(), self.time_steps);
+        let normalized = self.attention_norm.forward(tokens.component_add(&attended));
+
+        let hidden = self.feed_forward_in.forward(normalized.clone());
+        let hidden = self.feed_forward_activation.forward(hidden);
+        let fed_forward = self.feed_forward_out.forward(hidden);
+        let output = self
+            .feed_forward_norm
+            .forward(normalized.component_add(&fed_forward));
+
+        tokens_to_sequence(&output, self.time_steps)
+    }
+
+    fn backward(&mut self, epoch: usize, output_gradient: Matrix) -> Matrix {
+        let features = self.attention.features();
+        let output_gradient = sequence_to_tokens(&output_gradient, features);
+
+        // both the residual and the feed forward sublayer receive the gradient
+        let residual_gradient = self.feed_forward_norm.backward(epoch, output_gradient);
+        let hidden_gradient = self
+            .feed_forward_out
+            .backward(epoch, residual_gradient.clone());
+        let hidden_gradient = self
+            .feed_forward_activation
+            .backward(epoch, hidden_gradient);
+        let normalized_gradient = self
+            .feed_forward_in
+            .backward(epoch, hidden_gradient)
+            .component_add(&residual_gradient);
+
+        let residual_gradient = self.attention_norm.backward(epoch, normalized_gradient);
+        let input_gradient = self
+            .attention
+            .backward_tokens(epoch, residual_gradient.clone())
+            .component_add(&residual_gradient);
+
+        tokens_to_sequence(&input_gradient, self.time_steps)
+    }
+
+    fn regularization_loss(&self) -> Scalar {
+        self.attention.regularization_loss()
+            + self.attention_norm.regularization_loss()
+            + self.feed_forward_in.regularization_loss()
+            + self.feed_forward_out.regularization_loss()
+            + self.feed_forward_norm.regularization_loss()
+    }
+}
+
+impl GradientLayer for TransformerEncoderBlock {
+    fn gradients_mut(&mut self) -> Vec<GradientMut<'_>> {
+        self.layers_mut()
+            .into_iter()
+            .flat_map(|l| l.gradients_mut())
+            .collect()
+    }
+
+    fn zero_grad(&mut self) {
+        for layer in self.layers_mut() {
+            layer.zero_grad();
+        }
+    }
+
+    fn step(&mut self, epoch: usize) {
+        for layer in self.layers_mut() {
+            layer.step(epoch);
+        }
+    }
+}
+
+impl LearnableLayer for TransformerEncoderBlock {
+    // returns the parameters of the attention, its layer norm, both feed forward dense layers
+    // and their layer norm, each one preceded by its amount of columns
+    fn get_learnable_parameters(&self) -> Vec<Vec<Scalar>> {
+        join_learnable_parameters(
+            self.learnable_layers()
+                .iter()
+                .map(|layer| layer.get_learnable_parameters())
+                .collect(),
+        )
+    }
+
+    fn set_learnable_parameters(&mut self, params_matrix: &Vec<Vec<Scalar>>) {
+        let mut parts = split_learnable_parameters(params_matrix).into_iter();
+        let layers: [&mut dyn LearnableLayer; 5] = [
+            &mut self.attention,
+            &mut self.attention_norm,
+            &mut self.feed_forward_in,
+            &mut self.feed_forward_out,
+            &mut self.feed_forward_norm,
+        ];
+        for layer in layers {
+            layer.set_learnable_parameters(&parts.next().unwrap());
+        }
+    }
+}
+
+impl ParameterableLayer for TransformerEncoderBlock {
+    fn as_learnable_layer(&self) -> Option<&dyn LearnableLayer> {
+        Some(self)
+    }
+
+    fn as_learnable_layer_mut(&mut self) -> Option<&mut dyn LearnableLayer> {
+        Some(self)
+    }
+
+    fn as_dropout_layer(&mut self) -> Option<&mut dyn DropoutLayer> {
+        None
+    }
+}
+
+impl NetworkLayer for TransformerEncoderBlock {}
+
+impl fmt::Debug for TransformerEncoderBlock {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Transformer Encoder Block")
+    }
+}
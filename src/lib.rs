@@ -7,6 +7,8 @@ extern crate assert_float_eq;
 pub mod monitor;
 /// Activation functions and abstractions (sigmoid, relu, softmax...)
 pub mod activation;
+/// Reverse-mode automatic differentiation (tape, differentiable operations...)
+pub mod autodiff;
 /// Model performance benchmarking utilities
 pub mod benchmarking;
 #[cfg(feature = "data")]
@@ -15,6 +17,8 @@ pub mod dataset;
 #[cfg(feature = "data")]
 /// Wrapper around dataframes libaries
 pub mod datatable;
+/// Gradients clipping (by value, by layer norm, by global norm)
+pub mod gradient_clipping;
 /// Parameters initializers and abstractions (uniform, glorot...)
 pub mod initializers;
 /// Layers and abstractions (dense, full...)
@@ -34,6 +38,8 @@ pub mod optimizer;
 #[cfg(feature = "data")]
 /// Preprocessing and pipelining utilities (normalization, one-hot encoding...)
 pub mod preprocessing;
+/// Weights regularization (L1, L2, elastic net)
+pub mod regularization;
 /// Training methodologies (k-fold, split...)
 pub mod trainers;
 /// Utilities for `Vec<Scalar>`, `Vec<Vec<Scalar>>`...
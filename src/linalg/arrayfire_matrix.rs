@@ -297,6 +297,11 @@ impl MatrixTrait for Matrix {
         Self(sign(&self.0)).scalar_mul(-2.0).scalar_add(1.0)
     }
 
+    fn sign_zero_at_zero(&self) -> Self {
+        // arrayfire's sign is 1 for negative components and 0 otherwise
+        Self(sign(&self.scalar_mul(-1.0).0)).component_sub(&Self(sign(&self.0)))
+    }
+
     fn minof(&self, other: &Self) -> Self {
         Self(minof(&self.0, &other.0, false))
     }
@@ -34,6 +34,17 @@ pub use ndarray_matrix::Matrix;
 #[cfg(all(feature = "ndarray", not(feature = "arrayfire"), not(feature = "nalgebra")))]
 pub const BACKEND: Backends = Backends::Ndarray;
 
+/// Sign of `x`, 0 when `x` is 0 unlike `Scalar::signum`.
+pub fn sign_zero_at_zero(x: Scalar) -> Scalar {
+    if x > 0. {
+        1.
+    } else if x < 0. {
+        -1.
+    } else {
+        0.
+    }
+}
+
 pub trait MatrixTrait: Clone {
     fn is_backend_thread_safe() -> bool;
 
@@ -152,6 +163,9 @@ pub trait MatrixTrait: Clone {
 
     fn sign(&self) -> Self;
 
+    /// Same as `sign` but 0 where the components are 0.
+    fn sign_zero_at_zero(&self) -> Self;
+
     fn minof(&self, other: &Self) -> Self;
 
     fn sqrt(&self) -> Self;
@@ -4,7 +4,7 @@ use nalgebra::{DMatrix, DVector};
 use rand::Rng;
 use rand_distr::Distribution;
 
-use super::{MatrixTrait, Scalar};
+use super::{sign_zero_at_zero, MatrixTrait, Scalar};
 
 /// Column leading nalgebra Matrix
 
@@ -256,6 +256,10 @@ impl MatrixTrait for Matrix {
         Self(self.0.clone().map(|x| x.signum()))
     }
 
+    fn sign_zero_at_zero(&self) -> Self {
+        Self(self.0.clone().map(sign_zero_at_zero))
+    }
+
     fn minof(&self, other: &Self) -> Self {
         Self(self.0.clone().map(|x| x.min(other.0[(0, 0)])))
     }
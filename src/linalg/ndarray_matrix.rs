@@ -3,7 +3,7 @@ use std::{fmt};
 use ndarray::{Array2};
 use rand::Rng;
 
-use super::{MatrixTrait, Scalar};
+use super::{sign_zero_at_zero, MatrixTrait, Scalar};
 
 #[derive(Clone, Debug)]
 pub struct Matrix(pub Array2<Scalar>);
@@ -86,7 +86,7 @@ impl MatrixTrait for Matrix {
         let mut mat = Array2::<Scalar>::zeros((columns[0].0.nrows(), columns.len()));
 
         for i in 0..columns.len() {
-            mat.column_mut(i).assign(&columns[i].0);
+            mat.column_mut(i).assign(&columns[i].0.column(0));
         }
 
         Self(mat)
@@ -224,6 +224,10 @@ impl MatrixTrait for Matrix {
         Self(self.0.mapv(Scalar::signum))
     }
 
+    fn sign_zero_at_zero(&self) -> Self {
+        Self(self.0.mapv(sign_zero_at_zero))
+    }
+
     fn minof(&self, other: &Self) -> Self {
         let mat = Array2::from_shape_fn(
             (
@@ -0,0 +1,44 @@
+use crate::{
+    linalg::{Matrix, MatrixTrait, Scalar},
+    loss::Loss,
+};
+
+// keeps log(0) out of the loss and its gradient
+const EPSILON: Scalar = 1e-7;
+
+/// `y_true` has shape `(k, n)`, each column being a one-hot encoded class among `k`.
+pub fn smooth_labels(y_true: &Matrix, label_smoothing: Scalar) -> Matrix {
+    let classes = y_true.dim().0 as Scalar;
+    y_true
+        .scalar_mul(1. - label_smoothing)
+        .scalar_add(label_smoothing / classes)
+}
+
+fn clipped(y_pred: &Matrix) -> Matrix {
+    y_pred.maxof(&Matrix::constant(y_pred.dim().0, y_pred.dim().1, EPSILON))
+}
+
+/// Averages the cross entropy of each sample over the `n` samples.
+pub fn cce(y_true: &Matrix, y_pred: &Matrix) -> Scalar {
+    -y_true.component_mul(&clipped(y_pred).log()).sum() / y_true.dim().1 as Scalar
+}
+
+// like the other losses, the gradient is summed (not averaged) over the batch's samples
+pub fn cce_prime(y_true: &Matrix, y_pred: &Matrix) -> Matrix {
+    y_true.component_div(&clipped(y_pred)).scalar_mul(-1.)
+}
+
+/// `∂E/∂Z = Y_pred - Y_true` where `Y_pred = softmax(Z)`
+pub fn cce_softmax_prime(y_true: &Matrix, y_pred: &Matrix) -> Matrix {
+    y_pred.component_sub(y_true)
+}
+
+pub fn new(label_smoothing: Scalar) -> Loss {
+    Loss::new(
+        move |y_true, y_pred| cce(&smooth_labels(y_true, label_smoothing), y_pred),
+        move |y_true, y_pred| cce_prime(&smooth_labels(y_true, label_smoothing), y_pred),
+    )
+    .with_softmax_derivative(move |y_true, y_pred| {
+        cce_softmax_prime(&smooth_labels(y_true, label_smoothing), y_pred)
+    })
+}
@@ -0,0 +1,67 @@
+use crate::{
+    linalg::{Matrix, MatrixTrait, Scalar},
+    loss::Loss,
+};
+
+// keeps log(0) out of the loss and its gradient
+const EPSILON: Scalar = 1e-7;
+
+fn clipped(y_pred: &Matrix) -> Matrix {
+    let (nrow, ncol) = y_pred.dim();
+    y_pred
+        .maxof(&Matrix::constant(nrow, ncol, EPSILON))
+        .minof(&Matrix::constant(nrow, ncol, 1. - EPSILON))
+}
+
+fn pow(m: &Matrix, exponent: Scalar) -> Matrix {
+    m.log().scalar_mul(exponent).exp()
+}
+
+fn ones_minus(m: &Matrix) -> Matrix {
+    m.scalar_mul(-1.).scalar_add(1.)
+}
+
+/// `-alpha (1 - p)^gamma log(p)` for positive labels, `-(1 - alpha) p^gamma log(1 - p)` for negative ones
+fn focal(y_true: &Matrix, y_pred: &Matrix, gamma: Scalar, alpha: Scalar) -> Scalar {
+    let p = clipped(y_pred);
+    let q = ones_minus(&p);
+
+    let positive = y_true
+        .component_mul(&pow(&q, gamma))
+        .component_mul(&p.log())
+        .scalar_mul(alpha);
+    let negative = ones_minus(y_true)
+        .component_mul(&pow(&p, gamma))
+        .component_mul(&q.log())
+        .scalar_mul(1. - alpha);
+
+    -positive.component_add(&negative).mean()
+}
+
+fn focal_prime(y_true: &Matrix, y_pred: &Matrix, gamma: Scalar, alpha: Scalar) -> Matrix {
+    let p = clipped(y_pred);
+    let q = ones_minus(&p);
+
+    // ∂/∂p -(1 - p)^gamma log(p) = gamma (1 - p)^(gamma - 1) log(p) - (1 - p)^gamma / p
+    let positive = pow(&q, gamma - 1.)
+        .component_mul(&p.log())
+        .scalar_mul(gamma)
+        .component_sub(&pow(&q, gamma).component_div(&p))
+        .component_mul(y_true)
+        .scalar_mul(alpha);
+    // ∂/∂p -p^gamma log(1 - p) = p^gamma / (1 - p) - gamma p^(gamma - 1) log(1 - p)
+    let negative = pow(&p, gamma)
+        .component_div(&q)
+        .component_sub(&pow(&p, gamma - 1.).component_mul(&q.log()).scalar_mul(gamma))
+        .component_mul(&ones_minus(y_true))
+        .scalar_mul(1. - alpha);
+
+    positive.component_add(&negative)
+}
+
+pub fn new(gamma: Scalar, alpha: Scalar) -> Loss {
+    Loss::new(
+        move |y_true, y_pred| focal(y_true, y_pred, gamma, alpha),
+        move |y_true, y_pred| focal_prime(y_true, y_pred, gamma, alpha),
+    )
+}
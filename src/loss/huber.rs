@@ -0,0 +1,32 @@
+use crate::{
+    linalg::{Matrix, MatrixTrait, Scalar},
+    loss::Loss,
+};
+
+// quadratic for errors smaller than delta, linear beyond
+
+fn huber(y_true: &Matrix, y_pred: &Matrix, delta: Scalar) -> Scalar {
+    let errors = y_pred.component_sub(y_true);
+    let abs_errors = errors.component_mul(&errors.sign());
+    let deltas = Matrix::constant(errors.dim().0, errors.dim().1, delta);
+    // min(|e|, δ) * (|e| - min(|e|, δ) / 2)
+    let quadratic = abs_errors.minof(&deltas);
+    quadratic
+        .component_mul(&abs_errors.component_sub(&quadratic.scalar_mul(0.5)))
+        .mean()
+}
+
+fn huber_prime(y_true: &Matrix, y_pred: &Matrix, delta: Scalar) -> Matrix {
+    let errors = y_pred.component_sub(y_true);
+    let (nrow, ncol) = errors.dim();
+    errors
+        .minof(&Matrix::constant(nrow, ncol, delta))
+        .maxof(&Matrix::constant(nrow, ncol, -delta))
+}
+
+pub fn new(delta: Scalar) -> Loss {
+    Loss::new(
+        move |y_true, y_pred| huber(y_true, y_pred, delta),
+        move |y_true, y_pred| huber_prime(y_true, y_pred, delta),
+    )
+}
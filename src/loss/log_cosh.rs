@@ -0,0 +1,25 @@
+use crate::{
+    activation::sigmoid::sigmoid,
+    linalg::{Matrix, MatrixTrait, Scalar},
+    loss::Loss,
+};
+
+fn log_cosh(y_true: &Matrix, y_pred: &Matrix) -> Scalar {
+    // log(cosh(e)) = |e| + log(1 + e^(-2|e|)) - log(2) doesn't overflow for large errors
+    let errors = y_pred.component_sub(y_true);
+    let abs_errors = errors.component_mul(&errors.sign());
+    abs_errors
+        .component_add(&abs_errors.scalar_mul(-2.).exp().scalar_add(1.).log())
+        .scalar_sub((2.0 as Scalar).ln())
+        .mean()
+}
+
+fn log_cosh_prime(y_true: &Matrix, y_pred: &Matrix) -> Matrix {
+    // tanh(e) = 2σ(2e) - 1
+    let errors = y_pred.component_sub(y_true);
+    sigmoid(&errors.scalar_mul(2.)).scalar_mul(2.).scalar_sub(1.)
+}
+
+pub fn new() -> Loss {
+    Loss::new(log_cosh, log_cosh_prime)
+}
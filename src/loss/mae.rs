@@ -0,0 +1,17 @@
+use crate::{
+    linalg::{Matrix, MatrixTrait, Scalar},
+    loss::Loss,
+};
+
+fn mae(y_true: &Matrix, y_pred: &Matrix) -> Scalar {
+    let errors = y_pred.component_sub(y_true);
+    errors.component_mul(&errors.sign()).mean()
+}
+
+fn mae_prime(y_true: &Matrix, y_pred: &Matrix) -> Matrix {
+    y_pred.component_sub(y_true).sign_zero_at_zero()
+}
+
+pub fn new() -> Loss {
+    Loss::new(mae, mae_prime)
+}
@@ -1,3 +1,6 @@
+use std::collections::HashMap;
+use std::sync::{Arc, OnceLock, RwLock};
+
 use serde::{Deserialize, Serialize};
 
 use crate::linalg::Matrix;
@@ -6,11 +9,47 @@ use crate::linalg::Scalar;
 
 pub mod mse;
 pub mod bce;
+pub mod cce;
+pub mod sparse_cce;
+pub mod mae;
+pub mod huber;
+pub mod log_cosh;
+pub mod quantile;
+pub mod focal;
 
 #[derive(Serialize, Debug, Deserialize, Clone)]
 pub enum Losses {
     MSE,
     BCE,
+    /// Cross entropy of one-hot encoded classes, each true class' label being smoothed to
+    /// `1 - label_smoothing + label_smoothing / k` (and the others' to `label_smoothing / k`)
+    CategoricalCrossEntropy {
+        #[serde(default)]
+        label_smoothing: Scalar,
+    },
+    /// Same as `CategoricalCrossEntropy`, the expected values being a single row of class ids
+    SparseCategoricalCrossEntropy {
+        #[serde(default)]
+        label_smoothing: Scalar,
+    },
+    MAE,
+    /// Quadratic for errors smaller than `delta`, linear beyond
+    Huber {
+        delta: Scalar,
+    },
+    LogCosh,
+    /// Pinball loss, estimating the `tau` quantile (`0.5` being the median)
+    Quantile {
+        tau: Scalar,
+    },
+    /// Binary focal loss, down-weighting the well classified samples by `(1 - p_t)^gamma`,
+    /// the positive class being weighted by `alpha` and the negative one by `1 - alpha`
+    Focal {
+        gamma: Scalar,
+        alpha: Scalar,
+    },
+    /// Loss registered with `register_loss` under this name
+    Custom(String),
 }
 
 impl Losses {
@@ -18,31 +57,205 @@ impl Losses {
         match self {
             Losses::MSE => mse::new(),
             Losses::BCE => bce::new(),
+            Losses::CategoricalCrossEntropy { label_smoothing } => cce::new(*label_smoothing),
+            Losses::SparseCategoricalCrossEntropy { label_smoothing } => {
+                sparse_cce::new(*label_smoothing)
+            }
+            Losses::MAE => mae::new(),
+            Losses::Huber { delta } => huber::new(*delta),
+            Losses::LogCosh => log_cosh::new(),
+            Losses::Quantile { tau } => quantile::new(*tau),
+            Losses::Focal { gamma, alpha } => focal::new(*gamma, *alpha),
+            Losses::Custom(name) => {
+                let loss = get_registered_loss(name)
+                    .unwrap_or_else(|| panic!("Loss {} is not registered", name));
+                Loss::from_loss_function(loss)
+            }
         }
     }
 }
 
-pub type LossFn = fn(&Matrix, &Matrix) -> Scalar;
-pub type LossPrimeFn = fn(&Matrix, &Matrix) -> Matrix;
+/// User-defined loss, usable in models as `Losses::Custom(name)` once registered with `register_loss`.
+pub trait LossFunction: Send + Sync {
+    fn loss(&self, y_true: &Matrix, y_pred: &Matrix) -> Scalar;
+
+    /// Returns `∂E/∂Y_pred` which has the same shape as `y_pred`.
+    fn loss_prime(&self, y_true: &Matrix, y_pred: &Matrix) -> Matrix;
+}
+
+type LossRegistry = RwLock<HashMap<String, Arc<dyn LossFunction>>>;
+
+fn loss_registry() -> &'static LossRegistry {
+    static REGISTRY: OnceLock<LossRegistry> = OnceLock::new();
+    REGISTRY.get_or_init(|| RwLock::new(HashMap::new()))
+}
+
+/// Makes `loss` available as `Losses::Custom(name)`, replacing any loss previously registered under `name`.
+pub fn register_loss(name: &str, loss: impl LossFunction + 'static) {
+    loss_registry()
+        .write()
+        .unwrap()
+        .insert(name.to_string(), Arc::new(loss));
+}
+
+pub fn get_registered_loss(name: &str) -> Option<Arc<dyn LossFunction>> {
+    loss_registry().read().unwrap().get(name).cloned()
+}
+
+pub type LossFn = Arc<dyn Fn(&Matrix, &Matrix) -> Scalar + Send + Sync>;
+pub type LossPrimeFn = Arc<dyn Fn(&Matrix, &Matrix) -> Matrix + Send + Sync>;
 
 pub struct Loss {
     loss: LossFn,
     derivative: LossPrimeFn,
+    // gradient of the logits of a final softmax, when it can be fused with the loss' one
+    softmax_derivative: Option<LossPrimeFn>,
+    class_weights: Option<Vec<Scalar>>,
 }
 
 impl Loss {
-    pub fn new(loss: LossFn, derivative: LossPrimeFn) -> Self {
-        Self { loss, derivative }
+    pub fn new(
+        loss: impl Fn(&Matrix, &Matrix) -> Scalar + Send + Sync + 'static,
+        derivative: impl Fn(&Matrix, &Matrix) -> Matrix + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            loss: Arc::new(loss),
+            derivative: Arc::new(derivative),
+            softmax_derivative: None,
+            class_weights: None,
+        }
+    }
+
+    /// `softmax_derivative` returns `∂E/∂Z` where `Z` is the input of the softmax producing `y_pred`.
+    pub fn with_softmax_derivative(
+        self,
+        softmax_derivative: impl Fn(&Matrix, &Matrix) -> Matrix + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            softmax_derivative: Some(Arc::new(softmax_derivative)),
+            ..self
+        }
+    }
+
+    /// Weights each sample by the weight of its class.
+    ///
+    /// The class of a sample is its one-hot encoded `y_true`'s class, or `y_true` itself
+    /// when it is a single value (binary labels, sparse class ids).
+    pub fn with_class_weights(self, class_weights: Vec<Scalar>) -> Self {
+        Self {
+            class_weights: Some(class_weights),
+            ..self
+        }
+    }
+
+    pub fn from_loss_function(loss: Arc<dyn LossFunction>) -> Self {
+        let derivative = loss.clone();
+        Self::new(
+            move |y_true, y_pred| loss.loss(y_true, y_pred),
+            move |y_true, y_pred| derivative.loss_prime(y_true, y_pred),
+        )
     }
 }
 
 impl Loss {
     pub fn loss(&self, y_true: &Matrix, y_pred: &Matrix) -> Scalar {
-        (self.loss)(y_true, y_pred)
+        self.weighted_loss(y_true, y_pred, None)
     }
 
     pub fn loss_prime(&self, y_true: &Matrix, y_pred: &Matrix) -> Matrix {
-        (self.derivative)(y_true, y_pred)
+        self.weighted_loss_prime(y_true, y_pred, None)
+    }
+
+    /// Gradient of the input of the softmax producing `y_pred`, if the loss has a fused one.
+    pub fn softmax_loss_prime(&self, y_true: &Matrix, y_pred: &Matrix) -> Option<Matrix> {
+        self.weighted_softmax_loss_prime(y_true, y_pred, None)
+    }
+
+    /// `sample_weights` has one weight per sample (column) of the batch, multiplied by the class weights if any.
+    ///
+    /// Returns the sum of the weighted samples' losses divided by the number of samples.
+    pub fn weighted_loss(
+        &self,
+        y_true: &Matrix,
+        y_pred: &Matrix,
+        sample_weights: Option<&Vec<Scalar>>,
+    ) -> Scalar {
+        match self.weights(y_true, sample_weights) {
+            Some(weights) => {
+                let total: Scalar = weights
+                    .iter()
+                    .enumerate()
+                    .map(|(j, w)| {
+                        w * (self.loss)(
+                            &y_true.get_column_as_matrix(j),
+                            &y_pred.get_column_as_matrix(j),
+                        )
+                    })
+                    .sum();
+                total / weights.len() as Scalar
+            }
+            None => (self.loss)(y_true, y_pred),
+        }
+    }
+
+    pub fn weighted_loss_prime(
+        &self,
+        y_true: &Matrix,
+        y_pred: &Matrix,
+        sample_weights: Option<&Vec<Scalar>>,
+    ) -> Matrix {
+        let gradient = (self.derivative)(y_true, y_pred);
+        Self::weigh_columns(gradient, self.weights(y_true, sample_weights))
+    }
+
+    pub fn weighted_softmax_loss_prime(
+        &self,
+        y_true: &Matrix,
+        y_pred: &Matrix,
+        sample_weights: Option<&Vec<Scalar>>,
+    ) -> Option<Matrix> {
+        let gradient = self
+            .softmax_derivative
+            .as_ref()
+            .map(|derivative| derivative(y_true, y_pred))?;
+        Some(Self::weigh_columns(
+            gradient,
+            self.weights(y_true, sample_weights),
+        ))
+    }
+
+    fn weights(&self, y_true: &Matrix, sample_weights: Option<&Vec<Scalar>>) -> Option<Vec<Scalar>> {
+        let class_weights = self.class_weights.as_ref().map(|class_weights| {
+            y_true
+                .get_data_col_leading()
+                .iter()
+                .map(|y| class_weight(class_weights, y))
+                .collect::<Vec<_>>()
+        });
+
+        match (class_weights, sample_weights) {
+            (Some(class_weights), Some(sample_weights)) => Some(
+                class_weights
+                    .iter()
+                    .zip(sample_weights.iter())
+                    .map(|(c, s)| c * s)
+                    .collect(),
+            ),
+            (Some(class_weights), None) => Some(class_weights),
+            (None, Some(sample_weights)) => Some(sample_weights.clone()),
+            (None, None) => None,
+        }
+    }
+
+    // gradients are per-sample, so weighting a sample is scaling its column
+    fn weigh_columns(gradient: Matrix, weights: Option<Vec<Scalar>>) -> Matrix {
+        match weights {
+            Some(weights) => {
+                let ones = Matrix::constant(gradient.dim().0, 1, 1.0);
+                gradient.component_mul(&ones.dot(&Matrix::from_row_vector(&weights)))
+            }
+            None => gradient,
+        }
     }
 
     pub fn loss_vec(&self, y_true: &Vec<Vec<Scalar>>, y_pred: &Vec<Vec<Scalar>>) -> Scalar {
@@ -51,3 +264,20 @@ impl Loss {
         self.loss(&y_true, &y_pred)
     }
 }
+
+fn class_weight(class_weights: &[Scalar], y_true: &[Scalar]) -> Scalar {
+    if y_true.len() == 1 {
+        let class = y_true[0].round() as usize;
+        *class_weights
+            .get(class)
+            .unwrap_or_else(|| panic!("No weight for class {} in {:?}", class, class_weights))
+    } else {
+        assert!(
+            y_true.len() == class_weights.len(),
+            "Expected {} class weights, got {}",
+            y_true.len(),
+            class_weights.len()
+        );
+        y_true.iter().zip(class_weights.iter()).map(|(y, w)| y * w).sum()
+    }
+}
@@ -0,0 +1,31 @@
+use crate::{
+    linalg::{Matrix, MatrixTrait, Scalar},
+    loss::Loss,
+};
+
+// pinball loss: under-predictions cost tau, over-predictions cost 1 - tau
+
+fn quantile(y_true: &Matrix, y_pred: &Matrix, tau: Scalar) -> Scalar {
+    let errors = y_true.component_sub(y_pred);
+    let zeros = Matrix::constant(errors.dim().0, errors.dim().1, 0.);
+    errors
+        .maxof(&zeros)
+        .scalar_mul(tau)
+        .component_sub(&errors.minof(&zeros).scalar_mul(1. - tau))
+        .mean()
+}
+
+fn quantile_prime(y_true: &Matrix, y_pred: &Matrix, tau: Scalar) -> Matrix {
+    let signs = y_true.component_sub(y_pred).sign_zero_at_zero();
+    let zeros = Matrix::constant(signs.dim().0, signs.dim().1, 0.);
+    let under = signs.maxof(&zeros);
+    let over = signs.scalar_mul(-1.).maxof(&zeros);
+    over.scalar_mul(1. - tau).component_sub(&under.scalar_mul(tau))
+}
+
+pub fn new(tau: Scalar) -> Loss {
+    Loss::new(
+        move |y_true, y_pred| quantile(y_true, y_pred, tau),
+        move |y_true, y_pred| quantile_prime(y_true, y_pred, tau),
+    )
+}
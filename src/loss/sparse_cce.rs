@@ -0,0 +1,38 @@
+use crate::{
+    linalg::{Matrix, MatrixTrait, Scalar},
+    loss::Loss,
+};
+
+use super::cce::{cce, cce_prime, cce_softmax_prime, smooth_labels};
+
+/// `ids` has shape `(1, n)`, holding the class id of each of the `n` samples.
+///
+/// Returns the `(classes, n)` one-hot encoded classes.
+pub fn one_hot(ids: &Matrix, classes: usize) -> Matrix {
+    let ids: Vec<usize> = ids
+        .get_row(0)
+        .iter()
+        .map(|id| {
+            let class = id.round();
+            if class < 0. || class as usize >= classes {
+                panic!("Class id {} is out of the {} predicted classes", id, classes);
+            }
+            class as usize
+        })
+        .collect();
+    Matrix::from_fn(classes, ids.len(), |i, j| if ids[j] == i { 1. } else { 0. })
+}
+
+fn labels(y_true: &Matrix, y_pred: &Matrix, label_smoothing: Scalar) -> Matrix {
+    smooth_labels(&one_hot(y_true, y_pred.dim().0), label_smoothing)
+}
+
+pub fn new(label_smoothing: Scalar) -> Loss {
+    Loss::new(
+        move |y_true, y_pred| cce(&labels(y_true, y_pred, label_smoothing), y_pred),
+        move |y_true, y_pred| cce_prime(&labels(y_true, y_pred, label_smoothing), y_pred),
+    )
+    .with_softmax_derivative(move |y_true, y_pred| {
+        cce_softmax_prime(&labels(y_true, y_pred, label_smoothing), y_pred)
+    })
+}
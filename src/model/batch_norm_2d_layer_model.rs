@@ -0,0 +1,135 @@
+use serde::{Serialize, Deserialize};
+
+use crate::{linalg::Scalar, vision::{conv_optimizer::{ConvOptimizers, conv_sgd, conv_momentum, conv_adam, conv_rmsprop, conv_adagrad, conv_adadelta, conv_nadam, conv_amsgrad, conv_adamw}, conv_network::ConvNetworkLayer, conv_layer::batch_norm_2d_layer::BatchNorm2dLayer}, layer::defaults::{default_running_momentum, default_norm_epsilon}};
+
+use super::conv_network_model::ConvNetworkModelBuilder;
+
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct BatchNorm2dLayerModel {
+    /// Weight of the previous running statistics when updating them with a batch's statistics
+    #[serde(default = "default_running_momentum")]
+    pub momentum: Scalar,
+    #[serde(default = "default_norm_epsilon")]
+    pub epsilon: Scalar,
+    pub gamma_optimizer: ConvOptimizers,
+    pub beta_optimizer: ConvOptimizers,
+}
+
+impl BatchNorm2dLayerModel {
+    pub fn to_layer(self, in_img_dims: (usize, usize), in_channels: usize) -> ((usize, usize), usize, Box<dyn ConvNetworkLayer>) {
+        let layer = BatchNorm2dLayer::new(
+            in_channels,
+            self.momentum,
+            self.epsilon,
+            self.gamma_optimizer,
+            self.beta_optimizer,
+        );
+
+        (in_img_dims, in_channels, Box::new(layer))
+    }
+}
+
+pub struct BatchNorm2dLayerModelBuilder {
+    pub model: BatchNorm2dLayerModel,
+    parent: ConvNetworkModelBuilder
+}
+
+impl BatchNorm2dLayerModelBuilder {
+    pub fn new(parent: ConvNetworkModelBuilder) -> Self {
+        Self {
+            model: BatchNorm2dLayerModel {
+                momentum: default_running_momentum(),
+                epsilon: default_norm_epsilon(),
+                gamma_optimizer: conv_sgd(),
+                beta_optimizer: conv_sgd(),
+            },
+            parent,
+        }
+    }
+
+    pub fn end(self) -> ConvNetworkModelBuilder {
+        self.parent.accept_batch_norm(self.model)
+    }
+
+    pub fn running_momentum(self, momentum: Scalar) -> Self {
+        Self {
+            model: BatchNorm2dLayerModel {
+                momentum,
+                ..self.model
+            },
+            ..self
+        }
+    }
+
+    pub fn epsilon(self, epsilon: Scalar) -> Self {
+        Self {
+            model: BatchNorm2dLayerModel {
+                epsilon,
+                ..self.model
+            },
+            ..self
+        }
+    }
+
+    pub fn sgd(self) -> Self {
+        self.optimizer(conv_sgd())
+    }
+
+    pub fn momentum(self) -> Self {
+        self.optimizer(conv_momentum())
+    }
+
+    pub fn adam(self) -> Self {
+        self.optimizer(conv_adam())
+    }
+
+    pub fn rmsprop(self) -> Self {
+        self.optimizer(conv_rmsprop())
+    }
+
+    pub fn adagrad(self) -> Self {
+        self.optimizer(conv_adagrad())
+    }
+
+    pub fn adadelta(self) -> Self {
+        self.optimizer(conv_adadelta())
+    }
+
+    pub fn nadam(self) -> Self {
+        self.optimizer(conv_nadam())
+    }
+
+    pub fn amsgrad(self) -> Self {
+        self.optimizer(conv_amsgrad())
+    }
+
+    pub fn adamw(self) -> Self {
+        self.optimizer(conv_adamw())
+    }
+
+    pub fn optimizer(self, optimizer: ConvOptimizers) -> Self {
+        self.gamma_optimizer(optimizer.clone())
+            .beta_optimizer(optimizer)
+    }
+
+    pub fn gamma_optimizer(self, optimizer: ConvOptimizers) -> Self {
+        Self {
+            model: BatchNorm2dLayerModel {
+                gamma_optimizer: optimizer,
+                ..self.model
+            },
+            ..self
+        }
+    }
+
+    pub fn beta_optimizer(self, optimizer: ConvOptimizers) -> Self {
+        Self {
+            model: BatchNorm2dLayerModel {
+                beta_optimizer: optimizer,
+                ..self.model
+            },
+            ..self
+        }
+    }
+}
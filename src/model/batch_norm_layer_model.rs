@@ -0,0 +1,135 @@
+use serde::{Serialize, Deserialize};
+
+use crate::{linalg::Scalar, optimizer::{Optimizers, sgd, momentum, adam, rmsprop, adagrad, adadelta, nadam, amsgrad, adamw}, layer::{batch_norm_layer::BatchNormLayer, defaults::{default_running_momentum, default_norm_epsilon}}, network::NetworkLayer};
+
+use super::network_model::NetworkModelBuilder;
+
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct BatchNormLayerModel {
+    /// Weight of the previous running statistics when updating them with a batch's statistics
+    #[serde(default = "default_running_momentum")]
+    pub momentum: Scalar,
+    #[serde(default = "default_norm_epsilon")]
+    pub epsilon: Scalar,
+    pub gamma_optimizer: Optimizers,
+    pub beta_optimizer: Optimizers,
+}
+
+impl BatchNormLayerModel {
+    pub fn to_layer(self, in_size: usize) -> (usize, Box<dyn NetworkLayer>) {
+        let layer = BatchNormLayer::new(
+            in_size,
+            self.momentum,
+            self.epsilon,
+            self.gamma_optimizer,
+            self.beta_optimizer,
+        );
+
+        (in_size, Box::new(layer))
+    }
+}
+
+pub struct BatchNormLayerModelBuilder {
+    pub model: BatchNormLayerModel,
+    parent: NetworkModelBuilder
+}
+
+impl BatchNormLayerModelBuilder {
+    pub fn new(parent: NetworkModelBuilder) -> Self {
+        Self {
+            model: BatchNormLayerModel {
+                momentum: default_running_momentum(),
+                epsilon: default_norm_epsilon(),
+                gamma_optimizer: sgd(),
+                beta_optimizer: sgd(),
+            },
+            parent,
+        }
+    }
+
+    pub fn end(self) -> NetworkModelBuilder {
+        self.parent.accept_batch_norm(self.model)
+    }
+
+    pub fn running_momentum(self, momentum: Scalar) -> Self {
+        Self {
+            model: BatchNormLayerModel {
+                momentum,
+                ..self.model
+            },
+            ..self
+        }
+    }
+
+    pub fn epsilon(self, epsilon: Scalar) -> Self {
+        Self {
+            model: BatchNormLayerModel {
+                epsilon,
+                ..self.model
+            },
+            ..self
+        }
+    }
+
+    pub fn sgd(self) -> Self {
+        self.optimizer(sgd())
+    }
+
+    pub fn momentum(self) -> Self {
+        self.optimizer(momentum())
+    }
+
+    pub fn adam(self) -> Self {
+        self.optimizer(adam())
+    }
+
+    pub fn rmsprop(self) -> Self {
+        self.optimizer(rmsprop())
+    }
+
+    pub fn adagrad(self) -> Self {
+        self.optimizer(adagrad())
+    }
+
+    pub fn adadelta(self) -> Self {
+        self.optimizer(adadelta())
+    }
+
+    pub fn nadam(self) -> Self {
+        self.optimizer(nadam())
+    }
+
+    pub fn amsgrad(self) -> Self {
+        self.optimizer(amsgrad())
+    }
+
+    pub fn adamw(self) -> Self {
+        self.optimizer(adamw())
+    }
+
+    pub fn optimizer(self, optimizer: Optimizers) -> Self {
+        self.gamma_optimizer(optimizer.clone())
+            .beta_optimizer(optimizer)
+    }
+
+    pub fn gamma_optimizer(self, optimizer: Optimizers) -> Self {
+        Self {
+            model: BatchNormLayerModel {
+                gamma_optimizer: optimizer,
+                ..self.model
+            },
+            ..self
+        }
+    }
+
+    pub fn beta_optimizer(self, optimizer: Optimizers) -> Self {
+        Self {
+            model: BatchNormLayerModel {
+                beta_optimizer: optimizer,
+                ..self.model
+            },
+            ..self
+        }
+    }
+}
@@ -0,0 +1,122 @@
+use serde::{Serialize, Deserialize};
+
+use crate::{network::NetworkLayer, vision::{conv_network::{ConvNetwork, ConvNetworkLayer}, conv_layer::pool1d_layer::{AvgPool1dLayer, MaxPool1dLayer}}};
+
+use super::{full_conv1d_layer_model::{FullConv1dLayerModel, FullConv1dLayerModelBuilder}, network_model::NetworkModelBuilder};
+
+pub struct Conv1dNetworkModelBuilder {
+    pub model: Conv1dNetworkModel,
+    parent: NetworkModelBuilder,
+}
+
+impl Conv1dNetworkModelBuilder {
+    pub fn new(parent: NetworkModelBuilder, in_channels: usize) -> Self {
+        Self {
+            model: Conv1dNetworkModel { layers: vec![], in_channels },
+            parent,
+        }
+    }
+
+    pub fn end(self) -> NetworkModelBuilder {
+        self.parent.accept_conv1d_network(self.model)
+    }
+
+    pub fn conv1d(self, kernels_count: usize, kernels_size: usize) -> FullConv1dLayerModelBuilder {
+        FullConv1dLayerModelBuilder::new(self, kernels_count, kernels_size)
+    }
+
+    /// Keeps the maximum of each `window` long patch, patches being `stride` values apart
+    /// on an input padded with `padding` values on each end.
+    pub fn max_pool1d(mut self, window: usize, stride: usize, padding: usize) -> Self {
+        self.model.layers.push(Conv1dNetworkLayerModels::MaxPool1d { window, stride, padding });
+        self
+    }
+
+    pub fn avg_pool1d(mut self, kernel_size: usize) -> Self {
+        self.model.layers.push(Conv1dNetworkLayerModels::AvgPool1d { kernel_size });
+        self
+    }
+
+    pub fn accept_conv1d(mut self, model: FullConv1dLayerModel) -> Self {
+        self.model.layers.push(Conv1dNetworkLayerModels::Conv1d(model));
+        self
+    }
+}
+
+/// Convolutional network over 1D signals, whose inputs hold `length` values for each channel.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Conv1dNetworkModel {
+    pub in_channels: usize,
+    pub layers: Vec<Conv1dNetworkLayerModels>,
+}
+
+impl Conv1dNetworkModel {
+    /// Returns the length of the input signals for inputs of `in_dims` values.
+    pub fn in_length(&self, in_dims: usize) -> usize {
+        let length = in_dims / self.in_channels;
+        if length * self.in_channels != in_dims {
+            panic!(
+                "1D convolutional network cannot split {} input values into {} channels",
+                in_dims, self.in_channels
+            );
+        }
+        length
+    }
+
+    pub fn to_layer(self, in_dims: usize) -> (usize, Box<dyn NetworkLayer>) {
+        let mut layers = vec![];
+        let network_length = self.in_length(in_dims);
+        let mut in_length = network_length;
+        let mut in_channels = self.in_channels;
+
+        for layer_config in self.layers.into_iter() {
+            let (out_length, out_channels, conv_layer) = layer_config
+                .to_conv_layer(in_length, in_channels);
+
+            in_length = out_length;
+            in_channels = out_channels;
+            layers.push(conv_layer);
+        }
+
+        // signals are images with a single column
+        let network_layer = ConvNetwork::new(layers, (network_length, 1), self.in_channels);
+        (in_length * in_channels, Box::new(network_layer))
+    }
+}
+
+#[allow(clippy::large_enum_variant)]
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub enum Conv1dNetworkLayerModels {
+    Conv1d(FullConv1dLayerModel),
+    MaxPool1d {
+        window: usize,
+        stride: usize,
+        padding: usize,
+    },
+    AvgPool1d {
+        kernel_size: usize,
+    },
+}
+
+impl Conv1dNetworkLayerModels {
+    pub fn to_conv_layer(self, in_length: usize, in_channels: usize) -> (usize, usize, Box<dyn ConvNetworkLayer>) {
+        match self {
+            Self::Conv1d(model) => model.to_layer(in_length, in_channels),
+            Self::MaxPool1d { window, stride, padding } => {
+                let network_layer = MaxPool1dLayer::new(window, stride, padding);
+                let out_length = network_layer.out_length(in_length);
+                (out_length, in_channels, Box::new(network_layer))
+            }
+            Self::AvgPool1d { kernel_size } => {
+                let out_length = in_length / kernel_size;
+                if out_length * kernel_size != in_length {
+                    panic!(
+                        "Average pooling of size {} cannot split signals of length {}",
+                        kernel_size, in_length
+                    );
+                }
+                (out_length, in_channels, Box::new(AvgPool1dLayer::new(kernel_size)))
+            }
+        }
+    }
+}
@@ -1,25 +1,93 @@
 
 use serde::{Serialize, Deserialize};
 
-use crate::{network::NetworkLayer, vision::{conv_network::{ConvNetwork, ConvNetworkLayer}, conv_layer::avg_pooling_layer::AvgPoolingLayer}};
+use crate::{linalg::Scalar, network::NetworkLayer, vision::{conv_network::{ConvNetwork, ConvNetworkLayer}, conv_layer::{avg_pooling_layer::AvgPoolingLayer, max_pooling_layer::MaxPoolingLayer, dropout_2d::Dropout2d, global_pooling_layer::{GlobalAvgPoolingLayer, GlobalMaxPoolingLayer}, upsampling_layer::{UpsamplingLayer, UpsamplingModes}, defaults::{default_kernels_initializer, default_kernels_optimizer}}, conv_initializers::ConvInitializers, conv_optimizer::ConvOptimizers}};
 
-use super::{full_dense_conv_layer_model::{FullDenseConvLayerModel, FullDenseConvLayerModelBuilder}, network_model::NetworkModelBuilder, full_direct_conv_layer_model::{FullDirectConvLayerModel, FullDirectConvLayerModelBuilder}};
+use super::{full_dense_conv_layer_model::{FullDenseConvLayerModel, FullDenseConvLayerModelBuilder}, network_model::NetworkModelBuilder, full_direct_conv_layer_model::{FullDirectConvLayerModel, FullDirectConvLayerModelBuilder}, batch_norm_2d_layer_model::{BatchNorm2dLayerModel, BatchNorm2dLayerModelBuilder}, conv_residual_block_model::ConvResidualBlockModel, full_transposed_conv_layer_model::{FullTransposedConvLayerModel, FullTransposedConvLayerModelBuilder}, full_separable_conv_layer_model::{FullSeparableConvLayerModel, FullSeparableConvLayerModelBuilder}};
 
 pub struct ConvNetworkModelBuilder {
     pub model: ConvNetworkModel,
-    parent: NetworkModelBuilder,
+    parent: Option<NetworkModelBuilder>,
+    // builder of the network containing the residual block being built
+    enclosing: Option<Box<ConvNetworkModelBuilder>>,
 }
 
 impl ConvNetworkModelBuilder {
     pub fn new(parent: NetworkModelBuilder, in_channels: usize) -> Self {
         Self { 
-            model: ConvNetworkModel { layers: vec![], in_channels },
-            parent,
+            model: ConvNetworkModel { layers: vec![], in_channels, in_img_dims: None, to_dense: ConvToDense::Flatten },
+            parent: Some(parent),
+            enclosing: None,
         }
     }
 
     pub fn end(self) -> NetworkModelBuilder {
-        self.parent.accept_conv_network(self.model)
+        match self.parent {
+            Some(parent) => parent.accept_conv_network(self.model),
+            None => panic!("Residual block not ended, call end_residual() first"),
+        }
+    }
+
+    /// Starts a residual block, its layers being added until `end_residual` is called.
+    pub fn residual(self) -> Self {
+        Self {
+            model: ConvNetworkModel { layers: vec![], in_channels: self.model.in_channels, in_img_dims: None, to_dense: ConvToDense::Flatten },
+            parent: None,
+            enclosing: Some(Box::new(self)),
+        }
+    }
+
+    pub fn end_residual(self) -> Self {
+        self.end_residual_with_projection(default_kernels_initializer(), default_kernels_optimizer())
+    }
+
+    /// Ends a residual block whose input is projected with the given initializer and optimizer
+    /// when its layers change its dimensions or channels.
+    pub fn end_residual_with_projection(self, initializer: ConvInitializers, optimizer: ConvOptimizers) -> Self {
+        let enclosing = *self.enclosing.expect("No residual block started");
+        enclosing.accept_residual(ConvResidualBlockModel {
+            layers: self.model.layers,
+            projection_initializer: initializer,
+            projection_optimizer: optimizer,
+        })
+    }
+
+    pub fn accept_residual(mut self, model: ConvResidualBlockModel) -> Self {
+        self.model.layers.push(ConvNetworkLayerModels::Residual(model));
+        self
+    }
+
+    /// Sets the `(rows, cols)` of the input images, which are otherwise assumed to be square.
+    pub fn in_img_dims(mut self, rows: usize, cols: usize) -> Self {
+        if self.enclosing.is_some() {
+            panic!("The input images dimensions are set on the convolutional network, not on its residual blocks");
+        }
+        self.model.in_img_dims = Some((rows, cols));
+        self
+    }
+
+    /// Sets how the output images are turned into the vectors fed to the following dense layers.
+    pub fn to_dense(mut self, to_dense: ConvToDense) -> Self {
+        if self.enclosing.is_some() {
+            panic!("The conv-to-dense transition is set on the convolutional network, not on its residual blocks");
+        }
+        self.model.to_dense = to_dense;
+        self
+    }
+
+    /// Outputs all the pixels of all the channels (the default).
+    pub fn flatten(self) -> Self {
+        self.to_dense(ConvToDense::Flatten)
+    }
+
+    /// Outputs the mean of each channel.
+    pub fn global_avg_pooling_to_dense(self) -> Self {
+        self.to_dense(ConvToDense::GlobalAvgPooling)
+    }
+
+    /// Outputs the maximum of each channel.
+    pub fn global_max_pooling_to_dense(self) -> Self {
+        self.to_dense(ConvToDense::GlobalMaxPooling)
     }
 
     pub fn full_dense(self, kernels_count: usize, kernels_size: usize) -> FullDenseConvLayerModelBuilder {
@@ -30,11 +98,64 @@ impl ConvNetworkModelBuilder {
         FullDirectConvLayerModelBuilder::new(self, kernels_size)
     }
 
+    /// Depthwise convolution of each channel followed by a 1x1 convolution mixing them into `kernels_count` channels.
+    pub fn separable_conv(self, kernels_count: usize, kernels_size: usize) -> FullSeparableConvLayerModelBuilder {
+        FullSeparableConvLayerModelBuilder::new(self, kernels_count, kernels_size)
+    }
+
+    /// Transposed convolution increasing the images' dimensions by `kernels_size - 1`, times the stride if any.
+    pub fn transposed_conv(self, kernels_count: usize, kernels_size: usize) -> FullTransposedConvLayerModelBuilder {
+        FullTransposedConvLayerModelBuilder::new(self, kernels_count, kernels_size)
+    }
+
+    /// Multiplies the images' rows and columns by `factor`.
+    pub fn upsampling(mut self, factor: usize, mode: UpsamplingModes) -> Self {
+        self.model.layers.push(ConvNetworkLayerModels::Upsampling { factor, mode });
+        self
+    }
+
+    pub fn upsampling_nearest(self, factor: usize) -> Self {
+        self.upsampling(factor, UpsamplingModes::Nearest)
+    }
+
+    pub fn upsampling_bilinear(self, factor: usize) -> Self {
+        self.upsampling(factor, UpsamplingModes::Bilinear)
+    }
+
     pub fn avg_pooling(mut self, kernel_size: usize) -> Self {
         self.model.layers.push(ConvNetworkLayerModels::AvgPooling { kernel_size });
         self
     }
 
+    /// Keeps the maximum of each `window x window` patch, patches being `stride` pixels apart
+    /// on an input padded with `padding` pixels on each side.
+    pub fn max_pooling(mut self, window: usize, stride: usize, padding: usize) -> Self {
+        self.model.layers.push(ConvNetworkLayerModels::MaxPooling { window, stride, padding });
+        self
+    }
+
+    /// Reduces each channel to the mean of its pixels.
+    pub fn global_avg_pooling(mut self) -> Self {
+        self.model.layers.push(ConvNetworkLayerModels::GlobalAvgPooling);
+        self
+    }
+
+    /// Reduces each channel to its maximum pixel.
+    pub fn global_max_pooling(mut self) -> Self {
+        self.model.layers.push(ConvNetworkLayerModels::GlobalMaxPooling);
+        self
+    }
+
+    /// Zeroes whole channels with probability `rate` while training.
+    pub fn dropout_2d(mut self, rate: Scalar) -> Self {
+        self.model.layers.push(ConvNetworkLayerModels::Dropout2d { rate });
+        self
+    }
+
+    pub fn batch_norm(self) -> BatchNorm2dLayerModelBuilder {
+        BatchNorm2dLayerModelBuilder::new(self)
+    }
+
     pub fn accept_full_dense(mut self, model: FullDenseConvLayerModel) -> Self {
         self.model.layers.push(ConvNetworkLayerModels::FullDenseConv(model));
         self
@@ -44,19 +165,79 @@ impl ConvNetworkModelBuilder {
         self.model.layers.push(ConvNetworkLayerModels::FullDirectConv(model));
         self
     }
+
+    pub fn accept_separable_conv(mut self, model: FullSeparableConvLayerModel) -> Self {
+        self.model.layers.push(ConvNetworkLayerModels::SeparableConv(Box::new(model)));
+        self
+    }
+
+    pub fn accept_transposed_conv(mut self, model: FullTransposedConvLayerModel) -> Self {
+        self.model.layers.push(ConvNetworkLayerModels::TransposedConv(model));
+        self
+    }
+
+    pub fn accept_batch_norm(mut self, model: BatchNorm2dLayerModel) -> Self {
+        self.model.layers.push(ConvNetworkLayerModels::BatchNorm2d(model));
+        self
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct ConvNetworkModel {
     pub in_channels: usize,
+    /// (rows, cols) of the input images, assumed to be square when not set
+    #[serde(default)]
+    pub in_img_dims: Option<(usize, usize)>,
     pub layers: Vec<ConvNetworkLayerModels>,
+    /// How the output images are turned into vectors, flattened when not set
+    #[serde(default)]
+    pub to_dense: ConvToDense,
+}
+
+/// Transition from the images of a convolutional network to the vectors of the following layers.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq)]
+pub enum ConvToDense {
+    /// rows * cols * channels values
+    #[default]
+    Flatten,
+    /// One mean per channel
+    GlobalAvgPooling,
+    /// One maximum per channel
+    GlobalMaxPooling,
 }
 
 impl ConvNetworkModel {
+    /// Returns the `(rows, cols)` of the input images for inputs of `in_dims` values,
+    /// panicking if they don't match the configured dimensions and channels.
+    pub fn in_img_dims(&self, in_dims: usize) -> (usize, usize) {
+        let (rows, cols) = match self.in_img_dims {
+            Some(img_dims) => img_dims,
+            None => {
+                let size = (in_dims as f64 / self.in_channels as f64).sqrt().round() as usize;
+                if size * size * self.in_channels != in_dims {
+                    panic!(
+                        "Convolutional network cannot split {} input values into square images with {} channels, \
+                        set its input images dimensions with in_img_dims(rows, cols)",
+                        in_dims, self.in_channels
+                    );
+                }
+                (size, size)
+            }
+        };
+        if rows * cols * self.in_channels != in_dims {
+            panic!(
+                "Convolutional network expects {}x{} images with {} channels ({} values) but its input has {} values",
+                rows, cols, self.in_channels, rows * cols * self.in_channels, in_dims
+            );
+        }
+        (rows, cols)
+    }
+
     pub fn to_layer(self, in_dims: usize) -> (usize, Box<dyn NetworkLayer>) {
         let mut layers = vec![];
+        let network_img_dims = self.in_img_dims(in_dims);
+        let mut in_img_dims = network_img_dims;
         let mut in_channels = self.in_channels;
-        let mut in_img_dims = (in_dims as f64 / in_channels as f64).sqrt() as usize;
 
         for layer_config in self.layers.into_iter() {
             let (out_img_dims, out_channels, conv_layer) = layer_config
@@ -67,8 +248,22 @@ impl ConvNetworkModel {
             layers.push(conv_layer);
         }
 
-        let network_layer = ConvNetwork::new(layers, self.in_channels);
-        (in_img_dims * in_img_dims * in_channels, Box::new(network_layer))
+        let to_dense_layer = match self.to_dense {
+            ConvToDense::Flatten => None,
+            ConvToDense::GlobalAvgPooling => Some(ConvNetworkLayerModels::GlobalAvgPooling),
+            ConvToDense::GlobalMaxPooling => Some(ConvNetworkLayerModels::GlobalMaxPooling),
+        };
+        if let Some(layer_config) = to_dense_layer {
+            let (out_img_dims, out_channels, conv_layer) = layer_config
+                .to_conv_layer(in_img_dims, in_channels);
+
+            in_img_dims = out_img_dims;
+            in_channels = out_channels;
+            layers.push(conv_layer);
+        }
+
+        let network_layer = ConvNetwork::new(layers, network_img_dims, self.in_channels);
+        (in_img_dims.0 * in_img_dims.1 * in_channels, Box::new(network_layer))
     }
 }
 
@@ -76,22 +271,62 @@ impl ConvNetworkModel {
 pub enum ConvNetworkLayerModels {
     FullDenseConv(FullDenseConvLayerModel),
     FullDirectConv(FullDirectConvLayerModel),
+    SeparableConv(Box<FullSeparableConvLayerModel>),
+    TransposedConv(FullTransposedConvLayerModel),
     AvgPooling {
         kernel_size: usize,
     },
+    MaxPooling {
+        window: usize,
+        stride: usize,
+        padding: usize,
+    },
+    Upsampling {
+        factor: usize,
+        mode: UpsamplingModes,
+    },
+    GlobalAvgPooling,
+    GlobalMaxPooling,
+    Dropout2d {
+        rate: Scalar,
+    },
+    BatchNorm2d(BatchNorm2dLayerModel),
+    Residual(ConvResidualBlockModel),
 }
 
 impl ConvNetworkLayerModels {
-    pub fn to_conv_layer(self, in_img_dims: usize, in_channels: usize) -> (usize, usize, Box<dyn ConvNetworkLayer>) {
+    pub fn to_conv_layer(self, in_img_dims: (usize, usize), in_channels: usize) -> ((usize, usize), usize, Box<dyn ConvNetworkLayer>) {
         match self {
             Self::FullDenseConv(model) => model.to_layer(in_img_dims, in_channels),
             Self::FullDirectConv(model) => model.to_layer(in_img_dims, in_channels),
+            Self::SeparableConv(model) => model.to_layer(in_img_dims, in_channels),
+            Self::TransposedConv(model) => model.to_layer(in_img_dims, in_channels),
+            Self::BatchNorm2d(model) => model.to_layer(in_img_dims, in_channels),
+            Self::Residual(model) => model.to_layer(in_img_dims, in_channels),
             Self::AvgPooling { kernel_size } => {
-                let out_img_dims = in_img_dims / kernel_size;
+                let out_img_dims = (in_img_dims.0 / kernel_size, in_img_dims.1 / kernel_size);
+                if out_img_dims.0 * kernel_size != in_img_dims.0 || out_img_dims.1 * kernel_size != in_img_dims.1 {
+                    panic!(
+                        "Average pooling of size {} cannot split {}x{} images",
+                        kernel_size, in_img_dims.0, in_img_dims.1
+                    );
+                }
                 let out_channels = in_channels;
                 let network_layer = AvgPoolingLayer::new(kernel_size);
                 (out_img_dims, out_channels, Box::new(network_layer))
             }
+            Self::MaxPooling { window, stride, padding } => {
+                let network_layer = MaxPoolingLayer::new(window, stride, padding);
+                let out_img_dims = network_layer.out_img_dims(in_img_dims.0, in_img_dims.1);
+                (out_img_dims, in_channels, Box::new(network_layer))
+            }
+            Self::Upsampling { factor, mode } => {
+                let out_img_dims = (in_img_dims.0 * factor, in_img_dims.1 * factor);
+                (out_img_dims, in_channels, Box::new(UpsamplingLayer::new(factor, mode)))
+            }
+            Self::GlobalAvgPooling => ((1, 1), in_channels, Box::new(GlobalAvgPoolingLayer::new())),
+            Self::GlobalMaxPooling => ((1, 1), in_channels, Box::new(GlobalMaxPoolingLayer::new())),
+            Self::Dropout2d { rate } => (in_img_dims, in_channels, Box::new(Dropout2d::new(rate))),
         }
     }
 }
\ No newline at end of file
@@ -0,0 +1,62 @@
+use serde::{Serialize, Deserialize};
+
+use crate::vision::{conv_initializers::ConvInitializers, conv_optimizer::ConvOptimizers, conv_network::ConvNetworkLayer, conv_layer::{conv_padding::ConvPaddings, dense_conv_layer::DenseConvLayer, conv_residual_block::ConvResidualBlock, defaults::{default_kernels_initializer, default_kernels_optimizer}}};
+
+use super::conv_network_model::ConvNetworkLayerModels;
+
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ConvResidualBlockModel {
+    pub layers: Vec<ConvNetworkLayerModels>,
+    /// Used for the projection of the input when the layers change its dimensions or channels
+    #[serde(default = "default_kernels_initializer")]
+    pub projection_initializer: ConvInitializers,
+    #[serde(default = "default_kernels_optimizer")]
+    pub projection_optimizer: ConvOptimizers,
+}
+
+impl ConvResidualBlockModel {
+    pub fn to_layer(self, in_img_dims: (usize, usize), in_channels: usize) -> ((usize, usize), usize, Box<dyn ConvNetworkLayer>) {
+        let mut layers = vec![];
+        let mut out_img_dims = in_img_dims;
+        let mut out_channels = in_channels;
+        for layer_config in self.layers.into_iter() {
+            let (layer_out_img_dims, layer_out_channels, layer) = layer_config
+                .to_conv_layer(out_img_dims, out_channels);
+            out_img_dims = layer_out_img_dims;
+            out_channels = layer_out_channels;
+            layers.push(layer);
+        }
+
+        let projection = if out_img_dims != in_img_dims || out_channels != in_channels {
+            // a 1x1 convolution mixing the channels, strided to the output's dimensions
+            let stride = (1..=in_img_dims.0.max(in_img_dims.1))
+                .find(|&stride| {
+                    ConvPaddings::Valid.out_size(in_img_dims.0, 1, stride) == out_img_dims.0
+                        && ConvPaddings::Valid.out_size(in_img_dims.1, 1, stride) == out_img_dims.1
+                })
+                .unwrap_or_else(|| {
+                    panic!(
+                        "Residual block cannot project {}x{} images to {}x{} images with a strided 1x1 convolution",
+                        in_img_dims.0, in_img_dims.1, out_img_dims.0, out_img_dims.1
+                    )
+                });
+            Some(DenseConvLayer::new(
+                1,
+                1,
+                in_channels,
+                out_channels,
+                stride,
+                ConvPaddings::Valid,
+                self.projection_initializer,
+                ConvInitializers::Zeros,
+                self.projection_optimizer.clone(),
+                self.projection_optimizer,
+            ))
+        } else {
+            None
+        };
+
+        (out_img_dims, out_channels, Box::new(ConvResidualBlock::new(layers, projection)))
+    }
+}
@@ -0,0 +1,123 @@
+use serde::{Serialize, Deserialize};
+
+use crate::{initializers::Initializers, optimizer::{Optimizers, sgd, momentum, adam, rmsprop, adagrad, adadelta, nadam, amsgrad, adamw}, layer::{embedding_layer::EmbeddingLayer, defaults::{default_weights_initializer, default_weights_optimizer}}, network::NetworkLayer};
+
+use super::network_model::NetworkModelBuilder;
+
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct EmbeddingLayerModel {
+    /// Input row holding the category ids
+    pub input_index: usize,
+    pub categories_count: usize,
+    pub dim: usize,
+    #[serde(default = "default_weights_initializer")]
+    pub initializer: Initializers,
+    #[serde(default = "default_weights_optimizer")]
+    pub optimizer: Optimizers,
+}
+
+impl EmbeddingLayerModel {
+    pub fn new(input_index: usize, categories_count: usize, dim: usize) -> Self {
+        Self {
+            input_index,
+            categories_count,
+            dim,
+            initializer: default_weights_initializer(),
+            optimizer: default_weights_optimizer(),
+        }
+    }
+
+    pub fn to_layer(self, in_size: usize) -> (usize, Box<dyn NetworkLayer>) {
+        if self.input_index >= in_size {
+            panic!(
+                "Embedding of input {} is out of the layer's {} inputs",
+                self.input_index, in_size
+            );
+        }
+
+        let layer = EmbeddingLayer::new(
+            self.input_index,
+            self.categories_count,
+            self.dim,
+            self.initializer,
+            self.optimizer,
+        );
+
+        (in_size - 1 + self.dim, Box::new(layer))
+    }
+}
+
+pub struct EmbeddingLayerModelBuilder {
+    pub model: EmbeddingLayerModel,
+    parent: NetworkModelBuilder
+}
+
+impl EmbeddingLayerModelBuilder {
+    pub fn new(parent: NetworkModelBuilder, input_index: usize, categories_count: usize, dim: usize) -> Self {
+        Self {
+            model: EmbeddingLayerModel::new(input_index, categories_count, dim),
+            parent,
+        }
+    }
+
+    pub fn end(self) -> NetworkModelBuilder {
+        self.parent.accept_embedding(self.model)
+    }
+
+    pub fn init(self, initializer: Initializers) -> Self {
+        Self {
+            model: EmbeddingLayerModel {
+                initializer,
+                ..self.model
+            },
+            ..self
+        }
+    }
+
+    pub fn sgd(self) -> Self {
+        self.optimizer(sgd())
+    }
+
+    pub fn momentum(self) -> Self {
+        self.optimizer(momentum())
+    }
+
+    pub fn adam(self) -> Self {
+        self.optimizer(adam())
+    }
+
+    pub fn rmsprop(self) -> Self {
+        self.optimizer(rmsprop())
+    }
+
+    pub fn adagrad(self) -> Self {
+        self.optimizer(adagrad())
+    }
+
+    pub fn adadelta(self) -> Self {
+        self.optimizer(adadelta())
+    }
+
+    pub fn nadam(self) -> Self {
+        self.optimizer(nadam())
+    }
+
+    pub fn amsgrad(self) -> Self {
+        self.optimizer(amsgrad())
+    }
+
+    pub fn adamw(self) -> Self {
+        self.optimizer(adamw())
+    }
+
+    pub fn optimizer(self, optimizer: Optimizers) -> Self {
+        Self {
+            model: EmbeddingLayerModel {
+                optimizer,
+                ..self.model
+            },
+            ..self
+        }
+    }
+}
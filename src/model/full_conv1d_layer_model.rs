@@ -0,0 +1,315 @@
+use serde::{Serialize, Deserialize};
+
+use crate::vision::{conv_initializers::ConvInitializers, conv_activation::ConvActivation, conv_optimizer::{ConvOptimizers, conv_sgd, conv_momentum, conv_adam, conv_rmsprop, conv_adagrad, conv_adadelta, conv_nadam, conv_amsgrad, conv_adamw}, conv_network::ConvNetworkLayer, conv_layer::{conv_padding::ConvPaddings, defaults::default_stride, full_conv_layer::FullConvLayer, conv1d_layer::Conv1dLayer}};
+
+use super::conv1d_network_model::Conv1dNetworkModelBuilder;
+
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct FullConv1dLayerModel {
+    pub kernels_count: usize,
+    pub kernels_size: usize,
+    #[serde(default = "default_stride")]
+    pub stride: usize,
+    #[serde(default)]
+    pub padding: ConvPaddings,
+    pub activation: ConvActivation,
+    pub biases_initializer: ConvInitializers,
+    pub kernels_initializer: ConvInitializers,
+    pub biases_optimizer: ConvOptimizers,
+    pub kernels_optimizer: ConvOptimizers,
+    pub dropout: Option<f32>
+}
+
+impl FullConv1dLayerModel {
+    pub fn to_layer(self, in_length: usize, in_channels: usize) -> (usize, usize, Box<dyn ConvNetworkLayer>) {
+        let activation = self
+            .activation
+            .to_layer_with_optimizer(self.biases_optimizer.clone());
+
+        let inner_layer = Conv1dLayer::new(
+            self.kernels_size,
+            in_channels,
+            self.kernels_count,
+            self.stride,
+            self.padding,
+            self.kernels_initializer,
+            self.biases_initializer,
+            self.kernels_optimizer,
+            self.biases_optimizer
+        );
+
+        let (out_length, out_channels) = Conv1dLayer::out_length_and_channels(
+            in_length,
+            self.kernels_size,
+            self.kernels_count,
+            self.stride,
+            self.padding
+        );
+
+        let layer = FullConvLayer::new(
+            Box::new(inner_layer),
+            activation,
+            self.dropout
+        );
+
+        (out_length, out_channels, Box::new(layer))
+    }
+}
+
+pub struct FullConv1dLayerModelBuilder {
+    pub model: FullConv1dLayerModel,
+    parent: Conv1dNetworkModelBuilder
+}
+
+impl FullConv1dLayerModelBuilder {
+    pub fn new(parent: Conv1dNetworkModelBuilder, kernels_count: usize, kernels_size: usize) -> Self {
+        Self {
+            model: FullConv1dLayerModel {
+                kernels_count,
+                kernels_size,
+                stride: default_stride(),
+                padding: ConvPaddings::Valid,
+                activation: ConvActivation::ConvReLU,
+                biases_initializer: ConvInitializers::Zeros,
+                kernels_initializer: ConvInitializers::GlorotUniform,
+                biases_optimizer: conv_sgd(),
+                kernels_optimizer: conv_sgd(),
+                dropout: None
+            },
+            parent,
+        }
+    }
+
+    pub fn end(self) -> Conv1dNetworkModelBuilder {
+        self.parent.accept_conv1d(self.model)
+    }
+
+    /// Moves the kernels `stride` values at a time, downsampling the signals.
+    pub fn stride(self, stride: usize) -> Self {
+        Self {
+            model: FullConv1dLayerModel {
+                stride,
+                ..self.model
+            },
+            ..self
+        }
+    }
+
+    pub fn padding(self, padding: ConvPaddings) -> Self {
+        Self {
+            model: FullConv1dLayerModel {
+                padding,
+                ..self.model
+            },
+            ..self
+        }
+    }
+
+    pub fn zero_padding(self, padding: usize) -> Self {
+        self.padding(ConvPaddings::Zero(padding))
+    }
+
+    pub fn same_padding(self) -> Self {
+        self.padding(ConvPaddings::Same)
+    }
+
+    pub fn reflect_padding(self, padding: usize) -> Self {
+        self.padding(ConvPaddings::Reflect(padding))
+    }
+
+    pub fn dropout(self, dropped_rate: f32) -> Self {
+        Self {
+            model: FullConv1dLayerModel {
+                dropout: Some(dropped_rate),
+                ..self.model
+            },
+            ..self
+        }
+    }
+
+    pub fn activation(self, activation: ConvActivation) -> Self {
+        Self {
+            model: FullConv1dLayerModel {
+                activation,
+                ..self.model
+            },
+            ..self
+        }
+    }
+
+    pub fn relu(self) -> Self {
+        self.activation(ConvActivation::ConvReLU)
+    }
+
+    pub fn sigmoid(self) -> Self {
+        self.activation(ConvActivation::ConvSigmoid)
+    }
+
+    pub fn tanh(self) -> Self {
+        self.activation(ConvActivation::ConvTanh)
+    }
+    
+    pub fn linear(self) -> Self {
+        self.activation(ConvActivation::ConvLinear)
+    }
+
+    pub fn init_zeros(self) -> Self {
+        self.init(ConvInitializers::Zeros)
+    }
+
+    pub fn init_uniform(self) -> Self {
+        self.init(ConvInitializers::Uniform)
+    }
+
+    pub fn init_uniform_signed(self) -> Self {
+        self.init(ConvInitializers::UniformSigned)
+    }
+
+    pub fn init_glorot_uniform(self) -> Self {
+        self.init(ConvInitializers::GlorotUniform)
+    }
+
+    pub fn biases_init_zeros(self) -> Self {
+        self.biases_init(ConvInitializers::Zeros)
+    }
+
+    pub fn biases_init_uniform(self) -> Self {
+        self.biases_init(ConvInitializers::Uniform)
+    }
+
+    pub fn biases_init_uniform_signed(self) -> Self {
+        self.biases_init(ConvInitializers::UniformSigned)
+    }
+
+    pub fn biases_init_glorot_uniform(self) -> Self {
+        self.biases_init(ConvInitializers::GlorotUniform)
+    }
+
+    pub fn kernels_init_zeros(self) -> Self {
+        self.kernels_init(ConvInitializers::Zeros)
+    }
+
+    pub fn kernels_init_uniform(self) -> Self {
+        self.kernels_init(ConvInitializers::Uniform)
+    }
+
+    pub fn kernels_init_uniform_signed(self) -> Self {
+        self.kernels_init(ConvInitializers::UniformSigned)
+    }
+
+    pub fn kernels_init_glorot_uniform(self) -> Self {
+        self.kernels_init(ConvInitializers::GlorotUniform)
+    }
+
+    pub fn sgd(self) -> Self {
+        self.optimizer(conv_sgd())
+    }
+
+    pub fn momentum(self) -> Self {
+        self.optimizer(conv_momentum())
+    }
+
+    pub fn adam(self) -> Self {
+        self.optimizer(conv_adam())
+    }
+
+    pub fn rmsprop(self) -> Self {
+        self.optimizer(conv_rmsprop())
+    }
+
+    pub fn adagrad(self) -> Self {
+        self.optimizer(conv_adagrad())
+    }
+
+    pub fn adadelta(self) -> Self {
+        self.optimizer(conv_adadelta())
+    }
+
+    pub fn nadam(self) -> Self {
+        self.optimizer(conv_nadam())
+    }
+
+    pub fn amsgrad(self) -> Self {
+        self.optimizer(conv_amsgrad())
+    }
+
+    /// AdamW with its default weight decay, the biases being optimized by Adam without decay
+    pub fn adamw(self) -> Self {
+        self.biases_optimizer(conv_adam()).kernels_optimizer(conv_adamw())
+    }
+
+    pub fn biases_optimizer_sgd(self) -> Self {
+        self.biases_optimizer(conv_sgd())
+    }
+
+    pub fn biases_optimizer_momentum(self) -> Self {
+        self.biases_optimizer(conv_momentum())
+    }
+
+    pub fn biases_optimizer_adam(self) -> Self {
+        self.biases_optimizer(conv_adam())
+    }
+
+    pub fn kernels_optimizer_sgd(self) -> Self {
+        self.kernels_optimizer(conv_sgd())
+    }
+
+    pub fn kernels_optimizer_momentum(self) -> Self {
+        self.kernels_optimizer(conv_momentum())
+    }
+
+    pub fn kernels_optimizer_adam(self) -> Self {
+        self.kernels_optimizer(conv_adam())
+    }
+
+    pub fn optimizer(self, optimizer: ConvOptimizers) -> Self {
+        self.biases_optimizer(optimizer.clone())
+            .kernels_optimizer(optimizer)
+    }
+
+    pub fn init(self, initializer: ConvInitializers) -> Self {
+        self.biases_init(initializer.clone())
+            .kernels_init(initializer)
+    }
+
+    pub fn biases_optimizer(self, optimizer: ConvOptimizers) -> Self {
+        Self {
+            model: FullConv1dLayerModel {
+                biases_optimizer: optimizer,
+                ..self.model
+            },
+            ..self
+        }
+    }
+
+    pub fn kernels_optimizer(self, optimizer: ConvOptimizers) -> Self {
+        Self {
+            model: FullConv1dLayerModel {
+                kernels_optimizer: optimizer,
+                ..self.model
+            },
+            ..self
+        }
+    }
+
+    pub fn biases_init(self, initializer: ConvInitializers) -> Self {
+        Self {
+            model: FullConv1dLayerModel {
+                biases_initializer: initializer,
+                ..self.model
+            },
+            ..self
+        }
+    }
+
+    pub fn kernels_init(self, initializer: ConvInitializers) -> Self {
+        Self {
+            model: FullConv1dLayerModel {
+                kernels_initializer: initializer,
+                ..self.model
+            },
+            ..self
+        }
+    }
+}
\ No newline at end of file
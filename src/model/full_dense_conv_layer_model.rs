@@ -1,6 +1,7 @@
 use serde::{Serialize, Deserialize};
 
-use crate::vision::{conv_initializers::ConvInitializers, conv_activation::ConvActivation, conv_optimizer::{ConvOptimizers, conv_sgd, conv_momentum, conv_adam}, conv_network::ConvNetworkLayer, conv_layer::{full_conv_layer::FullConvLayer, dense_conv_layer::DenseConvLayer}};
+use crate::{linalg::Scalar, regularization::Regularization};
+use crate::vision::{conv_initializers::ConvInitializers, conv_activation::ConvActivation, conv_optimizer::{ConvOptimizers, conv_sgd, conv_momentum, conv_adam, conv_rmsprop, conv_adagrad, conv_adadelta, conv_nadam, conv_amsgrad, conv_adamw}, conv_network::ConvNetworkLayer, conv_layer::{conv_padding::ConvPaddings, defaults::default_stride, full_conv_layer::FullConvLayer, dense_conv_layer::DenseConvLayer}};
 
 use super::conv_network_model::ConvNetworkModelBuilder;
 
@@ -9,42 +10,60 @@ use super::conv_network_model::ConvNetworkModelBuilder;
 pub struct FullDenseConvLayerModel {
     pub kernels_count: usize,
     pub kernels_size: usize,
+    #[serde(default = "default_stride")]
+    pub stride: usize,
+    #[serde(default)]
+    pub padding: ConvPaddings,
     pub activation: ConvActivation,
     pub biases_initializer: ConvInitializers,
     pub kernels_initializer: ConvInitializers,
     pub biases_optimizer: ConvOptimizers,
     pub kernels_optimizer: ConvOptimizers,
-    pub dropout: Option<f32>
+    pub dropout: Option<f32>,
+    #[serde(default)]
+    pub regularization: Option<Regularization>
 }
 
 impl FullDenseConvLayerModel {
-    pub fn to_layer(self, in_img_dims: usize, in_channels: usize) -> (usize, usize, Box<dyn ConvNetworkLayer>) {
+    pub fn to_layer(self, in_img_dims: (usize, usize), in_channels: usize) -> ((usize, usize), usize, Box<dyn ConvNetworkLayer>) {
+        let activation = self
+            .activation
+            .to_layer_with_optimizer(self.biases_optimizer.clone());
+
         let inner_layer = DenseConvLayer::new(
             self.kernels_size,
             self.kernels_size,
             in_channels,
             self.kernels_count,
+            self.stride,
+            self.padding,
             self.kernels_initializer,
             self.biases_initializer,
             self.kernels_optimizer,
             self.biases_optimizer
         );
+        let inner_layer = match self.regularization {
+            Some(regularization) => inner_layer.with_regularization(regularization),
+            None => inner_layer,
+        };
         
-        let (out_img_dims, _, out_channels) = DenseConvLayer::out_img_dims_and_channels(
-            in_img_dims,
-            in_img_dims,
+        let (out_rows, out_cols, out_channels) = DenseConvLayer::out_img_dims_and_channels(
+            in_img_dims.0,
+            in_img_dims.1,
             self.kernels_size,
             self.kernels_size,
-            self.kernels_count
+            self.kernels_count,
+            self.stride,
+            self.padding
         );
         
         let layer = FullConvLayer::new(
             Box::new(inner_layer),
-            self.activation.to_layer(),
+            activation,
             self.dropout
         );
 
-        (out_img_dims, out_channels, Box::new(layer))
+        ((out_rows, out_cols), out_channels, Box::new(layer))
     }
 }
 
@@ -59,12 +78,15 @@ impl FullDenseConvLayerModelBuilder {
             model: FullDenseConvLayerModel {
                 kernels_count,
                 kernels_size,
+                stride: default_stride(),
+                padding: ConvPaddings::Valid,
                 activation: ConvActivation::ConvReLU,
                 biases_initializer: ConvInitializers::Zeros,
                 kernels_initializer: ConvInitializers::GlorotUniform,
                 biases_optimizer: conv_sgd(),
                 kernels_optimizer: conv_sgd(),
-                dropout: None
+                dropout: None,
+                regularization: None
             },
             parent,
         }
@@ -74,6 +96,39 @@ impl FullDenseConvLayerModelBuilder {
         self.parent.accept_full_dense(self.model)
     }
 
+    /// Moves the kernels `stride` pixels at a time, downsampling the images.
+    pub fn stride(self, stride: usize) -> Self {
+        Self {
+            model: FullDenseConvLayerModel {
+                stride,
+                ..self.model
+            },
+            ..self
+        }
+    }
+
+    pub fn padding(self, padding: ConvPaddings) -> Self {
+        Self {
+            model: FullDenseConvLayerModel {
+                padding,
+                ..self.model
+            },
+            ..self
+        }
+    }
+
+    pub fn zero_padding(self, padding: usize) -> Self {
+        self.padding(ConvPaddings::Zero(padding))
+    }
+
+    pub fn same_padding(self) -> Self {
+        self.padding(ConvPaddings::Same)
+    }
+
+    pub fn reflect_padding(self, padding: usize) -> Self {
+        self.padding(ConvPaddings::Reflect(padding))
+    }
+
     pub fn dropout(self, dropped_rate: f32) -> Self {
         Self {
             model: FullDenseConvLayerModel {
@@ -84,6 +139,29 @@ impl FullDenseConvLayerModelBuilder {
         }
     }
 
+    /// Penalizes the weights, see `Regularization`.
+    pub fn regularization(self, regularization: Regularization) -> Self {
+        Self {
+            model: FullDenseConvLayerModel {
+                regularization: Some(regularization),
+                ..self.model
+            },
+            ..self
+        }
+    }
+
+    pub fn l1(self, l1: Scalar) -> Self {
+        self.regularization(Regularization::l1(l1))
+    }
+
+    pub fn l2(self, l2: Scalar) -> Self {
+        self.regularization(Regularization::l2(l2))
+    }
+
+    pub fn elastic_net(self, l1: Scalar, l2: Scalar) -> Self {
+        self.regularization(Regularization::elastic_net(l1, l2))
+    }
+
     pub fn activation(self, activation: ConvActivation) -> Self {
         Self {
             model: FullDenseConvLayerModel {
@@ -170,6 +248,31 @@ impl FullDenseConvLayerModelBuilder {
         self.optimizer(conv_adam())
     }
 
+    pub fn rmsprop(self) -> Self {
+        self.optimizer(conv_rmsprop())
+    }
+
+    pub fn adagrad(self) -> Self {
+        self.optimizer(conv_adagrad())
+    }
+
+    pub fn adadelta(self) -> Self {
+        self.optimizer(conv_adadelta())
+    }
+
+    pub fn nadam(self) -> Self {
+        self.optimizer(conv_nadam())
+    }
+
+    pub fn amsgrad(self) -> Self {
+        self.optimizer(conv_amsgrad())
+    }
+
+    /// AdamW with its default weight decay, the biases being optimized by Adam without decay
+    pub fn adamw(self) -> Self {
+        self.biases_optimizer(conv_adam()).kernels_optimizer(conv_adamw())
+    }
+
     pub fn biases_optimizer_sgd(self) -> Self {
         self.biases_optimizer(conv_sgd())
     }
@@ -1,6 +1,6 @@
 use serde::{Serialize, Deserialize};
 
-use crate::{activation::Activation, initializers::Initializers, optimizer::{Optimizers, sgd, momentum, adam}, layer::{dense_layer::DenseLayer, full_layer::FullLayer}, network::NetworkLayer};
+use crate::{activation::Activation, initializers::Initializers, optimizer::{Optimizers, sgd, momentum, adam, rmsprop, adagrad, adadelta, nadam, amsgrad, adamw}, layer::{dense_layer::DenseLayer, full_layer::FullLayer}, network::NetworkLayer, linalg::Scalar, regularization::Regularization};
 
 use super::network_model::NetworkModelBuilder;
 
@@ -13,23 +13,33 @@ pub struct FullDenseLayerModel {
     pub weights_initializer: Initializers,
     pub biases_optimizer: Optimizers,
     pub weights_optimizer: Optimizers,
-    pub dropout: Option<f32>
+    pub dropout: Option<f32>,
+    #[serde(default)]
+    pub regularization: Option<Regularization>
 }
 
 impl FullDenseLayerModel {
     pub fn to_layer(self, in_size: usize) -> (usize, Box<dyn NetworkLayer>) {
+        let activation = self
+            .activation
+            .to_layer_with_optimizer(self.biases_optimizer.clone());
+
         let inner = DenseLayer::new(
             in_size,
             self.size,
-            self.biases_optimizer,
             self.weights_optimizer,
+            self.biases_optimizer,
             self.weights_initializer,
             self.biases_initializer,
         );
+        let inner = match self.regularization {
+            Some(regularization) => inner.with_regularization(regularization),
+            None => inner,
+        };
 
         let layer = FullLayer::new(
             inner,
-            self.activation.to_layer(),
+            activation,
             self.dropout
         );
 
@@ -52,7 +62,8 @@ impl FullDenseLayerModelBuilder {
                 weights_initializer: Initializers::GlorotUniform,
                 biases_optimizer: sgd(),
                 weights_optimizer: sgd(),
-                dropout: None
+                dropout: None,
+                regularization: None
             },
             parent,
         }
@@ -72,6 +83,29 @@ impl FullDenseLayerModelBuilder {
         }
     }
 
+    /// Penalizes the weights, see `Regularization`.
+    pub fn regularization(self, regularization: Regularization) -> Self {
+        Self {
+            model: FullDenseLayerModel {
+                regularization: Some(regularization),
+                ..self.model
+            },
+            ..self
+        }
+    }
+
+    pub fn l1(self, l1: Scalar) -> Self {
+        self.regularization(Regularization::l1(l1))
+    }
+
+    pub fn l2(self, l2: Scalar) -> Self {
+        self.regularization(Regularization::l2(l2))
+    }
+
+    pub fn elastic_net(self, l1: Scalar, l2: Scalar) -> Self {
+        self.regularization(Regularization::elastic_net(l1, l2))
+    }
+
     pub fn activation(self, activation: Activation) -> Self {
         Self {
             model: FullDenseLayerModel {
@@ -102,6 +136,47 @@ impl FullDenseLayerModelBuilder {
         self.activation(Activation::Softmax)
     }
 
+    pub fn leaky_relu(self, alpha: Scalar) -> Self {
+        self.activation(Activation::LeakyReLU(alpha))
+    }
+
+    pub fn elu(self, alpha: Scalar) -> Self {
+        self.activation(Activation::ELU(alpha))
+    }
+
+    pub fn selu(self) -> Self {
+        self.activation(Activation::SELU)
+    }
+
+    pub fn gelu(self) -> Self {
+        self.activation(Activation::GELU)
+    }
+
+    pub fn swish(self) -> Self {
+        self.activation(Activation::Swish)
+    }
+
+    pub fn silu(self) -> Self {
+        self.swish()
+    }
+
+    pub fn softplus(self) -> Self {
+        self.activation(Activation::Softplus)
+    }
+
+    pub fn hard_sigmoid(self) -> Self {
+        self.activation(Activation::HardSigmoid)
+    }
+
+    pub fn mish(self) -> Self {
+        self.activation(Activation::Mish)
+    }
+
+    /// PReLU starting with the given slope (usually 0.25)
+    pub fn prelu(self, slope: Scalar) -> Self {
+        self.activation(Activation::PReLU(slope))
+    }
+
     pub fn init_zeros(self) -> Self {
         self.init(Initializers::Zeros)
     }
@@ -162,6 +237,31 @@ impl FullDenseLayerModelBuilder {
         self.optimizer(adam())
     }
 
+    pub fn rmsprop(self) -> Self {
+        self.optimizer(rmsprop())
+    }
+
+    pub fn adagrad(self) -> Self {
+        self.optimizer(adagrad())
+    }
+
+    pub fn adadelta(self) -> Self {
+        self.optimizer(adadelta())
+    }
+
+    pub fn nadam(self) -> Self {
+        self.optimizer(nadam())
+    }
+
+    pub fn amsgrad(self) -> Self {
+        self.optimizer(amsgrad())
+    }
+
+    /// AdamW with its default weight decay, the biases being optimized by Adam without decay
+    pub fn adamw(self) -> Self {
+        self.biases_optimizer(adam()).weights_optimizer(adamw())
+    }
+
     pub fn biases_optimizer_sgd(self) -> Self {
         self.biases_optimizer(sgd())
     }
@@ -1,6 +1,6 @@
 use serde::{Serialize, Deserialize};
 
-use crate::vision::{conv_initializers::ConvInitializers, conv_activation::ConvActivation, conv_optimizer::{ConvOptimizers, conv_sgd, conv_momentum, conv_adam}, conv_network::ConvNetworkLayer, conv_layer::{direct_conv_layer::DirectConvLayer, full_conv_layer::FullConvLayer}};
+use crate::vision::{conv_initializers::ConvInitializers, conv_activation::ConvActivation, conv_optimizer::{ConvOptimizers, conv_sgd, conv_momentum, conv_adam, conv_rmsprop, conv_adagrad, conv_adadelta, conv_nadam, conv_amsgrad, conv_adamw}, conv_network::ConvNetworkLayer, conv_layer::{conv_padding::ConvPaddings, defaults::default_stride, direct_conv_layer::DirectConvLayer, full_conv_layer::FullConvLayer}};
 
 use super::conv_network_model::ConvNetworkModelBuilder;
 
@@ -8,6 +8,10 @@ use super::conv_network_model::ConvNetworkModelBuilder;
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct FullDirectConvLayerModel {
     pub kernels_size: usize,
+    #[serde(default = "default_stride")]
+    pub stride: usize,
+    #[serde(default)]
+    pub padding: ConvPaddings,
     pub activation: ConvActivation,
     pub biases_initializer: ConvInitializers,
     pub kernels_initializer: ConvInitializers,
@@ -17,32 +21,40 @@ pub struct FullDirectConvLayerModel {
 }
 
 impl FullDirectConvLayerModel {
-    pub fn to_layer(self, in_img_dims: usize, in_channels: usize) -> (usize, usize, Box<dyn ConvNetworkLayer>) {
+    pub fn to_layer(self, in_img_dims: (usize, usize), in_channels: usize) -> ((usize, usize), usize, Box<dyn ConvNetworkLayer>) {
+        let activation = self
+            .activation
+            .to_layer_with_optimizer(self.biases_optimizer.clone());
+
         let inner_layer = DirectConvLayer::new(
             self.kernels_size,
             self.kernels_size,
             in_channels,
+            self.stride,
+            self.padding,
             self.kernels_initializer,
             self.biases_initializer,
             self.kernels_optimizer,
             self.biases_optimizer
         );
         
-        let (out_img_dims, _, out_channels) = DirectConvLayer::out_img_dims_and_channels(
-            in_img_dims,
-            in_img_dims,
+        let (out_rows, out_cols, out_channels) = DirectConvLayer::out_img_dims_and_channels(
+            in_img_dims.0,
+            in_img_dims.1,
             in_channels,
             self.kernels_size,
             self.kernels_size,
+            self.stride,
+            self.padding,
         );
         
         let layer = FullConvLayer::new(
             Box::new(inner_layer),
-            self.activation.to_layer(),
+            activation,
             self.dropout
         );
 
-        (out_img_dims, out_channels, Box::new(layer))
+        ((out_rows, out_cols), out_channels, Box::new(layer))
     }
 }
 
@@ -56,6 +68,8 @@ impl FullDirectConvLayerModelBuilder {
         Self {
             model: FullDirectConvLayerModel {
                 kernels_size,
+                stride: default_stride(),
+                padding: ConvPaddings::Valid,
                 activation: ConvActivation::ConvReLU,
                 biases_initializer: ConvInitializers::Zeros,
                 kernels_initializer: ConvInitializers::GlorotUniform,
@@ -71,6 +85,39 @@ impl FullDirectConvLayerModelBuilder {
         self.parent.accept_full_direct(self.model)
     }
 
+    /// Moves the kernels `stride` pixels at a time, downsampling the images.
+    pub fn stride(self, stride: usize) -> Self {
+        Self {
+            model: FullDirectConvLayerModel {
+                stride,
+                ..self.model
+            },
+            ..self
+        }
+    }
+
+    pub fn padding(self, padding: ConvPaddings) -> Self {
+        Self {
+            model: FullDirectConvLayerModel {
+                padding,
+                ..self.model
+            },
+            ..self
+        }
+    }
+
+    pub fn zero_padding(self, padding: usize) -> Self {
+        self.padding(ConvPaddings::Zero(padding))
+    }
+
+    pub fn same_padding(self) -> Self {
+        self.padding(ConvPaddings::Same)
+    }
+
+    pub fn reflect_padding(self, padding: usize) -> Self {
+        self.padding(ConvPaddings::Reflect(padding))
+    }
+
     pub fn dropout(self, dropped_rate: f32) -> Self {
         Self {
             model: FullDirectConvLayerModel {
@@ -167,6 +214,31 @@ impl FullDirectConvLayerModelBuilder {
         self.optimizer(conv_adam())
     }
 
+    pub fn rmsprop(self) -> Self {
+        self.optimizer(conv_rmsprop())
+    }
+
+    pub fn adagrad(self) -> Self {
+        self.optimizer(conv_adagrad())
+    }
+
+    pub fn adadelta(self) -> Self {
+        self.optimizer(conv_adadelta())
+    }
+
+    pub fn nadam(self) -> Self {
+        self.optimizer(conv_nadam())
+    }
+
+    pub fn amsgrad(self) -> Self {
+        self.optimizer(conv_amsgrad())
+    }
+
+    /// AdamW with its default weight decay, the biases being optimized by Adam without decay
+    pub fn adamw(self) -> Self {
+        self.biases_optimizer(conv_adam()).kernels_optimizer(conv_adamw())
+    }
+
     pub fn biases_optimizer_sgd(self) -> Self {
         self.biases_optimizer(conv_sgd())
     }
@@ -0,0 +1,174 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    loss::{Loss, Losses},
+    network::graph::{GraphNetwork, GraphOps},
+};
+
+use super::{network_model::NetworkModel, ModelBuilder};
+
+pub struct GraphNetworkModelBuilder {
+    pub model: GraphNetworkModel,
+    pub parent: Option<ModelBuilder>,
+}
+
+impl GraphNetworkModelBuilder {
+    pub fn new() -> Self {
+        Self {
+            model: GraphNetworkModel {
+                inputs: vec![],
+                nodes: vec![],
+                outputs: vec![],
+            },
+            parent: None,
+        }
+    }
+
+    pub fn set_parent(mut self, parent: ModelBuilder) -> Self {
+        self.parent = Some(parent);
+        self
+    }
+
+    /// Adds a named input of the graph with `size` features.
+    pub fn input(mut self, name: &str, size: usize) -> Self {
+        self.model.inputs.push(GraphInputModel {
+            name: name.to_string(),
+            size,
+        });
+        self
+    }
+
+    /// Adds a node applying the layers of `network` to the `input` node.
+    pub fn layers(self, name: &str, input: &str, network: NetworkModel) -> Self {
+        self.node(name, &[input], GraphNodeModels::Layers(network))
+    }
+
+    /// Adds a node stacking the features of the `inputs` nodes on top of each other.
+    pub fn concat(self, name: &str, inputs: &[&str]) -> Self {
+        self.node(name, inputs, GraphNodeModels::Concat)
+    }
+
+    /// Adds a node summing the `inputs` nodes.
+    pub fn add(self, name: &str, inputs: &[&str]) -> Self {
+        self.node(name, inputs, GraphNodeModels::Add)
+    }
+
+    /// Adds a node keeping the features `start..start + size` of the `input` node.
+    pub fn split(self, name: &str, input: &str, start: usize, size: usize) -> Self {
+        self.node(name, &[input], GraphNodeModels::Split { start, size })
+    }
+
+    fn node(mut self, name: &str, inputs: &[&str], op: GraphNodeModels) -> Self {
+        self.model.nodes.push(GraphNodeModel {
+            name: name.to_string(),
+            inputs: inputs.iter().map(|i| i.to_string()).collect(),
+            op,
+        });
+        self
+    }
+
+    /// Marks the node `name` as an output of the graph, trained to minimize `loss`.
+    pub fn output(mut self, name: &str, loss: Losses) -> Self {
+        self.model.outputs.push(GraphOutputModel {
+            name: name.to_string(),
+            loss,
+        });
+        self
+    }
+
+    pub fn end(self) -> ModelBuilder {
+        match self.parent {
+            Some(parent) => parent.accept_graph_network(self.model),
+            None => panic!("No parent model builder set"),
+        }
+    }
+
+    pub fn build(self) -> GraphNetworkModel {
+        self.model
+    }
+}
+
+impl Default for GraphNetworkModelBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct GraphNetworkModel {
+    pub inputs: Vec<GraphInputModel>,
+    /// In an order where each node only takes inputs from the graph's inputs and the previous nodes
+    pub nodes: Vec<GraphNodeModel>,
+    pub outputs: Vec<GraphOutputModel>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct GraphInputModel {
+    pub name: String,
+    pub size: usize,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct GraphNodeModel {
+    pub name: String,
+    pub inputs: Vec<String>,
+    pub op: GraphNodeModels,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct GraphOutputModel {
+    pub name: String,
+    pub loss: Losses,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub enum GraphNodeModels {
+    Layers(NetworkModel),
+    Concat,
+    Add,
+    Split { start: usize, size: usize },
+}
+
+impl GraphNetworkModel {
+    pub fn to_graph_network(self) -> GraphNetwork {
+        let mut network = GraphNetwork::new();
+        for input in self.inputs.iter() {
+            network.add_node(&input.name, &[], GraphOps::Input(input.size));
+        }
+        for node in self.nodes.into_iter() {
+            let inputs: Vec<&str> = node.inputs.iter().map(|i| i.as_str()).collect();
+            match node.op {
+                GraphNodeModels::Layers(layers) => {
+                    if inputs.len() != 1 {
+                        panic!("Layers node {:?} must have exactly one input", node.name);
+                    }
+                    let (out_dims, layers) = layers.to_layers(network.dims(inputs[0]));
+                    network.add_layers_node(&node.name, inputs[0], layers, out_dims);
+                }
+                GraphNodeModels::Concat => {
+                    network.add_node(&node.name, &inputs, GraphOps::Concat);
+                }
+                GraphNodeModels::Add => {
+                    network.add_node(&node.name, &inputs, GraphOps::Add);
+                }
+                GraphNodeModels::Split { start, size } => {
+                    network.add_node(&node.name, &inputs, GraphOps::Split { start, size });
+                }
+            }
+        }
+        for output in self.outputs.iter() {
+            network.add_output(&output.name);
+        }
+        network
+    }
+
+    /// The loss of each output, by name, as expected by `GraphNetwork::train`.
+    pub fn losses(&self) -> HashMap<String, Loss> {
+        self.outputs
+            .iter()
+            .map(|o| (o.name.clone(), o.loss.to_loss()))
+            .collect()
+    }
+}
@@ -0,0 +1,120 @@
+use serde::{Serialize, Deserialize};
+
+use crate::{linalg::Scalar, optimizer::{Optimizers, sgd, momentum, adam, rmsprop, adagrad, adadelta, nadam, amsgrad, adamw}, layer::{layer_norm_layer::LayerNormLayer, defaults::default_norm_epsilon}, network::NetworkLayer};
+
+use super::network_model::NetworkModelBuilder;
+
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct LayerNormLayerModel {
+    #[serde(default = "default_norm_epsilon")]
+    pub epsilon: Scalar,
+    pub gamma_optimizer: Optimizers,
+    pub beta_optimizer: Optimizers,
+}
+
+impl LayerNormLayerModel {
+    pub fn to_layer(self, in_size: usize) -> (usize, Box<dyn NetworkLayer>) {
+        let layer = LayerNormLayer::new(
+            in_size,
+            self.epsilon,
+            self.gamma_optimizer,
+            self.beta_optimizer,
+        );
+
+        (in_size, Box::new(layer))
+    }
+}
+
+pub struct LayerNormLayerModelBuilder {
+    pub model: LayerNormLayerModel,
+    parent: NetworkModelBuilder
+}
+
+impl LayerNormLayerModelBuilder {
+    pub fn new(parent: NetworkModelBuilder) -> Self {
+        Self {
+            model: LayerNormLayerModel {
+                epsilon: default_norm_epsilon(),
+                gamma_optimizer: sgd(),
+                beta_optimizer: sgd(),
+            },
+            parent,
+        }
+    }
+
+    pub fn end(self) -> NetworkModelBuilder {
+        self.parent.accept_layer_norm(self.model)
+    }
+
+    pub fn epsilon(self, epsilon: Scalar) -> Self {
+        Self {
+            model: LayerNormLayerModel {
+                epsilon,
+                ..self.model
+            },
+            ..self
+        }
+    }
+
+    pub fn sgd(self) -> Self {
+        self.optimizer(sgd())
+    }
+
+    pub fn momentum(self) -> Self {
+        self.optimizer(momentum())
+    }
+
+    pub fn adam(self) -> Self {
+        self.optimizer(adam())
+    }
+
+    pub fn rmsprop(self) -> Self {
+        self.optimizer(rmsprop())
+    }
+
+    pub fn adagrad(self) -> Self {
+        self.optimizer(adagrad())
+    }
+
+    pub fn adadelta(self) -> Self {
+        self.optimizer(adadelta())
+    }
+
+    pub fn nadam(self) -> Self {
+        self.optimizer(nadam())
+    }
+
+    pub fn amsgrad(self) -> Self {
+        self.optimizer(amsgrad())
+    }
+
+    pub fn adamw(self) -> Self {
+        self.optimizer(adamw())
+    }
+
+    pub fn optimizer(self, optimizer: Optimizers) -> Self {
+        self.gamma_optimizer(optimizer.clone())
+            .beta_optimizer(optimizer)
+    }
+
+    pub fn gamma_optimizer(self, optimizer: Optimizers) -> Self {
+        Self {
+            model: LayerNormLayerModel {
+                gamma_optimizer: optimizer,
+                ..self.model
+            },
+            ..self
+        }
+    }
+
+    pub fn beta_optimizer(self, optimizer: Optimizers) -> Self {
+        Self {
+            model: LayerNormLayerModel {
+                beta_optimizer: optimizer,
+                ..self.model
+            },
+            ..self
+        }
+    }
+}
@@ -14,17 +14,33 @@ use crate::dataset::Dataset;
 #[cfg(feature = "data")]
 use crate::datatable::DataTable;
 
+use crate::gradient_clipping::GradientClipping;
 use crate::linalg::Scalar;
-use crate::loss::Losses;
-use crate::network::{Network};
+use crate::loss::{Loss, Losses};
+use crate::network::{Network, graph::GraphNetwork};
 
+use self::graph_network_model::{GraphNetworkModel, GraphNetworkModelBuilder};
 use self::network_model::{NetworkModel, NetworkModelBuilder};
 
 pub mod network_model;
+pub mod graph_network_model;
+pub mod batch_norm_layer_model;
+pub mod batch_norm_2d_layer_model;
+pub mod layer_norm_layer_model;
+pub mod recurrent_layer_model;
+pub mod multi_head_attention_layer_model;
+pub mod transformer_encoder_block_model;
+pub mod residual_block_model;
+pub mod conv_residual_block_model;
 pub mod conv_network_model;
+pub mod conv1d_network_model;
+pub mod embedding_layer_model;
 pub mod full_dense_layer_model;
 pub mod full_direct_conv_layer_model;
 pub mod full_dense_conv_layer_model;
+pub mod full_transposed_conv_layer_model;
+pub mod full_separable_conv_layer_model;
+pub mod full_conv1d_layer_model;
 
 pub struct ModelBuilder {
     pub model: Model
@@ -39,7 +55,10 @@ impl ModelBuilder {
                 loss: Losses::MSE,
                 epochs: 100,
                 batch_size: Some(32),
-                network: None
+                class_weights: None,
+                gradient_clipping: None,
+                network: None,
+                graph_network: None
             }
         }
     }
@@ -51,7 +70,10 @@ impl ModelBuilder {
                 loss: Losses::MSE,
                 epochs: 100,
                 batch_size: Some(32),
-                network: None
+                class_weights: None,
+                gradient_clipping: None,
+                network: None,
+                graph_network: None
             }
         }
     }
@@ -66,6 +88,29 @@ impl ModelBuilder {
         }
     }
 
+    /// Weights the loss of each sample by the weight of its class, see `Loss::with_class_weights`.
+    pub fn class_weights(self, class_weights: Vec<Scalar>) -> Self {
+        Self {
+            model: Model {
+                class_weights: Some(class_weights),
+                ..self.model
+            },
+        }
+    }
+
+    /// Clips the network's gradients before each update of its parameters, see `GradientClipping`.
+    ///
+    /// Panics if the clipping threshold isn't positive.
+    pub fn gradient_clipping(self, gradient_clipping: GradientClipping) -> Self {
+        let gradient_clipping = gradient_clipping.validate().unwrap_or_else(|e| panic!("{}", e));
+        Self {
+            model: Model {
+                gradient_clipping: Some(gradient_clipping),
+                ..self.model
+            },
+        }
+    }
+
     pub fn epochs(self, epochs: usize) -> Self {
         Self {
             model: Model {
@@ -95,6 +140,16 @@ impl ModelBuilder {
         self
     }
 
+    /// Configures a network with named inputs and outputs, used in place of `neural_network`.
+    pub fn graph_network(self) -> GraphNetworkModelBuilder {
+        GraphNetworkModelBuilder::new().set_parent(self)
+    }
+
+    pub(crate) fn accept_graph_network(mut self, network: GraphNetworkModel) -> Self {
+        self.model.graph_network = Some(network);
+        self
+    }
+
     pub fn build(self) -> Model {
         self.model
     }
@@ -106,8 +161,14 @@ pub struct Model {
     pub epochs: usize,
     pub loss: Losses,
     pub batch_size: Option<usize>,
+    #[serde(default)]
+    pub class_weights: Option<Vec<Scalar>>,
+    #[serde(default)]
+    pub gradient_clipping: Option<GradientClipping>,
     pub dataset_config: Dataset,
-    pub network: Option<NetworkModel>
+    pub network: Option<NetworkModel>,
+    #[serde(default)]
+    pub graph_network: Option<GraphNetworkModel>
 }
 
 #[cfg(not(feature = "data"))]
@@ -116,7 +177,13 @@ pub struct Model {
     pub epochs: usize,
     pub loss: Losses,
     pub batch_size: Option<usize>,
-    pub network: Option<NetworkModel>
+    #[serde(default)]
+    pub class_weights: Option<Vec<Scalar>>,
+    #[serde(default)]
+    pub gradient_clipping: Option<GradientClipping>,
+    pub network: Option<NetworkModel>,
+    #[serde(default)]
+    pub graph_network: Option<GraphNetworkModel>
 }
 
 impl Model {
@@ -156,13 +223,59 @@ impl Model {
     pub fn to_network(&self) -> Network {
         let network_config = self.network.clone().expect("You cannot create a network if it is not configurationified");
         let in_dims = self.dataset_config.in_features_names().len();
-        network_config.to_network(in_dims)
+        let network = network_config
+            .with_embedded_inputs(&self.dataset_config.embedded_inputs())
+            .to_network(in_dims);
+        self.with_gradient_clipping(network)
     }
 
     #[cfg(not(feature = "data"))]
     pub fn to_network(&self, in_dims: usize) -> Network {
         let network_config = self.network.clone().expect("You cannot create a network if it is not configurationified");
-        network_config.to_network(in_dims)
+        self.with_gradient_clipping(network_config.to_network(in_dims))
+    }
+
+    fn with_gradient_clipping(&self, network: Network) -> Network {
+        match self.gradient_clipping {
+            Some(gradient_clipping) => network.with_gradient_clipping(gradient_clipping),
+            None => network,
+        }
+    }
+
+    /// Returns the model's loss, weighted by its class weights if any.
+    pub fn to_loss(&self) -> Loss {
+        let loss = self.loss.to_loss();
+        match &self.class_weights {
+            Some(class_weights) => loss.with_class_weights(class_weights.clone()),
+            None => loss,
+        }
+    }
+
+    #[cfg(feature = "data")]
+    /// Converts a table without the predicted features to the network's inputs
+    /// and the samples' weights if the dataset has a `SampleWeight` feature.
+    pub fn inputs_and_weights(
+        &self,
+        x_table: &DataTable,
+        id_column: &str,
+    ) -> (Vec<Vec<Scalar>>, Option<Vec<Scalar>>) {
+        let x_table = x_table.drop_column(id_column);
+        match self.dataset_config.get_sample_weight_column() {
+            Some(weight_column) => (
+                x_table.drop_column(weight_column).to_vectors(),
+                Some(x_table.column_to_vector(weight_column)),
+            ),
+            None => (x_table.to_vectors(), None),
+        }
+    }
+
+    pub fn to_graph_network(&self) -> GraphNetwork {
+        let network_config = self.graph_network.clone().expect("You cannot create a graph network if it is not configured");
+        let mut network = network_config.to_graph_network();
+        if let Some(gradient_clipping) = self.gradient_clipping {
+            network.set_gradient_clipping(gradient_clipping);
+        }
+        network
     }
 
     #[cfg(feature = "data")]
@@ -176,14 +289,15 @@ impl Model {
         let (train_x_table, train_y_table) =
             train_data.random_order_in_out(&self.dataset_config.predicted_features_names());
 
-        let train_x = train_x_table.drop_column(id_column).to_vectors();
+        let (train_x, train_weights) = self.inputs_and_weights(&train_x_table, id_column);
         let train_y = train_y_table.to_vectors();
 
-        let train_loss = network.train(
+        let train_loss = network.train_weighted(
             epoch,
             &train_x,
             &train_y,
-            &self.loss.to_loss(),
+            train_weights.as_ref(),
+            &self.to_loss(),
             self.batch_size.unwrap_or(train_x.len()),
         );
 
@@ -202,7 +316,7 @@ impl Model {
             epoch,
             &train_x,
             &train_y,
-            &self.loss.to_loss(),
+            &self.to_loss(),
             self.batch_size.unwrap_or(train_x.len()),
         );
 
@@ -0,0 +1,177 @@
+use serde::{Serialize, Deserialize};
+
+use crate::{initializers::Initializers, optimizer::{Optimizers, sgd, momentum, adam, rmsprop, adagrad, adadelta, nadam, amsgrad, adamw}, layer::multi_head_attention_layer::MultiHeadAttentionLayer, network::NetworkLayer};
+
+use super::network_model::NetworkModelBuilder;
+
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct MultiHeadAttentionLayerModel {
+    pub heads: usize,
+    /// Number of features per token, the input being a flattened sequence of tokens
+    pub in_features: usize,
+    pub biases_initializer: Initializers,
+    pub weights_initializer: Initializers,
+    pub biases_optimizer: Optimizers,
+    pub weights_optimizer: Optimizers,
+}
+
+impl MultiHeadAttentionLayerModel {
+    pub fn to_layer(self, in_size: usize) -> (usize, Box<dyn NetworkLayer>) {
+        if in_size / self.in_features * self.in_features != in_size {
+            panic!(
+                "Multi-head attention expects tokens of {} features, but got {} inputs",
+                self.in_features, in_size
+            );
+        }
+
+        let layer = MultiHeadAttentionLayer::new(
+            self.in_features,
+            self.heads,
+            self.weights_optimizer,
+            self.biases_optimizer,
+            self.weights_initializer,
+            self.biases_initializer,
+        );
+
+        (in_size, Box::new(layer))
+    }
+}
+
+pub struct MultiHeadAttentionLayerModelBuilder {
+    pub model: MultiHeadAttentionLayerModel,
+    parent: NetworkModelBuilder
+}
+
+impl MultiHeadAttentionLayerModelBuilder {
+    pub fn new(parent: NetworkModelBuilder, heads: usize) -> Self {
+        Self {
+            model: MultiHeadAttentionLayerModel {
+                heads,
+                in_features: heads,
+                biases_initializer: Initializers::Zeros,
+                weights_initializer: Initializers::GlorotUniform,
+                biases_optimizer: sgd(),
+                weights_optimizer: sgd(),
+            },
+            parent,
+        }
+    }
+
+    pub fn end(self) -> NetworkModelBuilder {
+        self.parent.accept_multi_head_attention(self.model)
+    }
+
+    pub fn in_features(self, in_features: usize) -> Self {
+        Self {
+            model: MultiHeadAttentionLayerModel {
+                in_features,
+                ..self.model
+            },
+            ..self
+        }
+    }
+
+    pub fn init_zeros(self) -> Self {
+        self.init(Initializers::Zeros)
+    }
+
+    pub fn init_uniform(self) -> Self {
+        self.init(Initializers::Uniform)
+    }
+
+    pub fn init_uniform_signed(self) -> Self {
+        self.init(Initializers::UniformSigned)
+    }
+
+    pub fn init_glorot_uniform(self) -> Self {
+        self.init(Initializers::GlorotUniform)
+    }
+
+    pub fn sgd(self) -> Self {
+        self.optimizer(sgd())
+    }
+
+    pub fn momentum(self) -> Self {
+        self.optimizer(momentum())
+    }
+
+    pub fn adam(self) -> Self {
+        self.optimizer(adam())
+    }
+
+    pub fn rmsprop(self) -> Self {
+        self.optimizer(rmsprop())
+    }
+
+    pub fn adagrad(self) -> Self {
+        self.optimizer(adagrad())
+    }
+
+    pub fn adadelta(self) -> Self {
+        self.optimizer(adadelta())
+    }
+
+    pub fn nadam(self) -> Self {
+        self.optimizer(nadam())
+    }
+
+    pub fn amsgrad(self) -> Self {
+        self.optimizer(amsgrad())
+    }
+
+    /// AdamW with its default weight decay, the biases being optimized by Adam without decay
+    pub fn adamw(self) -> Self {
+        self.biases_optimizer(adam()).weights_optimizer(adamw())
+    }
+
+    pub fn optimizer(self, optimizer: Optimizers) -> Self {
+        self.biases_optimizer(optimizer.clone())
+            .weights_optimizer(optimizer)
+    }
+
+    pub fn init(self, initializer: Initializers) -> Self {
+        self.biases_init(initializer.clone())
+            .weights_init(initializer)
+    }
+
+    pub fn biases_optimizer(self, optimizer: Optimizers) -> Self {
+        Self {
+            model: MultiHeadAttentionLayerModel {
+                biases_optimizer: optimizer,
+                ..self.model
+            },
+            ..self
+        }
+    }
+
+    pub fn weights_optimizer(self, optimizer: Optimizers) -> Self {
+        Self {
+            model: MultiHeadAttentionLayerModel {
+                weights_optimizer: optimizer,
+                ..self.model
+            },
+            ..self
+        }
+    }
+
+    pub fn biases_init(self, initializer: Initializers) -> Self {
+        Self {
+            model: MultiHeadAttentionLayerModel {
+                biases_initializer: initializer,
+                ..self.model
+            },
+            ..self
+        }
+    }
+
+    pub fn weights_init(self, initializer: Initializers) -> Self {
+        Self {
+            model: MultiHeadAttentionLayerModel {
+                weights_initializer: initializer,
+                ..self.model
+            },
+            ..self
+        }
+    }
+}
@@ -1,19 +1,22 @@
 use serde::{Serialize, Deserialize};
 
-use crate::network::{Network, NetworkLayer};
+use crate::{network::{Network, NetworkLayer}, linalg::Scalar, layer::{dropout::{AlphaDropout, Dropout, GaussianDropout, GaussianNoise}, recurrent_layer::RecurrentCells, defaults::{default_weights_initializer, default_weights_optimizer}}, initializers::Initializers, optimizer::Optimizers};
 
-use super::{ModelBuilder, conv_network_model::{ConvNetworkModelBuilder, ConvNetworkModel}, full_dense_layer_model::{FullDenseLayerModel, FullDenseLayerModelBuilder}};
+use super::{ModelBuilder, conv_network_model::{ConvNetworkModelBuilder, ConvNetworkModel}, conv1d_network_model::{Conv1dNetworkModelBuilder, Conv1dNetworkModel}, full_dense_layer_model::{FullDenseLayerModel, FullDenseLayerModelBuilder}, batch_norm_layer_model::{BatchNormLayerModel, BatchNormLayerModelBuilder}, layer_norm_layer_model::{LayerNormLayerModel, LayerNormLayerModelBuilder}, recurrent_layer_model::{RecurrentLayerModel, RecurrentLayerModelBuilder}, multi_head_attention_layer_model::{MultiHeadAttentionLayerModel, MultiHeadAttentionLayerModelBuilder}, transformer_encoder_block_model::{TransformerEncoderBlockModel, TransformerEncoderBlockModelBuilder}, residual_block_model::ResidualBlockModel, embedding_layer_model::{EmbeddingLayerModel, EmbeddingLayerModelBuilder}};
 
 pub struct NetworkModelBuilder {
     pub model: NetworkModel,
-    pub parent: Option<ModelBuilder>
+    pub parent: Option<ModelBuilder>,
+    // builder of the network containing the residual block being built
+    enclosing: Option<Box<NetworkModelBuilder>>
 }
 
 impl NetworkModelBuilder {
     pub fn new() -> Self {
         Self {
             model: NetworkModel { layers: Vec::new() },
-            parent: None
+            parent: None,
+            enclosing: None
         }
     }
 
@@ -31,6 +34,16 @@ impl NetworkModelBuilder {
         self
     }
 
+    /// Starts a convolutional network over 1D signals of `in_channels` channels.
+    pub fn conv1d_network(self, in_channels: usize) -> Conv1dNetworkModelBuilder {
+        Conv1dNetworkModelBuilder::new(self, in_channels)
+    }
+
+    pub(crate) fn accept_conv1d_network(mut self, layer: Conv1dNetworkModel) -> Self {
+        self.model.layers.push(NetworkLayerModels::Conv1d(layer));
+        self
+    }
+
     pub fn full_dense(self, size: usize) -> FullDenseLayerModelBuilder {
         FullDenseLayerModelBuilder::new(self, size)
     }
@@ -40,7 +53,126 @@ impl NetworkModelBuilder {
         self
     }
 
+    /// Replaces the input at `input_index`, holding category ids, with a learned vector of `dim` values per category.
+    pub fn embedding(self, input_index: usize, categories_count: usize, dim: usize) -> EmbeddingLayerModelBuilder {
+        EmbeddingLayerModelBuilder::new(self, input_index, categories_count, dim)
+    }
+
+    pub(crate) fn accept_embedding(mut self, layer: EmbeddingLayerModel) -> Self {
+        self.model.layers.push(NetworkLayerModels::Embedding(layer));
+        self
+    }
+
+    /// Zeroes each input with probability `rate` while training.
+    pub fn dropout(mut self, rate: Scalar) -> Self {
+        self.model.layers.push(NetworkLayerModels::Dropout { rate });
+        self
+    }
+
+    /// Adds a centered gaussian noise of standard deviation `std_dev` to the inputs while training.
+    pub fn gaussian_noise(mut self, std_dev: Scalar) -> Self {
+        self.model.layers.push(NetworkLayerModels::GaussianNoise { std_dev });
+        self
+    }
+
+    /// Multiplies the inputs by a gaussian noise of mean 1 and variance `rate / (1 - rate)` while training.
+    pub fn gaussian_dropout(mut self, rate: Scalar) -> Self {
+        self.model.layers.push(NetworkLayerModels::GaussianDropout { rate });
+        self
+    }
+
+    /// Dropout for SELU activated inputs, keeping their mean and variance.
+    pub fn alpha_dropout(mut self, rate: Scalar) -> Self {
+        self.model.layers.push(NetworkLayerModels::AlphaDropout { rate });
+        self
+    }
+
+    pub fn batch_norm(self) -> BatchNormLayerModelBuilder {
+        BatchNormLayerModelBuilder::new(self)
+    }
+
+    pub(crate) fn accept_batch_norm(mut self, layer: BatchNormLayerModel) -> Self {
+        self.model.layers.push(NetworkLayerModels::BatchNorm(layer));
+        self
+    }
+
+    pub fn layer_norm(self) -> LayerNormLayerModelBuilder {
+        LayerNormLayerModelBuilder::new(self)
+    }
+
+    pub(crate) fn accept_layer_norm(mut self, layer: LayerNormLayerModel) -> Self {
+        self.model.layers.push(NetworkLayerModels::LayerNorm(layer));
+        self
+    }
+
+    pub fn rnn(self, size: usize) -> RecurrentLayerModelBuilder {
+        RecurrentLayerModelBuilder::new(self, RecurrentCells::SimpleRNN, size)
+    }
+
+    pub fn lstm(self, size: usize) -> RecurrentLayerModelBuilder {
+        RecurrentLayerModelBuilder::new(self, RecurrentCells::LSTM, size)
+    }
+
+    pub fn gru(self, size: usize) -> RecurrentLayerModelBuilder {
+        RecurrentLayerModelBuilder::new(self, RecurrentCells::GRU, size)
+    }
+
+    pub(crate) fn accept_recurrent(mut self, layer: RecurrentLayerModel) -> Self {
+        self.model.layers.push(NetworkLayerModels::Recurrent(layer));
+        self
+    }
+
+    pub fn multi_head_attention(self, heads: usize) -> MultiHeadAttentionLayerModelBuilder {
+        MultiHeadAttentionLayerModelBuilder::new(self, heads)
+    }
+
+    pub(crate) fn accept_multi_head_attention(mut self, layer: MultiHeadAttentionLayerModel) -> Self {
+        self.model.layers.push(NetworkLayerModels::MultiHeadAttention(layer));
+        self
+    }
+
+    pub fn transformer_encoder(self, heads: usize) -> TransformerEncoderBlockModelBuilder {
+        TransformerEncoderBlockModelBuilder::new(self, heads)
+    }
+
+    pub(crate) fn accept_transformer_encoder(mut self, layer: TransformerEncoderBlockModel) -> Self {
+        self.model.layers.push(NetworkLayerModels::TransformerEncoder(layer));
+        self
+    }
+
+    /// Starts a residual block, its layers being added until `end_residual` is called.
+    pub fn residual(self) -> Self {
+        Self {
+            model: NetworkModel { layers: Vec::new() },
+            parent: None,
+            enclosing: Some(Box::new(self))
+        }
+    }
+
+    pub fn end_residual(self) -> Self {
+        self.end_residual_with_projection(default_weights_initializer(), default_weights_optimizer())
+    }
+
+    /// Ends a residual block whose input is projected with the given initializer and optimizer
+    /// when its layers change its dimensions.
+    pub fn end_residual_with_projection(self, initializer: Initializers, optimizer: Optimizers) -> Self {
+        let enclosing = *self.enclosing.expect("No residual block started");
+        enclosing.accept_residual(ResidualBlockModel {
+            layers: self.model.layers,
+            projection_initializer: initializer,
+            projection_optimizer: optimizer,
+        })
+    }
+
+    pub(crate) fn accept_residual(mut self, layer: ResidualBlockModel) -> Self {
+        self.model.layers.push(NetworkLayerModels::Residual(layer));
+        self
+    }
+
     pub fn end(self) -> ModelBuilder {
+        if self.enclosing.is_some() {
+            panic!("Residual block not ended, call end_residual() first");
+        }
         match self.parent {
             Some(parent) => parent.accept_neural_network(self.model),
             None => panic!("No parent model builder set")
@@ -48,6 +180,9 @@ impl NetworkModelBuilder {
     }
 
     pub fn build(self) -> NetworkModel {
+        if self.enclosing.is_some() {
+            panic!("Residual block not ended, call end_residual() first");
+        }
         self.model
     }
 }
@@ -58,28 +193,105 @@ pub struct NetworkModel {
 }
 
 impl NetworkModel {
-    pub fn to_network(self, mut in_dims: usize) -> Network {
+    pub fn to_network(self, in_dims: usize) -> Network {
+        let (_, layers) = self.to_layers(in_dims);
+        Network::new(layers)
+    }
+
+    /// Adds an embedding for each `(input_index, categories_count, dim)` embedded input,
+    /// unless the network already starts with its own embedding of that input.
+    ///
+    /// The network's own leading embeddings are kept as they are and assumed to index the raw inputs too.
+    /// The added embeddings follow them, the last inputs first, their indexes being shifted
+    /// by the rows the network's own embeddings add before them.
+    pub fn with_embedded_inputs(mut self, embedded_inputs: &[(usize, usize, usize)]) -> Self {
+        let own_embeddings: Vec<&EmbeddingLayerModel> = self
+            .layers
+            .iter()
+            .map_while(|layer| match layer {
+                NetworkLayerModels::Embedding(embedding) => Some(embedding),
+                _ => None,
+            })
+            .collect();
+
+        let mut added: Vec<EmbeddingLayerModel> = embedded_inputs
+            .iter()
+            .filter(|(input_index, _, _)| {
+                !own_embeddings.iter().any(|embedding| embedding.input_index == *input_index)
+            })
+            .map(|&(input_index, categories_count, dim)| {
+                let shift: usize = own_embeddings
+                    .iter()
+                    .filter(|embedding| embedding.input_index < input_index)
+                    .map(|embedding| embedding.dim - 1)
+                    .sum();
+                EmbeddingLayerModel::new(input_index + shift, categories_count, dim)
+            })
+            .collect();
+
+        // embedding the last inputs first keeps the previous inputs' indexes
+        added.sort_by_key(|embedding| std::cmp::Reverse(embedding.input_index));
+        let position = own_embeddings.len();
+        self.layers
+            .splice(position..position, added.into_iter().map(NetworkLayerModels::Embedding));
+        self
+    }
+
+    /// Returns the layers' output dimensions along with the layers.
+    pub fn to_layers(self, mut in_dims: usize) -> (usize, Vec<Box<dyn NetworkLayer>>) {
         let mut layers = vec![];
         for layer_config in self.layers.into_iter() {
             let (out_dims, layer) = layer_config.to_layer(in_dims);
             in_dims = out_dims;
             layers.push(layer);
         }
-        Network::new(layers)
+        (in_dims, layers)
     }
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub enum NetworkLayerModels {
     Convolution(ConvNetworkModel),
-    FullDense(FullDenseLayerModel)
+    Conv1d(Conv1dNetworkModel),
+    Embedding(EmbeddingLayerModel),
+    Dropout {
+        rate: Scalar,
+    },
+    GaussianNoise {
+        std_dev: Scalar,
+    },
+    GaussianDropout {
+        rate: Scalar,
+    },
+    AlphaDropout {
+        rate: Scalar,
+    },
+    FullDense(FullDenseLayerModel),
+    BatchNorm(BatchNormLayerModel),
+    LayerNorm(LayerNormLayerModel),
+    Recurrent(RecurrentLayerModel),
+    MultiHeadAttention(MultiHeadAttentionLayerModel),
+    TransformerEncoder(TransformerEncoderBlockModel),
+    Residual(ResidualBlockModel)
 }
 
 impl NetworkLayerModels {
     pub fn to_layer(self, in_dims: usize) -> (usize, Box<dyn NetworkLayer>) {
         match self {
             Self::Convolution(network) => network.to_layer(in_dims),
-            Self::FullDense(layer) => layer.to_layer(in_dims)
+            Self::Conv1d(network) => network.to_layer(in_dims),
+            Self::Embedding(layer) => layer.to_layer(in_dims),
+            Self::Dropout { rate } => (in_dims, Box::new(Dropout::new(rate))),
+            Self::GaussianNoise { std_dev } => (in_dims, Box::new(GaussianNoise::new(std_dev))),
+            Self::GaussianDropout { rate } => (in_dims, Box::new(GaussianDropout::new(rate))),
+            Self::AlphaDropout { rate } => (in_dims, Box::new(AlphaDropout::new(rate))),
+            Self::FullDense(layer) => layer.to_layer(in_dims),
+            Self::BatchNorm(layer) => layer.to_layer(in_dims),
+            Self::LayerNorm(layer) => layer.to_layer(in_dims),
+            Self::Recurrent(layer) => layer.to_layer(in_dims),
+            Self::MultiHeadAttention(layer) => layer.to_layer(in_dims),
+            Self::TransformerEncoder(layer) => layer.to_layer(in_dims),
+            Self::Residual(layer) => layer.to_layer(in_dims)
         }
     }
 }
\ No newline at end of file
@@ -0,0 +1,217 @@
+use serde::{Serialize, Deserialize};
+
+use crate::{initializers::Initializers, optimizer::{Optimizers, sgd, momentum, adam, rmsprop, adagrad, adadelta, nadam, amsgrad, adamw}, layer::recurrent_layer::{RecurrentLayer, RecurrentCells}, network::NetworkLayer};
+
+use super::network_model::NetworkModelBuilder;
+
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct RecurrentLayerModel {
+    pub cell: RecurrentCells,
+    /// Size of the hidden state
+    pub size: usize,
+    /// Number of features per time step, the input being a flattened window of time steps
+    pub in_features: usize,
+    /// Outputs the hidden states of all the time steps instead of only the last one
+    pub return_sequences: bool,
+    /// Maximum number of time steps the gradients flow back through
+    pub truncation: Option<usize>,
+    pub biases_initializer: Initializers,
+    pub weights_initializer: Initializers,
+    pub biases_optimizer: Optimizers,
+    pub weights_optimizer: Optimizers,
+}
+
+impl RecurrentLayerModel {
+    pub fn to_layer(self, in_size: usize) -> (usize, Box<dyn NetworkLayer>) {
+        let time_steps = in_size / self.in_features;
+        if time_steps * self.in_features != in_size {
+            panic!(
+                "Recurrent layer expects a window of {} features per time step, but got {} inputs",
+                self.in_features, in_size
+            );
+        }
+
+        let layer = RecurrentLayer::new(
+            self.cell,
+            self.in_features,
+            self.size,
+            self.return_sequences,
+            self.truncation,
+            self.weights_initializer,
+            self.biases_initializer,
+            self.weights_optimizer,
+            self.biases_optimizer,
+        );
+
+        let out_size = if self.return_sequences {
+            time_steps * self.size
+        } else {
+            self.size
+        };
+
+        (out_size, Box::new(layer))
+    }
+}
+
+pub struct RecurrentLayerModelBuilder {
+    pub model: RecurrentLayerModel,
+    parent: NetworkModelBuilder
+}
+
+impl RecurrentLayerModelBuilder {
+    pub fn new(parent: NetworkModelBuilder, cell: RecurrentCells, size: usize) -> Self {
+        Self {
+            model: RecurrentLayerModel {
+                cell,
+                size,
+                in_features: 1,
+                return_sequences: false,
+                truncation: None,
+                biases_initializer: Initializers::Zeros,
+                weights_initializer: Initializers::GlorotUniform,
+                biases_optimizer: sgd(),
+                weights_optimizer: sgd(),
+            },
+            parent,
+        }
+    }
+
+    pub fn end(self) -> NetworkModelBuilder {
+        self.parent.accept_recurrent(self.model)
+    }
+
+    pub fn in_features(self, in_features: usize) -> Self {
+        Self {
+            model: RecurrentLayerModel {
+                in_features,
+                ..self.model
+            },
+            ..self
+        }
+    }
+
+    pub fn return_sequences(self) -> Self {
+        Self {
+            model: RecurrentLayerModel {
+                return_sequences: true,
+                ..self.model
+            },
+            ..self
+        }
+    }
+
+    /// Splits the sequences into blocks of `time_steps` steps, gradients not crossing the blocks' boundaries.
+    pub fn truncation(self, time_steps: usize) -> Self {
+        Self {
+            model: RecurrentLayerModel {
+                truncation: Some(time_steps),
+                ..self.model
+            },
+            ..self
+        }
+    }
+
+    pub fn init_zeros(self) -> Self {
+        self.init(Initializers::Zeros)
+    }
+
+    pub fn init_uniform(self) -> Self {
+        self.init(Initializers::Uniform)
+    }
+
+    pub fn init_uniform_signed(self) -> Self {
+        self.init(Initializers::UniformSigned)
+    }
+
+    pub fn init_glorot_uniform(self) -> Self {
+        self.init(Initializers::GlorotUniform)
+    }
+
+    pub fn sgd(self) -> Self {
+        self.optimizer(sgd())
+    }
+
+    pub fn momentum(self) -> Self {
+        self.optimizer(momentum())
+    }
+
+    pub fn adam(self) -> Self {
+        self.optimizer(adam())
+    }
+
+    pub fn rmsprop(self) -> Self {
+        self.optimizer(rmsprop())
+    }
+
+    pub fn adagrad(self) -> Self {
+        self.optimizer(adagrad())
+    }
+
+    pub fn adadelta(self) -> Self {
+        self.optimizer(adadelta())
+    }
+
+    pub fn nadam(self) -> Self {
+        self.optimizer(nadam())
+    }
+
+    pub fn amsgrad(self) -> Self {
+        self.optimizer(amsgrad())
+    }
+
+    /// AdamW with its default weight decay, the biases being optimized by Adam without decay
+    pub fn adamw(self) -> Self {
+        self.biases_optimizer(adam()).weights_optimizer(adamw())
+    }
+
+    pub fn optimizer(self, optimizer: Optimizers) -> Self {
+        self.biases_optimizer(optimizer.clone())
+            .weights_optimizer(optimizer)
+    }
+
+    pub fn init(self, initializer: Initializers) -> Self {
+        self.biases_init(initializer.clone())
+            .weights_init(initializer)
+    }
+
+    pub fn biases_optimizer(self, optimizer: Optimizers) -> Self {
+        Self {
+            model: RecurrentLayerModel {
+                biases_optimizer: optimizer,
+                ..self.model
+            },
+            ..self
+        }
+    }
+
+    pub fn weights_optimizer(self, optimizer: Optimizers) -> Self {
+        Self {
+            model: RecurrentLayerModel {
+                weights_optimizer: optimizer,
+                ..self.model
+            },
+            ..self
+        }
+    }
+
+    pub fn biases_init(self, initializer: Initializers) -> Self {
+        Self {
+            model: RecurrentLayerModel {
+                biases_initializer: initializer,
+                ..self.model
+            },
+            ..self
+        }
+    }
+
+    pub fn weights_init(self, initializer: Initializers) -> Self {
+        Self {
+            model: RecurrentLayerModel {
+                weights_initializer: initializer,
+                ..self.model
+            },
+            ..self
+        }
+    }
+}
@@ -0,0 +1,43 @@
+use serde::{Serialize, Deserialize};
+
+use crate::{initializers::Initializers, optimizer::Optimizers, layer::{dense_layer::DenseLayer, residual_block::ResidualBlock, defaults::{default_weights_initializer, default_weights_optimizer}}, network::NetworkLayer};
+
+use super::network_model::NetworkLayerModels;
+
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ResidualBlockModel {
+    pub layers: Vec<NetworkLayerModels>,
+    /// Used for the projection of the input when the layers change its dimensions
+    #[serde(default = "default_weights_initializer")]
+    pub projection_initializer: Initializers,
+    #[serde(default = "default_weights_optimizer")]
+    pub projection_optimizer: Optimizers,
+}
+
+impl ResidualBlockModel {
+    pub fn to_layer(self, in_dims: usize) -> (usize, Box<dyn NetworkLayer>) {
+        let mut layers = vec![];
+        let mut out_dims = in_dims;
+        for layer_config in self.layers.into_iter() {
+            let (layer_out_dims, layer) = layer_config.to_layer(out_dims);
+            out_dims = layer_out_dims;
+            layers.push(layer);
+        }
+
+        let projection = if out_dims != in_dims {
+            Some(DenseLayer::new(
+                in_dims,
+                out_dims,
+                self.projection_optimizer.clone(),
+                self.projection_optimizer,
+                self.projection_initializer,
+                Initializers::Zeros,
+            ))
+        } else {
+            None
+        };
+
+        (out_dims, Box::new(ResidualBlock::new(layers, projection)))
+    }
+}
@@ -0,0 +1,205 @@
+use serde::{Serialize, Deserialize};
+
+use crate::{linalg::Scalar, initializers::Initializers, optimizer::{Optimizers, sgd, momentum, adam, rmsprop, adagrad, adadelta, nadam, amsgrad, adamw}, layer::{transformer_encoder_block::TransformerEncoderBlock, defaults::default_norm_epsilon}, network::NetworkLayer};
+
+use super::network_model::NetworkModelBuilder;
+
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct TransformerEncoderBlockModel {
+    pub heads: usize,
+    /// Number of features per token, the input being a flattened sequence of tokens
+    pub in_features: usize,
+    /// Size of the hidden layer of the feed forward sublayer, 4 times the number of features per token if not set
+    pub feed_forward_size: Option<usize>,
+    #[serde(default = "default_norm_epsilon")]
+    pub epsilon: Scalar,
+    pub biases_initializer: Initializers,
+    pub weights_initializer: Initializers,
+    pub biases_optimizer: Optimizers,
+    pub weights_optimizer: Optimizers,
+}
+
+impl TransformerEncoderBlockModel {
+    pub fn to_layer(self, in_size: usize) -> (usize, Box<dyn NetworkLayer>) {
+        if in_size / self.in_features * self.in_features != in_size {
+            panic!(
+                "Transformer encoder block expects tokens of {} features, but got {} inputs",
+                self.in_features, in_size
+            );
+        }
+
+        let layer = TransformerEncoderBlock::new(
+            self.in_features,
+            self.heads,
+            self.feed_forward_size.unwrap_or(4 * self.in_features),
+            self.epsilon,
+            self.weights_optimizer,
+            self.biases_optimizer,
+            self.weights_initializer,
+            self.biases_initializer,
+        );
+
+        (in_size, Box::new(layer))
+    }
+}
+
+pub struct TransformerEncoderBlockModelBuilder {
+    pub model: TransformerEncoderBlockModel,
+    parent: NetworkModelBuilder
+}
+
+impl TransformerEncoderBlockModelBuilder {
+    pub fn new(parent: NetworkModelBuilder, heads: usize) -> Self {
+        Self {
+            model: TransformerEncoderBlockModel {
+                heads,
+                in_features: heads,
+                feed_forward_size: None,
+                epsilon: default_norm_epsilon(),
+                biases_initializer: Initializers::Zeros,
+                weights_initializer: Initializers::GlorotUniform,
+                biases_optimizer: sgd(),
+                weights_optimizer: sgd(),
+            },
+            parent,
+        }
+    }
+
+    pub fn end(self) -> NetworkModelBuilder {
+        self.parent.accept_transformer_encoder(self.model)
+    }
+
+    pub fn in_features(self, in_features: usize) -> Self {
+        Self {
+            model: TransformerEncoderBlockModel {
+                in_features,
+                ..self.model
+            },
+            ..self
+        }
+    }
+
+    pub fn feed_forward_size(self, feed_forward_size: usize) -> Self {
+        Self {
+            model: TransformerEncoderBlockModel {
+                feed_forward_size: Some(feed_forward_size),
+                ..self.model
+            },
+            ..self
+        }
+    }
+
+    pub fn epsilon(self, epsilon: Scalar) -> Self {
+        Self {
+            model: TransformerEncoderBlockModel {
+                epsilon,
+                ..self.model
+            },
+            ..self
+        }
+    }
+
+    pub fn init_zeros(self) -> Self {
+        self.init(Initializers::Zeros)
+    }
+
+    pub fn init_uniform(self) -> Self {
+        self.init(Initializers::Uniform)
+    }
+
+    pub fn init_uniform_signed(self) -> Self {
+        self.init(Initializers::UniformSigned)
+    }
+
+    pub fn init_glorot_uniform(self) -> Self {
+        self.init(Initializers::GlorotUniform)
+    }
+
+    pub fn sgd(self) -> Self {
+        self.optimizer(sgd())
+    }
+
+    pub fn momentum(self) -> Self {
+        self.optimizer(momentum())
+    }
+
+    pub fn adam(self) -> Self {
+        self.optimizer(adam())
+    }
+
+    pub fn rmsprop(self) -> Self {
+        self.optimizer(rmsprop())
+    }
+
+    pub fn adagrad(self) -> Self {
+        self.optimizer(adagrad())
+    }
+
+    pub fn adadelta(self) -> Self {
+        self.optimizer(adadelta())
+    }
+
+    pub fn nadam(self) -> Self {
+        self.optimizer(nadam())
+    }
+
+    pub fn amsgrad(self) -> Self {
+        self.optimizer(amsgrad())
+    }
+
+    /// AdamW with its default weight decay, the biases being optimized by Adam without decay
+    pub fn adamw(self) -> Self {
+        self.biases_optimizer(adam()).weights_optimizer(adamw())
+    }
+
+    pub fn optimizer(self, optimizer: Optimizers) -> Self {
+        self.biases_optimizer(optimizer.clone())
+            .weights_optimizer(optimizer)
+    }
+
+    pub fn init(self, initializer: Initializers) -> Self {
+        self.biases_init(initializer.clone())
+            .weights_init(initializer)
+    }
+
+    pub fn biases_optimizer(self, optimizer: Optimizers) -> Self {
+        Self {
+            model: TransformerEncoderBlockModel {
+                biases_optimizer: optimizer,
+                ..self.model
+            },
+            ..self
+        }
+    }
+
+    pub fn weights_optimizer(self, optimizer: Optimizers) -> Self {
+        Self {
+            model: TransformerEncoderBlockModel {
+                weights_optimizer: optimizer,
+                ..self.model
+            },
+            ..self
+        }
+    }
+
+    pub fn biases_init(self, initializer: Initializers) -> Self {
+        Self {
+            model: TransformerEncoderBlockModel {
+                biases_initializer: initializer,
+                ..self.model
+            },
+            ..self
+        }
+    }
+
+    pub fn weights_init(self, initializer: Initializers) -> Self {
+        Self {
+            model: TransformerEncoderBlockModel {
+                weights_initializer: initializer,
+                ..self.model
+            },
+            ..self
+        }
+    }
+}
@@ -0,0 +1,555 @@
+use std::collections::HashMap;
+
+use crate::{
+    gradient_clipping::GradientClipping,
+    layer::Layer,
+    linalg::{Matrix, MatrixTrait, Scalar},
+    loss::Loss,
+    monitor::TM,
+};
+
+use super::{params::NetworkParams, Network, NetworkLayer};
+
+/// Operation applied by a node of a `GraphNetwork` to the matrices of its input nodes.
+#[derive(Debug)]
+pub enum GraphOps {
+    /// A named input of the graph, with its number of features
+    Input(usize),
+    /// A sequence of layers applied to the node's single input
+    Layers(Vec<Box<dyn NetworkLayer>>),
+    /// Stacks the inputs' features on top of each other
+    Concat,
+    /// Sums the inputs, which must have the same number of features
+    Add,
+    /// Keeps the features `start..start + size` of the node's single input
+    Split { start: usize, size: usize },
+}
+
+#[derive(Debug)]
+struct GraphNode {
+    name: String,
+    inputs: Vec<usize>,
+    op: GraphOps,
+    out_dims: usize,
+}
+
+/// A network whose layers form a directed acyclic graph, with named inputs and outputs.
+///
+/// Each node's output is a `(i, n)` matrix, `i` being its number of features and `n` the number of samples.
+#[derive(Debug)]
+pub struct GraphNetwork {
+    // in topological order, each node only taking inputs from the previous ones
+    nodes: Vec<GraphNode>,
+    outputs: Vec<usize>,
+    gradient_clipping: Option<GradientClipping>,
+}
+
+impl GraphNetwork {
+    pub fn new() -> Self {
+        Self {
+            nodes: vec![],
+            outputs: vec![],
+            gradient_clipping: None,
+        }
+    }
+
+    /// Clips the gradients of all the layers after each backward pass, before the parameters are updated.
+    pub fn set_gradient_clipping(&mut self, gradient_clipping: GradientClipping) -> &mut Self {
+        self.gradient_clipping = Some(gradient_clipping);
+        self
+    }
+
+    /// Adds a node reading the matrices of the already added `inputs` nodes.
+    ///
+    /// Panics if the operation is incompatible with its inputs' dimensions.
+    /// Layers nodes are added with `add_layers_node` instead, as their output dimensions can't be inferred.
+    pub fn add_node(&mut self, name: &str, inputs: &[&str], op: GraphOps) -> &mut Self {
+        let inputs = self.resolve_inputs(name, inputs);
+        let in_dims: Vec<usize> = inputs.iter().map(|i| self.nodes[*i].out_dims).collect();
+
+        let out_dims = match &op {
+            GraphOps::Input(dims) => {
+                assert!(inputs.is_empty(), "Input node {:?} cannot have inputs", name);
+                *dims
+            }
+            GraphOps::Layers(_) => panic!("Use add_layers_node to add the layers node {:?}", name),
+            GraphOps::Split { .. } if in_dims.len() != 1 => {
+                panic!("Node {:?} must have exactly one input", name)
+            }
+            GraphOps::Split { start, size } => {
+                if start + size > in_dims[0] {
+                    panic!(
+                        "Node {:?} splits features {}..{} of an input with {} features",
+                        name,
+                        start,
+                        start + size,
+                        in_dims[0]
+                    );
+                }
+                *size
+            }
+            GraphOps::Concat => in_dims.iter().sum(),
+            GraphOps::Add => {
+                if in_dims.is_empty() || in_dims.iter().any(|d| *d != in_dims[0]) {
+                    panic!(
+                        "Node {:?} adds inputs with different dimensions {:?}",
+                        name, in_dims
+                    );
+                }
+                in_dims[0]
+            }
+        };
+
+        self.nodes.push(GraphNode {
+            name: name.to_string(),
+            inputs,
+            op,
+            out_dims,
+        });
+        self
+    }
+
+    /// Adds a node applying `layers`, whose output has `out_dims` features, to the `input` node.
+    pub fn add_layers_node(
+        &mut self,
+        name: &str,
+        input: &str,
+        layers: Vec<Box<dyn NetworkLayer>>,
+        out_dims: usize,
+    ) -> &mut Self {
+        let inputs = self.resolve_inputs(name, &[input]);
+        self.nodes.push(GraphNode {
+            name: name.to_string(),
+            inputs,
+            op: GraphOps::Layers(layers),
+            out_dims,
+        });
+        self
+    }
+
+    fn resolve_inputs(&self, name: &str, inputs: &[&str]) -> Vec<usize> {
+        if self.find(name).is_some() {
+            panic!("Graph network already has a node named {:?}", name);
+        }
+        inputs
+            .iter()
+            .map(|input| {
+                self.find(input).unwrap_or_else(|| {
+                    panic!("Node {:?} takes unknown node {:?} as input", name, input)
+                })
+            })
+            .collect()
+    }
+
+    /// Marks the node `name` as an output of the graph.
+    pub fn add_output(&mut self, name: &str) -> &mut Self {
+        let node = self
+            .find(name)
+            .unwrap_or_else(|| panic!("Unknown output node {:?}", name));
+        self.outputs.push(node);
+        self
+    }
+
+    /// Number of features of the node `name`'s output.
+    pub fn dims(&self, name: &str) -> usize {
+        self.nodes[self.find(name).unwrap()].out_dims
+    }
+
+    pub fn inputs_names(&self) -> Vec<String> {
+        self.nodes
+            .iter()
+            .filter(|n| matches!(n.op, GraphOps::Input(_)))
+            .map(|n| n.name.clone())
+            .collect()
+    }
+
+    pub fn outputs_names(&self) -> Vec<String> {
+        self.outputs
+            .iter()
+            .map(|o| self.nodes[*o].name.clone())
+            .collect()
+    }
+
+    fn find(&self, name: &str) -> Option<usize> {
+        self.nodes.iter().position(|n| n.name == name)
+    }
+
+    fn layers(&self) -> impl Iterator<Item = &Box<dyn NetworkLayer>> {
+        self.nodes.iter().flat_map(|n| match &n.op {
+            GraphOps::Layers(layers) => layers.iter().collect::<Vec<_>>(),
+            _ => vec![],
+        })
+    }
+
+    fn layers_mut(&mut self) -> impl Iterator<Item = &mut Box<dyn NetworkLayer>> {
+        self.nodes.iter_mut().flat_map(|n| match &mut n.op {
+            GraphOps::Layers(layers) => layers.iter_mut().collect::<Vec<_>>(),
+            _ => vec![],
+        })
+    }
+
+    /// One entry per learnable layer, in the order the nodes were added.
+    pub fn get_params(&self) -> NetworkParams {
+        let mut params = Vec::new();
+        for layer in self.layers() {
+            if let Some(l) = layer.as_learnable_layer() {
+                params.push(l.get_learnable_parameters());
+            }
+        }
+        NetworkParams(params)
+    }
+
+    pub fn load_params(&mut self, params: &NetworkParams) {
+        let mut params = params.0.iter();
+        for layer in self.layers_mut() {
+            if let Some(l) = layer.as_learnable_layer_mut() {
+                l.set_learnable_parameters(params.next().unwrap());
+            }
+        }
+    }
+
+    /// Enables the dropout and the batch statistics of the normalization layers for the next `forward` calls.
+    ///
+    /// `train` switches to this mode by itself.
+    pub fn train_mode(&mut self) {
+        for layer in self.layers_mut() {
+            if let Some(l) = layer.as_dropout_layer() {
+                l.enable_dropout();
+            }
+        }
+    }
+
+    /// Disables the dropout and uses the running statistics of the normalization layers for the next `forward` calls.
+    ///
+    /// `predict_many` switches to this mode by itself.
+    pub fn eval_mode(&mut self) {
+        for layer in self.layers_mut() {
+            if let Some(l) = layer.as_dropout_layer() {
+                l.disable_dropout();
+            }
+        }
+    }
+
+    /// Penalty added to the reported training loss by the layers' regularizations.
+    pub fn regularization_loss(&self) -> Scalar {
+        self.layers().map(|l| l.regularization_loss()).sum()
+    }
+
+    /// `inputs` has one `(i, n)` matrix per input node, by name.
+    ///
+    /// Returns one `(j, n)` matrix per output node, by name.
+    ///
+    /// The layers run in the mode set by the last `train_mode` or `eval_mode` call.
+    pub fn forward(&mut self, inputs: &HashMap<String, Matrix>) -> HashMap<String, Matrix> {
+        let mut values: Vec<Matrix> = Vec::with_capacity(self.nodes.len());
+
+        for node in self.nodes.iter_mut() {
+            let mut node_inputs = node.inputs.iter().map(|i| values[*i].clone());
+            let value = match &mut node.op {
+                GraphOps::Input(_) => inputs
+                    .get(&node.name)
+                    .unwrap_or_else(|| panic!("Missing graph input {:?}", node.name))
+                    .clone(),
+                GraphOps::Layers(layers) => layers.forward(node_inputs.next().unwrap()),
+                GraphOps::Split { start, size } => {
+                    let rows = node_inputs.next().unwrap().get_data_row_leading();
+                    Matrix::from_row_leading_vector2(&rows[*start..*start + *size].to_vec())
+                }
+                GraphOps::Concat => {
+                    let mut rows = vec![];
+                    for input in node_inputs {
+                        rows.append(&mut input.get_data_row_leading());
+                    }
+                    Matrix::from_row_leading_vector2(&rows)
+                }
+                GraphOps::Add => {
+                    let first = node_inputs.next().unwrap();
+                    node_inputs.fold(first, |acc, input| acc.component_add(&input))
+                }
+            };
+            values.push(value);
+        }
+
+        self.outputs
+            .iter()
+            .map(|o| (self.nodes[*o].name.clone(), values[*o].clone()))
+            .collect()
+    }
+
+    /// `outputs_gradients` has one `(j, n)` matrix per output node, by name.
+    ///
+    /// Returns one `(i, n)` gradient per input node, by name.
+    ///
+    /// The gradients of the layers' parameters are added to the ones accumulated since the last
+    /// `zero_grad` or `step`, the parameters only being updated by `step`.
+    pub fn backward(
+        &mut self,
+        epoch: usize,
+        outputs_gradients: HashMap<String, Matrix>,
+    ) -> HashMap<String, Matrix> {
+        let mut grads: Vec<Option<Matrix>> = (0..self.nodes.len()).map(|_| None).collect();
+        for (name, gradient) in outputs_gradients {
+            let node = self.find(&name).unwrap();
+            grads[node] = Some(accumulate(grads[node].take(), gradient));
+        }
+        let logits = vec![false; self.nodes.len()];
+        self.backward_nodes(epoch, grads, logits)
+    }
+
+    /// Same as `backward`, the outputs' gradients being the ones of their `losses` for the predictions
+    /// `preds` of the last `forward` and the expected values `y_true`, both with one `(j, n)` matrix per output node.
+    ///
+    /// `sample_weights` has one weight per sample, if any.
+    pub fn backward_loss(
+        &mut self,
+        epoch: usize,
+        y_true: &HashMap<String, Matrix>,
+        preds: &HashMap<String, Matrix>,
+        sample_weights: Option<&Vec<Scalar>>,
+        losses: &HashMap<String, Loss>,
+    ) -> HashMap<String, Matrix> {
+        let mut grads: Vec<Option<Matrix>> = (0..self.nodes.len()).map(|_| None).collect();
+        let mut logits = vec![false; self.nodes.len()];
+        for &output in self.outputs.iter() {
+            let name = &self.nodes[output].name;
+            let (y_true, pred, loss) = output_loss(name, y_true, preds, losses);
+
+            // softmax and loss gradients are fused when possible, which is cheaper and more stable,
+            // unless the output also feeds other nodes whose gradients would reach the softmax
+            let fusable = match &self.nodes[output].op {
+                GraphOps::Layers(layers) => {
+                    layers.has_softmax_output()
+                        && !self.nodes.iter().any(|n| n.inputs.contains(&output))
+                }
+                _ => false,
+            };
+            let logits_gradient = if fusable {
+                loss.weighted_softmax_loss_prime(y_true, pred, sample_weights)
+            } else {
+                None
+            };
+
+            grads[output] = Some(match logits_gradient {
+                Some(logits_gradient) => {
+                    logits[output] = true;
+                    logits_gradient
+                }
+                None => loss.weighted_loss_prime(y_true, pred, sample_weights),
+            });
+        }
+        self.backward_nodes(epoch, grads, logits)
+    }
+
+    /// `grads` holds the gradient of each node's output, or of the logits of its final softmax where `logits` is set.
+    fn backward_nodes(
+        &mut self,
+        epoch: usize,
+        mut grads: Vec<Option<Matrix>>,
+        logits: Vec<bool>,
+    ) -> HashMap<String, Matrix> {
+        let in_dims: Vec<Vec<usize>> = self
+            .nodes
+            .iter()
+            .map(|n| n.inputs.iter().map(|i| self.nodes[*i].out_dims).collect())
+            .collect();
+
+        let mut inputs_gradients = HashMap::new();
+        for (i, node) in self.nodes.iter_mut().enumerate().rev() {
+            // nodes that don't lead to any output receive no gradient
+            let grad = match grads[i].take() {
+                Some(grad) => grad,
+                None => continue,
+            };
+
+            let node_inputs_gradients = match &mut node.op {
+                GraphOps::Input(_) => {
+                    inputs_gradients.insert(node.name.clone(), grad);
+                    vec![]
+                }
+                GraphOps::Layers(layers) if logits[i] => vec![layers.backward_skipping_softmax(epoch, grad)],
+                GraphOps::Layers(layers) => vec![layers.backward(epoch, grad)],
+                GraphOps::Split { start, .. } => {
+                    let ncol = grad.dim().1;
+                    let mut rows = vec![vec![0.; ncol]; in_dims[i][0]];
+                    for (r, row) in grad.get_data_row_leading().into_iter().enumerate() {
+                        rows[*start + r] = row;
+                    }
+                    vec![Matrix::from_row_leading_vector2(&rows)]
+                }
+                GraphOps::Concat => {
+                    let rows = grad.get_data_row_leading();
+                    let mut start = 0;
+                    in_dims[i]
+                        .iter()
+                        .map(|dims| {
+                            let part = Matrix::from_row_leading_vector2(&rows[start..start + dims].to_vec());
+                            start += dims;
+                            part
+                        })
+                        .collect()
+                }
+                GraphOps::Add => node.inputs.iter().map(|_| grad.clone()).collect(),
+            };
+
+            for (input, gradient) in node.inputs.iter().zip(node_inputs_gradients) {
+                grads[*input] = Some(accumulate(grads[*input].take(), gradient));
+            }
+        }
+
+        inputs_gradients
+    }
+
+    /// Discards the gradients accumulated by the previous `backward` calls.
+    pub fn zero_grad(&mut self) {
+        self.layers_mut().for_each(|l| l.zero_grad());
+    }
+
+    /// Updates the parameters of all the layers with the accumulated gradients,
+    /// once clipped if the network has a gradient clipping, then discards them.
+    pub fn step(&mut self, epoch: usize) {
+        if let Some(gradient_clipping) = self.gradient_clipping {
+            gradient_clipping.clip(self.layers_mut().map(|l| l.gradients_mut()).collect());
+        }
+        self.layers_mut().for_each(|l| l.step(epoch));
+    }
+
+    /// `inputs` has, for each input node, the `(n, i)` values of the `n` samples.
+    ///
+    /// Returns, for each output node, the `(n, j)` predictions for the `n` samples.
+    pub fn predict_many(
+        &mut self,
+        inputs: &HashMap<String, Vec<Vec<Scalar>>>,
+        batch_size: usize,
+    ) -> HashMap<String, Vec<Vec<Scalar>>> {
+        TM::start("graphpredmany");
+        self.eval_mode();
+
+        let mut preds: HashMap<String, Vec<Vec<Scalar>>> = self
+            .outputs_names()
+            .into_iter()
+            .map(|name| (name, vec![]))
+            .collect();
+
+        for input_batch in batches(inputs, batch_size) {
+            for (name, pred) in self.forward(&input_batch) {
+                preds
+                    .get_mut(&name)
+                    .unwrap()
+                    .extend(pred.get_data_col_leading());
+            }
+        }
+
+        TM::end();
+        preds
+    }
+
+    /// `x_train` has, for each input node, the `(n, i)` values of the `n` samples.
+    ///
+    /// `y_train` and `losses` have, for each output node, the `(n, j)` expected values and the loss to minimize.
+    ///
+    /// Returns the average over all batches of the sum of the outputs' losses and of the regularization penalty.
+    pub fn train(
+        &mut self,
+        epoch: usize,
+        x_train: &HashMap<String, Vec<Vec<Scalar>>>,
+        y_train: &HashMap<String, Vec<Vec<Scalar>>>,
+        losses: &HashMap<String, Loss>,
+        batch_size: usize,
+    ) -> Scalar {
+        self.train_weighted(epoch, x_train, y_train, None, losses, batch_size)
+    }
+
+    /// Same as `train`, each sample's losses and gradients being weighted by its `sample_weights` value.
+    ///
+    /// `sample_weights` has one weight per sample, panicking otherwise.
+    pub fn train_weighted(
+        &mut self,
+        epoch: usize,
+        x_train: &HashMap<String, Vec<Vec<Scalar>>>,
+        y_train: &HashMap<String, Vec<Vec<Scalar>>>,
+        sample_weights: Option<&Vec<Scalar>>,
+        losses: &HashMap<String, Loss>,
+        batch_size: usize,
+    ) -> Scalar {
+        TM::start("graphtrain");
+        let input_batches = batches(x_train, batch_size);
+        let samples = x_train.values().next().map_or(0, |samples| samples.len());
+        let weight_batches = Network::weight_batches(sample_weights, samples, batch_size, input_batches.len());
+        self.train_mode();
+
+        let mut error = 0.;
+        let mut i = 0;
+        for ((input_batch, y_true_batch), weight_batch) in input_batches
+            .into_iter()
+            .zip(batches(y_train, batch_size))
+            .zip(weight_batches)
+        {
+            let weight_batch = weight_batch.as_ref();
+            self.zero_grad();
+            let preds = self.forward(&input_batch);
+
+            for name in preds.keys() {
+                let (y_true, pred, loss) = output_loss(name, &y_true_batch, &preds, losses);
+                error += loss.weighted_loss(y_true, pred, weight_batch);
+            }
+            error += self.regularization_loss();
+
+            self.backward_loss(epoch, &y_true_batch, &preds, weight_batch, losses);
+            self.step(epoch);
+            i += 1;
+        }
+        error /= i as Scalar;
+
+        TM::end_with_message(format!("avg_error: {:.4}", error));
+        error
+    }
+}
+
+/// Returns the expected values, the predictions and the loss of the output `name`.
+fn output_loss<'a>(
+    name: &str,
+    y_true: &'a HashMap<String, Matrix>,
+    preds: &'a HashMap<String, Matrix>,
+    losses: &'a HashMap<String, Loss>,
+) -> (&'a Matrix, &'a Matrix, &'a Loss) {
+    let y_true = y_true
+        .get(name)
+        .unwrap_or_else(|| panic!("Missing expected values for output {:?}", name));
+    let pred = preds
+        .get(name)
+        .unwrap_or_else(|| panic!("Missing predictions for output {:?}", name));
+    let loss = losses
+        .get(name)
+        .unwrap_or_else(|| panic!("Missing loss for output {:?}", name));
+    (y_true, pred, loss)
+}
+
+impl Default for GraphNetwork {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn accumulate(acc: Option<Matrix>, gradient: Matrix) -> Matrix {
+    match acc {
+        Some(acc) => acc.component_add(&gradient),
+        None => gradient,
+    }
+}
+
+/// Splits each named group of samples in batches of `batch_size` samples, as `(i, batch_size)` matrices.
+fn batches(
+    groups: &HashMap<String, Vec<Vec<Scalar>>>,
+    batch_size: usize,
+) -> Vec<HashMap<String, Matrix>> {
+    let mut batches: Vec<HashMap<String, Matrix>> = vec![];
+    for (name, samples) in groups.iter() {
+        for (i, chunk) in samples.chunks(batch_size).enumerate() {
+            if batches.len() <= i {
+                batches.push(HashMap::new());
+            }
+            batches[i].insert(name.clone(), Matrix::from_column_leading_vector2(&chunk.to_vec()));
+        }
+    }
+    batches
+}
@@ -1,7 +1,8 @@
 use std::fmt::Debug;
 
 use crate::{
-    layer::{Layer, ParameterableLayer},
+    gradient_clipping::{GradientClipping, GradientMut},
+    layer::{GradientLayer, Layer, ParameterableLayer},
     linalg::{Matrix, MatrixTrait, Scalar},
     loss::Loss, monitor::TM,
 };
@@ -9,17 +10,30 @@ use crate::{
 use self::params::NetworkParams;
 
 pub mod params;
+pub mod graph;
 
 #[derive(Debug)]
 pub struct Network {
     // May be one or more layers inside
     // A layer is a layer as long as it implements the Layer trait
     layers: Vec<Box<dyn NetworkLayer>>,
+    gradient_clipping: Option<GradientClipping>,
 }
 
 impl Network {
     pub fn new(layers: Vec<Box<dyn NetworkLayer>>) -> Self {
-        Self { layers }
+        Self {
+            layers,
+            gradient_clipping: None,
+        }
+    }
+
+    /// Clips the gradients of all the layers in each `step`, before the parameters are updated.
+    pub fn with_gradient_clipping(self, gradient_clipping: GradientClipping) -> Self {
+        Self {
+            gradient_clipping: Some(gradient_clipping),
+            ..self
+        }
     }
 
     pub fn get_params(&self) -> NetworkParams {
@@ -42,11 +56,7 @@ impl Network {
 
     /// `input` has shape `(i,)` where `i` is the number of inputs.
     pub fn predict(&mut self, input: &Vec<Scalar>) -> Vec<Scalar> {
-        self.layers.iter_mut().for_each(|l| {
-            l.as_dropout_layer().map(|l| {
-                l.disable_dropout();
-            });
-        });
+        self.eval_mode();
 
         self.layers
             .forward(Matrix::from_column_vector(input))
@@ -74,9 +84,7 @@ impl Network {
     pub fn predict_many(&mut self, inputs: &Vec<Vec<Scalar>>, batch_size: usize) -> Vec<Vec<Scalar>> {
         TM::start("predmany");
         TM::start("init");
-        self.layers.iter_mut().for_each(|l| {
-            l.as_dropout_layer().map(|l| l.disable_dropout());
-        });
+        self.eval_mode();
 
         let mut preds = vec![];
         let mut i = 0;
@@ -114,30 +122,45 @@ impl Network {
         ys: &Vec<Vec<Scalar>>,
         loss: &Loss,
         batch_size: usize
+    ) -> (Vec<Vec<Scalar>>, Scalar, Scalar) {
+        self.predict_evaluate_many_weighted(inputs, ys, None, loss, batch_size)
+    }
+
+    /// Same as `predict_evaluate_many`, each sample's loss being weighted by its `sample_weights` value.
+    ///
+    /// `sample_weights` has one weight per sample, panicking otherwise.
+    pub fn predict_evaluate_many_weighted(
+        &mut self,
+        inputs: &Vec<Vec<Scalar>>,
+        ys: &Vec<Vec<Scalar>>,
+        sample_weights: Option<&Vec<Scalar>>,
+        loss: &Loss,
+        batch_size: usize
     ) -> (Vec<Vec<Scalar>>, Scalar, Scalar) {
         TM::start("predevmany");
         TM::start("init");
-        self.layers.iter_mut().for_each(|l| {
-            l.as_dropout_layer().map(|l| l.disable_dropout());
-        });
+        self.eval_mode();
 
         let mut losses = vec![];
         let mut preds = vec![];
         let mut i = 0;
         let x_batches: Vec<_> = inputs.chunks(batch_size).map(|c| c.to_vec()).collect();
         let y_batches: Vec<_> = ys.chunks(batch_size).map(|c| c.to_vec()).collect();
+        let weight_batches = Self::weight_batches(sample_weights, inputs.len(), batch_size, x_batches.len());
         let n_batches = x_batches.len();
         TM::end();
         
         TM::start("batches");
-        for (input_batch, y_true_batch) in
-            x_batches.into_iter().zip(y_batches.into_iter())
+        for ((input_batch, y_true_batch), weight_batch) in x_batches
+            .into_iter()
+            .zip(y_batches)
+            .zip(weight_batches)
         {
             TM::start(format!("{}/{}", i, n_batches));
             let input_batch_matrix = Matrix::from_column_leading_vector2(&input_batch);
             let pred = self.layers.forward(input_batch_matrix);
             let y_true_batch_matrix = Matrix::from_column_leading_vector2(&y_true_batch);
-            let e = loss.loss(&y_true_batch_matrix, &pred);
+            let e = loss.weighted_loss(&y_true_batch_matrix, &pred, weight_batch.as_ref());
 
             losses.push(e);
             preds.extend(pred.get_data_col_leading());
@@ -170,36 +193,54 @@ impl Network {
         y_train: &Vec<Vec<Scalar>>,
         loss: &Loss,
         batch_size: usize,
+    ) -> Scalar {
+        self.train_weighted(epoch, x_train, y_train, None, loss, batch_size)
+    }
+
+    /// Same as `train`, each sample's loss and gradient being weighted by its `sample_weights` value.
+    ///
+    /// `sample_weights` has one weight per sample, panicking otherwise.
+    pub fn train_weighted(
+        &mut self,
+        epoch: usize,
+        x_train: &Vec<Vec<Scalar>>,
+        y_train: &Vec<Vec<Scalar>>,
+        sample_weights: Option<&Vec<Scalar>>,
+        loss: &Loss,
+        batch_size: usize,
     ) -> Scalar {
         TM::start("train");
         TM::start("init");
-        self.layers.iter_mut().for_each(|l| {
-            l.as_dropout_layer().map(|l| l.enable_dropout());
-        });
-
         let mut error = 0.;
         let mut i = 0;
         let x_train_batches: Vec<_> = x_train.chunks(batch_size).map(|c| c.to_vec()).collect();
         let y_train_batches: Vec<_> = y_train.chunks(batch_size).map(|c| c.to_vec()).collect();
+        let weight_batches = Self::weight_batches(sample_weights, x_train.len(), batch_size, x_train_batches.len());
         let n_batches = x_train_batches.len();
+        self.train_mode();
         TM::end();
         
         TM::start("batches");
-        for (input_batch, y_true_batch) in
-            x_train_batches.into_iter().zip(y_train_batches.into_iter())
+        for ((input_batch, y_true_batch), weight_batch) in x_train_batches
+            .into_iter()
+            .zip(y_train_batches)
+            .zip(weight_batches)
         {
             TM::start(format!("{}/{}", i, n_batches));
-            let input_batch_matrix = Matrix::from_column_leading_vector2(&input_batch);
+            self.zero_grad();
 
-            let pred = self.layers.forward(input_batch_matrix);
+            let input_batch_matrix = Matrix::from_column_leading_vector2(&input_batch);
+            let pred = self.forward(input_batch_matrix);
             
             let y_true_batch_matrix = Matrix::from_column_leading_vector2(&y_true_batch);
-            let e = loss.loss(&y_true_batch_matrix, &pred);
+            let weight_batch = weight_batch.as_ref();
+            let e = loss.weighted_loss(&y_true_batch_matrix, &pred, weight_batch)
+                + self.layers.regularization_loss();
 
             error += e;
 
-            let error_gradient = loss.loss_prime(&y_true_batch_matrix, &pred);
-            self.layers.backward(epoch, error_gradient.clone());
+            self.backward_loss(epoch, &y_true_batch_matrix, &pred, weight_batch, loss);
+            self.step(epoch);
             i += 1;
             TM::end_with_message(format!("error: {:.4} total_error: {:.4}", e, error));
         }
@@ -208,6 +249,108 @@ impl Network {
         TM::end_with_message(format!("avg_error: {:.4}", error));
         error
     }
+
+    /// Enables the dropout and the batch statistics of the normalization layers for the next `forward` calls.
+    ///
+    /// `train` switches to this mode by itself.
+    pub fn train_mode(&mut self) {
+        self.layers.iter_mut().for_each(|l| {
+            l.as_dropout_layer().map(|l| l.enable_dropout());
+        });
+    }
+
+    /// Disables the dropout and uses the running statistics of the normalization layers for the next `forward` calls.
+    ///
+    /// The `predict` methods switch to this mode by themselves.
+    pub fn eval_mode(&mut self) {
+        self.layers.iter_mut().for_each(|l| {
+            l.as_dropout_layer().map(|l| l.disable_dropout());
+        });
+    }
+
+    /// Forward pass of a batch, in the mode set by the last `train_mode` or `eval_mode` call.
+    ///
+    /// `input` has shape `(i, n)` where `i` is the number of inputs and `n` is the number of samples.
+    ///
+    /// Returns the output which has shape `(j, n)` where `j` is the number of outputs.
+    pub fn forward(&mut self, input: Matrix) -> Matrix {
+        self.layers.forward(input)
+    }
+
+    /// `output_gradient` is the gradient of the last `forward`'s output, with shape `(j, n)`.
+    ///
+    /// Returns the input gradient which has shape `(i, n)`.
+    ///
+    /// The gradients of the layers' parameters are added to the ones accumulated since the last
+    /// `zero_grad` or `step`, the parameters only being updated by `step`.
+    pub fn backward(&mut self, epoch: usize, output_gradient: Matrix) -> Matrix {
+        self.layers.backward(epoch, output_gradient)
+    }
+
+    /// Same as `backward`, the output gradient being the one of `loss` for the predictions `pred`
+    /// of the last `forward` and the expected values `y_true`, both with shape `(j, n)`.
+    ///
+    /// `sample_weights` has one weight per sample, if any.
+    pub fn backward_loss(
+        &mut self,
+        epoch: usize,
+        y_true: &Matrix,
+        pred: &Matrix,
+        sample_weights: Option<&Vec<Scalar>>,
+        loss: &Loss,
+    ) -> Matrix {
+        // softmax and loss gradients are fused when possible, which is cheaper and more stable
+        let logits_gradient = if self.layers.has_softmax_output() {
+            loss.weighted_softmax_loss_prime(y_true, pred, sample_weights)
+        } else {
+            None
+        };
+        match logits_gradient {
+            Some(logits_gradient) => self.layers.backward_skipping_softmax(epoch, logits_gradient),
+            None => {
+                let error_gradient = loss.weighted_loss_prime(y_true, pred, sample_weights);
+                self.layers.backward(epoch, error_gradient)
+            }
+        }
+    }
+
+    /// Discards the gradients accumulated by the previous `backward` calls.
+    pub fn zero_grad(&mut self) {
+        self.layers.zero_grad();
+    }
+
+    /// Gradients accumulated since the last `zero_grad` or `step`, grouped by layer.
+    pub fn gradients_mut(&mut self) -> Vec<Vec<GradientMut<'_>>> {
+        self.layers.iter_mut().map(|l| l.gradients_mut()).collect()
+    }
+
+    /// Updates the parameters of all the layers with the accumulated gradients,
+    /// once clipped if the network has a gradient clipping, then discards them.
+    pub fn step(&mut self, epoch: usize) {
+        if let Some(gradient_clipping) = self.gradient_clipping {
+            gradient_clipping.clip(self.gradients_mut());
+        }
+        self.layers.step(epoch);
+    }
+
+    fn weight_batches(
+        sample_weights: Option<&Vec<Scalar>>,
+        samples: usize,
+        batch_size: usize,
+        n_batches: usize,
+    ) -> Vec<Option<Vec<Scalar>>> {
+        match sample_weights {
+            Some(weights) => {
+                assert_eq!(
+                    weights.len(),
+                    samples,
+                    "Expected one sample weight per sample"
+                );
+                weights.chunks(batch_size).map(|c| Some(c.to_vec())).collect()
+            }
+            None => vec![None; n_batches],
+        }
+    }
 }
 
 impl Layer for Vec<Box<dyn NetworkLayer>> {
@@ -235,6 +378,45 @@ impl Layer for Vec<Box<dyn NetworkLayer>> {
         TM::end();
         error_gradient
     }
+
+    fn regularization_loss(&self) -> Scalar {
+        self.iter().map(|l| l.regularization_loss()).sum()
+    }
+
+    fn has_softmax_output(&self) -> bool {
+        self.last().is_some_and(|l| l.has_softmax_output())
+    }
+
+    fn backward_skipping_softmax(&mut self, epoch: usize, logits_gradient: Matrix) -> Matrix {
+        TM::start("net.back");
+        let n_layers = self.len();
+        let mut error_gradient = logits_gradient;
+        for (i, layer) in self.iter_mut().enumerate().rev() {
+            TM::start(format!("layer[{}]", i+1));
+            error_gradient = if i + 1 == n_layers {
+                layer.backward_skipping_softmax(epoch, error_gradient)
+            } else {
+                layer.backward(epoch, error_gradient)
+            };
+            TM::end();
+        }
+        TM::end();
+        error_gradient
+    }
+}
+
+impl GradientLayer for Vec<Box<dyn NetworkLayer>> {
+    fn gradients_mut(&mut self) -> Vec<GradientMut<'_>> {
+        self.iter_mut().flat_map(|l| l.gradients_mut()).collect()
+    }
+
+    fn zero_grad(&mut self) {
+        self.iter_mut().for_each(|l| l.zero_grad());
+    }
+
+    fn step(&mut self, epoch: usize) {
+        self.iter_mut().for_each(|l| l.step(epoch));
+    }
 }
 
 pub trait NetworkLayer: Layer + ParameterableLayer + Debug + Send {}
@@ -0,0 +1,96 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    learning_rate::LearningRateSchedule,
+    linalg::{Matrix, MatrixTrait, Scalar},
+};
+
+pub(crate) fn default_adadelta_rho() -> Scalar {
+    0.95
+}
+
+// AdaDelta's steps are sized by the moving average of the previous ones,
+// starting from sqrt(epsilon), so it needs a larger epsilon than Adam
+pub(crate) fn default_adadelta_epsilon() -> Scalar {
+    1e-6
+}
+
+// the learning rate only rescales the steps, AdaDelta has none in its paper
+pub(crate) fn default_adadelta_learning_rate() -> LearningRateSchedule {
+    LearningRateSchedule::Constant(1.0)
+}
+
+// https://arxiv.org/pdf/1212.5701.pdf
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct AdaDelta {
+    #[serde(default = "default_adadelta_rho")]
+    rho: Scalar,
+    #[serde(default = "default_adadelta_epsilon")]
+    epsilon: Scalar,
+    #[serde(default = "default_adadelta_learning_rate")]
+    learning_rate: LearningRateSchedule,
+    #[serde(skip)]
+    v: Option<Matrix>, // moving average of the squared gradients
+    #[serde(skip)]
+    u: Option<Matrix>, // moving average of the squared updates
+}
+
+impl Default for AdaDelta {
+    fn default() -> Self {
+        Self {
+            v: None,
+            u: None,
+            rho: default_adadelta_rho(),
+            epsilon: default_adadelta_epsilon(),
+            learning_rate: default_adadelta_learning_rate(),
+        }
+    }
+}
+
+impl AdaDelta {
+    pub fn new(learning_rate: LearningRateSchedule, rho: Scalar, epsilon: Scalar) -> Self {
+        Self {
+            v: None,
+            u: None,
+            rho,
+            epsilon,
+            learning_rate,
+        }
+    }
+
+    pub fn update_parameters(
+        &mut self,
+        epoch: usize,
+        parameters: &Matrix,
+        parameters_gradient: &Matrix,
+    ) -> Matrix {
+        let lr = self.learning_rate.get_learning_rate(epoch);
+        let (nrow, ncol) = parameters_gradient.dim();
+
+        if self.v.is_none() {
+            self.v = Some(Matrix::zeros(nrow, ncol));
+        }
+        if self.u.is_none() {
+            self.u = Some(Matrix::zeros(nrow, ncol));
+        }
+        let v = self.v.as_ref().unwrap();
+        let u = self.u.as_ref().unwrap();
+
+        let g = parameters_gradient;
+        let g2 = g.component_mul(g);
+        let v = v.scalar_mul(self.rho).component_add(&g2.scalar_mul(1.0 - self.rho));
+
+        let update = u
+            .scalar_add(self.epsilon)
+            .sqrt()
+            .component_div(&v.scalar_add(self.epsilon).sqrt())
+            .component_mul(g);
+        let u = u
+            .scalar_mul(self.rho)
+            .component_add(&update.component_mul(&update).scalar_mul(1.0 - self.rho));
+
+        self.v = Some(v);
+        self.u = Some(u);
+        parameters.component_sub(&update.scalar_mul(lr))
+    }
+}
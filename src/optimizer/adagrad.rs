@@ -0,0 +1,63 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    learning_rate::{default_learning_rate, LearningRateSchedule},
+    linalg::{Matrix, MatrixTrait, Scalar},
+    optimizer::adam::default_epsilon,
+};
+
+// https://jmlr.org/papers/volume12/duchi11a/duchi11a.pdf
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct AdaGrad {
+    #[serde(default = "default_epsilon")]
+    epsilon: Scalar,
+    #[serde(default = "default_learning_rate")]
+    learning_rate: LearningRateSchedule,
+    #[serde(skip)]
+    g2_sum: Option<Matrix>, // sum of all the squared gradients
+}
+
+impl Default for AdaGrad {
+    fn default() -> Self {
+        Self {
+            g2_sum: None,
+            epsilon: default_epsilon(),
+            learning_rate: default_learning_rate(),
+        }
+    }
+}
+
+impl AdaGrad {
+    pub fn new(learning_rate: LearningRateSchedule, epsilon: Scalar) -> Self {
+        Self {
+            g2_sum: None,
+            epsilon,
+            learning_rate,
+        }
+    }
+
+    pub fn update_parameters(
+        &mut self,
+        epoch: usize,
+        parameters: &Matrix,
+        parameters_gradient: &Matrix,
+    ) -> Matrix {
+        let lr = self.learning_rate.get_learning_rate(epoch);
+        let (nrow, ncol) = parameters_gradient.dim();
+
+        if self.g2_sum.is_none() {
+            self.g2_sum = Some(Matrix::zeros(nrow, ncol));
+        }
+        let g2_sum = self.g2_sum.as_ref().unwrap();
+
+        let g = parameters_gradient;
+        let g2_sum = g2_sum.component_add(&g.component_mul(g));
+
+        let new_params = parameters.component_sub(
+            &g.scalar_mul(lr)
+                .component_div(&g2_sum.sqrt().scalar_add(self.epsilon)),
+        );
+        self.g2_sum = Some(g2_sum);
+        new_params
+    }
+}
@@ -5,15 +5,15 @@ use crate::{
     linalg::{Matrix, MatrixTrait, Scalar},
 };
 
-fn default_beta1() -> Scalar {
+pub(crate) fn default_beta1() -> Scalar {
     0.9
 }
 
-fn default_beta2() -> Scalar {
+pub(crate) fn default_beta2() -> Scalar {
     0.999
 }
 
-fn default_epsilon() -> Scalar {
+pub(crate) fn default_epsilon() -> Scalar {
     1e-8
 }
 
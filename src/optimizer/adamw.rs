@@ -0,0 +1,108 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    learning_rate::{default_learning_rate, LearningRateSchedule},
+    linalg::{Matrix, MatrixTrait, Scalar},
+    optimizer::adam::{default_beta1, default_beta2, default_epsilon},
+};
+
+pub(crate) fn default_weight_decay() -> Scalar {
+    0.01
+}
+
+// Adam whose weight decay is applied to the parameters instead of being added to the gradients
+// https://arxiv.org/pdf/1711.05101.pdf
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct AdamW {
+    #[serde(default = "default_beta1")]
+    beta1: Scalar,
+    #[serde(default = "default_beta2")]
+    beta2: Scalar,
+    #[serde(default = "default_epsilon")]
+    epsilon: Scalar,
+    #[serde(default = "default_weight_decay")]
+    weight_decay: Scalar,
+    #[serde(default = "default_learning_rate")]
+    learning_rate: LearningRateSchedule,
+    #[serde(skip)]
+    m: Option<Matrix>, // first moment vector
+    #[serde(skip)]
+    v: Option<Matrix>, // second moment vector
+    #[serde(skip)]
+    t: i32, // number of updates
+}
+
+impl Default for AdamW {
+    fn default() -> Self {
+        Self {
+            m: None,
+            v: None,
+            t: 0,
+            beta1: default_beta1(),
+            beta2: default_beta2(),
+            epsilon: default_epsilon(),
+            weight_decay: default_weight_decay(),
+            learning_rate: default_learning_rate(),
+        }
+    }
+}
+
+impl AdamW {
+    pub fn new(
+        learning_rate: LearningRateSchedule,
+        beta1: Scalar,
+        beta2: Scalar,
+        epsilon: Scalar,
+        weight_decay: Scalar,
+    ) -> Self {
+        Self {
+            m: None,
+            v: None,
+            t: 0,
+            beta1,
+            beta2,
+            epsilon,
+            weight_decay,
+            learning_rate,
+        }
+    }
+
+    pub fn update_parameters(
+        &mut self,
+        epoch: usize,
+        parameters: &Matrix,
+        parameters_gradient: &Matrix,
+    ) -> Matrix {
+        let alpha = self.learning_rate.get_learning_rate(epoch);
+        let (nrow, ncol) = parameters_gradient.dim();
+
+        if self.m.is_none() {
+            self.m = Some(Matrix::zeros(nrow, ncol));
+        }
+        if self.v.is_none() {
+            self.v = Some(Matrix::zeros(nrow, ncol));
+        }
+        let m = self.m.as_ref().unwrap();
+        let v = self.v.as_ref().unwrap();
+        self.t += 1;
+
+        let g = parameters_gradient;
+        let g2 = g.component_mul(g);
+
+        let m = m.scalar_mul(self.beta1).component_add(&g.scalar_mul(1.0 - self.beta1));
+        let v = v.scalar_mul(self.beta2).component_add(&g2.scalar_mul(1.0 - self.beta2));
+
+        let m_bias_corrected = m.scalar_div(1.0 - self.beta1.powi(self.t));
+        let v_bias_corrected = v.scalar_div(1.0 - self.beta2.powi(self.t));
+
+        let decayed = parameters.scalar_mul(1.0 - alpha * self.weight_decay);
+        let new_params = decayed.component_sub(
+            &m_bias_corrected
+                .scalar_mul(alpha)
+                .component_div(&v_bias_corrected.sqrt().scalar_add(self.epsilon)),
+        );
+        self.m = Some(m);
+        self.v = Some(v);
+        new_params
+    }
+}
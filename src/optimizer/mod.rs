@@ -2,17 +2,32 @@ use serde::{Deserialize, Serialize};
 
 use crate::linalg::Matrix;
 
-use self::{adam::Adam, momentum::Momentum, sgd::SGD};
+use self::{
+    adadelta::AdaDelta, adagrad::AdaGrad, adam::Adam, adamw::AdamW, amsgrad::AMSGrad,
+    momentum::Momentum, nadam::Nadam, rmsprop::RMSProp, sgd::SGD,
+};
 
 pub mod adam;
 pub mod momentum;
 pub mod sgd;
+pub mod rmsprop;
+pub mod adagrad;
+pub mod adadelta;
+pub mod nadam;
+pub mod amsgrad;
+pub mod adamw;
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub enum Optimizers {
     SGD(SGD),
     Momentum(Momentum),
     Adam(Adam),
+    RMSProp(RMSProp),
+    AdaGrad(AdaGrad),
+    AdaDelta(AdaDelta),
+    Nadam(Nadam),
+    AMSGrad(AMSGrad),
+    AdamW(AdamW),
 }
 
 impl Optimizers {
@@ -30,6 +45,24 @@ impl Optimizers {
             Optimizers::Adam(adam) => {
                 adam.update_parameters(epoch, parameters, parameters_gradient)
             }
+            Optimizers::RMSProp(rmsprop) => {
+                rmsprop.update_parameters(epoch, parameters, parameters_gradient)
+            }
+            Optimizers::AdaGrad(adagrad) => {
+                adagrad.update_parameters(epoch, parameters, parameters_gradient)
+            }
+            Optimizers::AdaDelta(adadelta) => {
+                adadelta.update_parameters(epoch, parameters, parameters_gradient)
+            }
+            Optimizers::Nadam(nadam) => {
+                nadam.update_parameters(epoch, parameters, parameters_gradient)
+            }
+            Optimizers::AMSGrad(amsgrad) => {
+                amsgrad.update_parameters(epoch, parameters, parameters_gradient)
+            }
+            Optimizers::AdamW(adamw) => {
+                adamw.update_parameters(epoch, parameters, parameters_gradient)
+            }
         }
     }
 }
@@ -45,3 +78,27 @@ pub fn sgd() -> Optimizers {
 pub fn momentum() -> Optimizers {
     Optimizers::Momentum(Momentum::default())
 }
+
+pub fn rmsprop() -> Optimizers {
+    Optimizers::RMSProp(RMSProp::default())
+}
+
+pub fn adagrad() -> Optimizers {
+    Optimizers::AdaGrad(AdaGrad::default())
+}
+
+pub fn adadelta() -> Optimizers {
+    Optimizers::AdaDelta(AdaDelta::default())
+}
+
+pub fn nadam() -> Optimizers {
+    Optimizers::Nadam(Nadam::default())
+}
+
+pub fn amsgrad() -> Optimizers {
+    Optimizers::AMSGrad(AMSGrad::default())
+}
+
+pub fn adamw() -> Optimizers {
+    Optimizers::AdamW(AdamW::default())
+}
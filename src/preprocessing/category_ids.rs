@@ -0,0 +1,122 @@
+use std::collections::HashMap;
+
+use crate::{
+    dataset::Dataset,
+    datatable::DataTable,
+    linalg::Scalar,
+};
+
+use super::{CachedConfig, DataTransformation};
+
+/// Replaces the categories of embedded features with integer ids, in the categories' ascending order.
+///
+/// The categories are fitted on the first transformed data and kept for the following ones.
+/// Ids start at 1, categories that weren't seen when fitting being mapped to the reserved unknown id 0.
+/// Categories must be integers.
+///
+/// Ids aren't numerical values, so the embedded features are no longer log scaled, squared,
+/// filtered or normalized by the following transformations.
+pub struct CategoryIds {
+    pub features_categories: HashMap<String, Vec<i64>>,
+}
+
+impl CategoryIds {
+    /// Id of the categories that weren't seen when fitting.
+    pub const UNKNOWN_ID: usize = 0;
+
+    pub fn new() -> Self {
+        Self {
+            features_categories: HashMap::new(),
+        }
+    }
+}
+
+fn to_category(feature: &str, value: Scalar) -> i64 {
+    if value.fract() != 0.0 {
+        panic!(
+            "Embedded feature {} has the non-integer category {}",
+            feature, value
+        );
+    }
+    value as i64
+}
+
+impl Default for CategoryIds {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DataTransformation for CategoryIds {
+    fn transform(
+        &mut self,
+        _cached_config: &CachedConfig,
+        dataset_config: &Dataset,
+        data: &DataTable,
+    ) -> (Dataset, DataTable) {
+        let mut new_config = dataset_config.clone();
+        let mut new_data = data.clone();
+
+        for feature in dataset_config.features.iter() {
+            if feature.embedded.is_none() {
+                continue;
+            }
+
+            let categories = match self.features_categories.get(&feature.name) {
+                Some(categories) => categories.clone(),
+                None => {
+                    let mut categories: Vec<i64> = data
+                        .column_to_vector(&feature.name)
+                        .iter()
+                        .map(|v| to_category(&feature.name, *v))
+                        .collect();
+                    categories.sort();
+                    categories.dedup();
+                    categories
+                }
+            };
+
+            let ids: HashMap<i64, usize> = categories
+                .iter()
+                .enumerate()
+                .map(|(i, category)| (*category, i + 1))
+                .collect();
+            new_data = new_data.map_scalar_column(&feature.name, |v| {
+                let id = ids.get(&to_category(&feature.name, v));
+                *id.unwrap_or(&Self::UNKNOWN_ID) as Scalar
+            });
+
+            let mut new_feature = feature.clone();
+            new_feature.categories_count = Some(categories.len() + 1);
+            new_feature.log10 = false;
+            new_feature.squared = false;
+            new_feature.filter_outliers = false;
+            new_feature.normalized = false;
+            new_config = new_config.with_replaced_feature(&feature.name, new_feature);
+
+            self.features_categories.insert(feature.name.clone(), categories);
+        }
+
+        (new_config, new_data)
+    }
+
+    fn reverse_columnswise(&mut self, data: &DataTable) -> DataTable {
+        let mut reversed_data = data.clone();
+
+        // the unknown id is reverted to NaN
+        for (feature, categories) in self.features_categories.iter() {
+            if reversed_data.has_column(feature) {
+                reversed_data = reversed_data.map_scalar_column(feature, |id| match id as usize {
+                    Self::UNKNOWN_ID => Scalar::NAN,
+                    id => categories[id - 1] as Scalar,
+                });
+            }
+        }
+
+        reversed_data
+    }
+
+    fn get_name(&self) -> String {
+        "categoryids".to_string()
+    }
+}
@@ -9,12 +9,13 @@ use std::{
 use crate::{dataset::Dataset, datatable::DataTable, monitor::TM};
 
 use self::{
-    extract_months::ExtractMonths, extract_timestamps::ExtractTimestamps,
+    category_ids::CategoryIds, extract_months::ExtractMonths, extract_timestamps::ExtractTimestamps,
     filter_outliers::FilterOutliers, log_scale::LogScale10, map::Map, normalize::Normalize,
     one_hot_encode::OneHotEncode, square::Square,
 };
 
 pub mod attach_ids;
+pub mod category_ids;
 pub mod extract_months;
 pub mod extract_timestamps;
 pub mod feature_cached;
@@ -61,6 +62,7 @@ impl Pipeline {
     /// This may not fit your exact usecase, but it's a good starting point.
     ///
     /// The pipeline is:
+    /// - Replace embedded features' categories with ids if required
     /// - One hot encode categorical features if required
     /// - Extract months if required
     /// - Extract timestamps if required
@@ -73,6 +75,7 @@ impl Pipeline {
     pub fn basic_single_pass() -> Pipeline {
         let mut pipeline = Pipeline::new();
         pipeline
+            .push(CategoryIds::new())
             .push(OneHotEncode)
             .push(ExtractMonths)
             .push(ExtractTimestamps)
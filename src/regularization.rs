@@ -0,0 +1,73 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    linalg::{Matrix, MatrixTrait, Scalar},
+    vision::image::{Image, ImageTrait},
+};
+
+/// Penalty `l1 * Σ|w| + l2 * Σw²` on a layer's weights (elastic net when both are set),
+/// added to the reported training loss and to the weights' gradients.
+///
+/// As the reported loss is averaged over the batch's samples while the gradients are summed over them,
+/// layers scale the penalty's gradient by the number of samples.
+///
+/// Biases are only penalized when `biases` is set.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct Regularization {
+    #[serde(default)]
+    pub l1: Scalar,
+    #[serde(default)]
+    pub l2: Scalar,
+    #[serde(default)]
+    pub biases: bool,
+}
+
+impl Regularization {
+    pub fn l1(l1: Scalar) -> Self {
+        Self::elastic_net(l1, 0.0)
+    }
+
+    pub fn l2(l2: Scalar) -> Self {
+        Self::elastic_net(0.0, l2)
+    }
+
+    pub fn elastic_net(l1: Scalar, l2: Scalar) -> Self {
+        Self {
+            l1,
+            l2,
+            biases: false,
+        }
+    }
+
+    pub fn including_biases(self) -> Self {
+        Self {
+            biases: true,
+            ..self
+        }
+    }
+
+    pub fn penalty(&self, weights: &Matrix) -> Scalar {
+        let abs = weights.component_mul(&weights.sign());
+        self.l1 * abs.sum() + self.l2 * weights.square().sum()
+    }
+
+    /// Returns `∂penalty/∂W` which has the same shape as `weights`, the subgradient of `|w|` being 0 at 0.
+    pub fn gradient(&self, weights: &Matrix) -> Matrix {
+        weights
+            .sign_zero_at_zero()
+            .scalar_mul(self.l1)
+            .component_add(&weights.scalar_mul(2. * self.l2))
+    }
+
+    pub fn image_penalty(&self, kernels: &Image) -> Scalar {
+        let abs = kernels.component_mul(&kernels.sign());
+        self.l1 * abs.sum() + self.l2 * kernels.square().sum()
+    }
+
+    pub fn image_gradient(&self, kernels: &Image) -> Image {
+        kernels
+            .sign_zero_at_zero()
+            .scalar_mul(self.l1)
+            .component_add(&kernels.scalar_mul(2. * self.l2))
+    }
+}
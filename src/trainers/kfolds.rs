@@ -178,7 +178,8 @@ impl KFolds {
             validation.random_order_in_out(&predicted_features);
 
         // Convert the validation set to vectors
-        let validation_x = validation_x_table.drop_column(id_column).to_vectors();
+        let (validation_x, validation_weights) =
+            model.inputs_and_weights(&validation_x_table, id_column);
         let validation_y = validation_y_table.to_vectors();
 
         let mut fold_eval = TrainingEvaluation::new_empty();
@@ -197,12 +198,13 @@ impl KFolds {
             let train_loss = model.train_epoch(e, &mut network, &train_table, id_column);
 
             // Predict all values in the i-th fold
-            let loss_fn = model.loss.to_loss();
+            let loss_fn = model.to_loss();
             let (preds, loss_avg, loss_std) = if e == model.epochs - 1 || self.all_epochs_validation
             {
-                let vloss = network.predict_evaluate_many(
+                let vloss = network.predict_evaluate_many_weighted(
                     &validation_x,
                     &validation_y,
+                    validation_weights.as_ref(),
                     &loss_fn,
                     model.batch_size.unwrap_or(validation_x.len()),
                 );
@@ -294,7 +296,8 @@ impl KFolds {
                 validation.random_order_in_out(&predicted_features);
 
             // Convert the validation set to vectors
-            let validation_x = validation_x_table.drop_column(id_column).to_vectors();
+            let (validation_x, validation_weights) =
+                model.inputs_and_weights(&validation_x_table, id_column);
             let validation_y = validation_y_table.to_vectors();
 
             TM::end();
@@ -310,12 +313,13 @@ impl KFolds {
                 // Predict all values in the i-th fold
                 // It is costly and should be done only during the last epoch
                 // and made optional for all the others in the future
-                let loss_fn = model.loss.to_loss();
+                let loss_fn = model.to_loss();
                 let (preds, loss_avg, loss_std) = if e == model.epochs - 1 || all_epochs_validation
                 {
-                    let vloss = network.predict_evaluate_many(
+                    let vloss = network.predict_evaluate_many_weighted(
                         &validation_x,
                         &validation_y,
+                        validation_weights.as_ref(),
                         &loss_fn,
                         model.batch_size.unwrap_or(validation_x.len()),
                     );
@@ -105,7 +105,8 @@ impl SplitTraining {
             validation.random_order_in_out(&predicted_features);
 
         // Convert the validation set to vectors
-        let validation_x = validation_x_table.drop_column(id_column).to_vectors();
+        let (validation_x, validation_weights) =
+            model.inputs_and_weights(&validation_x_table, id_column);
         let validation_y = validation_y_table.to_vectors();
 
         TM::end_with_message(format!(
@@ -123,12 +124,13 @@ impl SplitTraining {
 
             let train_loss = model.train_epoch(e, &mut network, &train_table, id_column);
 
-            let loss_fn = model.loss.to_loss();
+            let loss_fn = model.to_loss();
             let (preds, loss_avg, loss_std) = if e == model.epochs - 1 || self.all_epochs_validation
             {
-                let vloss = network.predict_evaluate_many(
+                let vloss = network.predict_evaluate_many_weighted(
                     &validation_x,
                     &validation_y,
+                    validation_weights.as_ref(),
                     &loss_fn,
                     model.batch_size.unwrap_or(validation_x.len()),
                 );
@@ -220,7 +222,7 @@ impl SplitTraining {
 
             let train_loss = model.train_epoch(e, &mut network, &train_x, &train_y);
 
-            let loss_fn = model.loss.to_loss();
+            let loss_fn = model.to_loss();
             let (preds, loss_avg, loss_std) = if e == model.epochs - 1 || self.all_epochs_validation
             {
                 let vloss = network.predict_evaluate_many(
@@ -0,0 +1,28 @@
+use super::{zeros_like, ConvActivationLayer};
+use crate::{
+    linalg::Scalar,
+    vision::{image::Image, image::ImageTrait},
+};
+
+// https://arxiv.org/pdf/1511.07289.pdf
+
+pub fn elu(m: &Image, alpha: Scalar) -> Image {
+    let zeros = zeros_like(m);
+    let negative = m.minof(&zeros).exp().scalar_sub(1.).scalar_mul(alpha);
+    m.maxof(&zeros).component_add(&negative)
+}
+
+pub fn elu_prime(m: &Image, alpha: Scalar) -> Image {
+    let zeros = zeros_like(m);
+    let step = m.sign().maxof(&zeros);
+    let negative = m
+        .minof(&zeros)
+        .exp()
+        .scalar_mul(alpha)
+        .component_mul(&step.scalar_mul(-1.).scalar_add(1.));
+    step.component_add(&negative)
+}
+
+pub fn new(alpha: Scalar) -> ConvActivationLayer {
+    ConvActivationLayer::new(move |m| elu(m, alpha), move |m| elu_prime(m, alpha))
+}
@@ -0,0 +1,21 @@
+use super::{zeros_like, ConvActivationLayer};
+use crate::vision::{image::Image, image::ImageTrait};
+
+pub fn hard_sigmoid(m: &Image) -> Image {
+    // max(0, min(1, x / 6 + 1 / 2))
+    let zeros = zeros_like(m);
+    let ones = zeros.scalar_add(1.);
+    m.scalar_div(6.).scalar_add(0.5).minof(&ones).maxof(&zeros)
+}
+
+pub fn hard_sigmoid_prime(m: &Image) -> Image {
+    // 1 / 6 for -3 < x < 3, 0 elsewhere
+    let zeros = zeros_like(m);
+    let above = m.scalar_add(3.).sign().maxof(&zeros);
+    let below = m.scalar_sub(3.).scalar_mul(-1.).sign().maxof(&zeros);
+    above.component_mul(&below).scalar_div(6.)
+}
+
+pub fn new() -> ConvActivationLayer {
+    ConvActivationLayer::new(hard_sigmoid, hard_sigmoid_prime)
+}
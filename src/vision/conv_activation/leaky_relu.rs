@@ -0,0 +1,22 @@
+use super::{zeros_like, ConvActivationLayer};
+use crate::{
+    linalg::Scalar,
+    vision::{image::Image, image::ImageTrait},
+};
+
+pub fn leaky_relu(m: &Image, alpha: Scalar) -> Image {
+    let zeros = zeros_like(m);
+    m.maxof(&zeros).component_add(&m.minof(&zeros).scalar_mul(alpha))
+}
+
+pub fn leaky_relu_prime(m: &Image, alpha: Scalar) -> Image {
+    let step = m.sign().maxof(&zeros_like(m));
+    step.component_add(&step.scalar_mul(-1.).scalar_add(1.).scalar_mul(alpha))
+}
+
+pub fn new(alpha: Scalar) -> ConvActivationLayer {
+    ConvActivationLayer::new(
+        move |m| leaky_relu(m, alpha),
+        move |m| leaky_relu_prime(m, alpha),
+    )
+}
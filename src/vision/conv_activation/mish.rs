@@ -0,0 +1,25 @@
+use super::{sigmoid::sigmoid, softplus::softplus, ConvActivationLayer};
+use crate::vision::{image::Image, image::ImageTrait};
+
+// https://arxiv.org/pdf/1908.08681.pdf
+
+// tanh(softplus(x)), computed as 2σ(2z) - 1 so that it doesn't overflow
+fn tanh_softplus(m: &Image) -> Image {
+    sigmoid(&softplus(m).scalar_mul(2.))
+        .scalar_mul(2.)
+        .scalar_sub(1.)
+}
+
+pub fn mish(m: &Image) -> Image {
+    m.component_mul(&tanh_softplus(m))
+}
+
+pub fn mish_prime(m: &Image) -> Image {
+    let t = tanh_softplus(m);
+    let sech2 = t.square().scalar_mul(-1.).scalar_add(1.);
+    t.component_add(&m.component_mul(&sech2).component_mul(&sigmoid(m)))
+}
+
+pub fn new() -> ConvActivationLayer {
+    ConvActivationLayer::new(mish, mish_prime)
+}
@@ -1,40 +1,196 @@
-use std::fmt;
+use std::{fmt, sync::Arc};
 
 use serde::{Deserialize, Serialize};
 
-use super::{image::Image, image::ImageTrait};
+use crate::{
+    autodiff::{Tape, Var},
+    gradient_clipping::GradientMut,
+    layer::{GradientLayer, LearnableLayer},
+    linalg::{MatrixTrait, Scalar},
+};
 
+use super::{
+    conv_optimizer::{conv_sgd, ConvOptimizers},
+    image::{Image, ImageTrait},
+    image_layer::accumulate_image_gradient,
+};
+
+pub mod elu;
+pub mod gelu;
+pub mod hard_sigmoid;
+pub mod leaky_relu;
 pub mod linear;
+pub mod mish;
+pub mod prelu;
 pub mod relu;
+pub mod selu;
 pub mod sigmoid;
+pub mod softplus;
+pub mod swish;
 pub mod tanh;
 
-pub type ConvActivationFn = fn(&Image) -> Image;
+pub type ConvActivationFn = Arc<dyn Fn(&Image) -> Image + Send + Sync>;
+/// Activation (or derivative) depending on a slope, e.g. `x -> max(x, 0) + slope * min(x, 0)`
+pub type SlopeConvActivationFn = fn(&Image, Scalar) -> Image;
+
+pub(crate) fn zeros_like(m: &Image) -> Image {
+    let (nrow, ncol, nchan) = m.image_dims();
+    Image::constant(nrow, ncol, nchan, m.samples(), 0.)
+}
+
+/// Slope of an activation learned alongside the layer's other parameters (e.g. PReLU).
+pub struct LearnableConvSlope {
+    pub slope: Scalar,
+    optimizer: ConvOptimizers,
+    activation: SlopeConvActivationFn,
+    derivative: SlopeConvActivationFn,
+    // ∂Y/∂slope
+    slope_derivative: fn(&Image) -> Image,
+    // accumulated by backward, applied by step
+    gradient: Option<Image>,
+}
 
 pub struct ConvActivationLayer {
-    input: Option<Image>,
+    tape: Tape<Image>,
+    input: Option<Var>,
+    output: Option<Var>,
     activation: ConvActivationFn,
     derivative: ConvActivationFn,
+    learnable_slope: Option<LearnableConvSlope>,
 }
 
 impl ConvActivationLayer {
-    pub fn new(activation: ConvActivationFn, derivative: ConvActivationFn) -> Self {
+    pub fn new(
+        activation: impl Fn(&Image) -> Image + Send + Sync + 'static,
+        derivative: impl Fn(&Image) -> Image + Send + Sync + 'static,
+    ) -> Self {
         Self {
+            tape: Tape::new(),
             input: None,
+            output: None,
+            activation: Arc::new(activation),
+            derivative: Arc::new(derivative),
+            learnable_slope: None,
+        }
+    }
+
+    /// Activation whose `slope` is trained by `optimizer` along with the layer's other parameters.
+    pub fn new_learnable_slope(
+        activation: SlopeConvActivationFn,
+        derivative: SlopeConvActivationFn,
+        slope_derivative: fn(&Image) -> Image,
+        slope: Scalar,
+        optimizer: ConvOptimizers,
+    ) -> Self {
+        let mut layer = Self::new(|m| m.clone(), |m| m.clone());
+        layer.learnable_slope = Some(LearnableConvSlope {
+            slope,
+            optimizer,
             activation,
             derivative,
+            slope_derivative,
+            gradient: None,
+        });
+        layer.set_slope(slope);
+        layer
+    }
+
+    pub fn get_slope(&self) -> Option<Scalar> {
+        self.learnable_slope.as_ref().map(|s| s.slope)
+    }
+
+    /// Does nothing if the activation has no learnable slope.
+    pub fn set_slope(&mut self, slope: Scalar) {
+        if let Some(learnable_slope) = &mut self.learnable_slope {
+            let (activation, derivative) = (learnable_slope.activation, learnable_slope.derivative);
+            learnable_slope.slope = slope;
+            self.activation = Arc::new(move |m| activation(m, slope));
+            self.derivative = Arc::new(move |m| derivative(m, slope));
         }
     }
 
     pub fn forward(&mut self, input: Image) -> Image {
-        self.input = Some(input.clone());
-        (self.activation)(&input)
+        self.tape.clear();
+        let input = self.tape.var(input);
+        let derivative = self.derivative.clone();
+        let output = self
+            .tape
+            .map(input, &*self.activation, move |m| derivative(m));
+        self.input = Some(input);
+        self.output = Some(output);
+        self.tape.value(output).clone()
     }
 
     pub fn backward(&mut self, _epoch: usize, output_gradient: Image) -> Image {
-        let input = self.input.clone().unwrap();
-        let fprime_x = (self.derivative)(&input);
-        output_gradient.component_mul(&fprime_x)
+        if let Some(learnable_slope) = &mut self.learnable_slope {
+            let x = self.tape.value(self.input.unwrap());
+            // ∂E/∂slope = Σ ∂E/∂Y ⊙ ∂Y/∂slope
+            let slope_gradient = output_gradient
+                .component_mul(&(learnable_slope.slope_derivative)(x))
+                .sum();
+            accumulate_image_gradient(
+                &mut learnable_slope.gradient,
+                Image::constant(1, 1, 1, 1, slope_gradient),
+            );
+        }
+
+        let mut grads = self
+            .tape
+            .backward(self.output.unwrap(), output_gradient);
+
+        grads.take(self.input.unwrap()).unwrap()
+    }
+}
+
+impl GradientLayer for ConvActivationLayer {
+    fn gradients_mut(&mut self) -> Vec<GradientMut<'_>> {
+        self.learnable_slope
+            .iter_mut()
+            .filter_map(|s| s.gradient.as_mut())
+            .map(GradientMut::Image)
+            .collect()
+    }
+
+    fn zero_grad(&mut self) {
+        if let Some(learnable_slope) = &mut self.learnable_slope {
+            learnable_slope.gradient = None;
+        }
+    }
+
+    fn step(&mut self, epoch: usize) {
+        let slope = self.learnable_slope.as_mut().and_then(|learnable_slope| {
+            let gradient = learnable_slope.gradient.take()?;
+            Some(
+                learnable_slope
+                    .optimizer
+                    .update_parameters(
+                        epoch,
+                        &Image::constant(1, 1, 1, 1, learnable_slope.slope),
+                        &gradient,
+                    )
+                    .flatten()
+                    .index(0, 0),
+            )
+        });
+        if let Some(slope) = slope {
+            self.set_slope(slope);
+        }
+    }
+}
+
+impl LearnableLayer for ConvActivationLayer {
+    // returns a single column holding the slope, or nothing if the activation has no learnable slope
+    fn get_learnable_parameters(&self) -> Vec<Vec<Scalar>> {
+        match self.get_slope() {
+            Some(slope) => vec![vec![slope]],
+            None => vec![],
+        }
+    }
+
+    fn set_learnable_parameters(&mut self, params_matrix: &Vec<Vec<Scalar>>) {
+        if let Some(slope) = params_matrix.first() {
+            self.set_slope(slope[0]);
+        }
     }
 }
 
@@ -44,15 +200,42 @@ pub enum ConvActivation {
     ConvSigmoid,
     ConvReLU,
     ConvLinear,
+    /// Slope of the negative part
+    ConvLeakyReLU(Scalar),
+    /// Saturation value of the negative part
+    ConvELU(Scalar),
+    ConvSELU,
+    ConvGELU,
+    #[serde(alias = "ConvSiLU")]
+    ConvSwish,
+    ConvSoftplus,
+    ConvHardSigmoid,
+    ConvMish,
+    /// Initial slope of the negative part, which is then learned
+    ConvPReLU(Scalar),
 }
 
 impl ConvActivation {
     pub fn to_layer(&self) -> ConvActivationLayer {
+        self.to_layer_with_optimizer(conv_sgd())
+    }
+
+    /// `optimizer` trains the activation's learnable parameters, if any (PReLU's slope).
+    pub fn to_layer_with_optimizer(&self, optimizer: ConvOptimizers) -> ConvActivationLayer {
         match self {
             Self::ConvLinear => linear::new(),
             Self::ConvTanh => tanh::new(),
             Self::ConvSigmoid => sigmoid::new(),
             Self::ConvReLU => relu::new(),
+            Self::ConvLeakyReLU(alpha) => leaky_relu::new(*alpha),
+            Self::ConvELU(alpha) => elu::new(*alpha),
+            Self::ConvSELU => selu::new(),
+            Self::ConvGELU => gelu::new(),
+            Self::ConvSwish => swish::new(),
+            Self::ConvSoftplus => softplus::new(),
+            Self::ConvHardSigmoid => hard_sigmoid::new(),
+            Self::ConvMish => mish::new(),
+            Self::ConvPReLU(slope) => prelu::new(*slope, optimizer),
         }
     }
 }
@@ -0,0 +1,26 @@
+use super::{
+    leaky_relu::{leaky_relu, leaky_relu_prime},
+    zeros_like, ConvActivationLayer,
+};
+use crate::{
+    linalg::Scalar,
+    vision::{conv_optimizer::ConvOptimizers, image::Image, image::ImageTrait},
+};
+
+// https://arxiv.org/pdf/1502.01852.pdf
+
+/// ∂Y/∂slope
+pub fn prelu_slope_prime(m: &Image) -> Image {
+    m.minof(&zeros_like(m))
+}
+
+/// Leaky ReLU whose slope, shared by all the channels, is trained by `optimizer`.
+pub fn new(slope: Scalar, optimizer: ConvOptimizers) -> ConvActivationLayer {
+    ConvActivationLayer::new_learnable_slope(
+        leaky_relu,
+        leaky_relu_prime,
+        prelu_slope_prime,
+        slope,
+        optimizer,
+    )
+}
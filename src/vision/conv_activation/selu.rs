@@ -0,0 +1,22 @@
+use super::{
+    elu::{elu, elu_prime},
+    ConvActivationLayer,
+};
+use crate::{
+    activation::selu::{SELU_ALPHA, SELU_SCALE},
+    vision::{image::Image, image::ImageTrait},
+};
+
+// https://arxiv.org/pdf/1706.02515.pdf
+
+pub fn selu(m: &Image) -> Image {
+    elu(m, SELU_ALPHA).scalar_mul(SELU_SCALE)
+}
+
+pub fn selu_prime(m: &Image) -> Image {
+    elu_prime(m, SELU_ALPHA).scalar_mul(SELU_SCALE)
+}
+
+pub fn new() -> ConvActivationLayer {
+    ConvActivationLayer::new(selu, selu_prime)
+}
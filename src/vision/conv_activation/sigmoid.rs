@@ -1,7 +1,7 @@
 use super::ConvActivationLayer;
 use crate::vision::{image::Image, image::ImageTrait};
 
-fn sigmoid(m: &Image) -> Image {
+pub fn sigmoid(m: &Image) -> Image {
     let exp_neg = m.scalar_mul(-1.).exp();
     let ones = Image::constant(
         m.image_dims().0,
@@ -13,7 +13,7 @@ fn sigmoid(m: &Image) -> Image {
     ones.component_div(&(ones.component_add(&exp_neg)))
 }
 
-fn sigmoid_prime(m: &Image) -> Image {
+pub fn sigmoid_prime(m: &Image) -> Image {
     let sig = sigmoid(m);
     let ones = Image::constant(
         m.image_dims().0,
@@ -0,0 +1,17 @@
+use super::{sigmoid::sigmoid, zeros_like, ConvActivationLayer};
+use crate::vision::{image::Image, image::ImageTrait};
+
+pub fn softplus(m: &Image) -> Image {
+    // max(x, 0) + ln(1 + e^-|x|) doesn't overflow for large inputs
+    let abs = m.component_mul(&m.sign());
+    m.maxof(&zeros_like(m))
+        .component_add(&abs.scalar_mul(-1.).exp().scalar_add(1.).log())
+}
+
+pub fn softplus_prime(m: &Image) -> Image {
+    sigmoid(m)
+}
+
+pub fn new() -> ConvActivationLayer {
+    ConvActivationLayer::new(softplus, softplus_prime)
+}
@@ -0,0 +1,18 @@
+use super::{sigmoid::sigmoid, ConvActivationLayer};
+use crate::vision::{image::Image, image::ImageTrait};
+
+// also known as SiLU: https://arxiv.org/pdf/1710.05941.pdf
+
+pub fn swish(m: &Image) -> Image {
+    m.component_mul(&sigmoid(m))
+}
+
+pub fn swish_prime(m: &Image) -> Image {
+    let sig = sigmoid(m);
+    let sig_prime = sig.component_mul(&sig.scalar_mul(-1.).scalar_add(1.));
+    sig.component_add(&m.component_mul(&sig_prime))
+}
+
+pub fn new() -> ConvActivationLayer {
+    ConvActivationLayer::new(swish, swish_prime)
+}
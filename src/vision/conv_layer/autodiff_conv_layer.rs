@@ -0,0 +1,163 @@
+use std::fmt;
+
+use crate::{
+    autodiff::{Tape, Var},
+    gradient_clipping::GradientMut,
+    layer::{DropoutLayer, GradientLayer, LearnableLayer, ParameterableLayer},
+    linalg::{Matrix, MatrixTrait, Scalar},
+    vision::{
+        conv_network::ConvNetworkLayer, conv_optimizer::ConvOptimizers, image::Image,
+        image::ImageTrait, image_layer::{accumulate_image_gradient, ImageLayer},
+    },
+};
+
+/// Records the forward pass of an `AutodiffConvLayer` on the tape.
+///
+/// Takes the input variable and the parameters variables (in the order they were given to the layer),
+/// returns the output variable.
+pub type AutodiffConvForwardFn = Box<dyn Fn(&mut Tape<Image>, Var, &[Var]) -> Var + Send>;
+
+/// An image layer defined only by its forward pass, its gradients being derived automatically.
+pub struct AutodiffConvLayer {
+    forward_fn: AutodiffConvForwardFn,
+    parameters: Vec<Image>,
+    optimizers: Vec<ConvOptimizers>,
+    // one per parameter, accumulated by backward and applied by step
+    gradients: Vec<Option<Image>>,
+    tape: Tape<Image>,
+    input: Option<Var>,
+    parameters_vars: Vec<Var>,
+    output: Option<Var>,
+}
+
+impl AutodiffConvLayer {
+    /// `optimizers` must contain one optimizer per parameter.
+    pub fn new(
+        forward_fn: AutodiffConvForwardFn,
+        parameters: Vec<Image>,
+        optimizers: Vec<ConvOptimizers>,
+    ) -> Self {
+        assert_eq!(parameters.len(), optimizers.len());
+        Self {
+            forward_fn,
+            gradients: vec![None; parameters.len()],
+            parameters,
+            optimizers,
+            tape: Tape::new(),
+            input: None,
+            parameters_vars: vec![],
+            output: None,
+        }
+    }
+}
+
+impl ImageLayer for AutodiffConvLayer {
+    fn forward(&mut self, input: Image) -> Image {
+        self.tape.clear();
+        let input = self.tape.var(input);
+        self.parameters_vars = self
+            .parameters
+            .iter()
+            .map(|p| self.tape.var(p.clone()))
+            .collect();
+
+        let output = (self.forward_fn)(&mut self.tape, input, &self.parameters_vars);
+
+        self.input = Some(input);
+        self.output = Some(output);
+        self.tape.value(output).clone()
+    }
+
+    fn backward(&mut self, _epoch: usize, output_gradient: Image) -> Image {
+        let mut grads = self.tape.backward(self.output.unwrap(), output_gradient);
+
+        // a parameter not used in the forward pass has no gradient
+        for (var, acc) in self.parameters_vars.iter().zip(self.gradients.iter_mut()) {
+            if let Some(gradient) = grads.take(*var) {
+                accumulate_image_gradient(acc, gradient);
+            }
+        }
+
+        let input = self.tape.value(self.input.unwrap());
+        let (nrow, ncol, nchan) = input.image_dims();
+        let samples = input.samples();
+        grads
+            .take(self.input.unwrap())
+            .unwrap_or_else(|| Image::zeros(nrow, ncol, nchan, samples))
+    }
+}
+
+impl GradientLayer for AutodiffConvLayer {
+    fn gradients_mut(&mut self) -> Vec<GradientMut<'_>> {
+        self.gradients
+            .iter_mut()
+            .flatten()
+            .map(GradientMut::Image)
+            .collect()
+    }
+
+    fn zero_grad(&mut self) {
+        self.gradients.iter_mut().for_each(|g| *g = None);
+    }
+
+    fn step(&mut self, epoch: usize) {
+        for ((param, gradient), optimizer) in self
+            .parameters
+            .iter_mut()
+            .zip(self.gradients.iter_mut())
+            .zip(self.optimizers.iter_mut())
+        {
+            if let Some(gradient) = gradient.take() {
+                *param = optimizer.update_parameters(epoch, param, &gradient);
+            }
+        }
+    }
+}
+
+impl LearnableLayer for AutodiffConvLayer {
+    // returns the flattened samples of all the parameters one after the other
+    fn get_learnable_parameters(&self) -> Vec<Vec<Scalar>> {
+        let mut params = vec![];
+        for param in self.parameters.iter() {
+            params.append(&mut param.flatten().get_data_col_leading());
+        }
+        params
+    }
+
+    // takes the flattened samples of all the parameters one after the other
+    fn set_learnable_parameters(&mut self, params_matrix: &Vec<Vec<Scalar>>) {
+        let mut columns = params_matrix.iter();
+        for param in self.parameters.iter_mut() {
+            let samples: Vec<Vec<Scalar>> =
+                columns.by_ref().take(param.samples()).cloned().collect();
+            let (rows, cols, channels) = param.image_dims();
+            *param = Image::from_samples_with_dims(
+                &Matrix::from_column_leading_vector2(&samples),
+                (rows, cols),
+                channels,
+            );
+        }
+    }
+}
+
+impl ParameterableLayer for AutodiffConvLayer {
+    fn as_learnable_layer(&self) -> Option<&dyn LearnableLayer> {
+        Some(self)
+    }
+
+    fn as_learnable_layer_mut(&mut self) -> Option<&mut dyn LearnableLayer> {
+        Some(self)
+    }
+
+    fn as_dropout_layer(&mut self) -> Option<&mut dyn DropoutLayer> {
+        None
+    }
+}
+
+impl ConvNetworkLayer for AutodiffConvLayer {}
+
+impl fmt::Debug for AutodiffConvLayer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Autodiff Convolutional Layer")
+    }
+}
@@ -1,5 +1,5 @@
 use crate::{
-    layer::{LearnableLayer, ParameterableLayer},
+    layer::{GradientLayer, LearnableLayer, ParameterableLayer},
     linalg::{Scalar},
     vision::{
         image::Image,
@@ -52,6 +52,8 @@ impl ImageLayer for AvgPoolingLayer {
     }
 }
 
+impl GradientLayer for AvgPoolingLayer {}
+
 impl ParameterableLayer for AvgPoolingLayer {
     fn as_learnable_layer(&self) -> Option<&dyn LearnableLayer> {
         None
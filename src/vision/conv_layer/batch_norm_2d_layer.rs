@@ -0,0 +1,237 @@
+use std::fmt;
+
+use crate::{
+    gradient_clipping::GradientMut,
+    layer::{DropoutLayer, GradientLayer, LearnableLayer, ParameterableLayer},
+    linalg::{Matrix, MatrixTrait, Scalar},
+    vision::{
+        conv_network::ConvNetworkLayer, conv_optimizer::ConvOptimizers, image::Image,
+        image::ImageTrait, image_layer::{accumulate_image_gradient, ImageLayer},
+    },
+};
+
+// https://arxiv.org/pdf/1502.03167.pdf
+pub struct BatchNorm2dLayer {
+    // C channels in = C channels out, each channel is normalized across its pixels and the batch
+    // (1, 1, C, 1) scales
+    pub gamma: Image,
+    // (1, 1, C, 1) shifts
+    pub beta: Image,
+    // C means and variances used outside of training
+    pub running_mean: Vec<Scalar>,
+    pub running_var: Vec<Scalar>,
+    momentum: Scalar,
+    epsilon: Scalar,
+    training: bool,
+    // (r, c, C, n) normalized input of the last forward pass
+    normalized: Option<Image>,
+    // C values of 1 / √(σ² + ε) of the last forward pass
+    inv_stds: Vec<Scalar>,
+    // whether the last forward pass used the batch statistics
+    used_batch_stats: bool,
+    gamma_optimizer: ConvOptimizers,
+    beta_optimizer: ConvOptimizers,
+    // accumulated by backward, applied by step
+    gamma_gradient: Option<Image>,
+    beta_gradient: Option<Image>,
+}
+
+impl BatchNorm2dLayer {
+    pub fn new(
+        channels: usize,
+        momentum: Scalar,
+        epsilon: Scalar,
+        gamma_optimizer: ConvOptimizers,
+        beta_optimizer: ConvOptimizers,
+    ) -> Self {
+        Self {
+            gamma: Image::constant(1, 1, channels, 1, 1.0),
+            beta: Image::zeros(1, 1, channels, 1),
+            running_mean: vec![0.0; channels],
+            running_var: vec![1.0; channels],
+            momentum,
+            epsilon,
+            training: false,
+            normalized: None,
+            inv_stds: vec![],
+            used_batch_stats: false,
+            gamma_optimizer,
+            beta_optimizer,
+            gamma_gradient: None,
+            beta_gradient: None,
+        }
+    }
+}
+
+impl ImageLayer for BatchNorm2dLayer {
+    fn forward(&mut self, input: Image) -> Image {
+        let mut normalized_channels = vec![];
+        let mut output_channels = vec![];
+        self.inv_stds = vec![];
+
+        for c in 0..input.channels() {
+            let channel = input.get_channel_across_samples(c);
+
+            let (centered, var) = if self.training {
+                let mean = channel.mean();
+                let centered = channel.scalar_sub(mean);
+                let var = centered.square().mean();
+                // the running variance tracks the unbiased estimate m / (m - 1) σ²
+                let (nrow, ncol, _) = channel.image_dims();
+                let m = (nrow * ncol * channel.samples()) as Scalar;
+                let unbiased = if m > 1. { m / (m - 1.) } else { 1. };
+                self.running_mean[c] =
+                    self.momentum * self.running_mean[c] + (1. - self.momentum) * mean;
+                self.running_var[c] =
+                    self.momentum * self.running_var[c] + (1. - self.momentum) * unbiased * var;
+                (centered, var)
+            } else {
+                (channel.scalar_sub(self.running_mean[c]), self.running_var[c])
+            };
+
+            let inv_std = 1. / (var + self.epsilon).sqrt();
+            let normalized = centered.scalar_mul(inv_std);
+            let gamma = self.gamma.get_channel(c).sum();
+            let beta = self.beta.get_channel(c).sum();
+
+            output_channels.push(normalized.scalar_mul(gamma).scalar_add(beta));
+            normalized_channels.push(normalized);
+            self.inv_stds.push(inv_std);
+        }
+
+        self.normalized = Some(Image::join_channels(normalized_channels));
+        self.used_batch_stats = self.training;
+        Image::join_channels(output_channels)
+    }
+
+    fn backward(&mut self, _epoch: usize, output_gradient: Image) -> Image {
+        let normalized = self.normalized.as_ref().unwrap();
+        let (nrow, ncol, _) = normalized.image_dims();
+        let m = (nrow * ncol * normalized.samples()) as Scalar;
+
+        let mut input_grad_channels = vec![];
+        let mut gamma_grad_channels = vec![];
+        let mut beta_grad_channels = vec![];
+
+        for c in 0..normalized.channels() {
+            let grad = output_gradient.get_channel_across_samples(c);
+            let normalized = normalized.get_channel_across_samples(c);
+            let gamma = self.gamma.get_channel(c).sum();
+            let inv_std = self.inv_stds[c];
+
+            // ∂E/∂γ = Σ ∂E/∂Y ⊙ X̂, ∂E/∂β = Σ ∂E/∂Y
+            gamma_grad_channels.push(Image::constant(
+                1,
+                1,
+                1,
+                1,
+                grad.component_mul(&normalized).sum(),
+            ));
+            beta_grad_channels.push(Image::constant(1, 1, 1, 1, grad.sum()));
+
+            let normalized_grad = grad.scalar_mul(gamma);
+            let input_grad = if self.used_batch_stats {
+                // ∂E/∂X = (m ∂E/∂X̂ - Σ ∂E/∂X̂ - X̂ ⊙ Σ (∂E/∂X̂ ⊙ X̂)) / (m √(σ² + ε))
+                let normalized_grad_sum = normalized_grad.sum();
+                let normalized_grad_dot = normalized_grad.component_mul(&normalized).sum();
+                normalized_grad
+                    .scalar_mul(m)
+                    .scalar_sub(normalized_grad_sum)
+                    .component_sub(&normalized.scalar_mul(normalized_grad_dot))
+                    .scalar_mul(inv_std / m)
+            } else {
+                normalized_grad.scalar_mul(inv_std)
+            };
+            input_grad_channels.push(input_grad);
+        }
+
+        let gamma_grad = Image::join_channels(gamma_grad_channels);
+        let beta_grad = Image::join_channels(beta_grad_channels);
+        accumulate_image_gradient(&mut self.gamma_gradient, gamma_grad);
+        accumulate_image_gradient(&mut self.beta_gradient, beta_grad);
+
+        Image::join_channels(input_grad_channels)
+    }
+}
+
+impl GradientLayer for BatchNorm2dLayer {
+    fn gradients_mut(&mut self) -> Vec<GradientMut<'_>> {
+        self.gamma_gradient
+            .iter_mut()
+            .chain(self.beta_gradient.iter_mut())
+            .map(GradientMut::Image)
+            .collect()
+    }
+
+    fn zero_grad(&mut self) {
+        self.gamma_gradient = None;
+        self.beta_gradient = None;
+    }
+
+    fn step(&mut self, epoch: usize) {
+        if let Some(gamma_gradient) = self.gamma_gradient.take() {
+            self.gamma = self
+                .gamma_optimizer
+                .update_parameters(epoch, &self.gamma, &gamma_gradient);
+        }
+        if let Some(beta_gradient) = self.beta_gradient.take() {
+            self.beta = self
+                .beta_optimizer
+                .update_parameters(epoch, &self.beta, &beta_gradient);
+        }
+    }
+}
+
+impl LearnableLayer for BatchNorm2dLayer {
+    // returns the (C) gamma, beta, running mean and running variance columns
+    fn get_learnable_parameters(&self) -> Vec<Vec<Scalar>> {
+        vec![
+            self.gamma.flatten().get_column(0),
+            self.beta.flatten().get_column(0),
+            self.running_mean.clone(),
+            self.running_var.clone(),
+        ]
+    }
+
+    // takes the (C) gamma, beta, running mean and running variance columns
+    fn set_learnable_parameters(&mut self, params_matrix: &Vec<Vec<Scalar>>) {
+        let channels = params_matrix[0].len();
+        self.gamma = Image::from_samples(&Matrix::from_column_vector(&params_matrix[0]), channels);
+        self.beta = Image::from_samples(&Matrix::from_column_vector(&params_matrix[1]), channels);
+        self.running_mean = params_matrix[2].clone();
+        self.running_var = params_matrix[3].clone();
+    }
+}
+
+/// Batch statistics are used while dropout is enabled (training) and running statistics otherwise.
+impl DropoutLayer for BatchNorm2dLayer {
+    fn enable_dropout(&mut self) {
+        self.training = true;
+    }
+
+    fn disable_dropout(&mut self) {
+        self.training = false;
+    }
+}
+
+impl ParameterableLayer for BatchNorm2dLayer {
+    fn as_learnable_layer(&self) -> Option<&dyn LearnableLayer> {
+        Some(self)
+    }
+
+    fn as_learnable_layer_mut(&mut self) -> Option<&mut dyn LearnableLayer> {
+        Some(self)
+    }
+
+    fn as_dropout_layer(&mut self) -> Option<&mut dyn DropoutLayer> {
+        Some(self)
+    }
+}
+
+impl ConvNetworkLayer for BatchNorm2dLayer {}
+
+impl fmt::Debug for BatchNorm2dLayer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "2D Batch Normalization Layer")
+    }
+}
@@ -0,0 +1,111 @@
+use crate::{
+    gradient_clipping::GradientMut,
+    layer::{GradientLayer, LearnableLayer},
+    linalg::Scalar,
+    vision::{
+        conv_initializers::ConvInitializers, conv_optimizer::ConvOptimizers, image::Image,
+    },
+};
+
+use crate::vision::image_layer::ImageLayer;
+
+use super::{conv_padding::{ConvPaddings, PaddingAmounts}, dense_conv_layer::DenseConvLayer, ConvLayer};
+
+/// Convolution of 1D signals of shape `(length, channels, samples)`, stored as images with a single column.
+///
+/// Kernels have shape `(kernel_size, 1, in_chans, kernels_count)`, the input is only padded along its length.
+#[derive(Debug)]
+pub struct Conv1dLayer {
+    conv: DenseConvLayer,
+    kernel_size: usize,
+    stride: usize,
+    padding: ConvPaddings,
+    padding_amounts: PaddingAmounts,
+}
+
+impl Conv1dLayer {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        kernel_size: usize,
+        in_chans: usize,
+        kernels_count: usize,
+        stride: usize,
+        padding: ConvPaddings,
+        kernels_initializer: ConvInitializers,
+        biases_initializer: ConvInitializers,
+        kernels_optimizer: ConvOptimizers,
+        biases_optimizer: ConvOptimizers,
+    ) -> Self {
+        Self {
+            conv: DenseConvLayer::new(
+                kernel_size,
+                1,
+                in_chans,
+                kernels_count,
+                stride,
+                ConvPaddings::Valid,
+                kernels_initializer,
+                biases_initializer,
+                kernels_optimizer,
+                biases_optimizer,
+            ),
+            kernel_size,
+            stride,
+            padding,
+            padding_amounts: ((0, 0), (0, 0)),
+        }
+    }
+
+    pub fn out_length_and_channels(
+        in_length: usize,
+        kernel_size: usize,
+        kernels_count: usize,
+        stride: usize,
+        padding: ConvPaddings,
+    ) -> (usize, usize) {
+        (padding.out_size(in_length, kernel_size, stride), kernels_count)
+    }
+}
+
+impl ImageLayer for Conv1dLayer {
+    fn forward(&mut self, input: Image) -> Image {
+        let (input, padding_amounts) = self.padding.pad_1d(&input, self.kernel_size, self.stride);
+        self.padding_amounts = padding_amounts;
+        self.conv.forward(input)
+    }
+
+    fn backward(&mut self, epoch: usize, output_gradient: Image) -> Image {
+        let input_grad = self.conv.backward(epoch, output_gradient);
+        self.padding.unpad(&input_grad, self.padding_amounts)
+    }
+}
+
+impl GradientLayer for Conv1dLayer {
+    fn gradients_mut(&mut self) -> Vec<GradientMut<'_>> {
+        self.conv.gradients_mut()
+    }
+
+    fn zero_grad(&mut self) {
+        self.conv.zero_grad();
+    }
+
+    fn step(&mut self, epoch: usize) {
+        self.conv.step(epoch);
+    }
+}
+
+impl LearnableLayer for Conv1dLayer {
+    fn get_learnable_parameters(&self) -> Vec<Vec<Scalar>> {
+        self.conv.get_learnable_parameters()
+    }
+
+    fn set_learnable_parameters(&mut self, params_matrix: &Vec<Vec<Scalar>>) {
+        self.conv.set_learnable_parameters(params_matrix)
+    }
+}
+
+impl ConvLayer for Conv1dLayer {
+    fn scale_kernels(&mut self, scale: Scalar) {
+        self.conv.scale_kernels(scale);
+    }
+}
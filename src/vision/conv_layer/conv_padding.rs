@@ -0,0 +1,116 @@
+use serde::{Deserialize, Serialize};
+
+use crate::vision::image::{Image, ImageTrait};
+
+/// How a convolutional layer pads its input before cross-correlating it with its kernels.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Default)]
+pub enum ConvPaddings {
+    /// No padding, the kernels only visit positions fully inside the image
+    #[default]
+    Valid,
+    /// Pads each side with the given amount of zeros
+    Zero(usize),
+    /// Pads with zeros so that the output has `ceil(in / stride)` rows and columns
+    Same,
+    /// Pads each side with the given amount of pixels mirrored from the border, border included
+    Reflect(usize),
+}
+
+/// Padding added (before, after) along the rows and columns of an image.
+pub type PaddingAmounts = ((usize, usize), (usize, usize));
+
+impl ConvPaddings {
+    /// Returns the padding (before, after) along a dimension of `in_size` pixels.
+    pub fn amounts(&self, in_size: usize, kernel_size: usize, stride: usize) -> (usize, usize) {
+        match self {
+            Self::Valid => (0, 0),
+            Self::Zero(padding) | Self::Reflect(padding) => (*padding, *padding),
+            Self::Same => {
+                let out_size = in_size.div_ceil(stride);
+                let total = ((out_size - 1) * stride + kernel_size).saturating_sub(in_size);
+                (total / 2, total - total / 2)
+            }
+        }
+    }
+
+    /// Returns the size of the output along a dimension of `in_size` pixels.
+    pub fn out_size(&self, in_size: usize, kernel_size: usize, stride: usize) -> usize {
+        let (before, after) = self.amounts(in_size, kernel_size, stride);
+        let padded_size = in_size + before + after;
+        if padded_size < kernel_size {
+            panic!(
+                "Kernels of size {} are bigger than their padded input of size {}",
+                kernel_size, padded_size
+            );
+        }
+        (padded_size - kernel_size) / stride + 1
+    }
+
+    /// Pads the input for kernels of `(krows, kcols)` pixels and returns the padded input with the padding amounts.
+    pub fn pad(
+        &self,
+        input: &Image,
+        krows: usize,
+        kcols: usize,
+        stride: usize,
+    ) -> (Image, PaddingAmounts) {
+        let (in_rows, in_cols, _) = input.image_dims();
+        let amounts = (
+            self.amounts(in_rows, krows, stride),
+            self.amounts(in_cols, kcols, stride),
+        );
+        self.pad_with(input, amounts)
+    }
+
+    /// Pads a 1D input, whose images have a single column, along its length only.
+    pub fn pad_1d(&self, input: &Image, kernel_size: usize, stride: usize) -> (Image, PaddingAmounts) {
+        let amounts = (self.amounts(input.image_dims().0, kernel_size, stride), (0, 0));
+        self.pad_with(input, amounts)
+    }
+
+    fn pad_with(&self, input: &Image, amounts: PaddingAmounts) -> (Image, PaddingAmounts) {
+        let (in_rows, in_cols, _) = input.image_dims();
+        if amounts == ((0, 0), (0, 0)) {
+            return (input.clone(), amounts);
+        }
+        let mirror = matches!(self, Self::Reflect(_));
+        let too_big = |(before, after): (usize, usize), size: usize| before > size || after > size;
+        if mirror && (too_big(amounts.0, in_rows) || too_big(amounts.1, in_cols)) {
+            panic!(
+                "Cannot reflect {:?} pixels of a {}x{} image",
+                amounts, in_rows, in_cols
+            );
+        }
+        (input.pad(amounts.0, amounts.1, mirror), amounts)
+    }
+
+    /// Brings the gradient of the padded input back to the gradient of the input.
+    pub fn unpad(&self, input_gradient: &Image, amounts: PaddingAmounts) -> Image {
+        if amounts == ((0, 0), (0, 0)) {
+            return input_gradient.clone();
+        }
+        let mirror = matches!(self, Self::Reflect(_));
+        input_gradient.unpad(amounts.0, amounts.1, mirror)
+    }
+}
+
+/// Keeps one pixel every `stride` pixels along the rows and columns, starting from the first one.
+pub fn subsample(image: &Image, stride: usize) -> Image {
+    if stride == 1 {
+        return image.clone();
+    }
+    let (rows, cols, _) = image.image_dims();
+    image
+        .unwrap(1, 1, stride, stride, 0, 0)
+        .wrap((rows - 1) / stride + 1, (cols - 1) / stride + 1, 1, 1, 1, 1, 0, 0)
+}
+
+/// Inverse of `subsample`: spreads the pixels `stride` pixels apart on a `(rows, cols)` image of zeros.
+pub fn dilate(image: &Image, stride: usize, rows: usize, cols: usize) -> Image {
+    if stride == 1 {
+        return image.clone();
+    }
+    image
+        .unwrap(1, 1, 1, 1, 0, 0)
+        .wrap(rows, cols, 1, 1, stride, stride, 0, 0)
+}
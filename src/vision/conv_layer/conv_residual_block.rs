@@ -0,0 +1,149 @@
+use crate::{
+    gradient_clipping::GradientMut,
+    layer::{
+        join_learnable_parameters, split_learnable_parameters, DropoutLayer, GradientLayer,
+        LearnableLayer, ParameterableLayer,
+    },
+    linalg::Scalar,
+    vision::{conv_network::ConvNetworkLayer, image::Image, image::ImageTrait, image_layer::ImageLayer},
+};
+
+use super::dense_conv_layer::DenseConvLayer;
+
+// https://arxiv.org/pdf/1512.03385.pdf
+/// Adds the input of a sequence of convolutional layers to its output: `Y = F(X) + X`,
+/// or `Y = F(X) + K ⋆ X + B` when a projection is needed to match dimensions and channels.
+#[derive(Debug)]
+pub struct ConvResidualBlock {
+    layers: Vec<Box<dyn ConvNetworkLayer>>,
+    projection: Option<DenseConvLayer>,
+}
+
+impl ConvResidualBlock {
+    pub fn new(layers: Vec<Box<dyn ConvNetworkLayer>>, projection: Option<DenseConvLayer>) -> Self {
+        Self { layers, projection }
+    }
+}
+
+impl ImageLayer for ConvResidualBlock {
+    fn forward(&mut self, input: Image) -> Image {
+        let mut output = input.clone();
+        for layer in self.layers.iter_mut() {
+            output = layer.forward(output);
+        }
+
+        let shortcut = match &mut self.projection {
+            Some(projection) => projection.forward(input),
+            None => input,
+        };
+        output.component_add(&shortcut)
+    }
+
+    fn backward(&mut self, epoch: usize, output_gradient: Image) -> Image {
+        // the gradient flows through both the layers and the shortcut
+        let mut input_gradient = output_gradient.clone();
+        for layer in self.layers.iter_mut().rev() {
+            input_gradient = layer.backward(epoch, input_gradient);
+        }
+
+        let shortcut_gradient = match &mut self.projection {
+            Some(projection) => projection.backward(epoch, output_gradient),
+            None => output_gradient,
+        };
+        input_gradient.component_add(&shortcut_gradient)
+    }
+
+    fn regularization_loss(&self) -> Scalar {
+        self.layers.iter().map(|l| l.regularization_loss()).sum::<Scalar>()
+            + self.projection.as_ref().map_or(0., |p| p.regularization_loss())
+    }
+}
+
+impl GradientLayer for ConvResidualBlock {
+    fn gradients_mut(&mut self) -> Vec<GradientMut<'_>> {
+        let mut gradients: Vec<_> = self.layers.iter_mut().flat_map(|l| l.gradients_mut()).collect();
+        if let Some(projection) = &mut self.projection {
+            gradients.append(&mut projection.gradients_mut());
+        }
+        gradients
+    }
+
+    fn zero_grad(&mut self) {
+        self.layers.iter_mut().for_each(|l| l.zero_grad());
+        if let Some(projection) = &mut self.projection {
+            projection.zero_grad();
+        }
+    }
+
+    fn step(&mut self, epoch: usize) {
+        self.layers.iter_mut().for_each(|l| l.step(epoch));
+        if let Some(projection) = &mut self.projection {
+            projection.step(epoch);
+        }
+    }
+}
+
+impl LearnableLayer for ConvResidualBlock {
+    // returns each layer's params, then the projection's if any, each preceded by its amount of columns
+    fn get_learnable_parameters(&self) -> Vec<Vec<Scalar>> {
+        let mut layers_params: Vec<_> = self
+            .layers
+            .iter()
+            .map(|layer| match layer.as_learnable_layer() {
+                Some(l) => l.get_learnable_parameters(),
+                None => Vec::new(),
+            })
+            .collect();
+        if let Some(projection) = &self.projection {
+            layers_params.push(projection.get_learnable_parameters());
+        }
+        join_learnable_parameters(layers_params)
+    }
+
+    fn set_learnable_parameters(&mut self, params_matrix: &Vec<Vec<Scalar>>) {
+        let mut layers_params = split_learnable_parameters(params_matrix).into_iter();
+        for layer in self.layers.iter_mut() {
+            let layer_params = layers_params.next().unwrap();
+            if let Some(l) = layer.as_learnable_layer_mut() {
+                l.set_learnable_parameters(&layer_params);
+            }
+        }
+        if let Some(projection) = &mut self.projection {
+            projection.set_learnable_parameters(&layers_params.next().unwrap());
+        }
+    }
+}
+
+impl DropoutLayer for ConvResidualBlock {
+    fn enable_dropout(&mut self) {
+        self.layers.iter_mut().for_each(|l| {
+            if let Some(l) = l.as_dropout_layer() {
+                l.enable_dropout()
+            }
+        });
+    }
+
+    fn disable_dropout(&mut self) {
+        self.layers.iter_mut().for_each(|l| {
+            if let Some(l) = l.as_dropout_layer() {
+                l.disable_dropout()
+            }
+        });
+    }
+}
+
+impl ParameterableLayer for ConvResidualBlock {
+    fn as_learnable_layer(&self) -> Option<&dyn LearnableLayer> {
+        Some(self)
+    }
+
+    fn as_learnable_layer_mut(&mut self) -> Option<&mut dyn LearnableLayer> {
+        Some(self)
+    }
+
+    fn as_dropout_layer(&mut self) -> Option<&mut dyn DropoutLayer> {
+        Some(self)
+    }
+}
+
+impl ConvNetworkLayer for ConvResidualBlock {}
@@ -18,3 +18,7 @@ pub fn default_biases_optimizer() -> ConvOptimizers {
 pub fn default_kernels_optimizer() -> ConvOptimizers {
     conv_sgd()
 }
+
+pub fn default_stride() -> usize {
+    1
+}
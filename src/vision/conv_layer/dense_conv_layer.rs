@@ -1,31 +1,44 @@
 use crate::{
-    layer::LearnableLayer,
+    gradient_clipping::GradientMut,
+    layer::{GradientLayer, LearnableLayer},
     linalg::{Matrix, MatrixTrait, Scalar},
+    regularization::Regularization,
     vision::{
         conv_initializers::ConvInitializers, conv_optimizer::ConvOptimizers, image::Image,
         image::ImageTrait,
     },
 };
 
-use crate::vision::image_layer::ImageLayer;
+use crate::vision::image_layer::{accumulate_image_gradient, ImageLayer};
 
-use super::ConvLayer;
+use super::{biases_from_params, biases_gradient, conv_padding::{dilate, ConvPaddings, PaddingAmounts}, ConvLayer};
 
 #[derive(Debug)]
 pub struct DenseConvLayer {
     pub kernels: Image,
     biases: Image,
+    // padded input
     input: Option<Image>,
+    stride: usize,
+    padding: ConvPaddings,
+    padding_amounts: PaddingAmounts,
     kernels_optimizer: ConvOptimizers,
     biases_optimizer: ConvOptimizers,
+    // accumulated by backward, applied by step
+    kernels_gradient: Option<Image>,
+    biases_gradient: Option<Image>,
+    regularization: Option<Regularization>,
 }
 
 impl DenseConvLayer {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         nrow: usize,
         ncol: usize,
         nchan: usize,
         nkern: usize,
+        stride: usize,
+        padding: ConvPaddings,
         kernels_initializer: ConvInitializers,
         biases_initializer: ConvInitializers,
         kernels_optimizer: ConvOptimizers,
@@ -35,8 +48,21 @@ impl DenseConvLayer {
             kernels: kernels_initializer.gen_image(nrow, ncol, nchan, nkern),
             biases: biases_initializer.gen_image(1, 1, nkern, 1),
             input: None,
+            stride,
+            padding,
+            padding_amounts: ((0, 0), (0, 0)),
             kernels_optimizer,
             biases_optimizer,
+            kernels_gradient: None,
+            biases_gradient: None,
+            regularization: None,
+        }
+    }
+
+    pub fn with_regularization(self, regularization: Regularization) -> Self {
+        Self {
+            regularization: Some(regularization),
+            ..self
         }
     }
 
@@ -46,9 +72,11 @@ impl DenseConvLayer {
         krows: usize,
         kcols: usize,
         kchans: usize,
+        stride: usize,
+        padding: ConvPaddings,
     ) -> (usize, usize, usize) {
-        let out_rows = in_rows - krows + 1;
-        let out_cols = in_cols - kcols + 1;
+        let out_rows = padding.out_size(in_rows, krows, stride);
+        let out_cols = padding.out_size(in_cols, kcols, stride);
         let out_chans = kchans;
         (out_rows, out_cols, out_chans)
     }
@@ -56,23 +84,29 @@ impl DenseConvLayer {
 
 impl ImageLayer for DenseConvLayer {
     fn forward(&mut self, input: Image) -> Image {
-        let res = input
-            .cross_correlate(&self.kernels);
+        let (krows, kcols, _) = self.kernels.image_dims();
+        let (input, padding_amounts) = self.padding.pad(&input, krows, kcols, self.stride);
+        let res = input.cross_correlate_strided(&self.kernels, self.stride);
 
-        if self.biases.image_dims() != res.image_dims() {
-            self.biases = self.biases.tile(res.image_dims().0, res.image_dims().1, 1, 1);
-        }
-
-        let res = res
-            .component_add(&self.biases);
+        let (rows, cols, _) = res.image_dims();
+        let res = res.component_add(&self.biases.tile(rows, cols, 1, 1));
 
         self.input = Some(input);
+        self.padding_amounts = padding_amounts;
         res
     }
 
-    fn backward(&mut self, epoch: usize, output_gradient: Image) -> Image {
+    fn backward(&mut self, _epoch: usize, output_gradient: Image) -> Image {
         let input = self.input.as_ref().unwrap();
-        
+        let (krows, kcols, _) = self.kernels.image_dims();
+        // gradient of the stride 1 cross-correlation of the padded input
+        let output_gradient = dilate(
+            &output_gradient,
+            self.stride,
+            input.image_dims().0 - krows + 1,
+            input.image_dims().1 - kcols + 1,
+        );
+
         let mut input_grad_channels = vec![];
         for i in 0..input.channels() {
             let mut sum = Image::zeros(input.image_dims().0, input.image_dims().1, 1, input.samples());
@@ -84,7 +118,9 @@ impl ImageLayer for DenseConvLayer {
             }
             input_grad_channels.push(sum);
         }
-        let input_grad = Image::join_channels(input_grad_channels);
+        let input_grad = self
+            .padding
+            .unpad(&Image::join_channels(input_grad_channels), self.padding_amounts);
 
         let mut kern_grad_samples = vec![];
         for k in 0..self.kernels.samples() {
@@ -98,24 +134,64 @@ impl ImageLayer for DenseConvLayer {
             let kern_grad_sample = Image::join_channels(kern_grad_channels);
             kern_grad_samples.push(kern_grad_sample);
         }
-        let kern_grad = Image::join_samples(kern_grad_samples);
-
-        let mut biases_grad_channels = vec![];
-        for c in 0..self.biases.channels() {
-            let channel = output_gradient.get_channel_across_samples(c);
-            let channel = channel.sum_samples();
-            biases_grad_channels.push(channel);
+        let mut kern_grad = Image::join_samples(kern_grad_samples);
+
+        let mut biases_grad = biases_gradient(&output_gradient);
+
+        if let Some(regularization) = &self.regularization {
+            // the penalty is added once to the loss averaged over the samples, whose gradients are summed
+            let samples = input.samples() as Scalar;
+            kern_grad = kern_grad
+                .component_add(&regularization.image_gradient(&self.kernels).scalar_mul(samples));
+            if regularization.biases {
+                biases_grad = biases_grad
+                    .component_add(&regularization.image_gradient(&self.biases).scalar_mul(samples));
+            }
         }
-        let biases_grad = Image::join_channels(biases_grad_channels);
-
-        self.kernels = self
-            .kernels_optimizer
-            .update_parameters(epoch, &self.kernels, &kern_grad);
-        self.biases = self
-            .biases_optimizer
-            .update_parameters(epoch, &self.biases, &biases_grad);
+
+        accumulate_image_gradient(&mut self.kernels_gradient, kern_grad);
+        accumulate_image_gradient(&mut self.biases_gradient, biases_grad);
         input_grad
     }
+
+    fn regularization_loss(&self) -> Scalar {
+        match &self.regularization {
+            Some(regularization) if regularization.biases => {
+                regularization.image_penalty(&self.kernels)
+                    + regularization.image_penalty(&self.biases)
+            }
+            Some(regularization) => regularization.image_penalty(&self.kernels),
+            None => 0.0,
+        }
+    }
+}
+
+impl GradientLayer for DenseConvLayer {
+    fn gradients_mut(&mut self) -> Vec<GradientMut<'_>> {
+        self.kernels_gradient
+            .iter_mut()
+            .chain(self.biases_gradient.iter_mut())
+            .map(GradientMut::Image)
+            .collect()
+    }
+
+    fn zero_grad(&mut self) {
+        self.kernels_gradient = None;
+        self.biases_gradient = None;
+    }
+
+    fn step(&mut self, epoch: usize) {
+        if let Some(kernels_gradient) = self.kernels_gradient.take() {
+            self.kernels = self
+                .kernels_optimizer
+                .update_parameters(epoch, &self.kernels, &kernels_gradient);
+        }
+        if let Some(biases_gradient) = self.biases_gradient.take() {
+            self.biases = self
+                .biases_optimizer
+                .update_parameters(epoch, &self.biases, &biases_gradient);
+        }
+    }
 }
 
 impl LearnableLayer for DenseConvLayer {
@@ -128,14 +204,13 @@ impl LearnableLayer for DenseConvLayer {
     fn set_learnable_parameters(&mut self, params_matrix: &Vec<Vec<Scalar>>) {
         let mut kernels = params_matrix.clone();
         let biases = kernels.pop().unwrap();
-        self.kernels = Image::from_samples(
+        let (krows, kcols, kchans) = self.kernels.image_dims();
+        self.kernels = Image::from_samples_with_dims(
             &Matrix::from_column_leading_vector2(&kernels),
-            self.kernels.channels(),
-        );
-        self.biases = Image::from_samples(
-            &Matrix::from_column_vector(&biases),
-            self.biases.channels(),
+            (krows, kcols),
+            kchans,
         );
+        self.biases = biases_from_params(&biases, self.biases.channels());
     }
 }
 
@@ -1,5 +1,6 @@
 use crate::{
-    layer::LearnableLayer,
+    gradient_clipping::GradientMut,
+    layer::{GradientLayer, LearnableLayer},
     linalg::{Matrix, MatrixTrait, Scalar},
     vision::{
         conv_initializers::ConvInitializers, conv_optimizer::ConvOptimizers, image::Image,
@@ -7,24 +8,34 @@ use crate::{
     },
 };
 
-use crate::vision::image_layer::ImageLayer;
+use crate::vision::image_layer::{accumulate_image_gradient, ImageLayer};
 
-use super::ConvLayer;
+use super::{biases_from_params, biases_gradient, conv_padding::{dilate, ConvPaddings, PaddingAmounts}, ConvLayer};
 
 #[derive(Debug)]
 pub struct DirectConvLayer {
     pub kernels: Image,
     biases: Image,
+    // padded input
     input: Option<Image>,
+    stride: usize,
+    padding: ConvPaddings,
+    padding_amounts: PaddingAmounts,
     kernels_optimizer: ConvOptimizers,
     biases_optimizer: ConvOptimizers,
+    // accumulated by backward, applied by step
+    kernels_gradient: Option<Image>,
+    biases_gradient: Option<Image>,
 }
 
 impl DirectConvLayer {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         krows: usize,
         kcols: usize,
         in_chans: usize,
+        stride: usize,
+        padding: ConvPaddings,
         kernels_initializer: ConvInitializers,
         biases_initializer: ConvInitializers,
         kernels_optimizer: ConvOptimizers,
@@ -34,8 +45,13 @@ impl DirectConvLayer {
             kernels: kernels_initializer.gen_image(krows, kcols, in_chans, 1),
             biases: biases_initializer.gen_image(1, 1, in_chans, 1),
             input: None,
+            stride,
+            padding,
+            padding_amounts: ((0, 0), (0, 0)),
             kernels_optimizer,
             biases_optimizer,
+            kernels_gradient: None,
+            biases_gradient: None,
         }
     }
 
@@ -45,41 +61,50 @@ impl DirectConvLayer {
         in_chans: usize,
         krows: usize,
         kcols: usize,
+        stride: usize,
+        padding: ConvPaddings,
     ) -> (usize, usize, usize) {
-        let out_rows = in_rows - krows + 1;
-        let out_cols = in_cols - kcols + 1;
+        let out_rows = padding.out_size(in_rows, krows, stride);
+        let out_cols = padding.out_size(in_cols, kcols, stride);
         (out_rows, out_cols, in_chans)
     }
 }
 
 impl ImageLayer for DirectConvLayer {
     fn forward(&mut self, input: Image) -> Image {
+        let (krows, kcols, _) = self.kernels.image_dims();
+        let (input, padding_amounts) = self.padding.pad(&input, krows, kcols, self.stride);
+
         let mut channels = vec![];
         for c in 0..input.channels() {
             let channel = input.get_channel_across_samples(c);
 
             let kernel = self.kernels.get_channel(c);
 
-            let correlated = channel.cross_correlate(&kernel);
-
-            if self.biases.image_dims().0 != correlated.image_dims().0 {
-                self.biases =
-                    self.biases
-                        .tile(correlated.image_dims().0, correlated.image_dims().1, 1, 1);
-            }
+            let correlated = channel.cross_correlate_strided(&kernel, self.stride);
 
-            let bias = self.biases.get_channel(c);
+            let (rows, cols, _) = correlated.image_dims();
+            let bias = self.biases.get_channel(c).tile(rows, cols, 1, 1);
 
             let result_channel = correlated.component_add(&bias);
             channels.push(result_channel);
         }
         let res = Image::join_channels(channels);
         self.input = Some(input);
+        self.padding_amounts = padding_amounts;
         res
     }
 
-    fn backward(&mut self, epoch: usize, output_gradient: Image) -> Image {
+    fn backward(&mut self, _epoch: usize, output_gradient: Image) -> Image {
         let input = self.input.as_ref().unwrap();
+        let (krows, kcols, _) = self.kernels.image_dims();
+        // gradient of the stride 1 cross-correlation of the padded input
+        let output_gradient = dilate(
+            &output_gradient,
+            self.stride,
+            input.image_dims().0 - krows + 1,
+            input.image_dims().1 - kcols + 1,
+        );
 
         let mut input_grad_channels = vec![];
         for i in 0..input.channels() {
@@ -88,7 +113,9 @@ impl ImageLayer for DirectConvLayer {
             let correlated = output_grad_i.convolve_full(&kernel);
             input_grad_channels.push(correlated);
         }
-        let input_grad = Image::join_channels(input_grad_channels);
+        let input_grad = self
+            .padding
+            .unpad(&Image::join_channels(input_grad_channels), self.padding_amounts);
 
         let mut kern_grad_channels = vec![];
         for i in 0..input.channels() {
@@ -99,24 +126,42 @@ impl ImageLayer for DirectConvLayer {
         }
         let kern_grad = Image::join_channels(kern_grad_channels);
 
-        let mut biases_grad_channels = vec![];
-        for c in 0..self.biases.channels() {
-            let channel = output_gradient.get_channel_across_samples(c);
-            let channel = channel.sum_samples();
-            biases_grad_channels.push(channel);
-        }
-        let biases_grad = Image::join_channels(biases_grad_channels);
-
-        self.kernels = self
-            .kernels_optimizer
-            .update_parameters(epoch, &self.kernels, &kern_grad);
-        self.biases = self
-            .biases_optimizer
-            .update_parameters(epoch, &self.biases, &biases_grad);
+        let biases_grad = biases_gradient(&output_gradient);
+
+        accumulate_image_gradient(&mut self.kernels_gradient, kern_grad);
+        accumulate_image_gradient(&mut self.biases_gradient, biases_grad);
         input_grad
     }
 }
 
+impl GradientLayer for DirectConvLayer {
+    fn gradients_mut(&mut self) -> Vec<GradientMut<'_>> {
+        self.kernels_gradient
+            .iter_mut()
+            .chain(self.biases_gradient.iter_mut())
+            .map(GradientMut::Image)
+            .collect()
+    }
+
+    fn zero_grad(&mut self) {
+        self.kernels_gradient = None;
+        self.biases_gradient = None;
+    }
+
+    fn step(&mut self, epoch: usize) {
+        if let Some(kernels_gradient) = self.kernels_gradient.take() {
+            self.kernels = self
+                .kernels_optimizer
+                .update_parameters(epoch, &self.kernels, &kernels_gradient);
+        }
+        if let Some(biases_gradient) = self.biases_gradient.take() {
+            self.biases = self
+                .biases_optimizer
+                .update_parameters(epoch, &self.biases, &biases_gradient);
+        }
+    }
+}
+
 impl LearnableLayer for DirectConvLayer {
     fn get_learnable_parameters(&self) -> Vec<Vec<Scalar>> {
         let mut params = self.kernels.flatten().get_data_col_leading();
@@ -127,12 +172,13 @@ impl LearnableLayer for DirectConvLayer {
     fn set_learnable_parameters(&mut self, params_matrix: &Vec<Vec<Scalar>>) {
         let mut kernels = params_matrix.clone();
         let biases = kernels.pop().unwrap();
-        self.kernels = Image::from_samples(
+        let (krows, kcols, kchans) = self.kernels.image_dims();
+        self.kernels = Image::from_samples_with_dims(
             &Matrix::from_column_leading_vector2(&kernels),
-            self.kernels.channels(),
+            (krows, kcols),
+            kchans,
         );
-        self.biases =
-            Image::from_samples(&Matrix::from_column_vector(&biases), self.biases.channels());
+        self.biases = biases_from_params(&biases, self.biases.channels());
     }
 }
 
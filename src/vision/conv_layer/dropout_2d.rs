@@ -0,0 +1,103 @@
+use std::cmp::Ordering;
+
+use rand::Rng;
+
+use crate::{
+    layer::{DropoutLayer, GradientLayer, LearnableLayer, ParameterableLayer},
+    linalg::Scalar,
+    vision::{
+        image::Image,
+        image::ImageTrait, conv_network::ConvNetworkLayer,
+    },
+};
+
+use crate::vision::image_layer::ImageLayer;
+
+// https://arxiv.org/pdf/1411.4280.pdf
+/// Spatial dropout: zeroes whole channels with probability `rate` during training,
+/// scaling the kept channels by `1 / (1 - rate)` (inverted dropout).
+#[derive(Debug)]
+pub struct Dropout2d {
+    pub rate: Scalar,
+    enabled: bool,
+    // scaled mask of the last training forward pass
+    mask: Option<Image>,
+}
+
+impl Dropout2d {
+    pub fn new(rate: Scalar) -> Self {
+        Self {
+            rate,
+            enabled: false,
+            mask: None,
+        }
+    }
+
+    fn generate_dropout_mask(&self, channels: usize, samples: usize) -> Image {
+        let mut rng = rand::thread_rng();
+        Image::from_fn(1, 1, channels, samples, |_, _, _, _| {
+            if rng
+                .gen_range((0.0 as Scalar)..(1.0 as Scalar))
+                .total_cmp(&self.rate)
+                == Ordering::Greater
+            {
+                1.0 / (1.0 - self.rate)
+            } else {
+                0.0
+            }
+        })
+    }
+}
+
+impl ImageLayer for Dropout2d {
+    fn forward(&mut self, input: Image) -> Image {
+        if self.enabled {
+            let (rows, cols, channels) = input.image_dims();
+            let mask = self
+                .generate_dropout_mask(channels, input.samples())
+                .tile(rows, cols, 1, 1);
+            let output = input.component_mul(&mask);
+            self.mask = Some(mask);
+            output
+        } else {
+            self.mask = None;
+            input
+        }
+    }
+
+    fn backward(&mut self, _epoch: usize, output_gradient: Image) -> Image {
+        match &self.mask {
+            Some(mask) => output_gradient.component_mul(mask),
+            None => output_gradient,
+        }
+    }
+}
+
+impl GradientLayer for Dropout2d {}
+
+impl DropoutLayer for Dropout2d {
+    fn enable_dropout(&mut self) {
+        self.enabled = true;
+    }
+
+    fn disable_dropout(&mut self) {
+        self.enabled = false;
+    }
+}
+
+impl ParameterableLayer for Dropout2d {
+    fn as_learnable_layer(&self) -> Option<&dyn LearnableLayer> {
+        None
+    }
+
+    fn as_learnable_layer_mut(&mut self) -> Option<&mut dyn LearnableLayer> {
+        None
+    }
+
+    fn as_dropout_layer(&mut self) -> Option<&mut dyn DropoutLayer> {
+        Some(self)
+    }
+}
+
+impl ConvNetworkLayer for Dropout2d {
+}
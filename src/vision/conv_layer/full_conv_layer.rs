@@ -2,7 +2,8 @@ use std::cmp::Ordering;
 
 use rand::Rng;
 
-use crate::layer::{DropoutLayer, LearnableLayer, ParameterableLayer};
+use crate::gradient_clipping::GradientMut;
+use crate::layer::{DropoutLayer, GradientLayer, LearnableLayer, ParameterableLayer};
 use crate::linalg::Scalar;
 use crate::vision::conv_network::ConvNetworkLayer;
 
@@ -68,24 +69,18 @@ impl FullConvLayer {
 
 impl ImageLayer for FullConvLayer {
     fn forward(&mut self, mut input: Image) -> Image {
-        let output = if self.dropout_enabled {
-            if let Some((mask, _)) = self.generate_dropout_mask(input.image_dims(), input.samples())
+        // inverted dropout: kept inputs are scaled up while training so that inference uses the kernels as is
+        self.mask = None;
+        if self.dropout_enabled {
+            if let Some((mask, dropout_rate)) = self.generate_dropout_mask(input.image_dims(), input.samples())
             {
+                let mask = mask.scalar_div(1.0 - dropout_rate);
                 input = input.component_mul(&mask);
                 self.mask = Some(mask);
             };
-            self.conv.forward(input)
-        } else {
-            if let Some(dropout_rate) = self.dropout_rate {
-                self.conv.scale_kernels(1.0 - dropout_rate);
-                let output = self.conv.forward(input);
-                self.conv.scale_kernels(1.0 / (1.0 - dropout_rate));
-                output
-            } else {
-                self.conv.forward(input)
-            }
-        };
+        }
 
+        let output = self.conv.forward(input);
         self.activation.forward(output)
     }
 
@@ -99,15 +94,46 @@ impl ImageLayer for FullConvLayer {
             input_gradient
         }
     }
+
+    fn regularization_loss(&self) -> Scalar {
+        self.conv.regularization_loss()
+    }
+}
+
+impl GradientLayer for FullConvLayer {
+    fn gradients_mut(&mut self) -> Vec<GradientMut<'_>> {
+        let mut gradients = self.conv.gradients_mut();
+        gradients.append(&mut self.activation.gradients_mut());
+        gradients
+    }
+
+    fn zero_grad(&mut self) {
+        self.conv.zero_grad();
+        self.activation.zero_grad();
+    }
+
+    fn step(&mut self, epoch: usize) {
+        self.conv.step(epoch);
+        self.activation.step(epoch);
+    }
 }
 
 impl LearnableLayer for FullConvLayer {
+    // returns the convolution's params, followed by the activation's slope column if it is learnable (PReLU)
     fn get_learnable_parameters(&self) -> Vec<Vec<Scalar>> {
-        self.conv.get_learnable_parameters()
+        let mut params = self.conv.get_learnable_parameters();
+        params.append(&mut self.activation.get_learnable_parameters());
+        params
     }
 
     fn set_learnable_parameters(&mut self, params_matrix: &Vec<Vec<Scalar>>) {
-        self.conv.set_learnable_parameters(params_matrix)
+        match (self.activation.get_slope(), params_matrix.split_last()) {
+            (Some(_), Some((slope, conv_params))) => {
+                self.conv.set_learnable_parameters(&conv_params.to_vec());
+                self.activation.set_learnable_parameters(&vec![slope.clone()]);
+            }
+            _ => self.conv.set_learnable_parameters(params_matrix),
+        }
     }
 }
 
@@ -0,0 +1,139 @@
+use crate::{
+    layer::{GradientLayer, LearnableLayer, ParameterableLayer},
+    linalg::Scalar,
+    vision::{
+        image::Image,
+        image::ImageTrait, conv_network::ConvNetworkLayer,
+    },
+};
+
+use crate::vision::image_layer::ImageLayer;
+
+/// Reduces each channel to the mean of its pixels, outputting 1x1 images.
+#[derive(Debug)]
+pub struct GlobalAvgPoolingLayer {
+    input_dims: (usize, usize),
+}
+
+impl GlobalAvgPoolingLayer {
+    pub fn new() -> Self {
+        Self {
+            input_dims: (0, 0),
+        }
+    }
+}
+
+impl Default for GlobalAvgPoolingLayer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ImageLayer for GlobalAvgPoolingLayer {
+    fn forward(&mut self, input: Image) -> Image {
+        let (rows, cols, _) = input.image_dims();
+        self.input_dims = (rows, cols);
+
+        input
+            .unwrap(rows, cols, rows, cols, 0, 0)
+            .mean_along(0)
+            .wrap(1, 1, 1, 1, 1, 1, 0, 0)
+    }
+
+    fn backward(&mut self, _epoch: usize, output_gradient: Image) -> Image {
+        let (rows, cols) = self.input_dims;
+
+        output_gradient
+            .scalar_div((rows * cols) as Scalar)
+            .unwrap(1, 1, 1, 1, 0, 0)
+            .tile(rows * cols, 1, 1, 1)
+            .wrap(rows, cols, rows, cols, rows, cols, 0, 0)
+    }
+}
+
+impl GradientLayer for GlobalAvgPoolingLayer {}
+
+impl ParameterableLayer for GlobalAvgPoolingLayer {
+    fn as_learnable_layer(&self) -> Option<&dyn LearnableLayer> {
+        None
+    }
+
+    fn as_learnable_layer_mut(&mut self) -> Option<&mut dyn LearnableLayer> {
+        None
+    }
+
+    fn as_dropout_layer(&mut self) -> Option<&mut dyn crate::layer::DropoutLayer> {
+        None
+    }
+}
+
+impl ConvNetworkLayer for GlobalAvgPoolingLayer {
+}
+
+/// Reduces each channel to its maximum pixel, outputting 1x1 images.
+#[derive(Debug)]
+pub struct GlobalMaxPoolingLayer {
+    // 1 at the winning pixel of each channel, 0 elsewhere
+    argmax_mask: Option<Image>,
+    input_dims: (usize, usize),
+}
+
+impl GlobalMaxPoolingLayer {
+    pub fn new() -> Self {
+        Self {
+            argmax_mask: None,
+            input_dims: (0, 0),
+        }
+    }
+}
+
+impl Default for GlobalMaxPoolingLayer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ImageLayer for GlobalMaxPoolingLayer {
+    fn forward(&mut self, input: Image) -> Image {
+        let (rows, cols, _) = input.image_dims();
+        let unwrapped = input.unwrap(rows, cols, rows, cols, 0, 0);
+
+        self.argmax_mask = Some(unwrapped.argmax_mask_along(0));
+        self.input_dims = (rows, cols);
+
+        unwrapped.max_along(0).wrap(1, 1, 1, 1, 1, 1, 0, 0)
+    }
+
+    fn backward(&mut self, _epoch: usize, output_gradient: Image) -> Image {
+        let argmax_mask = self
+            .argmax_mask
+            .as_ref()
+            .expect("Global max pooling backward called before forward");
+        let (rows, cols) = self.input_dims;
+
+        output_gradient
+            .unwrap(1, 1, 1, 1, 0, 0)
+            .tile(rows * cols, 1, 1, 1)
+            .component_mul(argmax_mask)
+            .wrap(rows, cols, rows, cols, rows, cols, 0, 0)
+    }
+}
+
+impl GradientLayer for GlobalMaxPoolingLayer {}
+
+impl ParameterableLayer for GlobalMaxPoolingLayer {
+    fn as_learnable_layer(&self) -> Option<&dyn LearnableLayer> {
+        None
+    }
+
+    fn as_learnable_layer_mut(&mut self) -> Option<&mut dyn LearnableLayer> {
+        None
+    }
+
+    fn as_dropout_layer(&mut self) -> Option<&mut dyn crate::layer::DropoutLayer> {
+        None
+    }
+}
+
+impl ConvNetworkLayer for GlobalMaxPoolingLayer {
+}
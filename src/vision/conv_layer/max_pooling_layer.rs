@@ -0,0 +1,109 @@
+use crate::{
+    layer::{GradientLayer, LearnableLayer, ParameterableLayer},
+    linalg::Scalar,
+    vision::{
+        image::Image,
+        image::ImageTrait, conv_network::ConvNetworkLayer,
+    },
+};
+
+use crate::vision::image_layer::ImageLayer;
+
+/// Keeps the maximum of each `window x window` patch, patches being `stride` pixels apart
+/// on an input padded with `padding` pixels on each side. Padded pixels never win.
+#[derive(Debug)]
+pub struct MaxPoolingLayer {
+    pub window: usize,
+    pub stride: usize,
+    pub padding: usize,
+    // 1 at the winning pixel of each unwrapped patch, 0 elsewhere
+    argmax_mask: Option<Image>,
+    input_dims: (usize, usize),
+}
+
+impl MaxPoolingLayer {
+    pub fn new(
+        window: usize,
+        stride: usize,
+        padding: usize,
+    ) -> Self {
+        Self {
+            window,
+            stride,
+            padding,
+            argmax_mask: None,
+            input_dims: (0, 0),
+        }
+    }
+
+    /// Returns the output (rows, columns) for an input of `(rows, columns)` pixels.
+    pub fn out_img_dims(&self, rows: usize, cols: usize) -> (usize, usize) {
+        let out = |size: usize| {
+            if size + 2 * self.padding < self.window {
+                panic!(
+                    "Max pooling window of {} is bigger than its padded input of {}",
+                    self.window,
+                    size + 2 * self.padding
+                );
+            }
+            (size + 2 * self.padding - self.window) / self.stride + 1
+        };
+        (out(rows), out(cols))
+    }
+}
+
+impl ImageLayer for MaxPoolingLayer {
+    fn forward(&mut self, input: Image) -> Image {
+        let (rows, cols, channels) = input.image_dims();
+        let (w, s, p) = (self.window, self.stride, self.padding);
+
+        let mut unwrapped = input.unwrap(w, w, s, s, p, p);
+        if p > 0 {
+            // pushes the zeros of the padding below any pixel's value
+            let padding_mask = Image::constant(rows, cols, channels, input.samples(), 1.0)
+                .unwrap(w, w, s, s, p, p)
+                .scalar_sub(1.0)
+                .scalar_mul(Scalar::MAX);
+            unwrapped = unwrapped.component_add(&padding_mask);
+        }
+
+        self.argmax_mask = Some(unwrapped.argmax_mask_along(0));
+        self.input_dims = (rows, cols);
+
+        let (out_rows, out_cols) = self.out_img_dims(rows, cols);
+        unwrapped
+            .max_along(0)
+            .wrap(out_rows, out_cols, 1, 1, 1, 1, 0, 0)
+    }
+
+    fn backward(&mut self, _epoch: usize, output_gradient: Image) -> Image {
+        let argmax_mask = self.argmax_mask.as_ref().expect("Max pooling backward called before forward");
+        let (w, s, p) = (self.window, self.stride, self.padding);
+
+        // each patch's gradient only flows to its winning pixel, overlapping patches summing up
+        output_gradient
+            .unwrap(1, 1, 1, 1, 0, 0)
+            .tile(w * w, 1, 1, 1)
+            .component_mul(argmax_mask)
+            .wrap(self.input_dims.0, self.input_dims.1, w, w, s, s, p, p)
+    }
+}
+
+impl GradientLayer for MaxPoolingLayer {}
+
+impl ParameterableLayer for MaxPoolingLayer {
+    fn as_learnable_layer(&self) -> Option<&dyn LearnableLayer> {
+        None
+    }
+
+    fn as_learnable_layer_mut(&mut self) -> Option<&mut dyn LearnableLayer> {
+        None
+    }
+
+    fn as_dropout_layer(&mut self) -> Option<&mut dyn crate::layer::DropoutLayer> {
+        None
+    }
+}
+
+impl ConvNetworkLayer for MaxPoolingLayer {
+}
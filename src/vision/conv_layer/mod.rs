@@ -1,15 +1,51 @@
 use std::fmt::Debug;
 
-use crate::{layer::LearnableLayer, linalg::Scalar};
+use crate::{layer::LearnableLayer, linalg::{Matrix, MatrixTrait, Scalar}};
 
-use super::{image::Image, image_layer::ImageLayer};
+use super::{image::{Image, ImageTrait}, image_layer::ImageLayer};
 
+pub mod autodiff_conv_layer;
+pub mod batch_norm_2d_layer;
+pub mod conv1d_layer;
+pub mod conv_padding;
+pub mod conv_residual_block;
 pub mod defaults;
 pub mod dense_conv_layer;
 pub mod direct_conv_layer;
+pub mod dropout_2d;
 pub mod avg_pooling_layer;
+pub mod max_pooling_layer;
+pub mod global_pooling_layer;
+pub mod pool1d_layer;
 pub mod full_conv_layer;
+pub mod separable_conv_layer;
+pub mod transposed_conv_layer;
+pub mod upsampling_layer;
 
 pub trait ConvLayer: ImageLayer + LearnableLayer + Send + Debug {
     fn scale_kernels(&mut self, scale: Scalar);
 }
+
+/// Biases are shared by all the pixels of their channel and stored as a 1x1 image.
+///
+/// Panics when there isn't one bias per channel, like with biases saved tiled to the output's pixels.
+pub(crate) fn biases_from_params(params: &[Scalar], channels: usize) -> Image {
+    assert_eq!(
+        params.len(),
+        channels,
+        "Expected one bias per channel, got {} biases for {} channels",
+        params.len(),
+        channels
+    );
+    Image::from_samples_with_dims(&Matrix::from_column_vector(&params.to_vec()), (1, 1), channels)
+}
+
+/// Gradient of the biases shared by all the pixels of their channel, summed over the pixels and samples.
+pub(crate) fn biases_gradient(output_gradient: &Image) -> Image {
+    let (rows, cols, _) = output_gradient.image_dims();
+    output_gradient
+        .sum_samples()
+        .mean_along(0)
+        .mean_along(1)
+        .scalar_mul((rows * cols) as Scalar)
+}
@@ -0,0 +1,166 @@
+use crate::{
+    layer::{GradientLayer, LearnableLayer, ParameterableLayer},
+    linalg::Scalar,
+    vision::{
+        image::Image,
+        image::ImageTrait, conv_network::ConvNetworkLayer,
+    },
+};
+
+use crate::vision::image_layer::ImageLayer;
+
+/// Keeps the maximum of each `window` long patch of 1D signals stored as images with a single column,
+/// patches being `stride` values apart on an input padded with `padding` values on each end.
+#[derive(Debug)]
+pub struct MaxPool1dLayer {
+    pub window: usize,
+    pub stride: usize,
+    pub padding: usize,
+    // 1 at the winning value of each unwrapped patch, 0 elsewhere
+    argmax_mask: Option<Image>,
+    input_length: usize,
+}
+
+impl MaxPool1dLayer {
+    pub fn new(
+        window: usize,
+        stride: usize,
+        padding: usize,
+    ) -> Self {
+        Self {
+            window,
+            stride,
+            padding,
+            argmax_mask: None,
+            input_length: 0,
+        }
+    }
+
+    /// Returns the output length for an input of `length` values.
+    pub fn out_length(&self, length: usize) -> usize {
+        if length + 2 * self.padding < self.window {
+            panic!(
+                "Max pooling window of {} is bigger than its padded input of {}",
+                self.window,
+                length + 2 * self.padding
+            );
+        }
+        (length + 2 * self.padding - self.window) / self.stride + 1
+    }
+}
+
+impl ImageLayer for MaxPool1dLayer {
+    fn forward(&mut self, input: Image) -> Image {
+        let (length, _, channels) = input.image_dims();
+        let (w, s, p) = (self.window, self.stride, self.padding);
+
+        let mut unwrapped = input.unwrap(w, 1, s, 1, p, 0);
+        if p > 0 {
+            // pushes the zeros of the padding below any value
+            let padding_mask = Image::constant(length, 1, channels, input.samples(), 1.0)
+                .unwrap(w, 1, s, 1, p, 0)
+                .scalar_sub(1.0)
+                .scalar_mul(Scalar::MAX);
+            unwrapped = unwrapped.component_add(&padding_mask);
+        }
+
+        self.argmax_mask = Some(unwrapped.argmax_mask_along(0));
+        self.input_length = length;
+
+        unwrapped
+            .max_along(0)
+            .wrap(self.out_length(length), 1, 1, 1, 1, 1, 0, 0)
+    }
+
+    fn backward(&mut self, _epoch: usize, output_gradient: Image) -> Image {
+        let argmax_mask = self.argmax_mask.as_ref().expect("Max pooling backward called before forward");
+        let (w, s, p) = (self.window, self.stride, self.padding);
+
+        // each patch's gradient only flows to its winning value, overlapping patches summing up
+        output_gradient
+            .unwrap(1, 1, 1, 1, 0, 0)
+            .tile(w, 1, 1, 1)
+            .component_mul(argmax_mask)
+            .wrap(self.input_length, 1, w, 1, s, 1, p, 0)
+    }
+}
+
+impl GradientLayer for MaxPool1dLayer {}
+
+impl ParameterableLayer for MaxPool1dLayer {
+    fn as_learnable_layer(&self) -> Option<&dyn LearnableLayer> {
+        None
+    }
+
+    fn as_learnable_layer_mut(&mut self) -> Option<&mut dyn LearnableLayer> {
+        None
+    }
+
+    fn as_dropout_layer(&mut self) -> Option<&mut dyn crate::layer::DropoutLayer> {
+        None
+    }
+}
+
+impl ConvNetworkLayer for MaxPool1dLayer {
+}
+
+/// Averages each `div` long patch of 1D signals stored as images with a single column.
+#[derive(Debug)]
+pub struct AvgPool1dLayer {
+    pub div: usize,
+}
+
+impl AvgPool1dLayer {
+    pub fn new(
+        div: usize,
+    ) -> Self {
+        Self {
+            div,
+        }
+    }
+}
+
+impl ImageLayer for AvgPool1dLayer {
+    fn forward(&mut self, input: Image) -> Image {
+        input
+            .unwrap(self.div, 1, self.div, 1, 0, 0)
+            .mean_along(0)
+            .wrap(input.image_dims().0 / self.div, 1, 1, 1, 1, 1, 0, 0)
+    }
+
+    fn backward(&mut self, _epoch: usize, output_gradient: Image) -> Image {
+        output_gradient
+            .scalar_div(self.div as Scalar)
+            .unwrap(1, 1, 1, 1, 0, 0)
+            .tile(self.div, 1, 1, 1)
+            .wrap(
+                output_gradient.image_dims().0 * self.div,
+                1,
+                self.div,
+                1,
+                self.div,
+                1,
+                0,
+                0,
+            )
+    }
+}
+
+impl GradientLayer for AvgPool1dLayer {}
+
+impl ParameterableLayer for AvgPool1dLayer {
+    fn as_learnable_layer(&self) -> Option<&dyn LearnableLayer> {
+        None
+    }
+
+    fn as_learnable_layer_mut(&mut self) -> Option<&mut dyn LearnableLayer> {
+        None
+    }
+
+    fn as_dropout_layer(&mut self) -> Option<&mut dyn crate::layer::DropoutLayer> {
+        None
+    }
+}
+
+impl ConvNetworkLayer for AvgPool1dLayer {
+}
@@ -0,0 +1,81 @@
+use crate::{
+    gradient_clipping::GradientMut,
+    layer::{join_learnable_parameters, split_learnable_parameters, GradientLayer, LearnableLayer},
+    linalg::Scalar,
+    vision::image::Image,
+};
+
+use crate::vision::image_layer::ImageLayer;
+
+use super::{dense_conv_layer::DenseConvLayer, direct_conv_layer::DirectConvLayer, ConvLayer};
+
+// https://arxiv.org/pdf/1610.02357.pdf
+/// Depthwise-separable convolution: a depthwise convolution filtering each channel with its own kernel,
+/// followed by a 1x1 pointwise convolution mixing the channels.
+#[derive(Debug)]
+pub struct SeparableConvLayer {
+    depthwise: DirectConvLayer,
+    pointwise: DenseConvLayer,
+}
+
+impl SeparableConvLayer {
+    pub fn new(depthwise: DirectConvLayer, pointwise: DenseConvLayer) -> Self {
+        Self {
+            depthwise,
+            pointwise,
+        }
+    }
+}
+
+impl ImageLayer for SeparableConvLayer {
+    fn forward(&mut self, input: Image) -> Image {
+        let filtered = self.depthwise.forward(input);
+        self.pointwise.forward(filtered)
+    }
+
+    fn backward(&mut self, epoch: usize, output_gradient: Image) -> Image {
+        let filtered_gradient = self.pointwise.backward(epoch, output_gradient);
+        self.depthwise.backward(epoch, filtered_gradient)
+    }
+}
+
+impl GradientLayer for SeparableConvLayer {
+    fn gradients_mut(&mut self) -> Vec<GradientMut<'_>> {
+        let mut gradients = self.depthwise.gradients_mut();
+        gradients.append(&mut self.pointwise.gradients_mut());
+        gradients
+    }
+
+    fn zero_grad(&mut self) {
+        self.depthwise.zero_grad();
+        self.pointwise.zero_grad();
+    }
+
+    fn step(&mut self, epoch: usize) {
+        self.depthwise.step(epoch);
+        self.pointwise.step(epoch);
+    }
+}
+
+impl LearnableLayer for SeparableConvLayer {
+    // returns the depthwise params then the pointwise params, each preceded by its amount of columns
+    fn get_learnable_parameters(&self) -> Vec<Vec<Scalar>> {
+        join_learnable_parameters(vec![
+            self.depthwise.get_learnable_parameters(),
+            self.pointwise.get_learnable_parameters(),
+        ])
+    }
+
+    fn set_learnable_parameters(&mut self, params_matrix: &Vec<Vec<Scalar>>) {
+        let mut params = split_learnable_parameters(params_matrix).into_iter();
+        self.depthwise.set_learnable_parameters(&params.next().unwrap());
+        self.pointwise.set_learnable_parameters(&params.next().unwrap());
+    }
+}
+
+impl ConvLayer for SeparableConvLayer {
+    // only the kernels applied to the input are scaled, as in the other conv layers
+    fn scale_kernels(&mut self, scale: Scalar) {
+        self.depthwise.scale_kernels(scale);
+    }
+}
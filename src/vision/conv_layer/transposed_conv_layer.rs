@@ -0,0 +1,196 @@
+use crate::{
+    gradient_clipping::GradientMut,
+    layer::{GradientLayer, LearnableLayer},
+    linalg::{Matrix, MatrixTrait, Scalar},
+    vision::{
+        conv_initializers::ConvInitializers, conv_optimizer::ConvOptimizers, image::Image,
+        image::ImageTrait,
+    },
+};
+
+use crate::vision::image_layer::{accumulate_image_gradient, ImageLayer};
+
+use super::{biases_from_params, biases_gradient, conv_padding::{dilate, subsample}, ConvLayer};
+
+/// Transposed convolution, the gradient of a cross-correlation with respect to its input,
+/// used to increase the images' dimensions: `out_rows = (in_rows - 1) * stride + krows`.
+///
+/// Kernels have shape `(krows, kcols, in_chans, out_chans)`.
+#[derive(Debug)]
+pub struct TransposedConvLayer {
+    pub kernels: Image,
+    biases: Image,
+    // input dilated by the stride
+    input: Option<Image>,
+    stride: usize,
+    kernels_optimizer: ConvOptimizers,
+    biases_optimizer: ConvOptimizers,
+    // accumulated by backward, applied by step
+    kernels_gradient: Option<Image>,
+    biases_gradient: Option<Image>,
+}
+
+impl TransposedConvLayer {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        nrow: usize,
+        ncol: usize,
+        nchan: usize,
+        nkern: usize,
+        stride: usize,
+        kernels_initializer: ConvInitializers,
+        biases_initializer: ConvInitializers,
+        kernels_optimizer: ConvOptimizers,
+        biases_optimizer: ConvOptimizers,
+    ) -> Self {
+        Self {
+            kernels: kernels_initializer.gen_image(nrow, ncol, nchan, nkern),
+            biases: biases_initializer.gen_image(1, 1, nkern, 1),
+            input: None,
+            stride,
+            kernels_optimizer,
+            biases_optimizer,
+            kernels_gradient: None,
+            biases_gradient: None,
+        }
+    }
+
+    pub fn out_img_dims_and_channels(
+        in_rows: usize,
+        in_cols: usize,
+        krows: usize,
+        kcols: usize,
+        kchans: usize,
+        stride: usize,
+    ) -> (usize, usize, usize) {
+        let out_rows = (in_rows - 1) * stride + krows;
+        let out_cols = (in_cols - 1) * stride + kcols;
+        (out_rows, out_cols, kchans)
+    }
+
+    // swaps the kernels' input and output channels
+    fn transposed_kernels(&self) -> Image {
+        let mut samples = vec![];
+        for i in 0..self.kernels.channels() {
+            let mut channels = vec![];
+            for o in 0..self.kernels.samples() {
+                channels.push(self.kernels.get_sample(o).get_channel(i));
+            }
+            samples.push(Image::join_channels(channels));
+        }
+        Image::join_samples(samples)
+    }
+}
+
+impl ImageLayer for TransposedConvLayer {
+    fn forward(&mut self, input: Image) -> Image {
+        let (rows, cols, _) = input.image_dims();
+        let input = dilate(
+            &input,
+            self.stride,
+            (rows - 1) * self.stride + 1,
+            (cols - 1) * self.stride + 1,
+        );
+
+        let res = input.convolve_full(&self.kernels);
+
+        let (rows, cols, _) = res.image_dims();
+        let res = res.component_add(&self.biases.tile(rows, cols, 1, 1));
+
+        self.input = Some(input);
+        res
+    }
+
+    fn backward(&mut self, _epoch: usize, output_gradient: Image) -> Image {
+        let input = self.input.as_ref().unwrap();
+
+        // the gradient of a full convolution is a valid cross-correlation
+        let input_grad = subsample(
+            &output_gradient.cross_correlate(&self.transposed_kernels()),
+            self.stride,
+        );
+
+        let mut kern_grad = Image::zeros(
+            self.kernels.image_dims().0,
+            self.kernels.image_dims().1,
+            self.kernels.channels(),
+            self.kernels.samples(),
+        );
+        for s in 0..input.samples() {
+            let input_s = input.get_sample(s);
+            let output_grad_s = output_gradient.get_sample(s);
+            // one kernel per input channel, correlated with each output channel's gradient
+            let input_channels = Image::join_samples(
+                (0..input_s.channels())
+                    .map(|i| input_s.get_channel(i))
+                    .collect(),
+            );
+            let kern_grad_s = Image::join_samples(
+                (0..output_grad_s.channels())
+                    .map(|o| output_grad_s.get_channel(o).cross_correlate(&input_channels))
+                    .collect(),
+            );
+            kern_grad = kern_grad.component_add(&kern_grad_s);
+        }
+
+        let biases_grad = biases_gradient(&output_gradient);
+
+        accumulate_image_gradient(&mut self.kernels_gradient, kern_grad);
+        accumulate_image_gradient(&mut self.biases_gradient, biases_grad);
+        input_grad
+    }
+}
+
+impl GradientLayer for TransposedConvLayer {
+    fn gradients_mut(&mut self) -> Vec<GradientMut<'_>> {
+        self.kernels_gradient
+            .iter_mut()
+            .chain(self.biases_gradient.iter_mut())
+            .map(GradientMut::Image)
+            .collect()
+    }
+
+    fn zero_grad(&mut self) {
+        self.kernels_gradient = None;
+        self.biases_gradient = None;
+    }
+
+    fn step(&mut self, epoch: usize) {
+        if let Some(kernels_gradient) = self.kernels_gradient.take() {
+            self.kernels = self
+                .kernels_optimizer
+                .update_parameters(epoch, &self.kernels, &kernels_gradient);
+        }
+        if let Some(biases_gradient) = self.biases_gradient.take() {
+            self.biases = self
+                .biases_optimizer
+                .update_parameters(epoch, &self.biases, &biases_gradient);
+        }
+    }
+}
+
+impl LearnableLayer for TransposedConvLayer {
+    fn get_learnable_parameters(&self) -> Vec<Vec<Scalar>> {
+        let mut params = self.kernels.flatten().get_data_col_leading();
+        params.push(self.biases.flatten().get_column(0));
+        params
+    }
+
+    fn set_learnable_parameters(&mut self, params_matrix: &Vec<Vec<Scalar>>) {
+        let mut kernels = params_matrix.clone();
+        let biases = kernels.pop().unwrap();
+        let (krows, kcols, kchans) = self.kernels.image_dims();
+        self.kernels = Image::from_samples_with_dims(
+            &Matrix::from_column_leading_vector2(&kernels),
+            (krows, kcols),
+            kchans,
+        );
+        self.biases = biases_from_params(&biases, self.biases.channels());
+    }
+}
+
+impl ConvLayer for TransposedConvLayer {
+    fn scale_kernels(&mut self, scale: Scalar) {
+        self.kernels = self.kernels.scalar_mul(scale);
+    }
+}
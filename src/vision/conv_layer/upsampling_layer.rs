@@ -0,0 +1,157 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    layer::{GradientLayer, LearnableLayer, ParameterableLayer},
+    linalg::{Matrix, MatrixTrait, Scalar},
+    vision::{
+        image::Image,
+        image::ImageTrait, conv_network::ConvNetworkLayer,
+    },
+};
+
+use crate::vision::image_layer::ImageLayer;
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub enum UpsamplingModes {
+    /// Repeats each pixel
+    Nearest,
+    /// Interpolates linearly between the 4 closest pixels
+    Bilinear,
+}
+
+/// Multiplies the rows and columns of the images by `factor`.
+#[derive(Debug)]
+pub struct UpsamplingLayer {
+    pub factor: usize,
+    pub mode: UpsamplingModes,
+    // for each output pixel, the input pixels it's interpolated from with their weights
+    interpolation: Vec<Vec<(usize, Scalar)>>,
+    in_img_dims: (usize, usize),
+}
+
+impl UpsamplingLayer {
+    pub fn new(factor: usize, mode: UpsamplingModes) -> Self {
+        Self {
+            factor,
+            mode,
+            interpolation: vec![],
+            in_img_dims: (0, 0),
+        }
+    }
+
+    // returns, for an output coordinate along a dimension of `size` input pixels, the input coordinates with their weights
+    fn source(&self, out_coord: usize, size: usize) -> Vec<(usize, Scalar)> {
+        match self.mode {
+            UpsamplingModes::Nearest => vec![(out_coord / self.factor, 1.0)],
+            UpsamplingModes::Bilinear => {
+                // pixels' centers are aligned, the borders are clamped
+                let src = ((out_coord as Scalar + 0.5) / self.factor as Scalar - 0.5).max(0.0);
+                let low = (src.floor() as usize).min(size - 1);
+                let high = (low + 1).min(size - 1);
+                let weight = src - low as Scalar;
+                vec![(low, 1.0 - weight), (high, weight)]
+            }
+        }
+    }
+
+    fn build_interpolation(&mut self, rows: usize, cols: usize) {
+        let (out_rows, out_cols) = (rows * self.factor, cols * self.factor);
+        self.interpolation = Vec::with_capacity(out_rows * out_cols);
+        // pixels are in column-leading order
+        for c in 0..out_cols {
+            let src_cols = self.source(c, cols);
+            for r in 0..out_rows {
+                let src_rows = self.source(r, rows);
+                let mut sources = vec![];
+                for (sc, wc) in src_cols.iter() {
+                    for (sr, wr) in src_rows.iter() {
+                        sources.push((sc * rows + sr, wc * wr));
+                    }
+                }
+                self.interpolation.push(sources);
+            }
+        }
+        self.in_img_dims = (rows, cols);
+    }
+}
+
+impl ImageLayer for UpsamplingLayer {
+    fn forward(&mut self, input: Image) -> Image {
+        let (rows, cols, channels) = input.image_dims();
+        if self.in_img_dims != (rows, cols) {
+            self.build_interpolation(rows, cols);
+        }
+        let in_pixels = rows * cols;
+
+        let samples: Vec<Vec<Scalar>> = input
+            .flatten()
+            .get_data_col_leading()
+            .into_iter()
+            .map(|sample| {
+                let mut upsampled = Vec::with_capacity(self.interpolation.len() * channels);
+                for channel in sample.chunks(in_pixels) {
+                    for sources in self.interpolation.iter() {
+                        upsampled.push(sources.iter().map(|(i, w)| channel[*i] * w).sum());
+                    }
+                }
+                upsampled
+            })
+            .collect();
+
+        Image::from_samples_with_dims(
+            &Matrix::from_column_leading_vector2(&samples),
+            (rows * self.factor, cols * self.factor),
+            channels,
+        )
+    }
+
+    fn backward(&mut self, _epoch: usize, output_gradient: Image) -> Image {
+        let (rows, cols) = self.in_img_dims;
+        let channels = output_gradient.channels();
+        let out_pixels = self.interpolation.len();
+
+        // each output pixel's gradient flows back to the input pixels it's interpolated from
+        let samples: Vec<Vec<Scalar>> = output_gradient
+            .flatten()
+            .get_data_col_leading()
+            .into_iter()
+            .map(|sample| {
+                let mut input_grad = vec![0.0; rows * cols * channels];
+                for (c, channel) in sample.chunks(out_pixels).enumerate() {
+                    let offset = c * rows * cols;
+                    for (grad, sources) in channel.iter().zip(self.interpolation.iter()) {
+                        for (i, w) in sources.iter() {
+                            input_grad[offset + i] += grad * w;
+                        }
+                    }
+                }
+                input_grad
+            })
+            .collect();
+
+        Image::from_samples_with_dims(
+            &Matrix::from_column_leading_vector2(&samples),
+            (rows, cols),
+            channels,
+        )
+    }
+}
+
+impl GradientLayer for UpsamplingLayer {}
+
+impl ParameterableLayer for UpsamplingLayer {
+    fn as_learnable_layer(&self) -> Option<&dyn LearnableLayer> {
+        None
+    }
+
+    fn as_learnable_layer_mut(&mut self) -> Option<&mut dyn LearnableLayer> {
+        None
+    }
+
+    fn as_dropout_layer(&mut self) -> Option<&mut dyn crate::layer::DropoutLayer> {
+        None
+    }
+}
+
+impl ConvNetworkLayer for UpsamplingLayer {
+}
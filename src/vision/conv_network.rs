@@ -1,8 +1,12 @@
 use std::fmt::Debug;
 
 use crate::{
-    layer::{DropoutLayer, Layer, LearnableLayer, ParameterableLayer},
-    linalg::{Matrix, Scalar},
+    gradient_clipping::GradientMut,
+    layer::{
+        join_learnable_parameters, split_learnable_parameters, DropoutLayer, GradientLayer, Layer,
+        LearnableLayer, ParameterableLayer,
+    },
+    linalg::{Matrix, MatrixTrait, Scalar},
     network::NetworkLayer,
     vision::{image::Image, image::ImageTrait}, monitor::TM,
 };
@@ -12,20 +16,30 @@ use super::image_layer::ImageLayer;
 #[derive(Debug)]
 pub struct ConvNetwork {
     layers: Vec<Box<dyn ConvNetworkLayer>>,
+    // (rows, cols) of the input images
+    img_dims: (usize, usize),
     channels: usize,
+    out_img_dims: Option<(usize, usize)>,
     out_channels: Option<usize>
 }
 
 impl ConvNetwork {
-    pub fn new(layers: Vec<Box<dyn ConvNetworkLayer>>, channels: usize) -> Self {
-        Self { layers, channels, out_channels: None }
+    pub fn new(layers: Vec<Box<dyn ConvNetworkLayer>>, img_dims: (usize, usize), channels: usize) -> Self {
+        Self { layers, img_dims, channels, out_img_dims: None, out_channels: None }
     }
 }
 
 impl Layer for ConvNetwork {
     fn forward(&mut self, input: Matrix) -> Matrix {
         TM::start("cnet.forw");
-        let mut output = Image::from_samples(&input, self.channels);
+        let (rows, cols) = self.img_dims;
+        if input.dim().0 != rows * cols * self.channels {
+            panic!(
+                "Convolutional network expects {}x{} images with {} channels ({} values per sample), got {} values",
+                rows, cols, self.channels, rows * cols * self.channels, input.dim().0
+            );
+        }
+        let mut output = Image::from_samples_with_dims(&input, self.img_dims, self.channels);
         let n_layers = self.layers.len();
 
         for (i, layer) in self.layers.iter_mut().enumerate() {
@@ -34,7 +48,9 @@ impl Layer for ConvNetwork {
             TM::end();
         }
         
-        self.out_channels = Some(output.channels());
+        let (out_rows, out_cols, out_channels) = output.image_dims();
+        self.out_img_dims = Some((out_rows, out_cols));
+        self.out_channels = Some(out_channels);
         
         let out = output.flatten();
         TM::end();
@@ -43,7 +59,11 @@ impl Layer for ConvNetwork {
 
     fn backward(&mut self, epoch: usize, error_gradient: Matrix) -> Matrix {
         TM::start("cnet.back");
-        let mut error_gradient = Image::from_samples(&error_gradient, self.out_channels.unwrap());
+        let mut error_gradient = Image::from_samples_with_dims(
+            &error_gradient,
+            self.out_img_dims.unwrap(),
+            self.out_channels.unwrap()
+        );
         
         for (i, layer) in self.layers.iter_mut().enumerate().rev() {
             TM::start(format!("layer[{}]", i+1));
@@ -55,6 +75,24 @@ impl Layer for ConvNetwork {
         TM::end();
         grad
     }
+
+    fn regularization_loss(&self) -> Scalar {
+        self.layers.iter().map(|l| l.regularization_loss()).sum()
+    }
+}
+
+impl GradientLayer for ConvNetwork {
+    fn gradients_mut(&mut self) -> Vec<GradientMut<'_>> {
+        self.layers.iter_mut().flat_map(|l| l.gradients_mut()).collect()
+    }
+
+    fn zero_grad(&mut self) {
+        self.layers.iter_mut().for_each(|l| l.zero_grad());
+    }
+
+    fn step(&mut self, epoch: usize) {
+        self.layers.iter_mut().for_each(|l| l.step(epoch));
+    }
 }
 
 impl NetworkLayer for ConvNetwork {}
@@ -74,30 +112,24 @@ impl ParameterableLayer for ConvNetwork {
 }
 
 impl LearnableLayer for ConvNetwork {
+    // each layer's params are preceded in the params matrix by a line holding their amount of columns
     fn get_learnable_parameters(&self) -> Vec<Vec<Scalar>> {
-        let mut params = Vec::new();
-        for layer in self.layers.iter() {
-            layer.as_learnable_layer().map(|l| {
-                params.append(&mut l.get_learnable_parameters());
-            });
-            params.push(vec![-1.0; 1]);
-        }
-        params
+        join_learnable_parameters(
+            self.layers
+                .iter()
+                .map(|layer| match layer.as_learnable_layer() {
+                    Some(l) => l.get_learnable_parameters(),
+                    None => Vec::new(),
+                })
+                .collect(),
+        )
     }
 
     fn set_learnable_parameters(&mut self, params_matrix: &Vec<Vec<Scalar>>) {
-        // each layer's params are split in the params matrix at some line with a -1.0
-        let mut params_matrix = params_matrix.clone();
-        for layer in self.layers.iter_mut() {
-            let mut layer_params = Vec::new();
-            while let Some(param) = params_matrix.pop() {
-                if param[0] == -1.0 {
-                    break;
-                }
-                layer_params.push(param);
-            }
+        let layers_params = split_learnable_parameters(params_matrix);
+        for (layer, layer_params) in self.layers.iter_mut().zip(layers_params.iter()) {
             layer.as_learnable_layer_mut().map(|l| {
-                l.set_learnable_parameters(&layer_params);
+                l.set_learnable_parameters(layer_params);
             });
         }
     }
@@ -0,0 +1,86 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    learning_rate::LearningRateSchedule,
+    linalg::Scalar,
+    optimizer::adadelta::{
+        default_adadelta_epsilon, default_adadelta_learning_rate, default_adadelta_rho,
+    },
+    vision::{image::Image, image::ImageTrait},
+};
+
+// https://arxiv.org/pdf/1212.5701.pdf
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ConvAdaDelta {
+    #[serde(default = "default_adadelta_rho")]
+    rho: Scalar,
+    #[serde(default = "default_adadelta_epsilon")]
+    epsilon: Scalar,
+    #[serde(default = "default_adadelta_learning_rate")]
+    learning_rate: LearningRateSchedule,
+    #[serde(skip)]
+    v: Option<Image>, // moving average of the squared gradients
+    #[serde(skip)]
+    u: Option<Image>, // moving average of the squared updates
+}
+
+impl Default for ConvAdaDelta {
+    fn default() -> Self {
+        Self {
+            v: None,
+            u: None,
+            rho: default_adadelta_rho(),
+            epsilon: default_adadelta_epsilon(),
+            learning_rate: default_adadelta_learning_rate(),
+        }
+    }
+}
+
+impl ConvAdaDelta {
+    pub fn new(learning_rate: LearningRateSchedule, rho: Scalar, epsilon: Scalar) -> Self {
+        Self {
+            v: None,
+            u: None,
+            rho,
+            epsilon,
+            learning_rate,
+        }
+    }
+
+    pub fn update_parameters(
+        &mut self,
+        epoch: usize,
+        parameters: &Image,
+        parameters_gradient: &Image,
+    ) -> Image {
+        let lr = self.learning_rate.get_learning_rate(epoch);
+        let (nrow, ncol, nchan) = parameters_gradient.image_dims();
+        let n_sample = parameters_gradient.samples();
+
+        if self.v.is_none() {
+            self.v = Some(Image::zeros(nrow, ncol, nchan, n_sample));
+        }
+        if self.u.is_none() {
+            self.u = Some(Image::zeros(nrow, ncol, nchan, n_sample));
+        }
+        let v = self.v.as_ref().unwrap();
+        let u = self.u.as_ref().unwrap();
+
+        let g = parameters_gradient;
+        let g2 = g.component_mul(g);
+        let v = v.scalar_mul(self.rho).component_add(&g2.scalar_mul(1.0 - self.rho));
+
+        let update = u
+            .scalar_add(self.epsilon)
+            .sqrt()
+            .component_div(&v.scalar_add(self.epsilon).sqrt())
+            .component_mul(g);
+        let u = u
+            .scalar_mul(self.rho)
+            .component_add(&update.component_mul(&update).scalar_mul(1.0 - self.rho));
+
+        self.v = Some(v);
+        self.u = Some(u);
+        parameters.component_sub(&update.scalar_mul(lr))
+    }
+}
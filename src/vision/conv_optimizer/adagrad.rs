@@ -0,0 +1,65 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    learning_rate::{default_learning_rate, LearningRateSchedule},
+    linalg::Scalar,
+    optimizer::adam::default_epsilon,
+    vision::{image::Image, image::ImageTrait},
+};
+
+// https://jmlr.org/papers/volume12/duchi11a/duchi11a.pdf
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ConvAdaGrad {
+    #[serde(default = "default_epsilon")]
+    epsilon: Scalar,
+    #[serde(default = "default_learning_rate")]
+    learning_rate: LearningRateSchedule,
+    #[serde(skip)]
+    g2_sum: Option<Image>, // sum of all the squared gradients
+}
+
+impl Default for ConvAdaGrad {
+    fn default() -> Self {
+        Self {
+            g2_sum: None,
+            epsilon: default_epsilon(),
+            learning_rate: default_learning_rate(),
+        }
+    }
+}
+
+impl ConvAdaGrad {
+    pub fn new(learning_rate: LearningRateSchedule, epsilon: Scalar) -> Self {
+        Self {
+            g2_sum: None,
+            epsilon,
+            learning_rate,
+        }
+    }
+
+    pub fn update_parameters(
+        &mut self,
+        epoch: usize,
+        parameters: &Image,
+        parameters_gradient: &Image,
+    ) -> Image {
+        let lr = self.learning_rate.get_learning_rate(epoch);
+        let (nrow, ncol, nchan) = parameters_gradient.image_dims();
+        let n_sample = parameters_gradient.samples();
+
+        if self.g2_sum.is_none() {
+            self.g2_sum = Some(Image::zeros(nrow, ncol, nchan, n_sample));
+        }
+        let g2_sum = self.g2_sum.as_ref().unwrap();
+
+        let g = parameters_gradient;
+        let g2_sum = g2_sum.component_add(&g.component_mul(g));
+
+        let new_params = parameters.component_sub(
+            &g.scalar_mul(lr)
+                .component_div(&g2_sum.sqrt().scalar_add(self.epsilon)),
+        );
+        self.g2_sum = Some(g2_sum);
+        new_params
+    }
+}
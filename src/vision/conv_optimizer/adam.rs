@@ -3,21 +3,10 @@ use serde::{Deserialize, Serialize};
 use crate::{
     learning_rate::{default_learning_rate, LearningRateSchedule},
     linalg::Scalar,
+    optimizer::adam::{default_beta1, default_beta2, default_epsilon},
     vision::{image::Image, image::ImageTrait},
 };
 
-fn default_beta1() -> Scalar {
-    0.9
-}
-
-fn default_beta2() -> Scalar {
-    0.999
-}
-
-fn default_epsilon() -> Scalar {
-    1e-8
-}
-
 // https://arxiv.org/pdf/1412.6980.pdf
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct ConvAdam {
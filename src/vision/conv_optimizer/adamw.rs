@@ -0,0 +1,109 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    learning_rate::{default_learning_rate, LearningRateSchedule},
+    linalg::Scalar,
+    optimizer::{
+        adam::{default_beta1, default_beta2, default_epsilon},
+        adamw::default_weight_decay,
+    },
+    vision::{image::Image, image::ImageTrait},
+};
+
+// Adam whose weight decay is applied to the parameters instead of being added to the gradients
+// https://arxiv.org/pdf/1711.05101.pdf
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ConvAdamW {
+    #[serde(default = "default_beta1")]
+    beta1: Scalar,
+    #[serde(default = "default_beta2")]
+    beta2: Scalar,
+    #[serde(default = "default_epsilon")]
+    epsilon: Scalar,
+    #[serde(default = "default_weight_decay")]
+    weight_decay: Scalar,
+    #[serde(default = "default_learning_rate")]
+    learning_rate: LearningRateSchedule,
+    #[serde(skip)]
+    m: Option<Image>, // first moment vector
+    #[serde(skip)]
+    v: Option<Image>, // second moment vector
+    #[serde(skip)]
+    t: i32, // number of updates
+}
+
+impl Default for ConvAdamW {
+    fn default() -> Self {
+        Self {
+            m: None,
+            v: None,
+            t: 0,
+            beta1: default_beta1(),
+            beta2: default_beta2(),
+            epsilon: default_epsilon(),
+            weight_decay: default_weight_decay(),
+            learning_rate: default_learning_rate(),
+        }
+    }
+}
+
+impl ConvAdamW {
+    pub fn new(
+        learning_rate: LearningRateSchedule,
+        beta1: Scalar,
+        beta2: Scalar,
+        epsilon: Scalar,
+        weight_decay: Scalar,
+    ) -> Self {
+        Self {
+            m: None,
+            v: None,
+            t: 0,
+            beta1,
+            beta2,
+            epsilon,
+            weight_decay,
+            learning_rate,
+        }
+    }
+
+    pub fn update_parameters(
+        &mut self,
+        epoch: usize,
+        parameters: &Image,
+        parameters_gradient: &Image,
+    ) -> Image {
+        let alpha = self.learning_rate.get_learning_rate(epoch);
+        let (nrow, ncol, nchan) = parameters_gradient.image_dims();
+        let n_sample = parameters_gradient.samples();
+
+        if self.m.is_none() {
+            self.m = Some(Image::zeros(nrow, ncol, nchan, n_sample));
+        }
+        if self.v.is_none() {
+            self.v = Some(Image::zeros(nrow, ncol, nchan, n_sample));
+        }
+        let m = self.m.as_ref().unwrap();
+        let v = self.v.as_ref().unwrap();
+        self.t += 1;
+
+        let g = parameters_gradient;
+        let g2 = g.component_mul(g);
+
+        let m = m.scalar_mul(self.beta1).component_add(&g.scalar_mul(1.0 - self.beta1));
+        let v = v.scalar_mul(self.beta2).component_add(&g2.scalar_mul(1.0 - self.beta2));
+
+        let m_bias_corrected = m.scalar_div(1.0 - self.beta1.powi(self.t));
+        let v_bias_corrected = v.scalar_div(1.0 - self.beta2.powi(self.t));
+
+        let decayed = parameters.scalar_mul(1.0 - alpha * self.weight_decay);
+        let new_params = decayed.component_sub(
+            &m_bias_corrected
+                .scalar_mul(alpha)
+                .component_div(&v_bias_corrected.sqrt().scalar_add(self.epsilon)),
+        );
+        self.m = Some(m);
+        self.v = Some(v);
+        new_params
+    }
+}
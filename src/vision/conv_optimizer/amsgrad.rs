@@ -0,0 +1,110 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    learning_rate::{default_learning_rate, LearningRateSchedule},
+    linalg::Scalar,
+    optimizer::adam::{default_beta1, default_beta2, default_epsilon},
+    vision::{image::Image, image::ImageTrait},
+};
+
+// Adam whose second moment estimate never decreases
+// https://openreview.net/pdf?id=ryQu7f-RZ
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ConvAMSGrad {
+    #[serde(default = "default_beta1")]
+    beta1: Scalar,
+    #[serde(default = "default_beta2")]
+    beta2: Scalar,
+    #[serde(default = "default_epsilon")]
+    epsilon: Scalar,
+    #[serde(default = "default_learning_rate")]
+    learning_rate: LearningRateSchedule,
+    #[serde(skip)]
+    m: Option<Image>, // first moment vector
+    #[serde(skip)]
+    v: Option<Image>, // second moment vector
+    #[serde(skip)]
+    v_max: Option<Image>, // maximum of all the second moment vectors
+    #[serde(skip)]
+    t: i32, // number of updates
+}
+
+impl Default for ConvAMSGrad {
+    fn default() -> Self {
+        Self {
+            m: None,
+            v: None,
+            v_max: None,
+            t: 0,
+            beta1: default_beta1(),
+            beta2: default_beta2(),
+            learning_rate: default_learning_rate(),
+            epsilon: default_epsilon(),
+        }
+    }
+}
+
+impl ConvAMSGrad {
+    pub fn new(
+        learning_rate: LearningRateSchedule,
+        beta1: Scalar,
+        beta2: Scalar,
+        epsilon: Scalar,
+    ) -> Self {
+        Self {
+            m: None,
+            v: None,
+            v_max: None,
+            t: 0,
+            beta1,
+            beta2,
+            learning_rate,
+            epsilon,
+        }
+    }
+
+    pub fn update_parameters(
+        &mut self,
+        epoch: usize,
+        parameters: &Image,
+        parameters_gradient: &Image,
+    ) -> Image {
+        let alpha = self.learning_rate.get_learning_rate(epoch);
+        let (nrow, ncol, nchan) = parameters_gradient.image_dims();
+        let n_sample = parameters_gradient.samples();
+
+        if self.m.is_none() {
+            self.m = Some(Image::zeros(nrow, ncol, nchan, n_sample));
+        }
+        if self.v.is_none() {
+            self.v = Some(Image::zeros(nrow, ncol, nchan, n_sample));
+        }
+        if self.v_max.is_none() {
+            self.v_max = Some(Image::zeros(nrow, ncol, nchan, n_sample));
+        }
+        let m = self.m.as_ref().unwrap();
+        let v = self.v.as_ref().unwrap();
+        let v_max = self.v_max.as_ref().unwrap();
+        self.t += 1;
+
+        let g = parameters_gradient;
+        let g2 = g.component_mul(g);
+
+        let m = m.scalar_mul(self.beta1).component_add(&g.scalar_mul(1.0 - self.beta1));
+        let v = v.scalar_mul(self.beta2).component_add(&g2.scalar_mul(1.0 - self.beta2));
+        let v_max = v_max.maxof(&v);
+
+        let m_bias_corrected = m.scalar_div(1.0 - self.beta1.powi(self.t));
+        let v_max_bias_corrected = v_max.scalar_div(1.0 - self.beta2.powi(self.t));
+
+        let new_params = parameters.component_sub(
+            &m_bias_corrected
+                .scalar_mul(alpha)
+                .component_div(&v_max_bias_corrected.sqrt().scalar_add(self.epsilon)),
+        );
+        self.m = Some(m);
+        self.v = Some(v);
+        self.v_max = Some(v_max);
+        new_params
+    }
+}
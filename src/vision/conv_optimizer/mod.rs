@@ -1,18 +1,34 @@
 use serde::{Deserialize, Serialize};
 
-use self::{adam::ConvAdam, momentum::ConvMomentum, sgd::ConvSGD};
+use self::{
+    adadelta::ConvAdaDelta, adagrad::ConvAdaGrad, adam::ConvAdam, adamw::ConvAdamW,
+    amsgrad::ConvAMSGrad, momentum::ConvMomentum, nadam::ConvNadam, rmsprop::ConvRMSProp,
+    sgd::ConvSGD,
+};
 
 use super::image::Image;
 
 pub mod adam;
 pub mod momentum;
 pub mod sgd;
+pub mod rmsprop;
+pub mod adagrad;
+pub mod adadelta;
+pub mod nadam;
+pub mod amsgrad;
+pub mod adamw;
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub enum ConvOptimizers {
     ConvSGD(ConvSGD),
     ConvMomentum(ConvMomentum),
     ConvAdam(ConvAdam),
+    ConvRMSProp(ConvRMSProp),
+    ConvAdaGrad(ConvAdaGrad),
+    ConvAdaDelta(ConvAdaDelta),
+    ConvNadam(ConvNadam),
+    ConvAMSGrad(ConvAMSGrad),
+    ConvAdamW(ConvAdamW),
 }
 
 impl ConvOptimizers {
@@ -32,6 +48,24 @@ impl ConvOptimizers {
             ConvOptimizers::ConvAdam(adam) => {
                 adam.update_parameters(epoch, parameters, parameters_gradient)
             }
+            ConvOptimizers::ConvRMSProp(rmsprop) => {
+                rmsprop.update_parameters(epoch, parameters, parameters_gradient)
+            }
+            ConvOptimizers::ConvAdaGrad(adagrad) => {
+                adagrad.update_parameters(epoch, parameters, parameters_gradient)
+            }
+            ConvOptimizers::ConvAdaDelta(adadelta) => {
+                adadelta.update_parameters(epoch, parameters, parameters_gradient)
+            }
+            ConvOptimizers::ConvNadam(nadam) => {
+                nadam.update_parameters(epoch, parameters, parameters_gradient)
+            }
+            ConvOptimizers::ConvAMSGrad(amsgrad) => {
+                amsgrad.update_parameters(epoch, parameters, parameters_gradient)
+            }
+            ConvOptimizers::ConvAdamW(adamw) => {
+                adamw.update_parameters(epoch, parameters, parameters_gradient)
+            }
         }
     }
 }
@@ -47,3 +81,27 @@ pub fn conv_sgd() -> ConvOptimizers {
 pub fn conv_momentum() -> ConvOptimizers {
     ConvOptimizers::ConvMomentum(ConvMomentum::default())
 }
+
+pub fn conv_rmsprop() -> ConvOptimizers {
+    ConvOptimizers::ConvRMSProp(ConvRMSProp::default())
+}
+
+pub fn conv_adagrad() -> ConvOptimizers {
+    ConvOptimizers::ConvAdaGrad(ConvAdaGrad::default())
+}
+
+pub fn conv_adadelta() -> ConvOptimizers {
+    ConvOptimizers::ConvAdaDelta(ConvAdaDelta::default())
+}
+
+pub fn conv_nadam() -> ConvOptimizers {
+    ConvOptimizers::ConvNadam(ConvNadam::default())
+}
+
+pub fn conv_amsgrad() -> ConvOptimizers {
+    ConvOptimizers::ConvAMSGrad(ConvAMSGrad::default())
+}
+
+pub fn conv_adamw() -> ConvOptimizers {
+    ConvOptimizers::ConvAdamW(ConvAdamW::default())
+}
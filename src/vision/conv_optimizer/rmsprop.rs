@@ -0,0 +1,70 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    learning_rate::{default_learning_rate, LearningRateSchedule},
+    linalg::Scalar,
+    optimizer::{adam::default_epsilon, rmsprop::default_rho},
+    vision::{image::Image, image::ImageTrait},
+};
+
+// http://www.cs.toronto.edu/~tijmen/csc321/slides/lecture_slides_lec6.pdf
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ConvRMSProp {
+    #[serde(default = "default_rho")]
+    rho: Scalar,
+    #[serde(default = "default_epsilon")]
+    epsilon: Scalar,
+    #[serde(default = "default_learning_rate")]
+    learning_rate: LearningRateSchedule,
+    #[serde(skip)]
+    v: Option<Image>, // moving average of the squared gradients
+}
+
+impl Default for ConvRMSProp {
+    fn default() -> Self {
+        Self {
+            v: None,
+            rho: default_rho(),
+            epsilon: default_epsilon(),
+            learning_rate: default_learning_rate(),
+        }
+    }
+}
+
+impl ConvRMSProp {
+    pub fn new(learning_rate: LearningRateSchedule, rho: Scalar, epsilon: Scalar) -> Self {
+        Self {
+            v: None,
+            rho,
+            epsilon,
+            learning_rate,
+        }
+    }
+
+    pub fn update_parameters(
+        &mut self,
+        epoch: usize,
+        parameters: &Image,
+        parameters_gradient: &Image,
+    ) -> Image {
+        let lr = self.learning_rate.get_learning_rate(epoch);
+        let (nrow, ncol, nchan) = parameters_gradient.image_dims();
+        let n_sample = parameters_gradient.samples();
+
+        if self.v.is_none() {
+            self.v = Some(Image::zeros(nrow, ncol, nchan, n_sample));
+        }
+        let v = self.v.as_ref().unwrap();
+
+        let g = parameters_gradient;
+        let g2 = g.component_mul(g);
+        let v = v.scalar_mul(self.rho).component_add(&g2.scalar_mul(1.0 - self.rho));
+
+        let new_params = parameters.component_sub(
+            &g.scalar_mul(lr)
+                .component_div(&v.sqrt().scalar_add(self.epsilon)),
+        );
+        self.v = Some(v);
+        new_params
+    }
+}
@@ -1,9 +1,9 @@
 use std::fmt;
 
 use arrayfire::{
-    constant, exp, flip, index, join_many, maxof, mean, mean_all, minof, pow,
+    constant, eq, exp, flip, imax, index, iota, join_many, log, max, maxof, mean, mean_all, minof, pow,
     random_normal, random_uniform, sign, sqrt, sum, sum_all, unwrap, wrap, Array, Dim4,
-    RandomEngine, Seq, tile, convolve2_nn,
+    RandomEngine, Seq, tile, convolve2_nn, BorderType,
 };
 use rand::Rng;
 
@@ -134,6 +134,10 @@ impl ImageTrait for Image {
 
     fn from_samples(samples: &Matrix, channels: usize) -> Self {
         let image_size = ((samples.dim().0 / channels) as f64).sqrt() as usize;
+        Self::from_samples_with_dims(samples, (image_size, image_size), channels)
+    }
+
+    fn from_samples_with_dims(samples: &Matrix, img_dims: (usize, usize), channels: usize) -> Self {
         let mut samples_flattened = vec![];
         for i in 0..samples.dim().1 {
             let mut sample = samples.get_column(i);
@@ -143,8 +147,8 @@ impl ImageTrait for Image {
         Self(Array::new(
             samples_flattened.as_slice(),
             Dim4::new(&[
-                image_size.try_into().unwrap(),
-                image_size.try_into().unwrap(),
+                img_dims.0.try_into().unwrap(),
+                img_dims.1.try_into().unwrap(),
                 channels.try_into().unwrap(),
                 samples.dim().1.try_into().unwrap(),
             ]),
@@ -189,6 +193,21 @@ impl ImageTrait for Image {
         ))
     }
 
+    fn pad(&self, rows: (usize, usize), cols: (usize, usize), mirror: bool) -> Self {
+        let border = if mirror { BorderType::SYMMETRIC } else { BorderType::ZERO };
+        Self(arrayfire::pad(
+            &self.0,
+            Dim4::new(&[rows.0 as u64, cols.0 as u64, 0, 0]),
+            Dim4::new(&[rows.1 as u64, cols.1 as u64, 0, 0]),
+            border,
+        ))
+    }
+
+    fn unpad(&self, rows: (usize, usize), cols: (usize, usize), mirror: bool) -> Self {
+        let unpadded_rows = unpad_dim(&self.0, 0, rows, mirror);
+        Self(unpad_dim(&unpadded_rows, 1, cols, mirror))
+    }
+
     fn tile(
         &self,
         repetitions_row: usize,
@@ -292,12 +311,16 @@ impl ImageTrait for Image {
     }
 
     fn cross_correlate(&self, kernels: &Self) -> Self {
+        self.cross_correlate_strided(kernels, 1)
+    }
+
+    fn cross_correlate_strided(&self, kernels: &Self, stride: usize) -> Self {
         let kernels = Self(flip(&flip(&kernels.0, 0), 1));
-        self.convolve(&kernels, false)
+        self.convolve(&kernels, false, stride)
     }
 
     fn convolve_full(&self, kernels: &Self) -> Self {
-        self.convolve(&kernels, true)
+        self.convolve(&kernels, true, 1)
     }
 
     fn flatten(&self) -> Matrix {
@@ -408,6 +431,23 @@ impl ImageTrait for Image {
         Self(mean(&self.0, dim.try_into().unwrap()))
     }
 
+    fn max_along(&self, dim: usize) -> Self {
+        Self(max(&self.0, dim.try_into().unwrap()))
+    }
+
+    fn argmax_mask_along(&self, dim: usize) -> Self {
+        let (_, argmax) = imax(&self.0, dim.try_into().unwrap());
+
+        // positions along dim, repeated across the other dims
+        let mut positions_dims = [1u64; 4];
+        positions_dims[dim] = self.0.dims()[dim];
+        let mut repetitions = *self.0.dims().get();
+        repetitions[dim] = 1;
+        let positions = iota::<u32>(Dim4::new(&positions_dims), Dim4::new(&repetitions));
+
+        Self(eq(&positions, &argmax, true).cast::<Scalar>())
+    }
+
     fn exp(&self) -> Self {
         Self(exp(&self.0))
     }
@@ -420,6 +460,11 @@ impl ImageTrait for Image {
         Self(sign(&self.0)).scalar_mul(-2.0).scalar_add(1.0)
     }
 
+    fn sign_zero_at_zero(&self) -> Self {
+        // arrayfire's sign is 1 for negative components and 0 otherwise
+        Self(sign(&self.scalar_mul(-1.0).0)).component_sub(&Self(sign(&self.0)))
+    }
+
     fn minof(&self, other: &Self) -> Self {
         Self(minof(&self.0, &other.0, false))
     }
@@ -427,23 +472,31 @@ impl ImageTrait for Image {
     fn sqrt(&self) -> Self {
         Self(sqrt(&self.0))
     }
+
+    fn log(&self) -> Self {
+        Self(log(&self.0))
+    }
 }
 
 impl Image {
-    fn convolve(&self, kernels: &Self, full: bool) -> Self {
-        let (out_size, padding) = if full {
+    fn convolve(&self, kernels: &Self, full: bool, stride: usize) -> Self {
+        let (rows, cols, _) = self.image_dims();
+        let (krows, kcols, _) = kernels.image_dims();
+        let (out_rows, out_cols, padding) = if full {
             (
-                self.image_dims().0 + kernels.image_dims().0 - 1,
+                rows + krows - 1,
+                cols + kcols - 1,
                 Dim4::new(&[
-                    (kernels.image_dims().0 - 1).try_into().unwrap(), 
-                    (kernels.image_dims().1 - 1).try_into().unwrap(), 
+                    (krows - 1).try_into().unwrap(), 
+                    (kcols - 1).try_into().unwrap(), 
                     1, 
                     1
                 ]),
             )
         } else {
             (
-                self.image_dims().0 - kernels.image_dims().0 + 1,
+                (rows - krows) / stride + 1,
+                (cols - kcols) / stride + 1,
                 Dim4::new(&[0, 0, 0, 0])
             )
         };
@@ -451,7 +504,7 @@ impl Image {
         let res = convolve2_nn(
             &self.0,
             &kernels.0,
-            Dim4::new(&[1, 1, 1, 1]),
+            Dim4::new(&[stride.try_into().unwrap(), stride.try_into().unwrap(), 1, 1]),
             padding,
             Dim4::new(&[0, 0, 0, 0])
         );
@@ -459,8 +512,8 @@ impl Image {
         let res = Self(index(
             &res,
             &[
-                Seq::new(0, (out_size - 1).try_into().unwrap(), 1),
-                Seq::new(0, (out_size - 1).try_into().unwrap(), 1),
+                Seq::new(0, (out_rows - 1).try_into().unwrap(), 1),
+                Seq::new(0, (out_cols - 1).try_into().unwrap(), 1),
                 Seq::new(0, (kernels.samples() - 1).try_into().unwrap(), 1),
                 Seq::new(0, (self.samples() - 1).try_into().unwrap(), 1),
             ],
@@ -469,6 +522,35 @@ impl Image {
     }
 }
 
+/// Crops `(before, after)` pixels along `dim`, adding the mirrored pixels back to the border they were copied from.
+fn unpad_dim(array: &Array<Scalar>, dim: usize, (before, after): (usize, usize), mirror: bool) -> Array<Scalar> {
+    let size = array.dims()[dim] as usize;
+    let inner = size - before - after;
+    let slice = |start: usize, end: usize| {
+        let mut seqs = [Seq::<i32>::default(); 4];
+        seqs[dim] = Seq::new(start as i32, (end - 1) as i32, 1);
+        index(array, &seqs)
+    };
+    let padding_dims = |len: usize| {
+        let mut dims = [0u64; 4];
+        dims[dim] = len as u64;
+        Dim4::new(&dims)
+    };
+
+    let mut res = slice(before, before + inner);
+    if mirror {
+        if before > 0 {
+            let band = flip(&slice(0, before), dim as u32);
+            res = res + arrayfire::pad(&band, padding_dims(0), padding_dims(inner - before), BorderType::ZERO);
+        }
+        if after > 0 {
+            let band = flip(&slice(size - after, size), dim as u32);
+            res = res + arrayfire::pad(&band, padding_dims(inner - after), padding_dims(0), BorderType::ZERO);
+        }
+    }
+    res
+}
+
 impl fmt::Debug for Image {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("Image")
@@ -48,6 +48,9 @@ pub trait ImageTrait {
     /// Pixels are assumed to be in column-leading order with channels put in their entirety one after the other.
     fn from_samples(samples: &Matrix, channels: usize) -> Self;
 
+    /// Same as `from_samples` for images of `(rows, cols)` pixels, which don't need to be square.
+    fn from_samples_with_dims(samples: &Matrix, img_dims: (usize, usize), channels: usize) -> Self;
+
     /// Adds the components of self and other. Assumes both images have the same pixel sizes and channels count.
     ///
     /// If other has less samples than self, it will add the first sample of other to all samples of self.
@@ -78,6 +81,10 @@ pub trait ImageTrait {
 
     fn cross_correlate(&self, kernels: &Self) -> Self;
 
+    /// Only computes the cross-correlation every `stride` pixels along the rows and columns,
+    /// starting from the first one, instead of subsampling the whole cross-correlation.
+    fn cross_correlate_strided(&self, kernels: &Self, stride: usize) -> Self;
+
     fn convolve_full(&self, kernels: &Self) -> Self;
 
     fn flatten(&self) -> Matrix;
@@ -113,6 +120,13 @@ pub trait ImageTrait {
     
     fn unwrap(&self, wx: usize, wy: usize, sx: usize, sy: usize, px: usize, py: usize) -> Self;
 
+    /// Pads the rows and columns of each channel with (before, after) pixels,
+    /// either zeros or the pixels mirrored from the border, border included.
+    fn pad(&self, rows: (usize, usize), cols: (usize, usize), mirror: bool) -> Self;
+
+    /// Adjoint of `pad`: crops the padding, adding the mirrored pixels back to the pixels they were copied from.
+    fn unpad(&self, rows: (usize, usize), cols: (usize, usize), mirror: bool) -> Self;
+
     fn tile(&self, repetitions_row: usize, repetitions_col: usize, repetitions_chan: usize, repetition_sample: usize) -> Self;
 
     fn square(&self) -> Self;
@@ -123,13 +137,25 @@ pub trait ImageTrait {
 
     fn mean_along(&self, dim: usize) -> Self;
 
+    fn max_along(&self, dim: usize) -> Self;
+
+    /// Returns an image of the same shape holding 1 at the position of the maximum along `dim`, and 0 elsewhere.
+    ///
+    /// Only the first maximum is kept when there are ties.
+    fn argmax_mask_along(&self, dim: usize) -> Self;
+
     fn exp(&self) -> Self;
 
     fn maxof(&self, other: &Self) -> Self;
 
     fn sign(&self) -> Self;
 
+    /// Same as `sign` but 0 where the components are 0.
+    fn sign_zero_at_zero(&self) -> Self;
+
     fn minof(&self, other: &Self) -> Self;
 
     fn sqrt(&self) -> Self;
+
+    fn log(&self) -> Self;
 }
@@ -48,6 +48,10 @@ impl ImageTrait for Image {
         unimplemented!()
     }
 
+    fn from_samples_with_dims(samples: &Matrix, img_dims: (usize, usize), channels: usize) -> Self {
+        unimplemented!()
+    }
+
     fn wrap(
         &self,
         ox: usize,
@@ -66,6 +70,14 @@ impl ImageTrait for Image {
         unimplemented!()
     }
 
+    fn pad(&self, rows: (usize, usize), cols: (usize, usize), mirror: bool) -> Self {
+        unimplemented!()
+    }
+
+    fn unpad(&self, rows: (usize, usize), cols: (usize, usize), mirror: bool) -> Self {
+        unimplemented!()
+    }
+
     fn tile(
         &self,
         repetitions_row: usize,
@@ -112,6 +124,10 @@ impl ImageTrait for Image {
         unimplemented!()
     }
 
+    fn cross_correlate_strided(&self, kernels: &Self, stride: usize) -> Self {
+        unimplemented!()
+    }
+
     fn convolve_full(&self, kernels: &Self) -> Self {
         unimplemented!()
     }
@@ -172,6 +188,14 @@ impl ImageTrait for Image {
         unimplemented!()
     }
 
+    fn max_along(&self, dim: usize) -> Self {
+        unimplemented!()
+    }
+
+    fn argmax_mask_along(&self, dim: usize) -> Self {
+        unimplemented!()
+    }
+
     fn exp(&self) -> Self {
         unimplemented!()
     }
@@ -184,6 +208,10 @@ impl ImageTrait for Image {
         unimplemented!()
     }
 
+    fn sign_zero_at_zero(&self) -> Self {
+        unimplemented!()
+    }
+
     fn minof(&self, other: &Self) -> Self {
         unimplemented!()
     }
@@ -191,4 +219,8 @@ impl ImageTrait for Image {
     fn sqrt(&self) -> Self {
         unimplemented!()
     }
+
+    fn log(&self) -> Self {
+        unimplemented!()
+    }
 }
@@ -1,18 +1,22 @@
-use crate::linalg::{Matrix, Scalar};
+use ndarray::{concatenate, s, Array4, Axis, Zip};
+use rand::Rng;
+use rand_distr::{Distribution, Normal};
+
+use crate::linalg::{sign_zero_at_zero, Matrix, MatrixTrait, Scalar};
 
 use super::ImageTrait;
 
+/// Pixels are indexed as `[row, col, channel, sample]`.
 #[derive(Clone, Debug)]
-pub struct Image(usize);
+pub struct Image(pub Array4<Scalar>);
 
-#[allow(unused_variables)]
 impl ImageTrait for Image {
     fn zeros(nrow: usize, ncol: usize, nchan: usize, samples: usize) -> Self {
-        unimplemented!()
+        Self(Array4::zeros((nrow, ncol, nchan, samples)))
     }
 
     fn constant(nrow: usize, ncol: usize, nchan: usize, samples: usize, value: Scalar) -> Self {
-        unimplemented!()
+        Self(Array4::from_elem((nrow, ncol, nchan, samples), value))
     }
 
     fn random_uniform(
@@ -23,7 +27,10 @@ impl ImageTrait for Image {
         min: Scalar,
         max: Scalar,
     ) -> Self {
-        unimplemented!()
+        let mut rng = rand::thread_rng();
+        Self(Array4::from_shape_fn((nrow, ncol, nchan, samples), |_| {
+            rng.gen_range(min..max)
+        }))
     }
 
     fn random_normal(
@@ -34,18 +41,34 @@ impl ImageTrait for Image {
         mean: Scalar,
         stddev: Scalar,
     ) -> Self {
-        unimplemented!()
+        let mut rng = rand::thread_rng();
+        let normal = Normal::new(mean, stddev).unwrap();
+        Self(Array4::from_shape_fn((nrow, ncol, nchan, samples), |_| {
+            normal.sample(&mut rng)
+        }))
     }
 
-    fn from_fn<F>(nrows: usize, ncols: usize, nchan: usize, samples: usize, f: F) -> Self
+    fn from_fn<F>(nrows: usize, ncols: usize, nchan: usize, samples: usize, mut f: F) -> Self
     where
         F: FnMut(usize, usize, usize, usize) -> Scalar,
     {
-        unimplemented!()
+        Self(Array4::from_shape_fn(
+            (nrows, ncols, nchan, samples),
+            |(row, col, chan, sample)| f(col, row, chan, sample),
+        ))
     }
 
     fn from_samples(samples: &Matrix, channels: usize) -> Self {
-        unimplemented!()
+        let image_size = ((samples.dim().0 / channels) as f64).sqrt() as usize;
+        Self::from_samples_with_dims(samples, (image_size, image_size), channels)
+    }
+
+    fn from_samples_with_dims(samples: &Matrix, img_dims: (usize, usize), channels: usize) -> Self {
+        let (rows, cols) = img_dims;
+        Self(Array4::from_shape_fn(
+            (rows, cols, channels, samples.dim().1),
+            |(row, col, chan, sample)| samples.index(row + col * rows + chan * rows * cols, sample),
+        ))
     }
 
     fn wrap(
@@ -59,11 +82,70 @@ impl ImageTrait for Image {
         px: usize,
         py: usize,
     ) -> Self {
-        unimplemented!()
+        let (_, _, nchan) = self.image_dims();
+        let nx = (ox + 2 * px - wx) / sx + 1;
+        let ny = (oy + 2 * py - wy) / sy + 1;
+        let mut res = Array4::zeros((ox, oy, nchan, self.samples()));
+
+        for ((window, patch, chan, sample), value) in self.0.indexed_iter() {
+            let row = (patch % nx) * sx + window % wx;
+            let col = (patch / nx) * sy + window / wx;
+            if patch < nx * ny && row >= px && row < ox + px && col >= py && col < oy + py {
+                res[[row - px, col - py, chan, sample]] += value;
+            }
+        }
+        Self(res)
     }
 
     fn unwrap(&self, wx: usize, wy: usize, sx: usize, sy: usize, px: usize, py: usize) -> Self {
-        unimplemented!()
+        let (rows, cols, nchan) = self.image_dims();
+        let nx = (rows + 2 * px - wx) / sx + 1;
+        let ny = (cols + 2 * py - wy) / sy + 1;
+
+        Self(Array4::from_shape_fn(
+            (wx * wy, nx * ny, nchan, self.samples()),
+            |(window, patch, chan, sample)| {
+                let row = (patch % nx) * sx + window % wx;
+                let col = (patch / nx) * sy + window / wx;
+                if row >= px && row < rows + px && col >= py && col < cols + py {
+                    self.0[[row - px, col - py, chan, sample]]
+                } else {
+                    0.0
+                }
+            },
+        ))
+    }
+
+    fn pad(&self, rows: (usize, usize), cols: (usize, usize), mirror: bool) -> Self {
+        let (nrows, ncols, nchan) = self.image_dims();
+        Self(Array4::from_shape_fn(
+            (nrows + rows.0 + rows.1, ncols + cols.0 + cols.1, nchan, self.samples()),
+            |(row, col, chan, sample)| {
+                match (
+                    padded_source(row, rows.0, nrows, mirror),
+                    padded_source(col, cols.0, ncols, mirror),
+                ) {
+                    (Some(row), Some(col)) => self.0[[row, col, chan, sample]],
+                    _ => 0.0,
+                }
+            },
+        ))
+    }
+
+    fn unpad(&self, rows: (usize, usize), cols: (usize, usize), mirror: bool) -> Self {
+        let (nrows, ncols, nchan) = self.image_dims();
+        let (nrows, ncols) = (nrows - rows.0 - rows.1, ncols - cols.0 - cols.1);
+        let mut res = Array4::zeros((nrows, ncols, nchan, self.samples()));
+
+        for ((row, col, chan, sample), value) in self.0.indexed_iter() {
+            if let (Some(row), Some(col)) = (
+                padded_source(row, rows.0, nrows, mirror),
+                padded_source(col, cols.0, ncols, mirror),
+            ) {
+                res[[row, col, chan, sample]] += value;
+            }
+        }
+        Self(res)
     }
 
     fn tile(
@@ -73,122 +155,238 @@ impl ImageTrait for Image {
         repetitions_chan: usize,
         repetition_sample: usize,
     ) -> Self {
-        unimplemented!()
+        let (rows, cols, nchan) = self.image_dims();
+        let samples = self.samples();
+        Self(Array4::from_shape_fn(
+            (
+                rows * repetitions_row,
+                cols * repetitions_col,
+                nchan * repetitions_chan,
+                samples * repetition_sample,
+            ),
+            |(row, col, chan, sample)| {
+                self.0[[row % rows, col % cols, chan % nchan, sample % samples]]
+            },
+        ))
     }
 
     fn component_add(&self, other: &Self) -> Self {
-        unimplemented!()
+        self.component_op(other, |a, b| a + b)
     }
 
     fn component_sub(&self, other: &Self) -> Self {
-        unimplemented!()
+        self.component_op(other, |a, b| a - b)
     }
 
     fn component_mul(&self, other: &Self) -> Self {
-        unimplemented!()
+        self.component_op(other, |a, b| a * b)
     }
 
     fn component_div(&self, other: &Self) -> Self {
-        unimplemented!()
+        self.component_op(other, |a, b| a / b)
     }
 
     fn scalar_add(&self, scalar: Scalar) -> Self {
-        unimplemented!()
+        Self(&self.0 + scalar)
     }
 
     fn scalar_sub(&self, scalar: Scalar) -> Self {
-        unimplemented!()
+        Self(&self.0 - scalar)
     }
 
     fn scalar_mul(&self, scalar: Scalar) -> Self {
-        unimplemented!()
+        Self(&self.0 * scalar)
     }
 
     fn scalar_div(&self, scalar: Scalar) -> Self {
-        unimplemented!()
+        Self(&self.0 / scalar)
     }
 
     fn cross_correlate(&self, kernels: &Self) -> Self {
-        unimplemented!()
+        self.cross_correlate_strided(kernels, 1)
+    }
+
+    fn cross_correlate_strided(&self, kernels: &Self, stride: usize) -> Self {
+        let (rows, cols, nchan) = self.image_dims();
+        let (krows, kcols, _) = kernels.image_dims();
+
+        Self(Array4::from_shape_fn(
+            ((rows - krows) / stride + 1, (cols - kcols) / stride + 1, kernels.samples(), self.samples()),
+            |(row, col, kernel, sample)| {
+                let (row, col) = (row * stride, col * stride);
+                let mut sum = 0.0;
+                for chan in 0..nchan {
+                    for i in 0..krows {
+                        for j in 0..kcols {
+                            sum += self.0[[row + i, col + j, chan, sample]]
+                                * kernels.0[[i, j, chan, kernel]];
+                        }
+                    }
+                }
+                sum
+            },
+        ))
     }
 
     fn convolve_full(&self, kernels: &Self) -> Self {
-        unimplemented!()
+        let (rows, cols, nchan) = self.image_dims();
+        let (krows, kcols, _) = kernels.image_dims();
+
+        Self(Array4::from_shape_fn(
+            (rows + krows - 1, cols + kcols - 1, kernels.samples(), self.samples()),
+            |(row, col, kernel, sample)| {
+                let mut sum = 0.0;
+                for chan in 0..nchan {
+                    for i in row.saturating_sub(rows - 1)..krows.min(row + 1) {
+                        for j in col.saturating_sub(cols - 1)..kcols.min(col + 1) {
+                            sum += self.0[[row - i, col - j, chan, sample]]
+                                * kernels.0[[i, j, chan, kernel]];
+                        }
+                    }
+                }
+                sum
+            },
+        ))
     }
 
     fn flatten(&self) -> Matrix {
-        unimplemented!()
+        let (rows, cols, _) = self.image_dims();
+        let image_size = rows * cols * self.channels();
+        Matrix::from_fn(image_size, self.samples(), |i, sample| {
+            self.0[[i % rows, (i / rows) % cols, i / (rows * cols), sample]]
+        })
     }
 
     fn image_dims(&self) -> (usize, usize, usize) {
-        unimplemented!()
+        let (rows, cols, nchan, _) = self.0.dim();
+        (rows, cols, nchan)
     }
 
     fn channels(&self) -> usize {
-        unimplemented!()
+        self.0.dim().2
     }
 
     fn samples(&self) -> usize {
-        unimplemented!()
+        self.0.dim().3
     }
 
     fn get_sample(&self, sample: usize) -> Self {
-        unimplemented!()
+        Self(self.0.slice(s![.., .., .., sample..sample + 1]).to_owned())
     }
 
     fn get_channel(&self, channel: usize) -> Self {
-        unimplemented!()
+        Self(self.0.slice(s![.., .., channel..channel + 1, 0..1]).to_owned())
     }
 
     fn get_channel_across_samples(&self, channel: usize) -> Self {
-        unimplemented!()
+        Self(self.0.slice(s![.., .., channel..channel + 1, ..]).to_owned())
     }
 
     fn sum_samples(&self) -> Self {
-        unimplemented!()
+        Self(self.0.sum_axis(Axis(3)).insert_axis(Axis(3)))
     }
 
     fn join_channels(channels: Vec<Self>) -> Self {
-        unimplemented!()
+        let views: Vec<_> = channels.iter().map(|c| c.0.view()).collect();
+        Self(concatenate(Axis(2), &views).unwrap())
     }
 
     fn join_samples(samples: Vec<Self>) -> Self {
-        unimplemented!()
+        let views: Vec<_> = samples.iter().map(|s| s.0.view()).collect();
+        Self(concatenate(Axis(3), &views).unwrap())
     }
 
     fn square(&self) -> Self {
-        unimplemented!()
+        Self(self.0.mapv(|x| x * x))
     }
 
     fn sum(&self) -> Scalar {
-        unimplemented!()
+        self.0.sum()
     }
 
     fn mean(&self) -> Scalar {
-        unimplemented!()
+        self.0.mean().unwrap()
     }
 
     fn mean_along(&self, dim: usize) -> Self {
-        unimplemented!()
+        Self(self.0.mean_axis(Axis(dim)).unwrap().insert_axis(Axis(dim)))
+    }
+
+    fn max_along(&self, dim: usize) -> Self {
+        Self(
+            self.0
+                .fold_axis(Axis(dim), Scalar::NEG_INFINITY, |max, x| max.max(*x))
+                .insert_axis(Axis(dim)),
+        )
+    }
+
+    fn argmax_mask_along(&self, dim: usize) -> Self {
+        let mut mask = Array4::zeros(self.0.dim());
+        Zip::from(self.0.lanes(Axis(dim)))
+            .and(mask.lanes_mut(Axis(dim)))
+            .for_each(|lane, mut mask_lane| {
+                let mut argmax = 0;
+                for (i, x) in lane.iter().enumerate() {
+                    if *x > lane[argmax] {
+                        argmax = i;
+                    }
+                }
+                mask_lane[argmax] = 1.0;
+            });
+        Self(mask)
     }
 
     fn exp(&self) -> Self {
-        unimplemented!()
+        Self(self.0.mapv(Scalar::exp))
     }
 
     fn maxof(&self, other: &Self) -> Self {
-        unimplemented!()
+        Self(Zip::from(&self.0).and(&other.0).map_collect(|a, b| a.max(*b)))
     }
 
     fn sign(&self) -> Self {
-        unimplemented!()
+        Self(self.0.mapv(|x| if x < 0.0 { -1.0 } else { 1.0 }))
+    }
+
+    fn sign_zero_at_zero(&self) -> Self {
+        Self(self.0.mapv(sign_zero_at_zero))
     }
 
     fn minof(&self, other: &Self) -> Self {
-        unimplemented!()
+        Self(Zip::from(&self.0).and(&other.0).map_collect(|a, b| a.min(*b)))
     }
 
     fn sqrt(&self) -> Self {
-        unimplemented!()
+        Self(self.0.mapv(Scalar::sqrt))
+    }
+
+    fn log(&self) -> Self {
+        Self(self.0.mapv(Scalar::ln))
+    }
+}
+
+impl Image {
+    /// Applies `op` component-wise, broadcasting the first sample of `other` if it has a different amount of samples.
+    fn component_op(&self, other: &Self, op: impl Fn(Scalar, Scalar) -> Scalar) -> Self {
+        let other = if self.samples() == other.samples() {
+            other.0.view()
+        } else {
+            other.0.slice(s![.., .., .., 0..1])
+        };
+        Self(Zip::from(&self.0).and_broadcast(&other).map_collect(|a, b| op(*a, *b)))
+    }
+}
+
+/// Position in the unpadded dimension of size `size` the padded position `i` is copied from,
+/// `before` being the padding added before it. Mirrored padding includes the border.
+fn padded_source(i: usize, before: usize, size: usize, mirror: bool) -> Option<usize> {
+    if i >= before && i < before + size {
+        Some(i - before)
+    } else if !mirror {
+        None
+    } else if i < before {
+        Some((before - 1 - i) % size)
+    } else {
+        Some(size - 1 - (i - before - size) % size)
     }
 }
@@ -1,6 +1,21 @@
-use super::image::Image;
+use crate::{layer::GradientLayer, linalg::Scalar};
 
-pub trait ImageLayer {
+use super::image::{Image, ImageTrait};
+
+pub trait ImageLayer: GradientLayer {
     fn forward(&mut self, input: Image) -> Image;
     fn backward(&mut self, epoch: usize, output_gradient: Image) -> Image;
+
+    /// Penalty added to the reported training loss by the layer's regularization, if any.
+    fn regularization_loss(&self) -> Scalar {
+        0.0
+    }
+}
+
+/// Adds `gradient` to the one accumulated in `acc`, if any.
+pub(crate) fn accumulate_image_gradient(acc: &mut Option<Image>, gradient: Image) {
+    *acc = Some(match acc.take() {
+        Some(acc) => acc.component_add(&gradient),
+        None => gradient,
+    });
 }
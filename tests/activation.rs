@@ -0,0 +1,170 @@
+use jiro_nn::{
+    activation::{prelu, Activation},
+    layer::{GradientLayer, Layer, LearnableLayer},
+    linalg::{Matrix, MatrixTrait, Scalar},
+    model::network_model::{NetworkLayerModels, NetworkModel, NetworkModelBuilder},
+    optimizer::{sgd::SGD, Optimizers},
+};
+
+fn inputs() -> Vec<Scalar> {
+    vec![-4.0, -2.5, -1.0, -0.3, 0.2, 0.7, 1.5, 3.5]
+}
+
+fn forward(activation: Activation, input: &[Scalar]) -> Vec<Scalar> {
+    activation
+        .to_layer()
+        .forward(Matrix::from_column_vector(&input.to_vec()))
+        .get_column(0)
+}
+
+fn assert_close(actual: &[Scalar], expected: &[Scalar], tolerance: Scalar) {
+    for (a, e) in actual.iter().zip(expected.iter()) {
+        assert!((a - e).abs() < tolerance, "{:?} != {:?}", actual, expected);
+    }
+}
+
+#[test]
+fn test_parameterized_activations_values() {
+    let x = inputs();
+    let sigmoid = |v: Scalar| 1.0 / (1.0 + (-v).exp());
+    let softplus = |v: Scalar| (1.0 + v.exp()).ln();
+
+    let cases: Vec<(Activation, Box<dyn Fn(Scalar) -> Scalar>)> = vec![
+        (
+            Activation::LeakyReLU(0.1),
+            Box::new(|v| if v > 0.0 { v } else { 0.1 * v }),
+        ),
+        (
+            Activation::ELU(0.5),
+            Box::new(|v| if v > 0.0 { v } else { 0.5 * (v.exp() - 1.0) }),
+        ),
+        (
+            Activation::SELU,
+            Box::new(|v| 1.050_701 * if v > 0.0 { v } else { 1.673_263_2 * (v.exp() - 1.0) }),
+        ),
+        (
+            Activation::GELU,
+            Box::new(|v| {
+                0.5 * v * (1.0 + ((2.0 / std::f32::consts::PI as Scalar).sqrt() * (v + 0.044715 * v.powi(3))).tanh())
+            }),
+        ),
+        (Activation::Swish, Box::new(move |v| v * sigmoid(v))),
+        (Activation::Softplus, Box::new(softplus)),
+        (
+            Activation::HardSigmoid,
+            Box::new(|v| (v / 6.0 + 0.5).clamp(0.0, 1.0)),
+        ),
+        (Activation::Mish, Box::new(move |v| v * softplus(v).tanh())),
+        (
+            Activation::PReLU(0.25),
+            Box::new(|v| if v > 0.0 { v } else { 0.25 * v }),
+        ),
+    ];
+
+    for (activation, f) in cases {
+        let expected: Vec<Scalar> = x.iter().map(|v| f(*v)).collect();
+        assert_close(&forward(activation, &x), &expected, 1e-4);
+    }
+}
+
+#[test]
+fn test_parameterized_activations_derivatives() {
+    let x = inputs();
+    let h = 1e-3;
+    let activations = vec![
+        Activation::LeakyReLU(0.1),
+        Activation::ELU(0.5),
+        Activation::SELU,
+        Activation::GELU,
+        Activation::Swish,
+        Activation::Softplus,
+        Activation::HardSigmoid,
+        Activation::Mish,
+    ];
+
+    for activation in activations {
+        let mut layer = activation.to_layer();
+        layer.forward(Matrix::from_column_vector(&x));
+        let gradient = layer
+            .backward(0, Matrix::constant(x.len(), 1, 1.0))
+            .get_column(0);
+
+        // central finite differences
+        let plus: Vec<Scalar> = x.iter().map(|v| v + h).collect();
+        let minus: Vec<Scalar> = x.iter().map(|v| v - h).collect();
+        let expected: Vec<Scalar> = forward(activation, &plus)
+            .iter()
+            .zip(forward(activation, &minus).iter())
+            .map(|(p, m)| (p - m) / (2.0 * h))
+            .collect();
+
+        assert_close(&gradient, &expected, 1e-2);
+    }
+}
+
+#[test]
+fn test_prelu_learns_its_slope() {
+    let mut layer = prelu::new(0.25, Optimizers::SGD(SGD::with_const_lr(0.1)));
+    assert_eq!(layer.get_learnable_parameters(), vec![vec![0.25]]);
+
+    let output = layer.forward(Matrix::from_column_vector(&vec![-2.0, 1.0, -1.0]));
+    assert_close(&output.get_column(0), &[-0.5, 1.0, -0.25], 1e-6);
+
+    // ∂E/∂slope = Σ ∂E/∂Y ⊙ min(x, 0) = -3
+    let input_gradient = layer.backward(0, Matrix::constant(3, 1, 1.0));
+    assert_close(&input_gradient.get_column(0), &[0.25, 1.0, 0.25], 1e-6);
+    assert_eq!(layer.get_learnable_parameters(), vec![vec![0.25]]);
+    layer.step(0);
+    assert_close(&layer.get_learnable_parameters()[0], &[0.55], 1e-5);
+
+    layer.set_learnable_parameters(&vec![vec![0.1]]);
+    let output = layer.forward(Matrix::from_column_vector(&vec![-2.0]));
+    assert_close(&output.get_column(0), &[-0.2], 1e-6);
+}
+
+#[test]
+fn test_activations_model() {
+    let model = NetworkModelBuilder::new()
+        .full_dense(8)
+        .leaky_relu(0.01)
+        .end()
+        .full_dense(8)
+        .prelu(0.25)
+        .end()
+        .full_dense(8)
+        .gelu()
+        .end()
+        .full_dense(1)
+        .silu()
+        .end()
+        .build();
+
+    let json = serde_json::to_string(&model).unwrap();
+    assert!(json.contains("{\"LeakyReLU\":0.01}"));
+    assert!(json.contains("{\"PReLU\":0.25}"));
+
+    let model: NetworkModel = serde_json::from_str(&json).unwrap();
+    assert!(matches!(
+        &model.layers[0],
+        NetworkLayerModels::FullDense(l) if matches!(l.activation, Activation::LeakyReLU(a) if a == 0.01)
+    ));
+    assert!(matches!(
+        &model.layers[3],
+        NetworkLayerModels::FullDense(l) if matches!(l.activation, Activation::Swish)
+    ));
+
+    // SiLU is accepted as an alias of Swish
+    let silu: Activation = serde_json::from_str("\"SiLU\"").unwrap();
+    assert!(matches!(silu, Activation::Swish));
+
+    // PReLU's slope is saved with the layer's params
+    let mut network = model.to_network(4);
+    let mut params = network.get_params();
+    assert_eq!(params.0[0].len(), 5);
+    assert_eq!(params.0[1].len(), 10);
+    assert_eq!(params.0[1][9], vec![0.25]);
+
+    params.0[1][9] = vec![0.5];
+    network.load_params(&params);
+    assert_eq!(network.get_params().0[1][9], vec![0.5]);
+}
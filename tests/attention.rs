@@ -0,0 +1,175 @@
+#[macro_use]
+extern crate assert_float_eq;
+
+use jiro_nn::{
+    initializers::Initializers,
+    layer::{
+        multi_head_attention_layer::MultiHeadAttentionLayer,
+        transformer_encoder_block::TransformerEncoderBlock, Layer, LearnableLayer,
+    },
+    linalg::{Matrix, MatrixTrait, Scalar},
+    model::network_model::{NetworkModel, NetworkModelBuilder},
+    optimizer::{sgd::SGD, Optimizers},
+    regularization::Regularization,
+};
+
+fn frozen() -> Optimizers {
+    Optimizers::SGD(SGD::with_const_lr(0.0))
+}
+
+// 3 tokens of 4 features, 2 samples
+fn input() -> Matrix {
+    Matrix::random_uniform(12, 2, -1.0, 1.0)
+}
+
+fn check_input_gradient(layer: &mut dyn Layer) {
+    let input = input();
+    let output = layer.forward(input.clone());
+    assert_eq!(output.dim(), input.dim());
+    let output_gradient = Matrix::random_uniform(output.dim().0, output.dim().1, -1.0, 1.0);
+    let input_gradient = layer.backward(0, output_gradient.clone());
+
+    let mut weighted_output = |input: &Matrix| -> Scalar {
+        layer
+            .forward(input.clone())
+            .component_mul(&output_gradient)
+            .get_data_col_leading()
+            .iter()
+            .flatten()
+            .sum()
+    };
+
+    let h = 1e-3;
+    let columns = input.get_data_col_leading();
+    for j in 0..columns.len() {
+        for i in 0..columns[j].len() {
+            let mut plus = columns.clone();
+            plus[j][i] += h;
+            let mut minus = columns.clone();
+            minus[j][i] -= h;
+            let numeric = (weighted_output(&Matrix::from_column_leading_vector2(&plus))
+                - weighted_output(&Matrix::from_column_leading_vector2(&minus)))
+                / (2. * h);
+            assert_float_absolute_eq!(input_gradient.get_column(j)[i], numeric, 0.01);
+        }
+    }
+}
+
+#[test]
+fn test_multi_head_attention_gradients() {
+    let mut layer = MultiHeadAttentionLayer::new(
+        4,
+        2,
+        frozen(),
+        frozen(),
+        Initializers::GlorotUniform,
+        Initializers::UniformSigned,
+    );
+    check_input_gradient(&mut layer);
+}
+
+#[test]
+fn test_transformer_encoder_block_gradients() {
+    let mut layer = TransformerEncoderBlock::new(
+        4,
+        2,
+        8,
+        1e-5,
+        frozen(),
+        frozen(),
+        Initializers::GlorotUniform,
+        Initializers::UniformSigned,
+    );
+    check_input_gradient(&mut layer);
+}
+
+#[test]
+fn test_model_json_and_params_round_trip() {
+    let model = NetworkModelBuilder::new()
+        .multi_head_attention(2)
+        .in_features(4)
+        .adam()
+        .end()
+        .transformer_encoder(2)
+        .in_features(4)
+        .feed_forward_size(8)
+        .end()
+        .full_dense(1)
+        .linear()
+        .end()
+        .build();
+
+    let json = serde_json::to_string(&model).unwrap();
+    let model: NetworkModel = serde_json::from_str(&json).unwrap();
+
+    let mut network = model.clone().to_network(12);
+    let params = network.get_params();
+    // 4 projections of 4 weights columns and 1 biases column
+    assert_eq!(params.0[0].len(), 4 * 5);
+
+    let mut loaded = model.to_network(12);
+    loaded.load_params(&params);
+    assert_eq!(loaded.get_params().0, params.0);
+
+    let x = input().get_column(0);
+    assert_eq!(loaded.predict(&x), network.predict(&x));
+
+    let mut block = TransformerEncoderBlock::new(
+        4,
+        1,
+        2,
+        1e-5,
+        frozen(),
+        frozen(),
+        Initializers::GlorotUniform,
+        Initializers::GlorotUniform,
+    );
+    let params = block.get_learnable_parameters();
+    block.set_learnable_parameters(&params);
+    assert_eq!(block.get_learnable_parameters(), params);
+}
+
+#[test]
+fn test_transformer_encoder_in_residual_params() {
+    let model = NetworkModelBuilder::new()
+        .residual()
+            .transformer_encoder(2)
+            .in_features(4)
+            .feed_forward_size(8)
+            .end()
+        .end_residual()
+        .build();
+
+    let mut network = model.clone().to_network(12);
+    let params = network.get_params();
+
+    let mut loaded = model.to_network(12);
+    loaded.load_params(&params);
+    assert_eq!(loaded.get_params().0, params.0);
+
+    let x = input().get_column(0);
+    assert_eq!(loaded.predict(&x), network.predict(&x));
+}
+
+#[test]
+fn test_attention_regularization_loss() {
+    let layer = MultiHeadAttentionLayer::new(
+        4,
+        2,
+        frozen(),
+        frozen(),
+        Initializers::UniformSigned,
+        Initializers::UniformSigned,
+    )
+    .with_regularization(Regularization::l2(0.1));
+
+    // each of the 4 projections has 4 weights columns followed by 1 biases column
+    let params = layer.get_learnable_parameters();
+    let squares: Scalar = params
+        .chunks(5)
+        .flat_map(|projection| projection[..4].concat())
+        .map(|w| w * w)
+        .sum();
+    assert!(squares > 0.0);
+    assert_float_absolute_eq!(layer.regularization_loss(), 0.1 * squares, 1e-5);
+}
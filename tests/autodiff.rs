@@ -0,0 +1,162 @@
+#[macro_use]
+extern crate assert_float_eq;
+
+use jiro_nn::{
+    autodiff::Tape,
+    initializers::Initializers,
+    layer::{
+        autodiff_layer::AutodiffLayer, dense_layer::DenseLayer, DropoutLayer, GradientLayer, Layer,
+        LearnableLayer,
+    },
+    linalg::{Matrix, MatrixTrait},
+    optimizer::{sgd::SGD, Optimizers},
+};
+
+fn assert_matrices_eq(a: &Matrix, b: &Matrix) {
+    assert_eq!(a.dim(), b.dim());
+    for (col_a, col_b) in a
+        .get_data_col_leading()
+        .iter()
+        .zip(b.get_data_col_leading().iter())
+    {
+        for (x, y) in col_a.iter().zip(col_b.iter()) {
+            assert_float_absolute_eq!(x, y, 0.00001);
+        }
+    }
+}
+
+#[test]
+fn test_dot_gradients() {
+    let a = Matrix::from_row_leading_vector2(&vec![vec![1.0, 2.0], vec![3.0, 4.0]]);
+    let b = Matrix::from_row_leading_vector2(&vec![vec![0.5, -1.0, 2.0], vec![1.5, 0.0, -2.0]]);
+    let g = Matrix::from_row_leading_vector2(&vec![vec![1.0, 0.0, 2.0], vec![-1.0, 1.0, 0.5]]);
+
+    let mut tape = Tape::new();
+    let va = tape.var(a.clone());
+    let vb = tape.var(b.clone());
+    let out = tape.dot(va, vb);
+
+    let grads = tape.backward(out, g.clone());
+    assert_matrices_eq(grads.get(va).unwrap(), &g.dot(&b.transpose()));
+    assert_matrices_eq(grads.get(vb).unwrap(), &a.transpose().dot(&g));
+}
+
+#[test]
+fn test_gradients_accumulate() {
+    let x = Matrix::from_column_vector(&vec![1.0, -2.0, 3.0]);
+
+    let mut tape = Tape::new();
+    let vx = tape.var(x.clone());
+    let squared = tape.mul(vx, vx);
+    let out = tape.sum(squared);
+
+    let grads = tape.backward(out, Matrix::constant(1, 1, 1.0));
+    assert_matrices_eq(grads.get(vx).unwrap(), &x.scalar_mul(2.0));
+}
+
+#[test]
+fn test_constants_have_no_gradient() {
+    let mut tape = Tape::new();
+    let vx = tape.var(Matrix::constant(2, 2, 1.0));
+    let vc = tape.constant(Matrix::constant(2, 2, 3.0));
+    let out = tape.mul(vx, vc);
+
+    let grads = tape.backward(out, Matrix::constant(2, 2, 1.0));
+    assert_matrices_eq(grads.get(vx).unwrap(), &Matrix::constant(2, 2, 3.0));
+    assert!(grads.get(vc).is_none());
+}
+
+#[test]
+fn test_dense_layer_matches_hand_written_gradients() {
+    let lr = 0.1;
+    let mut layer = DenseLayer::new(
+        3,
+        2,
+        Optimizers::SGD(SGD::with_const_lr(lr)),
+        Optimizers::SGD(SGD::with_const_lr(lr)),
+        Initializers::GlorotUniform,
+        Initializers::GlorotUniform,
+    );
+    let weights = layer.weights.clone();
+    let biases = layer.biases.clone();
+
+    let input = Matrix::from_column_leading_vector2(&vec![vec![1.0, 2.0, 3.0], vec![-1.0, 0.5, 0.0]]);
+    let output_gradient = Matrix::from_column_leading_vector2(&vec![vec![0.5, -0.5], vec![1.0, 2.0]]);
+
+    let output = layer.forward(input.clone());
+    let expected_output = weights
+        .dot(&input)
+        .component_add(&biases.dot(&Matrix::constant(1, 2, 1.0)));
+    assert_matrices_eq(&output, &expected_output);
+
+    let input_gradient = layer.backward(0, output_gradient.clone());
+    assert_matrices_eq(&input_gradient, &weights.transpose().dot(&output_gradient));
+    layer.step(0);
+
+    let weights_gradient = output_gradient.dot(&input.transpose());
+    let biases_gradient = output_gradient.columns_sum();
+    assert_matrices_eq(
+        &layer.weights,
+        &weights.component_sub(&weights_gradient.scalar_mul(lr)),
+    );
+    assert_matrices_eq(
+        &layer.biases,
+        &biases.component_sub(&biases_gradient.scalar_mul(lr)),
+    );
+}
+
+fn new_dense_layer() -> DenseLayer {
+    DenseLayer::new(
+        3,
+        2,
+        Optimizers::SGD(SGD::with_const_lr(0.1)),
+        Optimizers::SGD(SGD::with_const_lr(0.1)),
+        Initializers::GlorotUniform,
+        Initializers::GlorotUniform,
+    )
+}
+
+#[test]
+fn test_dense_layer_inference_skips_the_tape() {
+    let mut layer = new_dense_layer();
+    let input = Matrix::from_column_leading_vector2(&vec![vec![1.0, 2.0, 3.0], vec![-1.0, 0.5, 0.0]]);
+    let training_output = layer.forward(input.clone());
+
+    layer.disable_dropout();
+    assert_matrices_eq(&layer.forward(input.clone()), &training_output);
+
+    layer.enable_dropout();
+    layer.forward(input);
+    layer.backward(0, Matrix::constant(2, 2, 1.0));
+}
+
+#[test]
+#[should_panic(expected = "needs a forward pass in training mode")]
+fn test_dense_layer_backward_after_inference() {
+    let mut layer = new_dense_layer();
+    layer.disable_dropout();
+    layer.forward(Matrix::constant(3, 2, 1.0));
+    layer.backward(0, Matrix::constant(2, 2, 1.0));
+}
+
+#[test]
+fn test_autodiff_layer_learns_parameters() {
+    let weights = Matrix::from_row_leading_vector2(&vec![vec![1.0, -1.0]]);
+    let mut layer = AutodiffLayer::new(
+        Box::new(|tape, input, params| tape.dot(params[0], input)),
+        vec![weights.clone()],
+        vec![Optimizers::SGD(SGD::with_const_lr(1.0))],
+    );
+
+    let input = Matrix::from_column_vector(&vec![2.0, 3.0]);
+    let output = layer.forward(input.clone());
+    assert_matrices_eq(&output, &Matrix::constant(1, 1, -1.0));
+
+    let input_gradient = layer.backward(0, Matrix::constant(1, 1, 1.0));
+    assert_matrices_eq(&input_gradient, &weights.transpose());
+    layer.step(0);
+
+    let params = layer.get_learnable_parameters();
+    assert_float_absolute_eq!(params[0][0], -1.0, 0.00001);
+    assert_float_absolute_eq!(params[1][0], -4.0, 0.00001);
+}
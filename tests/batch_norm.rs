@@ -0,0 +1,111 @@
+#[macro_use]
+extern crate assert_float_eq;
+
+use jiro_nn::{
+    layer::{batch_norm_layer::BatchNormLayer, DropoutLayer, Layer, LearnableLayer},
+    linalg::{Matrix, MatrixTrait, Scalar},
+    optimizer::{sgd::SGD, Optimizers},
+    vision::{
+        conv_layer::batch_norm_2d_layer::BatchNorm2dLayer,
+        conv_optimizer::conv_sgd,
+        image::{Image, ImageTrait},
+        image_layer::ImageLayer,
+    },
+};
+
+fn new_layer(i: usize, lr: Scalar) -> BatchNormLayer {
+    BatchNormLayer::new(
+        i,
+        0.9,
+        1e-5,
+        Optimizers::SGD(SGD::with_const_lr(lr)),
+        Optimizers::SGD(SGD::with_const_lr(lr)),
+    )
+}
+
+#[test]
+fn test_training_normalizes_across_batch() {
+    let mut layer = new_layer(2, 0.1);
+    layer.enable_dropout();
+
+    let input = Matrix::from_row_leading_vector2(&vec![
+        vec![1.0, 2.0, 3.0, 4.0],
+        vec![-10.0, 0.0, 10.0, 20.0],
+    ]);
+    let output = layer.forward(input);
+
+    for row in output.transpose().get_data_col_leading() {
+        let mean = row.iter().sum::<Scalar>() / row.len() as Scalar;
+        let var = row.iter().map(|x| (x - mean).powi(2)).sum::<Scalar>() / row.len() as Scalar;
+        assert_float_absolute_eq!(mean, 0.0, 0.0001);
+        assert_float_absolute_eq!(var, 1.0, 0.001);
+    }
+
+    let params = layer.get_learnable_parameters();
+    assert_float_absolute_eq!(params[2][0], 0.25, 0.0001);
+    assert_float_absolute_eq!(params[2][1], 0.5, 0.0001);
+    // unbiased variances 5/3 and 500/3
+    assert_float_absolute_eq!(params[3][0], 0.9 + 0.1 * 5. / 3., 0.0001);
+    assert_float_absolute_eq!(params[3][1], 0.9 + 0.1 * 500. / 3., 0.001);
+}
+
+#[test]
+fn test_input_gradient_matches_finite_differences() {
+    let input = Matrix::from_row_leading_vector2(&vec![vec![0.5, -1.0, 2.0], vec![3.0, 1.0, -2.0]]);
+    let output_gradient =
+        Matrix::from_row_leading_vector2(&vec![vec![1.0, -0.5, 0.2], vec![0.3, 0.7, -1.0]]);
+
+    let weighted_output = |input: &Matrix| -> Scalar {
+        let mut layer = new_layer(2, 0.0);
+        layer.enable_dropout();
+        layer
+            .forward(input.clone())
+            .component_mul(&output_gradient)
+            .get_data_col_leading()
+            .iter()
+            .flatten()
+            .sum()
+    };
+
+    let mut layer = new_layer(2, 0.0);
+    layer.enable_dropout();
+    layer.forward(input.clone());
+    let input_gradient = layer.backward(0, output_gradient.clone());
+
+    let h = 1e-3;
+    let columns = input.get_data_col_leading();
+    for j in 0..columns.len() {
+        for i in 0..columns[j].len() {
+            let mut plus = columns.clone();
+            plus[j][i] += h;
+            let mut minus = columns.clone();
+            minus[j][i] -= h;
+            let numeric = (weighted_output(&Matrix::from_column_leading_vector2(&plus))
+                - weighted_output(&Matrix::from_column_leading_vector2(&minus)))
+                / (2. * h);
+            assert_float_absolute_eq!(input_gradient.get_column(j)[i], numeric, 0.001);
+        }
+    }
+}
+
+#[test]
+fn test_inference_uses_loaded_running_statistics() {
+    let mut layer = new_layer(1, 0.1);
+    layer.set_learnable_parameters(&vec![vec![2.0], vec![1.0], vec![3.0], vec![4.0]]);
+    layer.disable_dropout();
+
+    let output = layer.forward(Matrix::from_row_leading_vector2(&vec![vec![5.0, 3.0]]));
+    assert_float_absolute_eq!(output.get_column(0)[0], 3.0, 0.0001);
+    assert_float_absolute_eq!(output.get_column(1)[0], 1.0, 0.0001);
+}
+
+#[test]
+fn test_2d_running_variance_is_unbiased() {
+    let mut layer = BatchNorm2dLayer::new(1, 0.9, 1e-5, conv_sgd(), conv_sgd());
+    layer.enable_dropout();
+    layer.forward(Image::from_samples(&Matrix::from_column_vector(&vec![1.0, 2.0, 3.0, 4.0]), 1));
+
+    let params = layer.get_learnable_parameters();
+    assert_float_absolute_eq!(params[2][0], 0.25, 0.0001);
+    assert_float_absolute_eq!(params[3][0], 0.9 + 0.1 * 5. / 3., 0.0001);
+}
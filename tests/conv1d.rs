@@ -0,0 +1,154 @@
+use jiro_nn::{
+    model::{
+        conv1d_network_model::Conv1dNetworkLayerModels,
+        network_model::{NetworkLayerModels, NetworkModel, NetworkModelBuilder},
+    },
+    vision::{
+        conv_initializers::ConvInitializers,
+        conv_layer::{
+            conv1d_layer::Conv1dLayer, conv_padding::ConvPaddings, pool1d_layer::MaxPool1dLayer,
+        },
+    },
+};
+
+#[test]
+fn test_conv1d_out_dims() {
+    assert_eq!(
+        Conv1dLayer::out_length_and_channels(10, 3, 8, 1, ConvPaddings::Valid),
+        (8, 8)
+    );
+    assert_eq!(
+        Conv1dLayer::out_length_and_channels(10, 3, 4, 2, ConvPaddings::Same),
+        (5, 4)
+    );
+    assert_eq!(MaxPool1dLayer::new(2, 2, 0).out_length(9), 4);
+    assert_eq!(MaxPool1dLayer::new(3, 1, 1).out_length(9), 9);
+}
+
+#[test]
+fn test_conv1d_network_model() {
+    let model = NetworkModelBuilder::new()
+        .conv1d_network(3)
+            .conv1d(8, 5)
+                .same_padding()
+                .stride(2)
+                .kernels_init_uniform()
+            .end()
+            .max_pool1d(2, 2, 0)
+            .conv1d(4, 3)
+            .end()
+            .avg_pool1d(2)
+        .end()
+        .full_dense(10)
+        .end()
+        .build();
+
+    let json = serde_json::to_string(&model).unwrap();
+    let model: NetworkModel = serde_json::from_str(&json).unwrap();
+
+    let conv = match &model.layers[0] {
+        NetworkLayerModels::Conv1d(conv) => conv.clone(),
+        _ => panic!("Expected a 1D convolutional network"),
+    };
+    assert_eq!(conv.in_channels, 3);
+    match &conv.layers[0] {
+        Conv1dNetworkLayerModels::Conv1d(layer) => {
+            assert_eq!((layer.kernels_count, layer.kernels_size, layer.stride), (8, 5, 2));
+            assert_eq!(layer.padding, ConvPaddings::Same);
+            assert!(matches!(layer.kernels_initializer, ConvInitializers::Uniform));
+            assert!(matches!(layer.biases_initializer, ConvInitializers::Zeros));
+        }
+        _ => panic!("Expected a 1D convolutional layer"),
+    }
+
+
+    let (out_length, out_channels, _) = conv.layers[1].clone().to_conv_layer(16, 8);
+    assert_eq!((out_length, out_channels), (8, 8));
+    let (out_length, out_channels, _) = conv.layers[3].clone().to_conv_layer(6, 4);
+    assert_eq!((out_length, out_channels), (3, 4));
+}
+
+#[test]
+#[should_panic(expected = "cannot split 10 input values into 3 channels")]
+fn test_conv1d_network_channels_mismatch() {
+    let model = NetworkModelBuilder::new()
+        .conv1d_network(3)
+            .max_pool1d(2, 2, 0)
+        .end()
+        .build();
+
+    model.to_layers(10);
+}
+
+mod image_layers {
+    use jiro_nn::{
+        linalg::{MatrixTrait, Scalar},
+        model::network_model::NetworkModelBuilder,
+        vision::{
+            conv_initializers::ConvInitializers,
+            conv_layer::{
+                conv1d_layer::Conv1dLayer,
+                conv_padding::ConvPaddings,
+                pool1d_layer::{AvgPool1dLayer, MaxPool1dLayer},
+            },
+            conv_optimizer::conv_sgd,
+            image::{Image, ImageTrait},
+            image_layer::ImageLayer,
+        },
+    };
+
+    #[test]
+    fn test_pool1d() {
+        let input = Image::from_fn(6, 1, 1, 1, |_, y, _, _| [1., 3., 2., 0., 5., 4.][y] as Scalar);
+
+        let mut max = MaxPool1dLayer::new(2, 2, 0);
+        assert_eq!(max.forward(input.clone()).flatten().get_column(0), vec![3., 2., 5.]);
+        let input_gradient = max.backward(0, Image::constant(3, 1, 1, 1, 1.0));
+        assert_eq!(input_gradient.flatten().get_column(0), vec![0., 1., 1., 0., 1., 0.]);
+
+        let mut avg = AvgPool1dLayer::new(3);
+        assert_eq!(avg.forward(input).flatten().get_column(0), vec![2., 3.]);
+        let input_gradient = avg.backward(0, Image::constant(2, 1, 1, 1, 3.0));
+        assert_eq!(input_gradient.flatten().get_column(0), vec![1.0; 6]);
+    }
+
+    #[test]
+    fn test_conv1d_forward_backward() {
+        let mut layer = Conv1dLayer::new(
+            3,
+            2,
+            4,
+            1,
+            ConvPaddings::Zero(1),
+            ConvInitializers::UniformSigned,
+            ConvInitializers::UniformSigned,
+            conv_sgd(),
+            conv_sgd(),
+        );
+        let input = Image::random_uniform(10, 1, 2, 3, -1.0, 1.0);
+
+        let output = layer.forward(input);
+        assert_eq!(output.image_dims(), (10, 1, 4));
+
+        let input_gradient = layer.backward(0, Image::random_uniform(10, 1, 4, 3, -1.0, 1.0));
+        assert_eq!(input_gradient.image_dims(), (10, 1, 2));
+    }
+
+    #[test]
+    fn test_conv1d_network() {
+        let mut network = NetworkModelBuilder::new()
+            .conv1d_network(2)
+                .conv1d(4, 3)
+                    .same_padding()
+                .end()
+                .max_pool1d(2, 2, 0)
+            .end()
+            .build()
+            .to_network(2 * 16);
+
+        let inputs = vec![vec![0.5; 2 * 16]; 5];
+        let outputs = network.predict_many(&inputs, 5);
+        assert_eq!(outputs.len(), 5);
+        assert!(outputs.iter().all(|output| output.len() == 4 * 8));
+    }
+}
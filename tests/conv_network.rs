@@ -0,0 +1,145 @@
+use jiro_nn::{
+    linalg::Scalar,
+    loss::Losses,
+    model::{
+        conv_network_model::{ConvNetworkLayerModels, ConvNetworkModel},
+        network_model::{NetworkLayerModels, NetworkModel, NetworkModelBuilder},
+    },
+};
+
+fn conv_network_model(model: NetworkModel) -> ConvNetworkModel {
+    match model.layers.into_iter().next() {
+        Some(NetworkLayerModels::Convolution(conv)) => conv,
+        _ => panic!("Expected a convolutional network"),
+    }
+}
+
+#[test]
+fn test_rectangular_images() {
+    let model = NetworkModelBuilder::new()
+        .conv_network(3)
+            .in_img_dims(6, 12)
+            .max_pooling(2, 2, 0)
+            .avg_pooling(3)
+        .end()
+        .build();
+
+    let json = serde_json::to_string(&model).unwrap();
+    let model: NetworkModel = serde_json::from_str(&json).unwrap();
+
+    let conv = conv_network_model(model.clone());
+    assert_eq!(conv.in_img_dims(3 * 6 * 12), (6, 12));
+
+    let (out_dims, _) = model.to_layers(3 * 6 * 12);
+    assert_eq!(out_dims, 3 * 1 * 2);
+
+    let (out_img_dims, _, _) = ConvNetworkLayerModels::MaxPooling {
+        window: 3,
+        stride: 1,
+        padding: 0,
+    }
+    .to_conv_layer((5, 9), 3);
+    assert_eq!(out_img_dims, (3, 7));
+}
+
+#[test]
+fn test_rectangular_params_round_trip() {
+    let model = NetworkModelBuilder::new()
+        .conv_network(2)
+            .in_img_dims(5, 8)
+            .full_dense(3, 3)
+            .end()
+            .full_direct(3)
+                .same_padding()
+            .end()
+            .transposed_conv(2, 2)
+            .end()
+        .end()
+        .build();
+
+    let x = vec![(0..80).map(|i| (i as Scalar / 10.0).cos()).collect::<Vec<_>>()];
+    let y = vec![vec![0.5; 2 * 4 * 7]];
+    let mut network = model.clone().to_network(80);
+    network.train(0, &x, &y, &Losses::MSE.to_loss(), 1);
+    let params = network.get_params();
+    // the biases follow each layer's kernels (3, then 1 for the direct conv, then 2) and hold one value per channel
+    let biases_lens: Vec<usize> = [1 + 3, 5 + 1 + 1, 8 + 2 + 1].iter().map(|&i| params.0[0][i].len()).collect();
+    assert_eq!(biases_lens, vec![3, 3, 2]);
+
+    // trained params load in a fresh network of rectangular images
+    let mut loaded = model.to_network(80);
+    loaded.load_params(&params);
+    assert_eq!(loaded.get_params().0, params.0);
+    assert_eq!(loaded.predict(&x[0]), network.predict(&x[0]));
+}
+
+#[test]
+#[should_panic(expected = "Expected one bias per channel")]
+fn test_tiled_biases_params_rejected() {
+    let model = NetworkModelBuilder::new()
+        .conv_network(1)
+            .in_img_dims(4, 4)
+            .full_dense(2, 3)
+            .end()
+        .end()
+        .build();
+
+    // biases tiled to the 2x2 output's pixels, as they were saved by earlier versions
+    let mut network = model.to_network(16);
+    let mut params = network.get_params();
+    params.0[0][1 + 2] = vec![0.1; 2 * 2 * 2];
+    network.load_params(&params);
+}
+
+#[test]
+fn test_square_images_by_default() {
+    let conv = conv_network_model(
+        NetworkModelBuilder::new()
+            .conv_network(2)
+                .max_pooling(2, 2, 0)
+            .end()
+            .build(),
+    );
+    assert_eq!(conv.in_img_dims(2 * 8 * 8), (8, 8));
+
+    // models saved before input images dimensions existed still load
+    let json = serde_json::to_string(&conv).unwrap().replace(",\"in_img_dims\":null", "");
+    assert!(!json.contains("in_img_dims"));
+    let conv: ConvNetworkModel = serde_json::from_str(&json).unwrap();
+    assert_eq!(conv.in_img_dims, None);
+}
+
+#[test]
+#[should_panic(expected = "expects 4x5 images with 2 channels (40 values) but its input has 36 values")]
+fn test_mismatched_input_dims() {
+    NetworkModelBuilder::new()
+        .conv_network(2)
+            .in_img_dims(4, 5)
+            .max_pooling(2, 2, 0)
+        .end()
+        .build()
+        .to_network(36);
+}
+
+#[test]
+#[should_panic(expected = "set its input images dimensions with in_img_dims(rows, cols)")]
+fn test_non_square_input_without_dims() {
+    NetworkModelBuilder::new()
+        .conv_network(1)
+            .max_pooling(2, 2, 0)
+        .end()
+        .build()
+        .to_network(6 * 4);
+}
+
+#[test]
+#[should_panic(expected = "Average pooling of size 2 cannot split 3x4 images")]
+fn test_avg_pooling_mismatch() {
+    NetworkModelBuilder::new()
+        .conv_network(1)
+            .in_img_dims(3, 4)
+            .avg_pooling(2)
+        .end()
+        .build()
+        .to_network(12);
+}
@@ -0,0 +1,215 @@
+use jiro_nn::{
+    linalg::{Matrix, MatrixTrait, Scalar},
+    model::{
+        conv_network_model::ConvNetworkLayerModels,
+        network_model::{NetworkLayerModels, NetworkModel, NetworkModelBuilder},
+    },
+    vision::{
+        conv_initializers::ConvInitializers,
+        conv_layer::{
+            conv_padding::{subsample, ConvPaddings}, dense_conv_layer::DenseConvLayer,
+            direct_conv_layer::DirectConvLayer,
+        },
+        conv_optimizer::conv_sgd,
+        image::{Image, ImageTrait},
+        image_layer::ImageLayer,
+    },
+};
+
+#[test]
+fn test_padding_amounts() {
+    assert_eq!(ConvPaddings::Valid.amounts(8, 3, 1), (0, 0));
+    assert_eq!(ConvPaddings::Zero(2).amounts(8, 3, 2), (2, 2));
+    assert_eq!(ConvPaddings::Reflect(1).amounts(8, 3, 1), (1, 1));
+    assert_eq!(ConvPaddings::Same.amounts(8, 3, 1), (1, 1));
+    // even kernels pad one more pixel after
+    assert_eq!(ConvPaddings::Same.amounts(8, 4, 1), (1, 2));
+    assert_eq!(ConvPaddings::Same.amounts(7, 3, 2), (1, 1));
+}
+
+#[test]
+fn test_out_img_dims() {
+    assert_eq!(
+        DenseConvLayer::out_img_dims_and_channels(8, 8, 3, 3, 4, 1, ConvPaddings::Valid),
+        (6, 6, 4)
+    );
+    assert_eq!(
+        DenseConvLayer::out_img_dims_and_channels(8, 8, 3, 3, 4, 1, ConvPaddings::Same),
+        (8, 8, 4)
+    );
+    assert_eq!(
+        DenseConvLayer::out_img_dims_and_channels(7, 7, 3, 3, 4, 2, ConvPaddings::Same),
+        (4, 4, 4)
+    );
+    assert_eq!(
+        DenseConvLayer::out_img_dims_and_channels(8, 8, 3, 3, 4, 2, ConvPaddings::Zero(1)),
+        (4, 4, 4)
+    );
+}
+
+#[test]
+fn test_strided_model() {
+    let model = NetworkModelBuilder::new()
+        .conv_network(1)
+            .full_dense(4, 3)
+                .same_padding()
+            .end()
+            .full_dense(8, 3)
+                .stride(2)
+                .zero_padding(1)
+            .end()
+            .full_direct(3)
+                .reflect_padding(1)
+            .end()
+        .end()
+        .build();
+
+    let json = serde_json::to_string(&model).unwrap();
+    let model: NetworkModel = serde_json::from_str(&json).unwrap();
+
+    let conv = match &model.layers[0] {
+        NetworkLayerModels::Convolution(conv) => conv,
+        _ => panic!("Expected a convolutional network"),
+    };
+    match &conv.layers[1] {
+        ConvNetworkLayerModels::FullDenseConv(layer) => {
+            assert_eq!(layer.stride, 2);
+            assert_eq!(layer.padding, ConvPaddings::Zero(1));
+        }
+        _ => panic!("Expected a dense convolutional layer"),
+    }
+    match &conv.layers[2] {
+        ConvNetworkLayerModels::FullDirectConv(layer) => {
+            assert_eq!(layer.stride, 1);
+            assert_eq!(layer.padding, ConvPaddings::Reflect(1));
+        }
+        _ => panic!("Expected a direct convolutional layer"),
+    }
+}
+
+#[test]
+fn test_defaults_to_valid_stride_1() {
+    let model = NetworkModelBuilder::new()
+        .conv_network(1)
+            .full_dense(2, 3)
+            .end()
+        .end()
+        .build();
+
+    // models saved before stride and padding existed still load
+    let json = serde_json::to_string(&model)
+        .unwrap()
+        .replace("\"stride\":1,\"padding\":\"Valid\",", "");
+    assert!(!json.contains("stride"));
+    let model: NetworkModel = serde_json::from_str(&json).unwrap();
+
+    match &model.layers[0] {
+        NetworkLayerModels::Convolution(conv) => match &conv.layers[0] {
+            ConvNetworkLayerModels::FullDenseConv(layer) => {
+                assert_eq!(layer.stride, 1);
+                assert_eq!(layer.padding, ConvPaddings::Valid);
+            }
+            _ => panic!("Expected a dense convolutional layer"),
+        },
+        _ => panic!("Expected a convolutional network"),
+    }
+}
+
+fn dot(a: &Image, b: &Image) -> Scalar {
+    a.component_mul(b).sum()
+}
+
+#[test]
+fn test_unpad_is_adjoint_of_pad() {
+    let x = Image::random_uniform(4, 5, 2, 2, -1.0, 1.0);
+    let y = Image::random_uniform(7, 7, 2, 2, -1.0, 1.0);
+    for mirror in [false, true] {
+        let padded = x.pad((1, 2), (2, 0), mirror);
+        assert_eq!(padded.image_dims(), (7, 7, 2));
+        let unpadded = y.unpad((1, 2), (2, 0), mirror);
+        assert_eq!(unpadded.image_dims(), (4, 5, 2));
+        assert!((dot(&padded, &y) - dot(&x, &unpadded)).abs() < 1e-4);
+    }
+}
+
+/// Compares the input gradient of `layer` against central differences of `Σ G ⊙ forward(x)`.
+fn check_input_gradient(mut layer: impl ImageLayer, input: Image, out_dims: (usize, usize, usize)) {
+    let (rows, cols, chans) = input.image_dims();
+    let output_gradient = Image::random_uniform(out_dims.0, out_dims.1, out_dims.2, 1, -1.0, 1.0);
+
+    let output = layer.forward(input.clone());
+    assert_eq!(output.image_dims(), out_dims);
+    let input_gradient = layer.backward(0, output_gradient.clone()).flatten().get_column(0);
+
+    let pixels = input.flatten().get_column(0);
+    let epsilon = 1e-2;
+    for i in 0..pixels.len() {
+        let mut objective = |shift: Scalar| {
+            let mut shifted = pixels.clone();
+            shifted[i] += shift;
+            let shifted = Image::from_samples_with_dims(&Matrix::from_column_vector(&shifted), (rows, cols), chans);
+            dot(&layer.forward(shifted), &output_gradient)
+        };
+        let numerical = (objective(epsilon) - objective(-epsilon)) / (2.0 * epsilon);
+        assert!((numerical - input_gradient[i]).abs() < 1e-2, "{} != {}", numerical, input_gradient[i]);
+    }
+}
+
+#[test]
+fn test_strided_padded_input_gradients() {
+    let input = Image::random_uniform(7, 6, 2, 1, -1.0, 1.0);
+    let dense = |stride, padding| {
+        DenseConvLayer::new(
+            3,
+            3,
+            2,
+            3,
+            stride,
+            padding,
+            ConvInitializers::UniformSigned,
+            ConvInitializers::UniformSigned,
+            conv_sgd(),
+            conv_sgd(),
+        )
+    };
+
+    check_input_gradient(dense(2, ConvPaddings::Reflect(1)), input.clone(), (4, 3, 3));
+    check_input_gradient(dense(2, ConvPaddings::Same), input.clone(), (4, 3, 3));
+    check_input_gradient(dense(1, ConvPaddings::Zero(2)), input.clone(), (9, 8, 3));
+    check_input_gradient(
+        DirectConvLayer::new(
+            3,
+            3,
+            2,
+            2,
+            ConvPaddings::Reflect(1),
+            ConvInitializers::UniformSigned,
+            ConvInitializers::UniformSigned,
+            conv_sgd(),
+            conv_sgd(),
+        ),
+        input,
+        (4, 3, 2),
+    );
+}
+
+#[test]
+fn test_strided_cross_correlation_matches_subsampling() {
+    let image = Image::from_fn(7, 6, 2, 2, |r, c, ch, s| {
+        ((r * 6 + c + ch * 42 + s * 84) as Scalar * 0.37).sin()
+    });
+    let kernels = Image::from_fn(3, 3, 2, 4, |r, c, ch, k| {
+        ((r * 3 + c + ch * 9 + k * 18) as Scalar * 0.53).cos()
+    });
+
+    for stride in 1..4 {
+        let strided = image.cross_correlate_strided(&kernels, stride);
+        let expected = subsample(&image.cross_correlate(&kernels), stride);
+        assert_eq!(strided.image_dims(), expected.image_dims());
+        let strided = strided.flatten().get_data_col_leading();
+        let expected = expected.flatten().get_data_col_leading();
+        for (a, e) in strided.iter().flatten().zip(expected.iter().flatten()) {
+            assert!((a - e).abs() < 1e-5);
+        }
+    }
+}
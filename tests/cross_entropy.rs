@@ -0,0 +1,130 @@
+use jiro_nn::{
+    activation::softmax,
+    layer::Layer,
+    linalg::{Matrix, MatrixTrait, Scalar},
+    loss::{sparse_cce::one_hot, Losses},
+    model::network_model::NetworkModelBuilder,
+};
+
+fn assert_close(actual: &Matrix, expected: &Matrix, tolerance: Scalar) {
+    let (actual, expected) = (actual.get_data_col_leading(), expected.get_data_col_leading());
+    for (a, e) in actual.iter().flatten().zip(expected.iter().flatten()) {
+        assert!((a - e).abs() < tolerance, "{:?} != {:?}", actual, expected);
+    }
+}
+
+fn cce(label_smoothing: Scalar) -> Losses {
+    Losses::CategoricalCrossEntropy { label_smoothing }
+}
+
+#[test]
+fn test_categorical_cross_entropy() {
+    let loss = cce(0.0).to_loss();
+    let y_true = Matrix::from_column_leading_vector2(&vec![vec![1.0, 0.0, 0.0], vec![0.0, 0.0, 1.0]]);
+    let y_pred = Matrix::from_column_leading_vector2(&vec![vec![0.7, 0.2, 0.1], vec![0.3, 0.3, 0.4]]);
+
+    let expected = -(0.7 as Scalar).ln() / 2.0 - (0.4 as Scalar).ln() / 2.0;
+    assert!((loss.loss(&y_true, &y_pred) - expected).abs() < 1e-5);
+
+    // a confident wrong prediction doesn't give an infinite loss
+    let wrong = Matrix::from_column_leading_vector2(&vec![vec![0.0, 1.0, 0.0], vec![0.0, 0.0, 1.0]]);
+    assert!(loss.loss(&y_true, &wrong).is_finite());
+}
+
+#[test]
+fn test_label_smoothing() {
+    let y_true = Matrix::from_column_leading_vector2(&vec![vec![1.0, 0.0, 0.0, 0.0]]);
+    let y_pred = Matrix::from_column_leading_vector2(&vec![vec![0.97, 0.01, 0.01, 0.01]]);
+
+    // smoothed labels are [0.925, 0.025, 0.025, 0.025]
+    let gradient = cce(0.1).to_loss().softmax_loss_prime(&y_true, &y_pred).unwrap();
+    let expected = Matrix::from_column_leading_vector2(&vec![vec![0.045, -0.015, -0.015, -0.015]]);
+    assert_close(&gradient, &expected, 1e-5);
+
+    assert!(cce(0.1).to_loss().loss(&y_true, &y_pred) > cce(0.0).to_loss().loss(&y_true, &y_pred));
+}
+
+#[test]
+fn test_fused_softmax_gradient_matches_jacobian() {
+    let logits = Matrix::from_column_leading_vector2(&vec![vec![2.0, -1.0, 0.5], vec![0.1, 0.2, 3.0]]);
+    let y_true = Matrix::from_column_leading_vector2(&vec![vec![0.0, 1.0, 0.0], vec![0.0, 0.0, 1.0]]);
+
+    for label_smoothing in [0.0, 0.2] {
+        let loss = cce(label_smoothing).to_loss();
+        let mut layer = softmax::new();
+        let y_pred = layer.forward(logits.clone());
+
+        let through_jacobian = layer.backward(0, loss.loss_prime(&y_true, &y_pred));
+        let fused = loss.softmax_loss_prime(&y_true, &y_pred).unwrap();
+        assert_close(&fused, &through_jacobian, 1e-4);
+    }
+}
+
+#[test]
+fn test_sparse_categorical_cross_entropy() {
+    let ids = Matrix::from_row_vector(&vec![0.0, 2.0]);
+    let y_true = one_hot(&ids, 3);
+    assert_eq!(y_true.get_data_col_leading(), vec![vec![1.0, 0.0, 0.0], vec![0.0, 0.0, 1.0]]);
+
+    let y_pred = Matrix::from_column_leading_vector2(&vec![vec![0.7, 0.2, 0.1], vec![0.3, 0.3, 0.4]]);
+    let sparse = Losses::SparseCategoricalCrossEntropy { label_smoothing: 0.1 }.to_loss();
+    let dense = cce(0.1).to_loss();
+
+    assert!((sparse.loss(&ids, &y_pred) - dense.loss(&y_true, &y_pred)).abs() < 1e-6);
+    assert_close(&sparse.loss_prime(&ids, &y_pred), &dense.loss_prime(&y_true, &y_pred), 1e-6);
+    assert_close(
+        &sparse.softmax_loss_prime(&ids, &y_pred).unwrap(),
+        &dense.softmax_loss_prime(&y_true, &y_pred).unwrap(),
+        1e-6,
+    );
+}
+
+#[test]
+#[should_panic(expected = "Class id 3 is out of the 3 predicted classes")]
+fn test_sparse_class_out_of_range() {
+    one_hot(&Matrix::from_row_vector(&vec![3.0]), 3);
+}
+
+#[test]
+fn test_softmax_classifier_training() {
+    let model = NetworkModelBuilder::new()
+        .full_dense(8)
+        .tanh()
+        .adam()
+        .end()
+        .full_dense(3)
+        .softmax()
+        .adam()
+        .end()
+        .build();
+
+    let json = serde_json::to_string(&Losses::SparseCategoricalCrossEntropy { label_smoothing: 0.05 }).unwrap();
+    assert_eq!(json, "{\"SparseCategoricalCrossEntropy\":{\"label_smoothing\":0.05}}");
+    let loss: Losses = serde_json::from_str("{\"SparseCategoricalCrossEntropy\":{}}").unwrap();
+    let loss = loss.to_loss();
+
+    // the class is the index of the largest input
+    let x: Vec<Vec<Scalar>> = (0..60)
+        .map(|i| {
+            let mut x = vec![0.1, 0.1, 0.1];
+            x[i % 3] = 1.0;
+            x[(i + 1) % 3] = (i % 5) as Scalar / 10.0;
+            x
+        })
+        .collect();
+    let y: Vec<Vec<Scalar>> = (0..60).map(|i| vec![(i % 3) as Scalar]).collect();
+
+    let mut network = model.to_network(3);
+    let first = network.train(0, &x, &y, &loss, 10);
+    let mut last = first;
+    for epoch in 1..100 {
+        last = network.train(epoch, &x, &y, &loss, 10);
+    }
+    assert!(last < first / 4.0, "{} -> {}", first, last);
+
+    let preds = network.predict_many(&x, 10);
+    for (pred, y) in preds.iter().zip(y.iter()) {
+        let class = (0..3).max_by(|a, b| pred[*a].total_cmp(&pred[*b])).unwrap();
+        assert_eq!(class as Scalar, y[0]);
+    }
+}
@@ -0,0 +1,103 @@
+use jiro_nn::{
+    activation::{register_activation, Activation, ActivationFunction},
+    layer::Layer,
+    linalg::{Matrix, MatrixTrait, Scalar},
+    loss::{register_loss, LossFunction, Losses},
+    model::network_model::{NetworkLayerModels, NetworkModel, NetworkModelBuilder},
+};
+
+struct Cube;
+
+impl ActivationFunction for Cube {
+    fn activate(&self, m: &Matrix) -> Matrix {
+        m.square().component_mul(m)
+    }
+
+    fn derivative(&self, m: &Matrix) -> Matrix {
+        m.square().scalar_mul(3.0)
+    }
+}
+
+// pinball loss, averaged over all the outputs
+struct Quantile(Scalar);
+
+impl LossFunction for Quantile {
+    fn loss(&self, y_true: &Matrix, y_pred: &Matrix) -> Scalar {
+        let errors = y_true.component_sub(y_pred);
+        let zeros = Matrix::constant(errors.dim().0, errors.dim().1, 0.0);
+        errors
+            .maxof(&zeros)
+            .scalar_mul(self.0)
+            .component_sub(&errors.minof(&zeros).scalar_mul(1.0 - self.0))
+            .mean()
+    }
+
+    fn loss_prime(&self, y_true: &Matrix, y_pred: &Matrix) -> Matrix {
+        let (nrow, ncol) = y_pred.dim();
+        let under = y_true.component_sub(y_pred).sign().maxof(&Matrix::constant(nrow, ncol, 0.0));
+        under
+            .scalar_mul(-1.0)
+            .scalar_add(1.0 - self.0)
+            .scalar_div((nrow * ncol) as Scalar)
+    }
+}
+
+#[test]
+fn test_custom_activation() {
+    register_activation("cube", Cube);
+
+    let model = NetworkModelBuilder::new()
+        .full_dense(4)
+        .activation(Activation::custom("cube"))
+        .end()
+        .build();
+
+    let json = serde_json::to_string(&model).unwrap();
+    assert!(json.contains("{\"Custom\":\"cube\"}"));
+    let model: NetworkModel = serde_json::from_str(&json).unwrap();
+    assert!(matches!(
+        &model.layers[0],
+        NetworkLayerModels::FullDense(l) if matches!(&l.activation, Activation::Custom(name) if name.as_str() == "cube")
+    ));
+
+    let mut layer = Activation::custom("cube").to_layer();
+    let output = layer.forward(Matrix::from_column_vector(&vec![-2.0, 0.5]));
+    assert_eq!(output.get_column(0), vec![-8.0, 0.125]);
+    let gradient = layer.backward(0, Matrix::constant(2, 1, 2.0));
+    assert_eq!(gradient.get_column(0), vec![24.0, 1.5]);
+
+    let mut network = model.to_network(3);
+    assert_eq!(network.predict(&vec![0.1, 0.2, 0.3]).len(), 4);
+}
+
+#[test]
+fn test_custom_loss() {
+    register_loss("quantile_0.9", Quantile(0.9));
+
+    let losses: Losses = serde_json::from_str("{\"Custom\":\"quantile_0.9\"}").unwrap();
+    assert_eq!(serde_json::to_string(&losses).unwrap(), "{\"Custom\":\"quantile_0.9\"}");
+
+    let loss = losses.to_loss();
+    let y_true = Matrix::from_column_vector(&vec![1.0, 1.0]);
+    let y_pred = Matrix::from_column_vector(&vec![0.0, 3.0]);
+    // (0.9 * 1 + 0.1 * 2) / 2
+    assert!((loss.loss(&y_true, &y_pred) - 0.55).abs() < 1e-6);
+    let prime = loss.loss_prime(&y_true, &y_pred).get_column(0);
+    assert!((prime[0] + 0.45).abs() < 1e-6 && (prime[1] - 0.05).abs() < 1e-6);
+
+    // built-in losses are unchanged
+    let mse: Losses = serde_json::from_str("\"MSE\"").unwrap();
+    assert!(matches!(mse, Losses::MSE));
+}
+
+#[test]
+#[should_panic(expected = "Loss unknown is not registered")]
+fn test_unregistered_loss() {
+    Losses::Custom("unknown".to_string()).to_loss();
+}
+
+#[test]
+#[should_panic(expected = "Activation unknown is not registered")]
+fn test_unregistered_activation() {
+    Activation::custom("unknown").to_layer();
+}
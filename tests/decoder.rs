@@ -0,0 +1,143 @@
+use jiro_nn::{
+    model::{
+        conv_network_model::ConvNetworkLayerModels,
+        network_model::{NetworkLayerModels, NetworkModel, NetworkModelBuilder},
+    },
+    vision::{
+        conv_initializers::ConvInitializers,
+        conv_layer::{transposed_conv_layer::TransposedConvLayer, upsampling_layer::UpsamplingModes},
+    },
+};
+
+#[test]
+fn test_transposed_conv_out_dims() {
+    assert_eq!(
+        TransposedConvLayer::out_img_dims_and_channels(4, 4, 3, 3, 8, 1),
+        (6, 6, 8)
+    );
+    assert_eq!(
+        TransposedConvLayer::out_img_dims_and_channels(4, 5, 2, 2, 3, 2),
+        (8, 10, 3)
+    );
+}
+
+#[test]
+fn test_decoder_model() {
+    let model = NetworkModelBuilder::new()
+        .conv_network(4)
+            .in_img_dims(2, 3)
+            .transposed_conv(8, 2)
+                .stride(2)
+                .kernels_init_uniform_signed()
+                .relu()
+            .end()
+            .upsampling_bilinear(2)
+            .upsampling_nearest(3)
+        .end()
+        .build();
+
+    let json = serde_json::to_string(&model).unwrap();
+    let model: NetworkModel = serde_json::from_str(&json).unwrap();
+
+    let conv = match &model.layers[0] {
+        NetworkLayerModels::Convolution(conv) => conv.clone(),
+        _ => panic!("Expected a convolutional network"),
+    };
+    match &conv.layers[0] {
+        ConvNetworkLayerModels::TransposedConv(layer) => {
+            assert_eq!((layer.kernels_count, layer.kernels_size, layer.stride), (8, 2, 2));
+            assert!(matches!(layer.kernels_initializer, ConvInitializers::UniformSigned));
+            assert!(matches!(layer.biases_initializer, ConvInitializers::Zeros));
+        }
+        _ => panic!("Expected a transposed convolutional layer"),
+    }
+
+    let mut img_dims = (4, 6);
+    for (layer, expected) in conv.layers.into_iter().skip(1).zip([(8, 12), (24, 36)]) {
+        let (out_img_dims, out_channels, _) = layer.to_conv_layer(img_dims, 8);
+        assert_eq!((out_img_dims, out_channels), (expected, 8));
+        img_dims = out_img_dims;
+    }
+}
+
+#[test]
+fn test_upsampling_modes_json() {
+    let layer = ConvNetworkLayerModels::Upsampling {
+        factor: 2,
+        mode: UpsamplingModes::Bilinear,
+    };
+    let json = serde_json::to_string(&layer).unwrap();
+    assert_eq!(json, "{\"Upsampling\":{\"factor\":2,\"mode\":\"Bilinear\"}}");
+}
+
+mod image_layers {
+    use jiro_nn::{
+        linalg::{MatrixTrait, Scalar},
+        vision::{
+            conv_initializers::ConvInitializers,
+            conv_layer::{
+                transposed_conv_layer::TransposedConvLayer,
+                upsampling_layer::{UpsamplingLayer, UpsamplingModes},
+            },
+            conv_optimizer::conv_sgd,
+            image::{Image, ImageTrait},
+            image_layer::ImageLayer,
+        },
+    };
+
+    #[test]
+    fn test_nearest_upsampling() {
+        let input = Image::from_fn(2, 2, 1, 1, |x, y, _, _| (x * 2 + y) as Scalar);
+        let mut layer = UpsamplingLayer::new(2, UpsamplingModes::Nearest);
+
+        let output = layer.forward(input);
+        assert_eq!(output.image_dims(), (4, 4, 1));
+        assert_eq!(
+            output.flatten().get_column(0),
+            vec![0., 0., 1., 1., 0., 0., 1., 1., 2., 2., 3., 3., 2., 2., 3., 3.]
+        );
+
+        // each input pixel gathers the gradient of its 4 copies
+        let input_gradient = layer.backward(0, Image::constant(4, 4, 1, 1, 1.0));
+        assert_eq!(input_gradient.flatten().get_column(0), vec![4.0; 4]);
+    }
+
+    #[test]
+    fn test_transposed_conv_input_gradient() {
+        let mut layer = TransposedConvLayer::new(
+            2,
+            2,
+            2,
+            3,
+            2,
+            ConvInitializers::UniformSigned,
+            ConvInitializers::UniformSigned,
+            conv_sgd(),
+            conv_sgd(),
+        );
+        let input = Image::random_uniform(3, 3, 2, 1, -1.0, 1.0);
+        let output_gradient = Image::random_uniform(6, 6, 3, 1, -1.0, 1.0);
+
+        let loss = |layer: &mut TransposedConvLayer, input: &Image| {
+            layer.forward(input.clone()).component_mul(&output_gradient).sum()
+        };
+        let base = loss(&mut layer, &input);
+        let input_values = input.flatten().get_column(0);
+
+        let h = 1e-2;
+        let mut numerical = vec![];
+        for i in 0..input_values.len() {
+            let shifted = Image::from_fn(3, 3, 2, 1, |x, y, c, _| {
+                let j = c * 9 + x * 3 + y;
+                input_values[j] + if j == i { h } else { 0.0 }
+            });
+            numerical.push((loss(&mut layer, &shifted) - base) / h);
+        }
+
+        layer.forward(input);
+        let analytical = layer.backward(0, output_gradient.clone()).flatten().get_column(0);
+        for (a, n) in analytical.iter().zip(numerical.iter()) {
+            assert!((a - n).abs() < 1e-2);
+        }
+    }
+}
@@ -0,0 +1,137 @@
+use jiro_nn::{
+    layer::{
+        dropout::{AlphaDropout, Dropout, GaussianDropout, GaussianNoise},
+        DropoutLayer, Layer,
+    },
+    linalg::{Matrix, MatrixTrait, Scalar},
+    model::network_model::{NetworkLayerModels, NetworkModel, NetworkModelBuilder},
+};
+
+fn mean_and_variance(matrix: &Matrix) -> (Scalar, Scalar) {
+    let mean = matrix.mean();
+    let variance = matrix.scalar_sub(mean).square().mean();
+    (mean, variance)
+}
+
+#[test]
+fn test_dropout() {
+    let mut layer = Dropout::new(0.25);
+    let input = Matrix::constant(100, 50, 2.0);
+
+    // disabled at inference, nothing is rescaled
+    assert_eq!(layer.forward(input.clone()).get_data_col_leading(), input.get_data_col_leading());
+
+    layer.enable_dropout();
+    let output = layer.forward(input.clone());
+    let kept = 2.0 / 0.75;
+    assert!(output
+        .get_data_col_leading()
+        .iter()
+        .flatten()
+        .all(|v| *v == 0.0 || (v - kept).abs() < 1e-5));
+    // inverted dropout keeps the mean
+    assert!((output.mean() - 2.0).abs() < 0.1);
+
+    // the gradient flows through the kept inputs only, scaled the same way
+    let gradient = layer.backward(0, Matrix::constant(100, 50, 2.0));
+    assert_eq!(gradient.get_data_col_leading(), output.get_data_col_leading());
+}
+
+#[test]
+fn test_gaussian_noise() {
+    let mut layer = GaussianNoise::new(0.5);
+    let input = Matrix::constant(100, 50, 1.0);
+
+    assert_eq!(layer.forward(input.clone()).get_data_col_leading(), input.get_data_col_leading());
+
+    layer.enable_dropout();
+    let (mean, variance) = mean_and_variance(&layer.forward(input));
+    assert!((mean - 1.0).abs() < 0.05);
+    assert!((variance - 0.25).abs() < 0.05);
+
+    let gradient = Matrix::constant(100, 50, 3.0);
+    assert_eq!(
+        layer.backward(0, gradient.clone()).get_data_col_leading(),
+        gradient.get_data_col_leading()
+    );
+}
+
+#[test]
+fn test_gaussian_dropout() {
+    let mut layer = GaussianDropout::new(0.2);
+    layer.enable_dropout();
+
+    let output = layer.forward(Matrix::constant(100, 50, 1.0));
+    let (mean, variance) = mean_and_variance(&output);
+    assert!((mean - 1.0).abs() < 0.05);
+    assert!((variance - 0.25).abs() < 0.05);
+
+    layer.disable_dropout();
+    let input = Matrix::constant(10, 5, 1.0);
+    assert_eq!(layer.forward(input.clone()).get_data_col_leading(), input.get_data_col_leading());
+}
+
+#[test]
+fn test_alpha_dropout_keeps_mean_and_variance() {
+    let mut layer = AlphaDropout::new(0.2);
+    layer.enable_dropout();
+
+    // SELU activations are normalized
+    let input = Matrix::from_fn(200, 100, |i, j| {
+        let u = ((i * 100 + j) as Scalar + 0.5) / 20000.0;
+        // inverse of the logistic distribution's cdf, close to a normal one
+        (u / (1.0 - u)).ln() * 0.5513
+    });
+    let (mean, variance) = mean_and_variance(&input);
+    assert!(mean.abs() < 0.01 && (variance - 1.0).abs() < 0.05);
+
+    let (mean, variance) = mean_and_variance(&layer.forward(input));
+    assert!(mean.abs() < 0.1);
+    assert!((variance - 1.0).abs() < 0.1);
+}
+
+#[test]
+fn test_dropout_layers_model() {
+    let model = NetworkModelBuilder::new()
+        .gaussian_noise(0.1)
+        .full_dense(16)
+        .end()
+        .dropout(0.5)
+        .gaussian_dropout(0.3)
+        .alpha_dropout(0.1)
+        .full_dense(2)
+        .end()
+        .build();
+
+    let json = serde_json::to_string(&model).unwrap();
+    let model: NetworkModel = serde_json::from_str(&json).unwrap();
+    assert!(matches!(model.layers[2], NetworkLayerModels::Dropout { rate } if rate == 0.5));
+
+    let (out_dims, layers) = model.clone().to_layers(4);
+    assert_eq!((out_dims, layers.len()), (2, 6));
+
+    // dropout is disabled when predicting
+    let mut network = model.to_network(4);
+    let input = vec![vec![0.1, 0.2, 0.3, 0.4]; 3];
+    assert_eq!(network.predict_many(&input, 3), network.predict_many(&input, 3));
+}
+
+#[test]
+fn test_dropout_2d_drops_whole_channels() {
+    use jiro_nn::vision::{
+        conv_layer::dropout_2d::Dropout2d,
+        image::{Image, ImageTrait},
+        image_layer::ImageLayer,
+    };
+
+    let mut layer = Dropout2d::new(0.5);
+    layer.enable_dropout();
+
+    let output = layer.forward(Image::constant(3, 3, 16, 4, 1.0));
+    for s in 0..4 {
+        for c in 0..16 {
+            let channel = output.get_sample(s).get_channel(c).flatten().get_column(0);
+            assert!(channel.iter().all(|v| *v == 0.0) || channel.iter().all(|v| *v == 2.0));
+        }
+    }
+}
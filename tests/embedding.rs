@@ -0,0 +1,195 @@
+use jiro_nn::{
+    dataset::{Dataset, FeatureTags},
+    datatable::DataTable,
+    initializers::Initializers,
+    layer::{embedding_layer::EmbeddingLayer, GradientLayer, Layer, LearnableLayer},
+    linalg::{Matrix, MatrixTrait, Scalar},
+    model::network_model::{NetworkLayerModels, NetworkModelBuilder},
+    optimizer::sgd,
+    preprocessing::{category_ids::CategoryIds, CachedConfig, DataTransformation},
+};
+
+#[test]
+fn test_embedding_forward() {
+    let mut layer = EmbeddingLayer::new(1, 3, 2, Initializers::UniformSigned, sgd());
+    let embeddings = layer.get_learnable_parameters();
+
+    // 2 samples of [x, id, y]
+    let input = Matrix::from_column_leading_vector2(&vec![vec![0.5, 2.0, -1.0], vec![0.1, 0.0, 0.2]]);
+    let output = layer.forward(input).get_data_col_leading();
+
+    assert_eq!(
+        output[0],
+        vec![0.5, embeddings[2][0], embeddings[2][1], -1.0]
+    );
+    assert_eq!(
+        output[1],
+        vec![0.1, embeddings[0][0], embeddings[0][1], 0.2]
+    );
+}
+
+#[test]
+fn test_embedding_sparse_update() {
+    let mut layer = EmbeddingLayer::new(0, 4, 3, Initializers::UniformSigned, sgd());
+    let embeddings = layer.get_learnable_parameters();
+
+    let input = Matrix::from_column_leading_vector2(&vec![vec![1.0, 7.0], vec![3.0, 8.0], vec![1.0, 9.0]]);
+    layer.forward(input);
+
+    let output_gradient = Matrix::from_column_leading_vector2(&vec![vec![1.0, 1.0, 1.0, 5.0]; 3]);
+    let input_gradient = layer.backward(0, output_gradient).get_data_col_leading();
+    assert_eq!(input_gradient, vec![vec![0.0, 5.0]; 3]);
+    layer.step(0);
+
+    let updated = layer.get_learnable_parameters();
+    // only the categories seen in the batch are updated
+    assert_eq!(updated[0], embeddings[0]);
+    assert_eq!(updated[2], embeddings[2]);
+    assert_ne!(updated[1], embeddings[1]);
+    assert_ne!(updated[3], embeddings[3]);
+    // category 1 was seen twice, its gradients sum up
+    let step_1 = embeddings[1][0] - updated[1][0];
+    let step_3 = embeddings[3][0] - updated[3][0];
+    assert!((step_1 - 2.0 * step_3).abs() < 1e-5);
+}
+
+#[test]
+#[should_panic(expected = "Category id 4 is out of the embedding's 4 categories")]
+fn test_embedding_unknown_category() {
+    let mut layer = EmbeddingLayer::new(0, 4, 3, Initializers::UniformSigned, sgd());
+    layer.forward(Matrix::from_column_vector(&vec![4.0]));
+}
+
+#[test]
+fn test_embedded_inputs_insertion() {
+    let model = NetworkModelBuilder::new()
+        .full_dense(8)
+        .end()
+        .build();
+
+    let embedded = model.clone().with_embedded_inputs(&[(1, 10, 3), (3, 5, 2)]);
+    match (&embedded.layers[0], &embedded.layers[1]) {
+        (NetworkLayerModels::Embedding(first), NetworkLayerModels::Embedding(second)) => {
+            assert_eq!((first.input_index, first.categories_count, first.dim), (3, 5, 2));
+            assert_eq!((second.input_index, second.categories_count, second.dim), (1, 10, 3));
+        }
+        _ => panic!("Expected embeddings to be inserted first"),
+    }
+
+    let (out_dims, layers) = embedded.layers[..2]
+        .iter()
+        .fold((4, vec![]), |(in_dims, mut layers), layer| {
+            let (out_dims, layer) = layer.clone().to_layer(in_dims);
+            layers.push(layer);
+            (out_dims, layers)
+        });
+    assert_eq!((out_dims, layers.len()), (4 - 1 + 2 - 1 + 3, 2));
+
+    // explicit embeddings take precedence and stay first, the other inputs are still embedded
+    let model = NetworkModelBuilder::new()
+        .embedding(1, 10, 6)
+            .adam()
+        .end()
+        .full_dense(8)
+        .end()
+        .build()
+        .with_embedded_inputs(&[(1, 10, 3), (2, 4, 2), (0, 3, 2)]);
+    assert_eq!(model.layers.len(), 4);
+    let embeddings: Vec<(usize, usize, usize)> = model.layers[..3]
+        .iter()
+        .map(|layer| match layer {
+            NetworkLayerModels::Embedding(e) => (e.input_index, e.categories_count, e.dim),
+            _ => panic!("Expected embeddings first"),
+        })
+        .collect();
+    // input 2 is at 2 + 6 - 1 once input 1 is embedded by the network's own embedding
+    assert_eq!(embeddings, vec![(1, 10, 6), (7, 4, 2), (0, 3, 2)]);
+    let (out_dims, _) = model.to_layers(3);
+    assert_eq!(out_dims, 8);
+
+    // the network's own embeddings keep their order
+    let model = NetworkModelBuilder::new()
+        .embedding(0, 3, 2)
+        .end()
+        .embedding(2, 4, 2)
+        .end()
+        .full_dense(8)
+        .end()
+        .build()
+        .with_embedded_inputs(&[(0, 3, 2), (2, 4, 2)]);
+    assert_eq!(model.layers.len(), 3);
+    assert!(matches!(&model.layers[0], NetworkLayerModels::Embedding(e) if e.input_index == 0));
+    assert!(matches!(&model.layers[1], NetworkLayerModels::Embedding(e) if e.input_index == 2));
+}
+
+#[test]
+fn test_category_ids() {
+    let mut dataset = Dataset::from_features_tags(&[
+        &[FeatureTags::Name("zipcode"), FeatureTags::Embedded(4)],
+        &[FeatureTags::Name("price"), FeatureTags::Predicted],
+        &[FeatureTags::Name("area")],
+    ]);
+    dataset.tag_all(FeatureTags::Normalized);
+
+    let data = DataTable::from_vectors(
+        &["zipcode", "price", "area"],
+        &vec![
+            vec![98103.0, 1.0, 50.0],
+            vec![98001.0, 2.0, 60.0],
+            vec![98103.0, 3.0, 70.0],
+            vec![98500.0, 4.0, 80.0],
+        ],
+    );
+
+    let mut category_ids = CategoryIds::new();
+    let (dataset, ids_data) = category_ids.transform(&CachedConfig::NotCached, &dataset, &data);
+
+    // 0 is the unknown category's id
+    assert_eq!(ids_data.column_to_vector("zipcode"), vec![2.0, 1.0, 2.0, 3.0]);
+    let zipcode = dataset.features.iter().find(|f| f.name == "zipcode").unwrap();
+    assert_eq!((zipcode.embedded, zipcode.categories_count), (Some(4), Some(4)));
+    assert!(!zipcode.normalized);
+    assert_eq!(dataset.embedded_inputs(), vec![(0, 4, 4)]);
+
+    let reverted = category_ids.reverse_columnswise(&ids_data);
+    assert_eq!(
+        reverted.column_to_vector("zipcode"),
+        vec![98103.0, 98001.0, 98103.0, 98500.0]
+    );
+}
+
+#[test]
+fn test_category_ids_unseen_category() {
+    let dataset = Dataset::from_features_tags(&[
+        &[FeatureTags::Name("zipcode"), FeatureTags::Embedded(4)],
+        &[FeatureTags::Name("price"), FeatureTags::Predicted],
+    ]);
+    let train = DataTable::from_vectors(
+        &["zipcode", "price"],
+        &vec![vec![98103.0, 1.0], vec![98001.0, 2.0]],
+    );
+    let test = DataTable::from_vectors(
+        &["zipcode", "price"],
+        &vec![vec![98001.0, 3.0], vec![98500.0, 4.0]],
+    );
+
+    let mut category_ids = CategoryIds::new();
+    category_ids.transform(&CachedConfig::NotCached, &dataset, &train);
+    let (test_dataset, test_ids) = category_ids.transform(&CachedConfig::NotCached, &dataset, &test);
+
+    // the categories fitted on the training data are kept, 98500 being unknown
+    assert_eq!(test_ids.column_to_vector("zipcode"), vec![1.0, CategoryIds::UNKNOWN_ID as Scalar]);
+    assert_eq!(test_dataset.embedded_inputs(), vec![(0, 3, 4)]);
+
+    let reverted = category_ids.reverse_columnswise(&test_ids).column_to_vector("zipcode");
+    assert_eq!(reverted[0], 98001.0);
+    assert!(reverted[1].is_nan());
+}
+
+#[test]
+#[should_panic(expected = "non-integer category")]
+fn test_category_ids_non_integer_category() {
+    let dataset = Dataset::from_features_tags(&[&[FeatureTags::Name("zipcode"), FeatureTags::Embedded(4)]]);
+    let data = DataTable::from_vectors(&["zipcode"], &vec![vec![1.5]]);
+    CategoryIds::new().transform(&CachedConfig::NotCached, &dataset, &data);
+}
@@ -0,0 +1,171 @@
+use jiro_nn::{
+    dataset::{Dataset, FeatureTags},
+    gradient_clipping::{gradients_norm, GradientClipping, GradientMut},
+    initializers::Initializers,
+    layer::{dense_layer::DenseLayer, GradientLayer, Layer},
+    linalg::{Matrix, MatrixTrait, Scalar},
+    loss::Losses,
+    model::{Model, ModelBuilder},
+    network::params::NetworkParams,
+    optimizer::{sgd::SGD, Optimizers},
+};
+
+fn sgd(lr: Scalar) -> Optimizers {
+    Optimizers::SGD(SGD::with_const_lr(lr))
+}
+
+fn gradients() -> (Matrix, Matrix, Matrix) {
+    (
+        Matrix::from_column_vector(&vec![3.0, -4.0]),
+        Matrix::from_column_vector(&vec![0.5]),
+        Matrix::from_column_vector(&vec![12.0]),
+    )
+}
+
+#[test]
+fn test_clipping_modes() {
+    // two layers, the first one holding two gradients
+    let clip = |clipping: GradientClipping| -> Vec<Vec<Scalar>> {
+        let (mut a, mut b, mut c) = gradients();
+        clipping.clip(vec![
+            vec![GradientMut::Matrix(&mut a), GradientMut::Matrix(&mut b)],
+            vec![GradientMut::Matrix(&mut c)],
+        ]);
+        vec![a.get_column(0), b.get_column(0), c.get_column(0)]
+    };
+
+    let clipped = clip(GradientClipping::Value(1.0));
+    assert_eq!(clipped, vec![vec![1.0, -1.0], vec![0.5], vec![1.0]]);
+
+    // the first layer's norm is √25.25, the second one's is 12
+    let clipped = clip(GradientClipping::Norm(5.0));
+    let scale = 5.0 / (25.25 as Scalar).sqrt();
+    let expected = [3.0 * scale, -4.0 * scale, 0.5 * scale, 5.0];
+    for (c, e) in clipped.concat().iter().zip(expected.iter()) {
+        assert!((c - e).abs() < 1e-5, "{:?}", clipped);
+    }
+
+    // the global norm is √169.25, all the gradients keep their direction
+    let clipped = clip(GradientClipping::GlobalNorm(1.0));
+    let scale = 1.0 / (169.25 as Scalar).sqrt();
+    let expected = [3.0 * scale, -4.0 * scale, 0.5 * scale, 12.0 * scale];
+    for (c, e) in clipped.concat().iter().zip(expected.iter()) {
+        assert!((c - e).abs() < 1e-5, "{:?}", clipped);
+    }
+
+    // gradients under the threshold are left as is
+    let clipped = clip(GradientClipping::GlobalNorm(100.0));
+    assert_eq!(clipped, vec![vec![3.0, -4.0], vec![0.5], vec![12.0]]);
+}
+
+#[test]
+fn test_layer_gradients_applied_separately() {
+    let mut layer = DenseLayer::new(
+        3,
+        2,
+        sgd(0.1),
+        sgd(0.1),
+        Initializers::UniformSigned,
+        Initializers::UniformSigned,
+    );
+    let weights = layer.weights.clone();
+    assert!(layer.gradients_mut().is_empty());
+
+    layer.forward(Matrix::constant(3, 4, 1.0));
+    layer.backward(0, Matrix::constant(2, 4, 1.0));
+
+    // the parameters don't change until the gradients are applied
+    assert_eq!(layer.weights.get_data_col_leading(), weights.get_data_col_leading());
+    let mut gradients = layer.gradients_mut();
+    assert_eq!(gradients.len(), 2);
+    // ∂E/∂W = ∂E/∂Y . Xᵀ is 4 everywhere, ∂E/∂B is 4 too
+    assert!((gradients_norm(&gradients) - (8.0 * 16.0 as Scalar).sqrt()).abs() < 1e-4);
+    gradients.iter_mut().for_each(|g| g.scale(0.25));
+
+    layer.step(0);
+    let expected: Vec<Scalar> = weights.get_data_col_leading().concat().iter().map(|w| w - 0.1).collect();
+    for (w, e) in layer.weights.get_data_col_leading().concat().iter().zip(expected.iter()) {
+        assert!((w - e).abs() < 1e-5);
+    }
+    assert!(layer.gradients_mut().is_empty());
+}
+
+fn clipped_model(gradient_clipping: Option<GradientClipping>) -> Model {
+    let dataset = Dataset::from_features_tags(&[
+        &[FeatureTags::Name("x1")],
+        &[FeatureTags::Name("x2")],
+        &[FeatureTags::Name("y"), FeatureTags::Predicted],
+    ]);
+    let builder = ModelBuilder::new(dataset)
+        .neural_network()
+        .full_dense(8)
+        .relu()
+        .optimizer(sgd(0.01))
+        .end()
+        .full_dense(1)
+        .linear()
+        .optimizer(sgd(0.01))
+        .end()
+        .end();
+    let builder = match gradient_clipping {
+        Some(gradient_clipping) => builder.gradient_clipping(gradient_clipping),
+        None => builder,
+    };
+    builder.build()
+}
+
+fn params_distance(a: &NetworkParams, b: &NetworkParams) -> Scalar {
+    let (a, b) = (a.0.concat().concat(), b.0.concat().concat());
+    a.iter().zip(b.iter()).map(|(x, y)| (x - y).powi(2)).sum::<Scalar>().sqrt()
+}
+
+#[test]
+fn test_network_global_norm_clipping() {
+    let model: Model = serde_json::from_str(
+        &serde_json::to_string(&clipped_model(Some(GradientClipping::GlobalNorm(1.0)))).unwrap(),
+    )
+    .unwrap();
+    assert_eq!(model.gradient_clipping, Some(GradientClipping::GlobalNorm(1.0)));
+
+    // unnormalized targets make the gradients explode
+    let x = vec![vec![10.0, -20.0], vec![30.0, 5.0], vec![-15.0, 25.0]];
+    let y = vec![vec![1e4], vec![-2e4], vec![3e4]];
+    let loss = Losses::MSE.to_loss();
+
+    let mut unclipped = clipped_model(None).to_network();
+    let initial = unclipped.get_params();
+    let mut clipped = model.to_network();
+    clipped.load_params(&initial);
+
+    unclipped.train(0, &x, &y, &loss, 3);
+    clipped.train(0, &x, &y, &loss, 3);
+
+    // with SGD, a step is at most the learning rate times the gradients' maximum norm
+    assert!(params_distance(&clipped.get_params(), &initial) <= 0.01 * 1.0 + 1e-5);
+    assert!(params_distance(&unclipped.get_params(), &initial) > 1.0);
+}
+
+#[test]
+fn test_invalid_thresholds_rejected() {
+    for clipping in [
+        GradientClipping::Value(0.0),
+        GradientClipping::Norm(-1.0),
+        GradientClipping::GlobalNorm(Scalar::NAN),
+    ] {
+        assert!(clipping.validate().is_err());
+    }
+    assert_eq!(GradientClipping::Norm(2.0).validate(), Ok(GradientClipping::Norm(2.0)));
+
+    assert!(serde_json::from_str::<GradientClipping>("{\"GlobalNorm\":0.0}").is_err());
+    assert!(serde_json::from_str::<GradientClipping>("{\"Value\":-0.5}").is_err());
+    assert_eq!(
+        serde_json::from_str::<GradientClipping>("{\"Value\":0.5}").unwrap(),
+        GradientClipping::Value(0.5)
+    );
+}
+
+#[test]
+#[should_panic(expected = "Gradient clipping threshold must be positive")]
+fn test_builder_rejects_invalid_threshold() {
+    clipped_model(Some(GradientClipping::Norm(0.0)));
+}
@@ -0,0 +1,198 @@
+use jiro_nn::{
+    gradient_clipping::GradientMut,
+    initializers::Initializers,
+    layer::{dense_layer::DenseLayer, GradientLayer, Layer, LearnableLayer},
+    linalg::{Matrix, MatrixTrait, Scalar},
+    loss::Losses,
+    model::network_model::NetworkModelBuilder,
+    network::{params::NetworkParams, Network},
+    optimizer::{sgd::SGD, Optimizers},
+};
+
+fn sgd(lr: Scalar) -> Optimizers {
+    Optimizers::SGD(SGD::with_const_lr(lr))
+}
+
+fn assert_close(actual: &[Scalar], expected: &[Scalar], tolerance: Scalar) {
+    assert_eq!(actual.len(), expected.len());
+    for (a, e) in actual.iter().zip(expected.iter()) {
+        assert!((a - e).abs() < tolerance, "{:?} != {:?}", actual, expected);
+    }
+}
+
+fn flatten(gradient: &GradientMut) -> Vec<Scalar> {
+    match gradient {
+        GradientMut::Matrix(m) => m.get_data_col_leading().concat(),
+        GradientMut::Image(_) => panic!("Expected a matrix gradient"),
+    }
+}
+
+/// Two networks with the same parameters.
+fn networks() -> (Network, Network) {
+    let model = NetworkModelBuilder::new()
+        .full_dense(3)
+        .tanh()
+        .optimizer(sgd(0.1))
+        .end()
+        .full_dense(2)
+        .linear()
+        .optimizer(sgd(0.1))
+        .end()
+        .build();
+    let network = model.clone().to_network(2);
+    let mut copy = model.to_network(2);
+    copy.load_params(&network.get_params());
+    (network, copy)
+}
+
+fn data() -> (Vec<Vec<Scalar>>, Vec<Vec<Scalar>>) {
+    (
+        vec![vec![0.1, 0.2], vec![0.5, -0.3], vec![-0.4, 0.8]],
+        vec![vec![1.0, 0.0], vec![0.0, 1.0], vec![0.5, 0.5]],
+    )
+}
+
+#[test]
+fn test_layer_gradients_accumulated_until_step() {
+    let mut layer = DenseLayer::new(
+        3,
+        2,
+        sgd(0.1),
+        sgd(0.1),
+        Initializers::UniformSigned,
+        Initializers::UniformSigned,
+    );
+    let params = layer.get_learnable_parameters();
+    let input = Matrix::from_column_leading_vector2(&vec![vec![0.5, -1.0, 2.0], vec![1.5, 0.0, -0.5]]);
+    let output_gradient = Matrix::from_column_leading_vector2(&vec![vec![1.0, -2.0], vec![0.5, 3.0]]);
+
+    layer.forward(input.clone());
+    layer.backward(0, output_gradient.clone());
+    let once: Vec<Vec<Scalar>> = layer.gradients_mut().iter().map(flatten).collect();
+
+    layer.forward(input);
+    layer.backward(0, output_gradient);
+    let twice: Vec<Vec<Scalar>> = layer.gradients_mut().iter().map(flatten).collect();
+    for (twice, once) in twice.iter().zip(once.iter()) {
+        let doubled: Vec<Scalar> = once.iter().map(|g| 2.0 * g).collect();
+        assert_close(twice, &doubled, 1e-5);
+    }
+
+    // discarded gradients are never applied
+    layer.zero_grad();
+    assert!(layer.gradients_mut().is_empty());
+    layer.step(0);
+    assert_eq!(layer.get_learnable_parameters(), params);
+}
+
+#[test]
+fn test_manual_loop_matches_train() {
+    let (x, y) = data();
+    let loss = Losses::MSE.to_loss();
+    let (mut trained, mut stepped) = networks();
+
+    trained.train(0, &x, &y, &loss, 3);
+
+    stepped.train_mode();
+    stepped.zero_grad();
+    let y_true = Matrix::from_column_leading_vector2(&y);
+    let pred = stepped.forward(Matrix::from_column_leading_vector2(&x));
+    stepped.backward_loss(0, &y_true, &pred, None, &loss);
+    stepped.step(0);
+
+    assert_eq!(stepped.get_params().0, trained.get_params().0);
+}
+
+#[test]
+fn test_gradient_accumulation_over_batches() {
+    let (x, y) = data();
+    let loss = Losses::MSE.to_loss();
+    let (mut trained, mut accumulated) = networks();
+
+    trained.train(0, &x, &y, &loss, 3);
+
+    // the MSE gradient is summed over the samples, so accumulating one sample at a time gives the batch's one
+    accumulated.train_mode();
+    accumulated.zero_grad();
+    for (x, y) in x.iter().zip(y.iter()) {
+        let y_true = Matrix::from_column_vector(y);
+        let pred = accumulated.forward(Matrix::from_column_vector(x));
+        accumulated.backward_loss(0, &y_true, &pred, None, &loss);
+    }
+    accumulated.step(0);
+
+    let params = |network: &Network| network.get_params().0.concat().concat();
+    assert_close(&params(&accumulated), &params(&trained), 1e-5);
+}
+
+#[test]
+fn test_gradient_check() {
+    let (mut network, _) = networks();
+    let (x, _) = data();
+    let input = Matrix::from_column_leading_vector2(&x);
+    // E = Σ G ⊙ Y, so that ∂E/∂Y = G
+    let output_gradient = Matrix::from_column_leading_vector2(&vec![vec![1.0, -0.5], vec![0.3, 2.0], vec![-1.5, 0.7]]);
+    let objective = |network: &mut Network| -> Scalar {
+        network
+            .forward(input.clone())
+            .component_mul(&output_gradient)
+            .sum()
+    };
+
+    network.zero_grad();
+    network.forward(input.clone());
+    network.backward(0, output_gradient.clone());
+    let gradients: Vec<Vec<Vec<Scalar>>> = network
+        .gradients_mut()
+        .iter()
+        .map(|layer| layer.iter().map(flatten).collect())
+        .collect();
+
+    // central differences on the first layer's weights, stored column by column
+    let params = network.get_params();
+    let epsilon = 1e-2;
+    let mut numerical = vec![];
+    for c in 0..2 {
+        for r in 0..3 {
+            let mut shifted = NetworkParams(params.0.clone());
+            shifted.0[0][c][r] += epsilon;
+            network.load_params(&shifted);
+            let plus = objective(&mut network);
+            shifted.0[0][c][r] -= 2.0 * epsilon;
+            network.load_params(&shifted);
+            let minus = objective(&mut network);
+            numerical.push((plus - minus) / (2.0 * epsilon));
+        }
+    }
+    assert_close(&gradients[0][0], &numerical, 1e-2);
+
+    // the perturbed forward passes left the accumulated gradients untouched
+    network.load_params(&params);
+    network.step(0);
+    assert_ne!(network.get_params().0, params.0);
+}
+
+#[test]
+fn test_forward_follows_the_mode() {
+    let mut network = NetworkModelBuilder::new()
+        .full_dense(32)
+        .linear()
+        .dropout(0.5)
+        .end()
+        .build()
+        .to_network(2);
+    let (x, _) = data();
+    let input = Matrix::from_column_leading_vector2(&x);
+
+    network.eval_mode();
+    let eval = network.forward(input.clone()).get_data_col_leading();
+    assert_eq!(network.forward(input.clone()).get_data_col_leading(), eval);
+    assert_eq!(network.predict_many(&x, 3), eval);
+
+    network.train_mode();
+    assert_ne!(network.forward(input.clone()).get_data_col_leading(), eval);
+
+    // predicting switches back to the evaluation mode
+    network.predict_many(&x, 3);
+    assert_eq!(network.forward(input).get_data_col_leading(), eval);
+}
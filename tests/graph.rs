@@ -0,0 +1,224 @@
+#[macro_use]
+extern crate assert_float_eq;
+
+use std::collections::HashMap;
+
+use jiro_nn::{
+    layer::autodiff_layer::AutodiffLayer,
+    linalg::{Matrix, MatrixTrait, Scalar},
+    loss::{Loss, Losses},
+    model::{
+        graph_network_model::{GraphNetworkModel, GraphNetworkModelBuilder},
+        network_model::NetworkModelBuilder,
+    },
+    network::graph::{GraphNetwork, GraphOps},
+    optimizer::{sgd::SGD, Optimizers},
+};
+
+fn linear_layer(weights: &Matrix) -> AutodiffLayer {
+    AutodiffLayer::new(
+        Box::new(|tape, input, params| tape.dot(params[0], input)),
+        vec![weights.clone()],
+        vec![Optimizers::SGD(SGD::with_const_lr(0.0))],
+    )
+}
+
+#[test]
+fn test_merge_nodes_gradients() {
+    let weights = Matrix::from_row_leading_vector2(&vec![vec![1.0, 2.0], vec![-1.0, 0.5]]);
+    let mut network = GraphNetwork::new();
+    network
+        .add_node("a", &[], GraphOps::Input(2))
+        .add_node("b", &[], GraphOps::Input(2))
+        .add_layers_node("a_tower", "a", vec![Box::new(linear_layer(&weights))], 2)
+        .add_node("merged", &["a_tower", "b"], GraphOps::Concat)
+        .add_node("sum", &["a_tower", "b"], GraphOps::Add)
+        .add_node("head", &["merged"], GraphOps::Split { start: 1, size: 2 })
+        .add_output("head")
+        .add_output("sum");
+    assert_eq!(network.dims("merged"), 4);
+
+    let a = Matrix::from_column_vector(&vec![1.0, -2.0]);
+    let b = Matrix::from_column_vector(&vec![0.5, 3.0]);
+    let inputs = HashMap::from([("a".to_string(), a.clone()), ("b".to_string(), b.clone())]);
+    let outputs = network.forward(&inputs);
+
+    let a_tower = weights.dot(&a).get_column(0);
+    assert_eq!(outputs["head"].get_column(0), vec![a_tower[1], 0.5]);
+    assert_eq!(outputs["sum"].get_column(0), vec![a_tower[0] + 0.5, a_tower[1] + 3.0]);
+
+    let gradients = HashMap::from([
+        ("head".to_string(), Matrix::from_column_vector(&vec![1.0, 2.0])),
+        ("sum".to_string(), Matrix::from_column_vector(&vec![0.5, -1.0])),
+    ]);
+    let inputs_gradients = network.backward(0, gradients);
+
+    // b receives the head's gradient on its first feature and the sum's gradient
+    assert_eq!(inputs_gradients["b"].get_column(0), vec![2.0 + 0.5, -1.0]);
+    // a_tower receives the head's gradient on its second feature and the sum's gradient
+    let a_tower_gradient = Matrix::from_column_vector(&vec![0.5, 1.0 - 1.0]);
+    let expected = weights.transpose().dot(&a_tower_gradient).get_column(0);
+    for (x, y) in inputs_gradients["a"].get_column(0).iter().zip(expected.iter()) {
+        assert_float_absolute_eq!(x, y, 0.00001);
+    }
+}
+
+fn two_towers_model() -> GraphNetworkModel {
+    GraphNetworkModelBuilder::new()
+        .input("a", 3)
+        .input("b", 2)
+        .layers(
+            "a_tower",
+            "a",
+            NetworkModelBuilder::new().full_dense(4).tanh().end().build(),
+        )
+        .layers(
+            "b_tower",
+            "b",
+            NetworkModelBuilder::new().full_dense(2).tanh().end().build(),
+        )
+        .concat("merged", &["a_tower", "b_tower"])
+        .layers(
+            "regression",
+            "merged",
+            NetworkModelBuilder::new().full_dense(1).linear().end().build(),
+        )
+        .layers(
+            "classification",
+            "merged",
+            NetworkModelBuilder::new().full_dense(2).sigmoid().end().build(),
+        )
+        .output("regression", Losses::MSE)
+        .output("classification", Losses::BCE)
+        .build()
+}
+
+#[test]
+fn test_two_towers_two_heads() {
+    let model = two_towers_model();
+    let json = serde_json::to_string(&model).unwrap();
+    let model: GraphNetworkModel = serde_json::from_str(&json).unwrap();
+
+    let mut network = model.clone().to_graph_network();
+    assert_eq!(network.inputs_names(), vec!["a", "b"]);
+    assert_eq!(network.outputs_names(), vec!["regression", "classification"]);
+
+    let x = HashMap::from([
+        (
+            "a".to_string(),
+            vec![vec![1.0, 0.0, -1.0], vec![0.2, 0.4, 0.6], vec![-0.5, 0.5, 0.0]],
+        ),
+        (
+            "b".to_string(),
+            vec![vec![1.0, 0.0], vec![0.0, 1.0], vec![0.5, 0.5]],
+        ),
+    ]);
+    let y = HashMap::from([
+        ("regression".to_string(), vec![vec![1.0], vec![-1.0], vec![0.0]]),
+        (
+            "classification".to_string(),
+            vec![vec![1.0, 0.0], vec![0.0, 1.0], vec![1.0, 0.0]],
+        ),
+    ]);
+
+    let losses = model.losses();
+    let first_error = network.train(0, &x, &y, &losses, 2);
+    let mut error = first_error;
+    for epoch in 1..50 {
+        error = network.train(epoch, &x, &y, &losses, 2);
+    }
+    assert!(error < first_error);
+
+    let preds = network.predict_many(&x, 2);
+    assert_eq!(preds["regression"].len(), 3);
+    assert_eq!(preds["classification"][2].len(), 2);
+
+    let params = network.get_params();
+    assert_eq!(params.0.len(), 4);
+
+    let mut loaded = model.to_graph_network();
+    loaded.load_params(&params);
+    assert_eq!(loaded.get_params().0, params.0);
+    assert_eq!(loaded.predict_many(&x, 3), preds);
+}
+
+#[test]
+#[should_panic]
+fn test_add_with_different_dimensions() {
+    GraphNetworkModelBuilder::new()
+        .input("a", 3)
+        .input("b", 2)
+        .add("sum", &["a", "b"])
+        .build()
+        .to_graph_network();
+}
+
+/// MSE whose fused softmax gradient isn't the chain rule's one, so that fusing can be told apart.
+fn fused_loss() -> HashMap<String, Loss> {
+    let loss = Losses::MSE
+        .to_loss()
+        .with_softmax_derivative(|y_true, y_pred| y_pred.component_sub(y_true));
+    HashMap::from([("y".to_string(), loss)])
+}
+
+#[test]
+fn test_train_like_network() {
+    let model = NetworkModelBuilder::new()
+        .full_dense(3)
+        .softmax()
+        .optimizer(Optimizers::SGD(SGD::with_const_lr(0.5)))
+        .end()
+        .build();
+    let mut network = model.clone().to_network(2);
+    let (_, layers) = model.to_layers(2);
+    let mut graph = GraphNetwork::new();
+    graph
+        .add_node("x", &[], GraphOps::Input(2))
+        .add_layers_node("y", "x", layers, 3)
+        .add_output("y");
+    graph.load_params(&network.get_params());
+
+    let x = vec![vec![0.1, 0.2], vec![0.5, -0.3], vec![-0.4, 0.8]];
+    let y = vec![vec![1.0, 0.0, 0.0], vec![0.0, 1.0, 0.0], vec![0.0, 0.0, 1.0]];
+    let weights = vec![1.0, 0.0, 2.0];
+
+    let network_loss = network.train_weighted(0, &x, &y, Some(&weights), &fused_loss()["y"], 2);
+    let graph_loss = graph.train_weighted(
+        0,
+        &HashMap::from([("x".to_string(), x)]),
+        &HashMap::from([("y".to_string(), y)]),
+        Some(&weights),
+        &fused_loss(),
+        2,
+    );
+
+    assert_float_absolute_eq!(graph_loss, network_loss, 1e-6);
+    let params = |params: jiro_nn::network::params::NetworkParams| -> Vec<Scalar> { params.0.concat().concat() };
+    for (g, n) in params(graph.get_params()).iter().zip(params(network.get_params()).iter()) {
+        assert_float_absolute_eq!(g, n, 1e-6);
+    }
+}
+
+#[test]
+fn test_forward_follows_the_mode() {
+    let (_, layers) = NetworkModelBuilder::new()
+        .full_dense(32)
+        .linear()
+        .dropout(0.5)
+        .end()
+        .build()
+        .to_layers(2);
+    let mut graph = GraphNetwork::new();
+    graph
+        .add_node("x", &[], GraphOps::Input(2))
+        .add_layers_node("y", "x", layers, 32)
+        .add_output("y");
+    let inputs = HashMap::from([("x".to_string(), Matrix::from_column_vector(&vec![0.5, -1.0]))]);
+
+    graph.eval_mode();
+    let eval = graph.forward(&inputs)["y"].get_column(0);
+    assert_eq!(graph.forward(&inputs)["y"].get_column(0), eval);
+
+    graph.train_mode();
+    assert_ne!(graph.forward(&inputs)["y"].get_column(0), eval);
+}
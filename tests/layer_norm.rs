@@ -0,0 +1,52 @@
+#[macro_use]
+extern crate assert_float_eq;
+
+use jiro_nn::{
+    linalg::Scalar,
+    loss::Losses,
+    model::network_model::NetworkModelBuilder,
+    network::params::NetworkParams,
+};
+
+#[test]
+fn test_predict_normalizes_single_sample() {
+    let mut network = NetworkModelBuilder::new()
+        .layer_norm()
+        .end()
+        .build()
+        .to_network(4);
+
+    let output = network.predict(&vec![1.0, 2.0, 3.0, 6.0]);
+
+    let mean = output.iter().sum::<Scalar>() / 4.;
+    let var = output.iter().map(|x| (x - mean).powi(2)).sum::<Scalar>() / 4.;
+    assert_float_absolute_eq!(mean, 0.0, 0.0001);
+    assert_float_absolute_eq!(var, 1.0, 0.001);
+}
+
+#[test]
+fn test_params_round_trip() {
+    let model = NetworkModelBuilder::new()
+        .full_dense(3)
+        .linear()
+        .end()
+        .layer_norm()
+        .end()
+        .build();
+
+    let mut network = model.clone().to_network(2);
+    let x = vec![vec![1.0, 2.0], vec![-1.0, 0.5]];
+    let y = vec![vec![1.0, 0.0, 0.5], vec![0.0, 1.0, 0.0]];
+    let loss = Losses::MSE.to_loss();
+    for epoch in 0..5 {
+        network.train(epoch, &x, &y, &loss, 2);
+    }
+
+    let params = network.get_params();
+    assert_eq!(params.0[1].len(), 2);
+
+    let mut loaded = model.to_network(2);
+    loaded.load_params(&NetworkParams(params.0.clone()));
+    assert_eq!(loaded.get_params().0, params.0);
+    assert_eq!(loaded.predict(&x[0]), network.predict(&x[0]));
+}
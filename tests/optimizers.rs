@@ -0,0 +1,147 @@
+use jiro_nn::{
+    learning_rate::LearningRateSchedule,
+    linalg::{Matrix, MatrixTrait, Scalar},
+    loss::Losses,
+    model::network_model::{NetworkLayerModels, NetworkModel, NetworkModelBuilder},
+    optimizer::{
+        adadelta::AdaDelta, adagrad::AdaGrad, adam::Adam, amsgrad::AMSGrad, nadam::Nadam,
+        rmsprop::RMSProp, Optimizers,
+    },
+};
+
+fn lr(lr: Scalar) -> LearningRateSchedule {
+    LearningRateSchedule::Constant(lr)
+}
+
+fn optimizers() -> Vec<Optimizers> {
+    vec![
+        Optimizers::Adam(Adam::new(lr(0.1), 0.9, 0.999, 1e-8)),
+        Optimizers::RMSProp(RMSProp::new(lr(0.05), 0.9, 1e-8)),
+        Optimizers::AdaGrad(AdaGrad::new(lr(0.5), 1e-8)),
+        Optimizers::AdaDelta(AdaDelta::new(lr(1.0), 0.9, 1e-2)),
+        Optimizers::Nadam(Nadam::new(lr(0.1), 0.9, 0.999, 1e-8)),
+        Optimizers::AMSGrad(AMSGrad::new(lr(0.1), 0.9, 0.999, 1e-8)),
+    ]
+}
+
+fn distance(parameters: &Matrix, target: &Matrix) -> Scalar {
+    parameters.component_sub(target).square().sum()
+}
+
+#[test]
+fn test_optimizers_minimize_quadratic() {
+    let target = Matrix::from_column_leading_vector2(&vec![vec![1.0, -2.0], vec![0.5, 3.0]]);
+
+    for mut optimizer in optimizers() {
+        let mut parameters = Matrix::zeros(2, 2);
+        let initial = distance(&parameters, &target);
+
+        for epoch in 0..200 {
+            // gradient of the squared distance to the target
+            let gradient = parameters.component_sub(&target).scalar_mul(2.0);
+            parameters = optimizer.update_parameters(epoch, &parameters, &gradient);
+        }
+
+        let last = distance(&parameters, &target);
+        assert!(last < initial / 100.0, "{:?}: {} -> {}", optimizer, initial, last);
+    }
+}
+
+#[test]
+fn test_optimizers_first_step() {
+    let parameters = Matrix::from_column_vector(&vec![1.0, 1.0]);
+    let gradient = Matrix::from_column_vector(&vec![4.0, -0.5]);
+
+    // (optimizer, size of its first step in the opposite direction of the gradient)
+    let cases = vec![
+        (Optimizers::RMSProp(RMSProp::new(lr(0.1), 0.9, 0.0)), 0.1 / (0.1 as Scalar).sqrt()),
+        (Optimizers::AdaGrad(AdaGrad::new(lr(0.1), 0.0)), 0.1),
+        (Optimizers::AMSGrad(AMSGrad::new(lr(0.1), 0.9, 0.999, 0.0)), 0.1),
+        // the nesterov momentum adds beta1 times the bias corrected gradient
+        (Optimizers::Nadam(Nadam::new(lr(0.1), 0.9, 0.999, 0.0)), 0.1 * 1.9),
+    ];
+
+    for (mut optimizer, step) in cases {
+        let updated = optimizer.update_parameters(0, &parameters, &gradient).get_column(0);
+        assert!((updated[0] - (1.0 - step)).abs() < 1e-4, "{:?}: {:?}", optimizer, updated);
+        assert!((updated[1] - (1.0 + step)).abs() < 1e-4, "{:?}: {:?}", optimizer, updated);
+    }
+
+    // AdaDelta's first step is sqrt(epsilon) / sqrt((1 - rho) g^2 + epsilon) times the gradient
+    let mut adadelta = Optimizers::AdaDelta(AdaDelta::new(lr(1.0), 0.9, 1e-2));
+    let updated = adadelta.update_parameters(0, &parameters, &gradient).get_column(0);
+    let expected = 1.0 - 0.1 / (0.1 * 16.0 + 1e-2 as Scalar).sqrt() * 4.0;
+    assert!((updated[0] - expected).abs() < 1e-4);
+}
+
+#[test]
+fn test_optimizers_serialization() {
+    // unspecified hyperparameters take Adam's defaults
+    let optimizers: Vec<Optimizers> = serde_json::from_str(
+        "[{\"RMSProp\":{}},{\"AdaGrad\":{}},{\"AdaDelta\":{}},{\"Nadam\":{\"beta1\":0.8}},{\"AMSGrad\":{}}]",
+    )
+    .unwrap();
+    assert_eq!(optimizers.len(), 5);
+
+    let json = serde_json::to_string(&optimizers[3]).unwrap();
+    assert_eq!(
+        json,
+        "{\"Nadam\":{\"beta1\":0.8,\"beta2\":0.999,\"epsilon\":1e-8,\"learning_rate\":{\"Constant\":0.001}}}"
+    );
+
+    let model = NetworkModelBuilder::new()
+        .full_dense(4)
+        .rmsprop()
+        .end()
+        .full_dense(4)
+        .adagrad()
+        .end()
+        .full_dense(4)
+        .adadelta()
+        .end()
+        .full_dense(4)
+        .nadam()
+        .end()
+        .full_dense(1)
+        .amsgrad()
+        .end()
+        .build();
+
+    let json = serde_json::to_string(&model).unwrap();
+    let model: NetworkModel = serde_json::from_str(&json).unwrap();
+    assert!(matches!(
+        &model.layers[0],
+        NetworkLayerModels::FullDense(l) if matches!(l.weights_optimizer, Optimizers::RMSProp(_))
+    ));
+    assert!(matches!(
+        &model.layers[4],
+        NetworkLayerModels::FullDense(l) if matches!(l.biases_optimizer, Optimizers::AMSGrad(_))
+    ));
+
+    let mut network = model.to_network(3);
+    let x = vec![vec![0.1, 0.2, 0.3], vec![0.3, 0.2, 0.1]];
+    let y = vec![vec![1.0], vec![0.0]];
+    let loss = Losses::MSE.to_loss();
+    for epoch in 0..3 {
+        assert!(network.train(epoch, &x, &y, &loss, 2).is_finite());
+    }
+}
+
+#[test]
+fn test_conv_optimizers_minimize_quadratic() {
+    use jiro_nn::vision::{
+        conv_optimizer::{conv_adadelta, conv_adagrad, conv_amsgrad, conv_nadam, conv_rmsprop},
+        image::{Image, ImageTrait},
+    };
+
+    let target = Image::constant(2, 2, 1, 1, 1.5);
+    for mut optimizer in [conv_rmsprop(), conv_adagrad(), conv_adadelta(), conv_nadam(), conv_amsgrad()] {
+        let mut parameters = Image::zeros(2, 2, 1, 1);
+        for epoch in 0..10 {
+            let gradient = parameters.component_sub(&target).scalar_mul(2.0);
+            parameters = optimizer.update_parameters(epoch, &parameters, &gradient);
+        }
+        // all of them step towards the target
+        assert!(parameters.component_sub(&target).square().sum() < 4.0 * 1.5 * 1.5);
+    }
+}
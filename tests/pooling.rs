@@ -0,0 +1,132 @@
+use jiro_nn::{
+    model::{
+        conv_network_model::{ConvNetworkLayerModels, ConvNetworkModel, ConvToDense},
+        network_model::{NetworkModel, NetworkModelBuilder},
+    },
+    vision::conv_layer::max_pooling_layer::MaxPoolingLayer,
+};
+
+#[test]
+fn test_max_pooling_output_dims() {
+    assert_eq!(MaxPoolingLayer::new(2, 2, 0).out_img_dims(6, 6), (3, 3));
+    assert_eq!(MaxPoolingLayer::new(3, 1, 1).out_img_dims(5, 4), (5, 4));
+    assert_eq!(MaxPoolingLayer::new(3, 2, 0).out_img_dims(7, 8), (3, 3));
+}
+
+#[test]
+fn test_max_pooling_model() {
+    let model = NetworkModelBuilder::new()
+        .conv_network(2)
+            .max_pooling(3, 2, 1)
+        .end()
+        .build();
+
+    let json = serde_json::to_string(&model).unwrap();
+    let model: NetworkModel = serde_json::from_str(&json).unwrap();
+
+    let (out_dims, _) = model.to_layers(2 * 8 * 8);
+    assert_eq!(out_dims, 2 * 4 * 4);
+
+    let (out_img_dims, out_channels, _) = ConvNetworkLayerModels::MaxPooling {
+        window: 2,
+        stride: 2,
+        padding: 0,
+    }
+    .to_conv_layer((6, 4), 3);
+    assert_eq!((out_img_dims, out_channels), ((3, 2), 3));
+}
+
+#[test]
+fn test_global_pooling_model() {
+    let model = NetworkModelBuilder::new()
+        .conv_network(2)
+            .in_img_dims(6, 4)
+            .global_max_pooling()
+        .end()
+        .full_dense(10)
+        .end()
+        .build();
+
+    let json = serde_json::to_string(&model).unwrap();
+    let model: NetworkModel = serde_json::from_str(&json).unwrap();
+
+    let (out_dims, _) = model.clone().to_layers(2 * 6 * 4);
+    assert_eq!(out_dims, 10);
+
+    let (out_img_dims, out_channels, _) =
+        ConvNetworkLayerModels::GlobalAvgPooling.to_conv_layer((6, 4), 3);
+    assert_eq!((out_img_dims, out_channels), ((1, 1), 3));
+}
+
+#[test]
+fn test_conv_to_dense_transition() {
+    let model = NetworkModelBuilder::new()
+        .conv_network(3)
+            .max_pooling(2, 2, 0)
+            .global_avg_pooling_to_dense()
+        .end()
+        .build();
+
+    let json = serde_json::to_string(&model).unwrap();
+    let model: NetworkModel = serde_json::from_str(&json).unwrap();
+
+    let (out_dims, _) = model.to_layers(3 * 8 * 8);
+    assert_eq!(out_dims, 3);
+
+    // networks serialized before the transition was configurable are flattened
+    let conv: ConvNetworkModel =
+        serde_json::from_str("{\"in_channels\":3,\"layers\":[{\"AvgPooling\":{\"kernel_size\":2}}]}").unwrap();
+    assert_eq!(conv.to_dense, ConvToDense::Flatten);
+    let (out_dims, _) = conv.to_layer(3 * 8 * 8);
+    assert_eq!(out_dims, 3 * 4 * 4);
+}
+
+#[test]
+fn test_max_pooling_routes_gradient_to_argmax() {
+    use jiro_nn::{
+        linalg::{MatrixTrait, Scalar},
+        vision::{image::{Image, ImageTrait}, image_layer::ImageLayer},
+    };
+
+    // 4x4 image holding its column-leading pixel index, the last pixel of each 2x2 patch wins
+    let input = Image::from_fn(4, 4, 1, 1, |x, y, _, _| (x * 4 + y) as Scalar);
+    let mut layer = MaxPoolingLayer::new(2, 2, 0);
+
+    let output = layer.forward(input);
+    assert_eq!(output.flatten().get_column(0), vec![5.0, 7.0, 13.0, 15.0]);
+
+    let input_gradient = layer.backward(0, Image::constant(2, 2, 1, 1, 1.0));
+    let expected: Vec<Scalar> = (0..16)
+        .map(|i| if [5, 7, 13, 15].contains(&i) { 1.0 } else { 0.0 })
+        .collect();
+    assert_eq!(input_gradient.flatten().get_column(0), expected);
+}
+
+#[test]
+fn test_global_pooling_gradients() {
+    use jiro_nn::{
+        linalg::{MatrixTrait, Scalar},
+        vision::{
+            conv_layer::global_pooling_layer::{GlobalAvgPoolingLayer, GlobalMaxPoolingLayer},
+            image::{Image, ImageTrait},
+            image_layer::ImageLayer,
+        },
+    };
+
+    // 2x3 image with 2 channels holding their column-leading pixel index
+    let input = Image::from_fn(2, 3, 2, 1, |x, y, c, _| (c * 6 + x * 2 + y) as Scalar);
+
+    let mut avg = GlobalAvgPoolingLayer::new();
+    let output = avg.forward(input.clone());
+    assert_eq!(output.image_dims(), (1, 1, 2));
+    assert_eq!(output.flatten().get_column(0), vec![2.5, 8.5]);
+    let input_gradient = avg.backward(0, Image::constant(1, 1, 2, 1, 6.0));
+    assert_eq!(input_gradient.flatten().get_column(0), vec![1.0; 12]);
+
+    let mut max = GlobalMaxPoolingLayer::new();
+    let output = max.forward(input);
+    assert_eq!(output.flatten().get_column(0), vec![5.0, 11.0]);
+    let input_gradient = max.backward(0, Image::constant(1, 1, 2, 1, 1.0));
+    let expected: Vec<Scalar> = (0..12).map(|i| if i == 5 || i == 11 { 1.0 } else { 0.0 }).collect();
+    assert_eq!(input_gradient.flatten().get_column(0), expected);
+}
@@ -0,0 +1,156 @@
+#[macro_use]
+extern crate assert_float_eq;
+
+use jiro_nn::{
+    initializers::Initializers,
+    layer::{
+        recurrent_layer::{RecurrentCells, RecurrentLayer},
+        Layer, LearnableLayer,
+    },
+    linalg::{Matrix, MatrixTrait, Scalar},
+    model::network_model::{NetworkModel, NetworkModelBuilder},
+    optimizer::{sgd::SGD, Optimizers},
+};
+
+fn new_layer(cell: RecurrentCells, return_sequences: bool, truncation: Option<usize>) -> RecurrentLayer {
+    RecurrentLayer::new(
+        cell,
+        2,
+        3,
+        return_sequences,
+        truncation,
+        Initializers::GlorotUniform,
+        Initializers::UniformSigned,
+        Optimizers::SGD(SGD::with_const_lr(0.0)),
+        Optimizers::SGD(SGD::with_const_lr(0.0)),
+    )
+}
+
+// 3 time steps of 2 features, 2 samples
+fn input() -> Matrix {
+    Matrix::from_row_leading_vector2(&vec![
+        vec![0.5, -0.2],
+        vec![0.1, 0.3],
+        vec![-0.4, 0.8],
+        vec![0.2, 0.0],
+        vec![0.7, -0.6],
+        vec![-0.1, 0.4],
+    ])
+}
+
+fn check_input_gradient(cell: RecurrentCells, return_sequences: bool) {
+    let mut layer = new_layer(cell, return_sequences, None);
+    let input = input();
+    let output = layer.forward(input.clone());
+    let output_gradient = Matrix::random_uniform(output.dim().0, output.dim().1, -1.0, 1.0);
+    let input_gradient = layer.backward(0, output_gradient.clone());
+
+    let mut weighted_output = |input: &Matrix| -> Scalar {
+        layer
+            .forward(input.clone())
+            .component_mul(&output_gradient)
+            .get_data_col_leading()
+            .iter()
+            .flatten()
+            .sum()
+    };
+
+    let h = 1e-3;
+    let columns = input.get_data_col_leading();
+    for j in 0..columns.len() {
+        for i in 0..columns[j].len() {
+            let mut plus = columns.clone();
+            plus[j][i] += h;
+            let mut minus = columns.clone();
+            minus[j][i] -= h;
+            let numeric = (weighted_output(&Matrix::from_column_leading_vector2(&plus))
+                - weighted_output(&Matrix::from_column_leading_vector2(&minus)))
+                / (2. * h);
+            assert_float_absolute_eq!(input_gradient.get_column(j)[i], numeric, 0.005);
+        }
+    }
+}
+
+#[test]
+fn test_rnn_gradients() {
+    check_input_gradient(RecurrentCells::SimpleRNN, false);
+}
+
+#[test]
+fn test_lstm_gradients() {
+    check_input_gradient(RecurrentCells::LSTM, false);
+    check_input_gradient(RecurrentCells::LSTM, true);
+}
+
+#[test]
+fn test_gru_gradients() {
+    check_input_gradient(RecurrentCells::GRU, true);
+}
+
+#[test]
+fn test_truncation_stops_gradients() {
+    let mut layer = new_layer(RecurrentCells::LSTM, false, Some(1));
+    layer.forward(input());
+    let input_gradient = layer.backward(0, Matrix::constant(3, 2, 1.0));
+
+    // only the last time step receives gradients
+    for row in input_gradient.get_data_row_leading().iter().take(4) {
+        for x in row {
+            assert_float_absolute_eq!(*x, 0.0, 0.0000001);
+        }
+    }
+}
+
+#[test]
+fn test_truncation_blocks() {
+    let mut layer = new_layer(RecurrentCells::SimpleRNN, true, Some(2));
+    layer.forward(input());
+
+    // the steps are split in the blocks [0, 1] and [2], so the last hidden state's gradient
+    // doesn't reach the previous step even though it is within 2 steps
+    let mut output_gradient = vec![vec![0.0; 2]; 9];
+    output_gradient[6..].iter_mut().for_each(|row| *row = vec![1.0; 2]);
+    let input_gradient = layer
+        .backward(0, Matrix::from_row_leading_vector2(&output_gradient))
+        .get_data_row_leading();
+    assert!(input_gradient[..4].iter().flatten().all(|x| *x == 0.0));
+    assert!(input_gradient[4..].iter().flatten().any(|x| *x != 0.0));
+
+    // within a block, the gradients flow back to the block's first step
+    let mut output_gradient = vec![vec![0.0; 2]; 9];
+    output_gradient[3..6].iter_mut().for_each(|row| *row = vec![1.0; 2]);
+    let input_gradient = layer
+        .backward(0, Matrix::from_row_leading_vector2(&output_gradient))
+        .get_data_row_leading();
+    assert!(input_gradient[..2].iter().flatten().any(|x| *x != 0.0));
+    assert!(input_gradient[4..].iter().flatten().all(|x| *x == 0.0));
+}
+
+#[test]
+fn test_model_json_round_trip() {
+    let model = NetworkModelBuilder::new()
+        .lstm(4)
+        .in_features(2)
+        .return_sequences()
+        .truncation(5)
+        .adam()
+        .end()
+        .gru(3)
+        .in_features(4)
+        .end()
+        .build();
+
+    let json = serde_json::to_string(&model).unwrap();
+    let model: NetworkModel = serde_json::from_str(&json).unwrap();
+
+    let mut network = model.to_network(6);
+    assert_eq!(network.predict(&vec![0.0; 6]).len(), 3);
+    // 4 gates of W (2 columns), U (4 columns) and b (1 column), then 3 gates of W (4), U (3) and b (1)
+    assert_eq!(network.get_params().0[0].len(), 4 * (2 + 4 + 1));
+    assert_eq!(network.get_params().0[1].len(), 3 * (4 + 3 + 1));
+
+    let mut layer = new_layer(RecurrentCells::GRU, false, None);
+    let params = layer.get_learnable_parameters();
+    layer.set_learnable_parameters(&params);
+    assert_eq!(layer.get_learnable_parameters(), params);
+}
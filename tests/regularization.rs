@@ -0,0 +1,239 @@
+use std::collections::HashMap;
+
+use jiro_nn::{
+    gradient_clipping::GradientMut,
+    initializers::Initializers,
+    layer::{dense_layer::DenseLayer, GradientLayer, Layer, LearnableLayer},
+    learning_rate::LearningRateSchedule,
+    linalg::{Matrix, MatrixTrait, Scalar},
+    loss::Losses,
+    model::network_model::{NetworkLayerModels, NetworkModel, NetworkModelBuilder},
+    network::graph::{GraphNetwork, GraphOps},
+    optimizer::{adamw::AdamW, sgd::SGD, Optimizers},
+    regularization::Regularization,
+    vision::image::{Image, ImageTrait},
+};
+
+fn assert_close(actual: &[Scalar], expected: &[Scalar], tolerance: Scalar) {
+    for (a, e) in actual.iter().zip(expected.iter()) {
+        assert!((a - e).abs() < tolerance, "{:?} != {:?}", actual, expected);
+    }
+}
+
+fn sgd(lr: Scalar) -> Optimizers {
+    Optimizers::SGD(SGD::with_const_lr(lr))
+}
+
+#[test]
+fn test_regularization_penalty_and_gradient() {
+    let weights = Matrix::from_column_vector(&vec![1.0, -2.0, 0.5]);
+
+    let elastic_net = Regularization::elastic_net(0.1, 0.01);
+    // 0.1 * 3.5 + 0.01 * 5.25
+    assert!((elastic_net.penalty(&weights) - 0.4025).abs() < 1e-6);
+    assert_close(&elastic_net.gradient(&weights).get_column(0), &[0.12, -0.14, 0.11], 1e-6);
+
+    assert!((Regularization::l1(0.1).penalty(&weights) - 0.35).abs() < 1e-6);
+    assert!((Regularization::l2(0.1).penalty(&weights) - 0.525).abs() < 1e-6);
+    assert!(!Regularization::l2(0.1).biases);
+
+    // the L1 subgradient is null where the weights are
+    let weights = Matrix::from_column_vector(&vec![0.0, -2.0]);
+    assert_close(&Regularization::l1(0.1).gradient(&weights).get_column(0), &[0.0, -0.1], 1e-6);
+
+    let kernels = Image::from_samples(&Matrix::from_column_vector(&vec![0.0, -2.0, 0.5, 0.0]), 1);
+    assert_close(
+        &Regularization::l1(0.1).image_gradient(&kernels).flatten().get_column(0),
+        &[0.0, -0.1, 0.1, 0.0],
+        1e-6,
+    );
+}
+
+#[test]
+fn test_dense_layer_weight_decay() {
+    let new_layer = |regularization: Regularization| {
+        DenseLayer::new(
+            2,
+            2,
+            sgd(0.1),
+            sgd(0.1),
+            Initializers::UniformSigned,
+            Initializers::UniformSigned,
+        )
+        .with_regularization(regularization)
+    };
+
+    for regularization in [Regularization::l2(0.5), Regularization::l2(0.5).including_biases()] {
+        let mut layer = new_layer(regularization.clone());
+        let weights = layer.weights.get_data_col_leading();
+        let biases = layer.biases.get_column(0);
+
+        let squares: Scalar = weights.iter().flatten().map(|w| w * w).sum();
+        let biases_squares: Scalar = biases.iter().map(|b| b * b).sum();
+        let expected = 0.5 * (squares + if regularization.biases { biases_squares } else { 0.0 });
+        assert!((layer.regularization_loss() - expected).abs() < 1e-5);
+
+        // without any error, the weights only shrink by lr * 2 * l2 for each of the 3 samples
+        layer.forward(Matrix::constant(2, 3, 1.0));
+        layer.backward(0, Matrix::zeros(2, 3));
+        layer.step(0);
+
+        let shrunk: Vec<Scalar> = weights.iter().flatten().map(|w| w * 0.7).collect();
+        let new_weights: Vec<Scalar> = layer.weights.get_data_col_leading().concat();
+        assert_close(&new_weights, &shrunk, 1e-5);
+
+        let expected_biases: Vec<Scalar> = if regularization.biases {
+            biases.iter().map(|b| b * 0.7).collect()
+        } else {
+            biases.clone()
+        };
+        assert_close(&layer.biases.get_column(0), &expected_biases, 1e-5);
+    }
+}
+
+#[test]
+fn test_penalty_gradient_matches_finite_differences() {
+    let mut layer = DenseLayer::new(
+        2,
+        1,
+        sgd(0.0),
+        sgd(0.0),
+        Initializers::UniformSigned,
+        Initializers::UniformSigned,
+    )
+    .with_regularization(Regularization::elastic_net(0.1, 0.2).including_biases());
+    let params = vec![vec![0.5], vec![-0.8], vec![0.3]];
+    let x = Matrix::from_column_leading_vector2(&vec![vec![0.1, 0.2], vec![0.5, -0.3], vec![-0.4, 0.8]]);
+    let y = Matrix::from_column_leading_vector2(&vec![vec![1.0], vec![0.0], vec![0.5]]);
+    let loss = Losses::MSE.to_loss();
+
+    // the reported training loss: averaged over the samples, plus the penalty
+    let objective = |layer: &mut DenseLayer, params: &Vec<Vec<Scalar>>| {
+        layer.set_learnable_parameters(params);
+        let pred = layer.forward(x.clone());
+        loss.loss(&y, &pred) + layer.regularization_loss()
+    };
+
+    let eps = 1e-2;
+    let numerical: Vec<Scalar> = (0..params.len())
+        .map(|i| {
+            let mut plus = params.clone();
+            plus[i][0] += eps;
+            let mut minus = params.clone();
+            minus[i][0] -= eps;
+            (objective(&mut layer, &plus) - objective(&mut layer, &minus)) / (2.0 * eps)
+        })
+        .collect();
+
+    layer.set_learnable_parameters(&params);
+    let pred = layer.forward(x.clone());
+    layer.backward(0, loss.loss_prime(&y, &pred));
+    let gradients: Vec<Scalar> = layer
+        .gradients_mut()
+        .iter()
+        .flat_map(|gradient| match gradient {
+            GradientMut::Matrix(m) => m.get_data_col_leading().concat(),
+            GradientMut::Image(_) => panic!("Expected a matrix gradient"),
+        })
+        .collect();
+
+    // like the data gradient, the penalty gradient is summed over the 3 samples
+    let expected: Vec<Scalar> = numerical.iter().map(|g| 3.0 * g).collect();
+    assert_close(&gradients, &expected, 1e-3);
+}
+
+#[test]
+fn test_graph_network_regularization() {
+    let (out_dims, layers) = NetworkModelBuilder::new()
+        .full_dense(1)
+        .linear()
+        .l2(0.1)
+        .optimizer(sgd(0.0))
+        .end()
+        .build()
+        .to_layers(2);
+    let penalty: Scalar = layers.iter().map(|l| l.regularization_loss()).sum();
+
+    let mut network = GraphNetwork::new();
+    network
+        .add_node("x", &[], GraphOps::Input(2))
+        .add_layers_node("y", "x", layers, out_dims)
+        .add_output("y");
+    assert!(penalty > 0.0);
+    assert!((network.regularization_loss() - penalty).abs() < 1e-6);
+
+    let x = HashMap::from([("x".to_string(), vec![vec![0.1, 0.2], vec![0.5, -0.3]])]);
+    let y = HashMap::from([("y".to_string(), vec![vec![1.0], vec![0.0]])]);
+    let losses = HashMap::from([("y".to_string(), Losses::MSE.to_loss())]);
+
+    // weights don't move with a null learning rate
+    let preds = network.predict_many(&x, 2);
+    let data_loss = losses["y"].loss_vec(&y["y"], &preds["y"]);
+    let train_loss = network.train(0, &x, &y, &losses, 2);
+    assert!((train_loss - (data_loss + penalty)).abs() < 1e-5);
+}
+
+#[test]
+fn test_regularization_in_reported_loss() {
+    let model = NetworkModelBuilder::new()
+        .full_dense(3)
+        .tanh()
+        .l2(0.1)
+        .optimizer(sgd(0.0))
+        .end()
+        .full_dense(1)
+        .linear()
+        .elastic_net(0.1, 0.2)
+        .optimizer(sgd(0.0))
+        .end()
+        .build();
+
+    let json = serde_json::to_string(&model).unwrap();
+    assert!(json.contains("\"regularization\":{\"l1\":0.1,\"l2\":0.2,\"biases\":false}"));
+    let model: NetworkModel = serde_json::from_str(&json).unwrap();
+
+    let mut network = model.to_network(2);
+    let x = vec![vec![0.1, 0.2], vec![0.5, -0.3], vec![-0.4, 0.8]];
+    let y = vec![vec![1.0], vec![0.0], vec![0.5]];
+    let loss = Losses::MSE.to_loss();
+
+    // weights don't move with a null learning rate
+    let (_, data_loss, _) = network.predict_evaluate_many(&x, &y, &loss, 3);
+    let params = network.get_params().0;
+    let weights = |layer: &Vec<Vec<Scalar>>| -> Vec<Scalar> { layer[..layer.len() - 1].concat() };
+    let penalty = 0.1 * weights(&params[0]).iter().map(|w| w * w).sum::<Scalar>()
+        + 0.1 * weights(&params[1]).iter().map(|w| w.abs()).sum::<Scalar>()
+        + 0.2 * weights(&params[1]).iter().map(|w| w * w).sum::<Scalar>();
+
+    let train_loss = network.train(0, &x, &y, &loss, 3);
+    assert!((train_loss - (data_loss + penalty)).abs() < 1e-5);
+}
+
+#[test]
+fn test_adamw() {
+    let lr = LearningRateSchedule::Constant(0.1);
+    let mut adamw = Optimizers::AdamW(AdamW::new(lr, 0.9, 0.999, 1e-8, 0.5));
+    let parameters = Matrix::from_column_vector(&vec![2.0, -1.0]);
+
+    // the decay doesn't go through the gradients' moments
+    let updated = adamw.update_parameters(0, &parameters, &Matrix::zeros(2, 1));
+    assert_close(&updated.get_column(0), &[1.9, -0.95], 1e-6);
+
+    let updated = adamw.update_parameters(1, &updated, &Matrix::from_column_vector(&vec![3.0, 0.0]));
+    // the moments only saw the second gradient, bias corrected at t = 2
+    let m_hat = 0.1 * 3.0 / (1.0 - 0.9 * 0.9);
+    let v_hat = 0.001 * 9.0 / (1.0 - 0.999 * 0.999 as Scalar);
+    let step = 0.1 * m_hat / (v_hat.sqrt() + 1e-8);
+    assert_close(&updated.get_column(0), &[1.9 * 0.95 - step, -0.95 * 0.95], 1e-4);
+
+    let json = serde_json::to_string(&serde_json::from_str::<Optimizers>("{\"AdamW\":{}}").unwrap()).unwrap();
+    assert!(json.contains("\"weight_decay\":0.01"));
+
+    // biases aren't decayed by default
+    let model = NetworkModelBuilder::new().full_dense(2).adamw().end().build();
+    assert!(matches!(
+        &model.layers[0],
+        NetworkLayerModels::FullDense(l)
+            if matches!(l.weights_optimizer, Optimizers::AdamW(_)) && matches!(l.biases_optimizer, Optimizers::Adam(_))
+    ));
+}
@@ -0,0 +1,267 @@
+#[macro_use]
+extern crate assert_float_eq;
+
+use jiro_nn::{
+    initializers::Initializers,
+    layer::{autodiff_layer::AutodiffLayer, dense_layer::DenseLayer, residual_block::ResidualBlock, Layer},
+    linalg::{Matrix, MatrixTrait, Scalar},
+    loss::Losses,
+    model::{
+        conv_network_model::ConvNetworkLayerModels,
+        conv_residual_block_model::ConvResidualBlockModel,
+        network_model::{NetworkLayerModels, NetworkModel, NetworkModelBuilder},
+    },
+    optimizer::{sgd::SGD, Optimizers},
+    regularization::Regularization,
+    vision::{
+        conv_initializers::ConvInitializers,
+        conv_layer::{
+            conv_padding::ConvPaddings, conv_residual_block::ConvResidualBlock,
+            dense_conv_layer::DenseConvLayer,
+        },
+        conv_optimizer::conv_sgd,
+        image::{Image, ImageTrait},
+        image_layer::ImageLayer,
+    },
+};
+
+#[test]
+fn test_gradient_flows_through_both_branches() {
+    let weights = Matrix::from_row_leading_vector2(&vec![vec![1.0, 2.0], vec![-1.0, 0.5]]);
+    let inner = AutodiffLayer::new(
+        Box::new(|tape, input, params| tape.dot(params[0], input)),
+        vec![weights.clone()],
+        vec![Optimizers::SGD(SGD::with_const_lr(0.0))],
+    );
+    let mut block = ResidualBlock::new(vec![Box::new(inner)], None);
+
+    let input = Matrix::from_column_vector(&vec![1.0, -2.0]);
+    let output = block.forward(input.clone());
+    let expected_output = weights.dot(&input).component_add(&input);
+    assert_eq!(output.get_column(0), expected_output.get_column(0));
+
+    let output_gradient = Matrix::from_column_vector(&vec![0.5, 1.0]);
+    let input_gradient = block.backward(0, output_gradient.clone());
+    let expected_gradient = weights
+        .transpose()
+        .dot(&output_gradient)
+        .component_add(&output_gradient);
+    for (x, y) in input_gradient
+        .get_column(0)
+        .iter()
+        .zip(expected_gradient.get_column(0).iter())
+    {
+        assert_float_absolute_eq!(x, y, 0.00001);
+    }
+}
+
+#[test]
+fn test_residual_model_with_projection() {
+    let model = NetworkModelBuilder::new()
+        .residual()
+            .full_dense(4)
+                .relu()
+            .end()
+            .full_dense(4)
+                .linear()
+            .end()
+        .end_residual()
+        .residual()
+            .full_dense(3)
+                .linear()
+            .end()
+        .end_residual()
+        .build();
+
+    let json = serde_json::to_string(&model).unwrap();
+    let model: NetworkModel = serde_json::from_str(&json).unwrap();
+
+    let mut network = model.clone().to_network(4);
+    let x = vec![vec![1.0, 0.0, -1.0, 0.5], vec![0.2, 0.4, 0.6, 0.8]];
+    let y = vec![vec![1.0, 0.0, 0.0], vec![0.0, 1.0, 0.0]];
+    let loss = Losses::MSE.to_loss();
+    for epoch in 0..5 {
+        network.train(epoch, &x, &y, &loss, 2);
+    }
+    assert_eq!(network.predict(&x[0]).len(), 3);
+
+    let params = network.get_params();
+    // the second block projects its 4 inputs to 3 outputs: its dense layer's 4 + 1 columns then the projection's,
+    // each preceded by their amount of columns
+    assert_eq!(params.0[1].len(), 1 + (4 + 1) + 1 + (4 + 1));
+
+    let mut loaded = model.to_network(4);
+    loaded.load_params(&params);
+    assert_eq!(loaded.get_params().0, params.0);
+    assert_eq!(loaded.predict(&x[1]), network.predict(&x[1]));
+}
+
+fn assert_params_round_trip(model: NetworkModel, in_dims: usize, x: &Vec<Vec<Scalar>>, y: &Vec<Vec<Scalar>>) {
+    let mut network = model.clone().to_network(in_dims);
+    network.train(0, x, y, &Losses::MSE.to_loss(), 2);
+    let params = network.get_params();
+
+    let mut loaded = model.to_network(in_dims);
+    loaded.load_params(&params);
+    assert_eq!(loaded.get_params().0, params.0);
+    assert_eq!(loaded.predict(&x[0]), network.predict(&x[0]));
+}
+
+#[test]
+fn test_nested_residual_params() {
+    let model = NetworkModelBuilder::new()
+        .residual()
+            .full_dense(4)
+                .relu()
+            .end()
+            .residual()
+                .full_dense(3)
+                    .linear()
+                .end()
+            .end_residual()
+        .end_residual()
+        .build();
+
+    let x = vec![vec![1.0, 0.0, -1.0, 0.5], vec![0.2, 0.4, 0.6, 0.8]];
+    let y = vec![vec![1.0, 0.0, 0.0], vec![0.0, 1.0, 0.0]];
+    assert_params_round_trip(model, 4, &x, &y);
+}
+
+#[test]
+fn test_residual_params_holding_minus_one() {
+    let model = NetworkModelBuilder::new()
+        .residual()
+            .full_dense(1)
+                .linear()
+            .end()
+        .end_residual()
+        .build();
+
+    let mut network = model.clone().to_network(1);
+    let mut params = network.get_params();
+    // the 1x1 weights and the bias
+    params.0[0][1] = vec![-1.0];
+    params.0[0][2] = vec![-1.0];
+    network.load_params(&params);
+    assert_eq!(network.get_params().0, params.0);
+    assert_eq!(network.predict(&vec![2.0]), vec![2.0 - 2.0 - 1.0]);
+}
+
+#[test]
+fn test_nested_conv_residual_params() {
+    let model = NetworkModelBuilder::new()
+        .conv_network(1)
+            .in_img_dims(5, 5)
+            .residual()
+                .full_dense(2, 3)
+                    .same_padding()
+                .end()
+                .residual()
+                    .full_dense(2, 3)
+                        .same_padding()
+                    .end()
+                .end_residual()
+            .end_residual()
+        .end()
+        .build();
+
+    let x = vec![vec![0.5; 25], (0..25).map(|i| i as Scalar / 25.0).collect()];
+    let y = vec![vec![0.0; 50], vec![1.0; 50]];
+    assert_params_round_trip(model, 25, &x, &y);
+}
+
+fn conv_residual_model(in_img_dims: (usize, usize), model: NetworkModel) -> ConvResidualBlockModel {
+    match &model.layers[0] {
+        NetworkLayerModels::Convolution(conv) => {
+            assert_eq!(conv.in_img_dims, Some(in_img_dims));
+            match &conv.layers[0] {
+                ConvNetworkLayerModels::Residual(residual) => residual.clone(),
+                _ => panic!("Expected a residual block"),
+            }
+        }
+        _ => panic!("Expected a convolutional network"),
+    }
+}
+
+#[test]
+fn test_conv_residual_strided_projection() {
+    let model = NetworkModelBuilder::new()
+        .conv_network(1)
+            .in_img_dims(8, 6)
+            .residual()
+                .full_dense(4, 3)
+                    .same_padding()
+                    .stride(2)
+                .end()
+            .end_residual()
+        .end()
+        .build();
+
+    let residual = conv_residual_model((8, 6), model);
+    let (out_img_dims, out_channels, mut block) = residual.to_layer((8, 6), 1);
+    assert_eq!((out_img_dims, out_channels), ((4, 3), 4));
+
+    // the projection is a 1x1 convolution: 4 kernels of 1 pixel and their biases
+    let params = block.as_learnable_layer().unwrap().get_learnable_parameters();
+    let projection = &params[1 + 4 + 1 + 1..];
+    assert_eq!(projection.len(), 4 + 1);
+    assert!(projection[..4].iter().all(|kernel| kernel.len() == 1));
+
+    let output = block.forward(Image::random_uniform(8, 6, 1, 2, -1.0, 1.0));
+    assert_eq!(output.image_dims(), (4, 3, 4));
+    assert_eq!(output.samples(), 2);
+    let input_gradient = block.backward(0, Image::random_uniform(4, 3, 4, 2, -1.0, 1.0));
+    assert_eq!(input_gradient.image_dims(), (8, 6, 1));
+}
+
+#[test]
+#[should_panic(expected = "strided 1x1 convolution")]
+fn test_conv_residual_unprojectable_dims() {
+    let model = NetworkModelBuilder::new()
+        .conv_network(1)
+            .in_img_dims(6, 6)
+            .residual()
+                .full_dense(4, 3)
+                .end()
+            .end_residual()
+        .end()
+        .build();
+
+    // 6x6 images can't be strided to 4x4 ones
+    conv_residual_model((6, 6), model).to_layer((6, 6), 1);
+}
+
+#[test]
+fn test_projection_regularization_loss() {
+    let projection = DenseLayer::new(
+        2,
+        3,
+        Optimizers::SGD(SGD::with_const_lr(0.0)),
+        Optimizers::SGD(SGD::with_const_lr(0.0)),
+        Initializers::UniformSigned,
+        Initializers::UniformSigned,
+    )
+    .with_regularization(Regularization::l2(0.1));
+    let penalty = projection.regularization_loss();
+    assert!(penalty > 0.0);
+    let block = ResidualBlock::new(vec![], Some(projection));
+    assert_float_absolute_eq!(block.regularization_loss(), penalty, 1e-6);
+
+    let projection = DenseConvLayer::new(
+        1,
+        1,
+        2,
+        3,
+        1,
+        ConvPaddings::Valid,
+        ConvInitializers::UniformSigned,
+        ConvInitializers::UniformSigned,
+        conv_sgd(),
+        conv_sgd(),
+    )
+    .with_regularization(Regularization::l1(0.1));
+    let penalty = ImageLayer::regularization_loss(&projection);
+    assert!(penalty > 0.0);
+    let block = ConvResidualBlock::new(vec![], Some(projection));
+    assert_float_absolute_eq!(ImageLayer::regularization_loss(&block), penalty, 1e-6);
+}
@@ -0,0 +1,132 @@
+use jiro_nn::{
+    dataset::{Dataset, FeatureTags},
+    datatable::DataTable,
+    linalg::{Matrix, MatrixTrait, Scalar},
+    loss::Losses,
+    model::{Model, ModelBuilder},
+    trainers::{kfolds::KFolds, split::SplitTraining},
+};
+
+fn y_true() -> Matrix {
+    Matrix::from_column_leading_vector2(&vec![vec![1.0, -2.0], vec![0.5, 3.0]])
+}
+
+fn y_pred() -> Matrix {
+    Matrix::from_column_leading_vector2(&vec![vec![1.3, 1.0], vec![-0.5, 2.9]])
+}
+
+fn losses() -> Vec<Losses> {
+    vec![
+        Losses::MAE,
+        Losses::Huber { delta: 1.0 },
+        Losses::LogCosh,
+        Losses::Quantile { tau: 0.9 },
+    ]
+}
+
+#[test]
+fn test_robust_losses_values() {
+    // errors are y_pred - y_true
+    let errors: [Scalar; 4] = [0.3, 3.0, -1.0, -0.1];
+    let mean = |f: &dyn Fn(Scalar) -> Scalar| errors.iter().map(|e| f(*e)).sum::<Scalar>() / 4.0;
+
+    let expected = [
+        mean(&|e| e.abs()),
+        mean(&|e| if e.abs() <= 1.0 { 0.5 * e * e } else { e.abs() - 0.5 }),
+        mean(&|e| e.cosh().ln()),
+        mean(&|e| if e < 0.0 { -0.9 * e } else { 0.1 * e }),
+    ];
+
+    for (loss, expected) in losses().iter().zip(expected.iter()) {
+        let value = loss.to_loss().loss(&y_true(), &y_pred());
+        assert!((value - expected).abs() < 1e-5, "{:?}: {} != {}", loss, value, expected);
+    }
+}
+
+#[test]
+fn test_robust_losses_derivatives() {
+    let h = 1e-3;
+    let (nrow, ncol) = y_pred().dim();
+
+    for loss in losses() {
+        let loss = loss.to_loss();
+        let gradient = loss.loss_prime(&y_true(), &y_pred()).get_data_col_leading();
+
+        // like MSE's, the gradients are the ones of the summed (not averaged) per-component losses
+        for j in 0..ncol {
+            for i in 0..nrow {
+                let shifted = |delta: Scalar| {
+                    let mut pred = y_pred();
+                    *pred.index_mut(i, j) += delta;
+                    loss.loss(&y_true(), &pred) * (nrow * ncol) as Scalar
+                };
+                let expected = (shifted(h) - shifted(-h)) / (2.0 * h);
+                assert!((gradient[j][i] - expected).abs() < 1e-2);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_robust_losses_null_derivative_at_fit() {
+    let y_pred = Matrix::from_column_leading_vector2(&vec![vec![1.0, 0.0], vec![0.5, 2.9]]);
+    for loss in losses() {
+        let gradient = loss.to_loss().loss_prime(&y_true(), &y_pred).get_data_col_leading();
+        assert_eq!(gradient[0][0], 0.0, "{:?}", loss);
+        assert_eq!(gradient[1][0], 0.0, "{:?}", loss);
+        assert!(gradient[0][1] != 0.0 && gradient[1][1] != 0.0, "{:?}", loss);
+    }
+}
+
+#[test]
+fn test_robust_losses_serialization() {
+    let json = serde_json::to_string(&losses()).unwrap();
+    assert_eq!(json, "[\"MAE\",{\"Huber\":{\"delta\":1.0}},\"LogCosh\",{\"Quantile\":{\"tau\":0.9}}]");
+
+    let losses: Vec<Losses> = serde_json::from_str(&json).unwrap();
+    assert!(matches!(losses[1], Losses::Huber { delta } if delta == 1.0));
+    assert!(matches!(losses[3], Losses::Quantile { tau } if tau == 0.9));
+}
+
+#[test]
+fn test_robust_losses_trainers() {
+    let dataset = Dataset::from_features_tags(&[
+        &[FeatureTags::Name("id"), FeatureTags::IsId],
+        &[FeatureTags::Name("x")],
+        &[FeatureTags::Name("y"), FeatureTags::Predicted],
+    ]);
+
+    let rows: Vec<Vec<Scalar>> = (0..40)
+        .map(|i| {
+            let x = i as Scalar / 40.0;
+            // a few outliers
+            let y = if i % 10 == 0 { 50.0 } else { 2.0 * x };
+            vec![i as Scalar, x, y]
+        })
+        .collect();
+    let data = DataTable::from_vectors(&["id", "x", "y"], &rows);
+
+    for loss in losses() {
+        let model = ModelBuilder::new(dataset.clone())
+            .neural_network()
+            .full_dense(1)
+            .linear()
+            .end()
+            .end()
+            .loss(loss)
+            .epochs(3)
+            .batch_size(8)
+            .build();
+
+        let model: Model = serde_json::from_str(&serde_json::to_string(&model).unwrap()).unwrap();
+
+        let (_, eval) = SplitTraining::new(0.8).run(&model, &data);
+        assert_eq!(eval.get_n_epochs(), 3);
+        assert!(eval.epochs_avg_train_loss().iter().all(|l| l.is_finite()));
+        assert!(eval.epochs_avg_test_loss().iter().all(|l| l.is_finite()));
+
+        let (_, eval) = KFolds::new(2).run(&model, &data);
+        assert_eq!(eval.get_n_folds(), 2);
+        assert!(eval.epochs_avg_test_loss().iter().all(|l| l.is_finite()));
+    }
+}
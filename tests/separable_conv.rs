@@ -0,0 +1,136 @@
+use jiro_nn::{
+    linalg::Scalar,
+    loss::Losses,
+    model::{
+        conv_network_model::ConvNetworkLayerModels,
+        network_model::{NetworkLayerModels, NetworkModel, NetworkModelBuilder},
+    },
+    vision::{conv_initializers::ConvInitializers, conv_optimizer::{conv_adam, ConvOptimizers}},
+};
+
+#[test]
+fn test_separable_conv_model() {
+    let model = NetworkModelBuilder::new()
+        .conv_network(3)
+            .in_img_dims(8, 10)
+            .separable_conv(16, 3)
+                .same_padding()
+                .stride(2)
+                .depthwise_kernels_init(ConvInitializers::UniformSigned)
+                .pointwise_kernels_optimizer(conv_adam())
+                .relu()
+            .end()
+            .separable_conv(4, 3)
+            .end()
+        .end()
+        .build();
+
+    let json = serde_json::to_string(&model).unwrap();
+    let model: NetworkModel = serde_json::from_str(&json).unwrap();
+
+    let conv = match &model.layers[0] {
+        NetworkLayerModels::Convolution(conv) => conv.clone(),
+        _ => panic!("Expected a convolutional network"),
+    };
+    match &conv.layers[0] {
+        ConvNetworkLayerModels::SeparableConv(layer) => {
+            assert_eq!((layer.kernels_count, layer.kernels_size, layer.stride), (16, 3, 2));
+            assert!(matches!(layer.depthwise_kernels_initializer, ConvInitializers::UniformSigned));
+            assert!(matches!(layer.pointwise_kernels_initializer, ConvInitializers::GlorotUniform));
+            assert!(matches!(layer.depthwise_kernels_optimizer, ConvOptimizers::ConvSGD(_)));
+            assert!(matches!(layer.pointwise_kernels_optimizer, ConvOptimizers::ConvAdam(_)));
+        }
+        _ => panic!("Expected a separable convolutional layer"),
+    }
+}
+
+#[test]
+fn test_separable_conv_network_params() {
+    let model = NetworkModelBuilder::new()
+        .conv_network(2)
+            .in_img_dims(6, 6)
+            .separable_conv(3, 3)
+                .same_padding()
+            .end()
+            .separable_conv(2, 3)
+            .end()
+        .end()
+        .build();
+
+    let x = vec![(0..72).map(|i| (i as Scalar / 36.0).sin()).collect::<Vec<_>>()];
+    let y = vec![vec![1.0; 32]];
+    let mut network = model.clone().to_network(72);
+    network.train(0, &x, &y, &Losses::MSE.to_loss(), 1);
+    let params = network.get_params();
+
+    let mut loaded = model.to_network(72);
+    loaded.load_params(&params);
+    assert_eq!(loaded.get_params().0, params.0);
+    assert_eq!(loaded.predict(&x[0]), network.predict(&x[0]));
+}
+
+mod image_layers {
+    use jiro_nn::{
+        layer::LearnableLayer,
+        vision::{
+            conv_initializers::ConvInitializers,
+            conv_layer::{
+                conv_padding::ConvPaddings, dense_conv_layer::DenseConvLayer,
+                direct_conv_layer::DirectConvLayer, separable_conv_layer::SeparableConvLayer,
+            },
+            conv_optimizer::conv_sgd,
+            image::{Image, ImageTrait},
+            image_layer::ImageLayer,
+        },
+    };
+
+    fn separable_conv(in_channels: usize, kernels_count: usize) -> SeparableConvLayer {
+        SeparableConvLayer::new(
+            DirectConvLayer::new(
+                3,
+                3,
+                in_channels,
+                1,
+                ConvPaddings::Same,
+                ConvInitializers::UniformSigned,
+                ConvInitializers::UniformSigned,
+                conv_sgd(),
+                conv_sgd(),
+            ),
+            DenseConvLayer::new(
+                1,
+                1,
+                in_channels,
+                kernels_count,
+                1,
+                ConvPaddings::Valid,
+                ConvInitializers::UniformSigned,
+                ConvInitializers::UniformSigned,
+                conv_sgd(),
+                conv_sgd(),
+            ),
+        )
+    }
+
+    #[test]
+    fn test_separable_conv_forward_backward() {
+        let mut layer = separable_conv(3, 5);
+        let input = Image::random_uniform(6, 7, 3, 2, -1.0, 1.0);
+
+        let output = layer.forward(input);
+        assert_eq!(output.image_dims(), (6, 7, 5));
+        assert_eq!(output.samples(), 2);
+
+        let input_gradient = layer.backward(0, Image::random_uniform(6, 7, 5, 2, -1.0, 1.0));
+        assert_eq!(input_gradient.image_dims(), (6, 7, 3));
+    }
+
+    #[test]
+    fn test_separable_conv_params() {
+        let layer = separable_conv(3, 5);
+        let mut other = separable_conv(3, 5);
+
+        other.set_learnable_parameters(&layer.get_learnable_parameters());
+        assert_eq!(other.get_learnable_parameters(), layer.get_learnable_parameters());
+    }
+}
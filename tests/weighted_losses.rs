@@ -0,0 +1,164 @@
+use jiro_nn::{
+    dataset::{Dataset, FeatureTags},
+    datatable::DataTable,
+    linalg::{Matrix, MatrixTrait, Scalar},
+    loss::Losses,
+    model::{network_model::NetworkModelBuilder, Model, ModelBuilder},
+    trainers::{kfolds::KFolds, split::SplitTraining},
+};
+
+fn y_true() -> Matrix {
+    Matrix::from_row_vector(&vec![1.0, 0.0, 0.0, 1.0])
+}
+
+fn y_pred() -> Matrix {
+    Matrix::from_row_vector(&vec![0.9, 0.2, 0.6, 0.3])
+}
+
+#[test]
+fn test_class_and_sample_weights() {
+    let bce = Losses::BCE.to_loss();
+    let per_sample: Vec<Scalar> = (0..4)
+        .map(|j| bce.loss(&y_true().get_column_as_matrix(j), &y_pred().get_column_as_matrix(j)))
+        .collect();
+    let gradient = bce.loss_prime(&y_true(), &y_pred()).get_row(0);
+
+    // the positive class weighs 3 times more than the negative one
+    let weighted = Losses::BCE.to_loss().with_class_weights(vec![1.0, 3.0]);
+    let weights = [3.0, 1.0, 1.0, 3.0];
+    let expected: Scalar = per_sample.iter().zip(weights.iter()).map(|(l, w)| l * w).sum::<Scalar>() / 4.0;
+    assert!((weighted.loss(&y_true(), &y_pred()) - expected).abs() < 1e-5);
+
+    let weighted_gradient = weighted.loss_prime(&y_true(), &y_pred()).get_row(0);
+    for j in 0..4 {
+        assert!((weighted_gradient[j] - weights[j] * gradient[j]).abs() < 1e-5);
+    }
+
+    // sample weights are multiplied by the class weights
+    let sample_weights = vec![0.0, 2.0, 1.0, 1.0];
+    let expected = (2.0 * per_sample[1] + per_sample[2] + 3.0 * per_sample[3]) / 4.0;
+    let value = weighted.weighted_loss(&y_true(), &y_pred(), Some(&sample_weights));
+    assert!((value - expected).abs() < 1e-5);
+    let weighted_gradient = weighted
+        .weighted_loss_prime(&y_true(), &y_pred(), Some(&sample_weights))
+        .get_row(0);
+    assert_eq!(weighted_gradient[0], 0.0);
+    assert!((weighted_gradient[3] - 3.0 * gradient[3]).abs() < 1e-5);
+
+    // one-hot encoded classes
+    let cce = Losses::CategoricalCrossEntropy { label_smoothing: 0.0 }
+        .to_loss()
+        .with_class_weights(vec![1.0, 4.0]);
+    let y_true = Matrix::from_column_leading_vector2(&vec![vec![0.0, 1.0], vec![1.0, 0.0]]);
+    let y_pred = Matrix::from_column_leading_vector2(&vec![vec![0.5, 0.5], vec![0.5, 0.5]]);
+    let value = cce.loss(&y_true, &y_pred);
+    assert!((value - 5.0 * (2.0 as Scalar).ln() / 2.0).abs() < 1e-5);
+}
+
+#[test]
+fn test_focal_loss() {
+    let (gamma, alpha) = (2.0, 0.25);
+    let focal = Losses::Focal { gamma, alpha }.to_loss();
+
+    let expected = y_true()
+        .get_row(0)
+        .iter()
+        .zip(y_pred().get_row(0).iter())
+        .map(|(y, p)| {
+            if *y == 1.0 {
+                -alpha * (1.0 - p).powf(gamma) * p.ln()
+            } else {
+                -(1.0 - alpha) * p.powf(gamma) * (1.0 - p).ln()
+            }
+        })
+        .sum::<Scalar>()
+        / 4.0;
+    assert!((focal.loss(&y_true(), &y_pred()) - expected).abs() < 1e-5);
+
+    // without focusing, it is a weighted binary cross entropy
+    let unfocused = Losses::Focal { gamma: 0.0, alpha: 0.5 }.to_loss();
+    let bce = Losses::BCE.to_loss().loss(&y_true(), &y_pred());
+    assert!((unfocused.loss(&y_true(), &y_pred()) - bce / 2.0).abs() < 1e-5);
+
+    let h = 1e-3;
+    let gradient = focal.loss_prime(&y_true(), &y_pred()).get_row(0);
+    for j in 0..4 {
+        let shifted = |delta: Scalar| {
+            let mut pred = y_pred();
+            *pred.index_mut(0, j) += delta;
+            focal.loss(&y_true(), &pred) * 4.0
+        };
+        let expected = (shifted(h) - shifted(-h)) / (2.0 * h);
+        assert!((gradient[j] - expected).abs() < 1e-2, "{} != {}", gradient[j], expected);
+    }
+
+    let json = serde_json::to_string(&Losses::Focal { gamma, alpha }).unwrap();
+    assert_eq!(json, "{\"Focal\":{\"gamma\":2.0,\"alpha\":0.25}}");
+}
+
+fn weighted_model(dataset: &Dataset) -> Model {
+    let model = ModelBuilder::new(dataset.clone())
+        .neural_network()
+        .full_dense(1)
+        .sigmoid()
+        .end()
+        .end()
+        .loss(Losses::Focal { gamma: 2.0, alpha: 0.5 })
+        .class_weights(vec![1.0, 10.0])
+        .epochs(3)
+        .batch_size(8)
+        .build();
+
+    serde_json::from_str(&serde_json::to_string(&model).unwrap()).unwrap()
+}
+
+#[test]
+fn test_sample_weight_feature() {
+    let dataset = Dataset::from_features_tags(&[
+        &[FeatureTags::Name("id"), FeatureTags::IsId],
+        &[FeatureTags::Name("x")],
+        &[FeatureTags::Name("weight"), FeatureTags::SampleWeight],
+        &[FeatureTags::Name("y"), FeatureTags::Predicted],
+    ]);
+    assert_eq!(dataset.in_features_names(), vec!["x"]);
+    assert_eq!(dataset.get_sample_weight_column(), Some("weight"));
+
+    let rows = |weight: Scalar| -> DataTable {
+        let rows: Vec<Vec<Scalar>> = (0..40)
+            .map(|i| {
+                let x = i as Scalar / 40.0;
+                let y = if i % 10 == 0 { 1.0 } else { 0.0 };
+                vec![i as Scalar, x, weight, y]
+            })
+            .collect();
+        DataTable::from_vectors(&["id", "x", "weight", "y"], &rows)
+    };
+
+    let model = weighted_model(&dataset);
+    assert_eq!(model.class_weights, Some(vec![1.0, 10.0]));
+
+    let (_, eval) = SplitTraining::new(0.8).run(&model, &rows(1.0));
+    let final_epoch = eval.folds[0].get_final_epoch();
+    assert!(final_epoch.train_loss > 0.0 && final_epoch.test_loss_avg > 0.0);
+
+    // samples weighing nothing don't count in the training nor in the validation losses
+    let (_, eval) = SplitTraining::new(0.8).run(&model, &rows(0.0));
+    let final_epoch = eval.folds[0].get_final_epoch();
+    assert_eq!(final_epoch.train_loss, 0.0);
+    assert_eq!(final_epoch.test_loss_avg, 0.0);
+
+    let (_, eval) = KFolds::new(2).run(&model, &rows(0.0));
+    assert!(eval.epochs_avg_train_loss().iter().all(|l| *l == 0.0));
+    assert_eq!(eval.epochs_avg_test_loss().last(), Some(&0.0));
+}
+
+#[test]
+#[should_panic(expected = "Expected one sample weight per sample")]
+fn test_sample_weights_count_mismatch() {
+    let mut network = NetworkModelBuilder::new().full_dense(1).end().build().to_network(1);
+    let x = vec![vec![0.1], vec![0.2], vec![0.3]];
+    let y = vec![vec![1.0], vec![0.0], vec![1.0]];
+    let loss = Losses::MSE.to_loss();
+
+    network.train_weighted(0, &x, &y, Some(&vec![1.0, 2.0]), &loss, 2);
+}